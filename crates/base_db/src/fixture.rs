@@ -123,13 +123,19 @@ impl ChangeFixture {
             }
 
             if let Some(krate) = meta.krate {
-                let crate_name = CrateName::normalize_dashes(&krate);
+                let (krate, version) = match krate.split_once('@') {
+                    Some((krate, version)) => (krate, Some(version.to_string())),
+                    None => (krate.as_str(), None),
+                };
+                let crate_name = CrateName::normalize_dashes(krate);
                 let crate_id = crate_graph.add_crate_root(
                     file_id,
                     meta.edition,
                     Some(crate_name.clone().into()),
+                    version,
                     meta.cfg.clone(),
                     meta.cfg,
+                    meta.features,
                     meta.env,
                     Default::default(),
                 );
@@ -158,8 +164,10 @@ impl ChangeFixture {
                 crate_root,
                 Edition::CURRENT,
                 Some(CrateName::new("test").unwrap().into()),
+                None,
                 default_cfg.clone(),
                 default_cfg,
+                Default::default(),
                 Env::default(),
                 Default::default(),
             );
@@ -187,8 +195,10 @@ impl ChangeFixture {
                 core_file,
                 Edition::Edition2021,
                 Some(CrateDisplayName::from_canonical_name("core".to_string())),
+                None,
                 CfgOptions::default(),
                 CfgOptions::default(),
+                Default::default(),
                 Env::default(),
                 Vec::new(),
             );
@@ -211,6 +221,7 @@ struct FileMeta {
     krate: Option<String>,
     deps: Vec<String>,
     cfg: CfgOptions,
+    features: FxHashMap<String, Vec<String>>,
     edition: Edition,
     env: Env,
     introduce_new_source_root: bool,
@@ -227,6 +238,7 @@ impl From<Fixture> for FileMeta {
             krate: f.krate,
             deps: f.deps,
             cfg,
+            features: f.features,
             edition: f.edition.as_ref().map_or(Edition::CURRENT, |v| Edition::from_str(v).unwrap()),
             env: f.env.into_iter().collect(),
             introduce_new_source_root: f.introduce_new_source_root,
@@ -6,11 +6,11 @@
 //! actual IO. See `vfs` and `project_model` in the `rust-analyzer` crate for how
 //! actual IO is done and lowered to input.
 
-use std::{fmt, iter::FromIterator, ops, panic::RefUnwindSafe, str::FromStr, sync::Arc};
+use std::{fmt, iter::FromIterator, ops, panic::RefUnwindSafe, sync::Arc};
 
 use cfg::CfgOptions;
 use rustc_hash::{FxHashMap, FxHashSet};
-use syntax::SmolStr;
+use syntax::{Edition, SmolStr};
 use tt::{ExpansionError, Subtree};
 use vfs::{file_set::FileSet, FileId, VfsPath};
 
@@ -142,6 +142,9 @@ impl CrateDisplayName {
         let crate_name = CrateName::normalize_dashes(&canonical_name);
         CrateDisplayName { crate_name, canonical_name }
     }
+    pub fn canonical_name(&self) -> &str {
+        &self.canonical_name
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -181,24 +184,19 @@ pub struct CrateData {
     /// For purposes of analysis, crates are anonymous (only names in
     /// `Dependency` matters), this name should only be used for UI.
     pub display_name: Option<CrateDisplayName>,
+    /// The version of the package the crate belongs to, if known (from
+    /// `[package].version` for Cargo projects).
+    pub version: Option<String>,
     pub cfg_options: CfgOptions,
     pub potential_cfg_options: CfgOptions,
+    /// The Cargo features of the crate's package, each mapped to the features
+    /// and dependencies it enables, as in the `[features]` table.
+    pub features: FxHashMap<String, Vec<String>>,
     pub env: Env,
     pub dependencies: Vec<Dependency>,
     pub proc_macro: Vec<ProcMacro>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Edition {
-    Edition2015,
-    Edition2018,
-    Edition2021,
-}
-
-impl Edition {
-    pub const CURRENT: Edition = Edition::Edition2018;
-}
-
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Env {
     entries: FxHashMap<String, String>,
@@ -216,8 +214,10 @@ impl CrateGraph {
         file_id: FileId,
         edition: Edition,
         display_name: Option<CrateDisplayName>,
+        version: Option<String>,
         cfg_options: CfgOptions,
         potential_cfg_options: CfgOptions,
+        features: FxHashMap<String, Vec<String>>,
         env: Env,
         proc_macro: Vec<ProcMacro>,
     ) -> CrateId {
@@ -225,8 +225,10 @@ impl CrateGraph {
             root_file_id: file_id,
             edition,
             display_name,
+            version,
             cfg_options,
             potential_cfg_options,
+            features,
             env,
             proc_macro,
             dependencies: Vec::new(),
@@ -419,30 +421,6 @@ impl CrateData {
     }
 }
 
-impl FromStr for Edition {
-    type Err = ParseEditionError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let res = match s {
-            "2015" => Edition::Edition2015,
-            "2018" => Edition::Edition2018,
-            "2021" => Edition::Edition2021,
-            _ => return Err(ParseEditionError { invalid_input: s.to_string() }),
-        };
-        Ok(res)
-    }
-}
-
-impl fmt::Display for Edition {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Edition::Edition2015 => "2015",
-            Edition::Edition2018 => "2018",
-            Edition::Edition2021 => "2021",
-        })
-    }
-}
-
 impl FromIterator<(String, String)> for Env {
     fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
         Env { entries: FromIterator::from_iter(iter) }
@@ -463,19 +441,6 @@ impl Env {
     }
 }
 
-#[derive(Debug)]
-pub struct ParseEditionError {
-    invalid_input: String,
-}
-
-impl fmt::Display for ParseEditionError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "invalid edition: {:?}", self.invalid_input)
-    }
-}
-
-impl std::error::Error for ParseEditionError {}
-
 #[derive(Debug)]
 pub struct CyclicDependenciesError {
     from: (CrateId, Option<CrateDisplayName>),
@@ -503,8 +468,10 @@ mod tests {
             FileId(1u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -512,8 +479,10 @@ mod tests {
             FileId(2u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -521,8 +490,10 @@ mod tests {
             FileId(3u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -538,8 +509,10 @@ mod tests {
             FileId(1u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -547,8 +520,10 @@ mod tests {
             FileId(2u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -563,8 +538,10 @@ mod tests {
             FileId(1u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -572,8 +549,10 @@ mod tests {
             FileId(2u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -581,8 +560,10 @@ mod tests {
             FileId(3u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -597,8 +578,10 @@ mod tests {
             FileId(1u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -606,8 +589,10 @@ mod tests {
             FileId(2u32),
             Edition2018,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -11,11 +11,12 @@ use syntax::{ast, Parse, SourceFile, TextRange, TextSize};
 pub use crate::{
     change::Change,
     input::{
-        CrateData, CrateDisplayName, CrateGraph, CrateId, CrateName, Dependency, Edition, Env,
-        ProcMacro, ProcMacroExpander, ProcMacroId, ProcMacroKind, SourceRoot, SourceRootId,
+        CrateData, CrateDisplayName, CrateGraph, CrateId, CrateName, Dependency, Env, ProcMacro,
+        ProcMacroExpander, ProcMacroId, ProcMacroKind, SourceRoot, SourceRootId,
     },
 };
 pub use salsa::{self, Cancelled};
+pub use syntax::Edition;
 pub use vfs::{file_set::FileSet, AnchoredPath, AnchoredPathBuf, FileId, VfsPath};
 
 #[macro_export]
@@ -65,6 +66,10 @@ pub trait SourceDatabase: FileLoader + std::fmt::Debug {
     #[salsa::invoke(parse_query)]
     fn parse(&self, file_id: FileId) -> Parse<ast::SourceFile>;
 
+    /// The edition of the crate the file belongs to, which determines how the
+    /// file is parsed.
+    fn file_edition(&self, file_id: FileId) -> Edition;
+
     /// The crate graph.
     #[salsa::input]
     fn crate_graph(&self) -> Arc<CrateGraph>;
@@ -73,7 +78,13 @@ pub trait SourceDatabase: FileLoader + std::fmt::Debug {
 fn parse_query(db: &dyn SourceDatabase, file_id: FileId) -> Parse<ast::SourceFile> {
     let _p = profile::span("parse_query").detail(|| format!("{:?}", file_id));
     let text = db.file_text(file_id);
-    SourceFile::parse(&*text)
+    SourceFile::parse_with_edition(&text, db.file_edition(file_id))
+}
+
+fn file_edition(db: &dyn SourceDatabase, file_id: FileId) -> Edition {
+    let graph = db.crate_graph();
+    // If the file is shared between several crates, pick one deterministically.
+    db.relevant_crates(file_id).iter().min().map_or(Edition::CURRENT, |&krate| graph[krate].edition)
 }
 
 /// We don't want to give HIR knowledge of source roots, hence we extract these
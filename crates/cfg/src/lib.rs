@@ -98,6 +98,14 @@ impl CfgDiff {
     pub fn len(&self) -> usize {
         self.enable.len() + self.disable.len()
     }
+
+    pub fn enable(&self) -> &[CfgAtom] {
+        &self.enable
+    }
+
+    pub fn disable(&self) -> &[CfgAtom] {
+        &self.disable
+    }
 }
 
 impl fmt::Display for CfgDiff {
@@ -56,6 +56,7 @@ pub struct FlycheckHandle {
     // XXX: drop order is significant
     sender: Sender<Restart>,
     thread: jod_thread::JoinHandle,
+    id: usize,
 }
 
 impl FlycheckHandle {
@@ -71,18 +72,40 @@ impl FlycheckHandle {
             .name("Flycheck".to_owned())
             .spawn(move || actor.run(receiver))
             .expect("failed to spawn thread");
-        FlycheckHandle { sender, thread }
+        FlycheckHandle { sender, thread, id }
     }
 
     /// Schedule a re-start of the cargo check worker.
     pub fn update(&self) {
-        self.sender.send(Restart).unwrap();
+        self.sender.send(Restart::Workspace).unwrap();
+    }
+
+    /// Schedule a re-start of the cargo check worker, checking only the
+    /// packages with the given `-p` flags.
+    pub fn update_packages(&self, packages: Vec<String>) {
+        self.sender.send(Restart::Packages(packages)).unwrap();
+    }
+
+    /// Schedule a re-start of the check worker, running the given command
+    /// instead of the configured one.
+    pub fn update_with_command(&self, command: String, args: Vec<String>, cwd: AbsPathBuf) {
+        self.sender.send(Restart::Command { command, args, cwd }).unwrap();
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
     }
 }
 
 pub enum Message {
     /// Request adding a diagnostic with fixes included to a file
-    AddDiagnostic { workspace_root: AbsPathBuf, diagnostic: Diagnostic },
+    AddDiagnostic {
+        workspace_root: AbsPathBuf,
+        /// The cargo package id of the package the diagnostic was emitted for,
+        /// unless the check command isn't cargo.
+        package_id: Option<String>,
+        diagnostic: Diagnostic,
+    },
 
     /// Request check progress notification to client
     Progress {
@@ -95,9 +118,10 @@ pub enum Message {
 impl fmt::Debug for Message {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Message::AddDiagnostic { workspace_root, diagnostic } => f
+            Message::AddDiagnostic { workspace_root, package_id, diagnostic } => f
                 .debug_struct("AddDiagnostic")
                 .field("workspace_root", workspace_root)
+                .field("package_id", package_id)
                 .field("diagnostic_code", &diagnostic.code.as_ref().map(|it| &it.code))
                 .finish(),
             Message::Progress { id, progress } => {
@@ -109,13 +133,21 @@ impl fmt::Debug for Message {
 
 #[derive(Debug)]
 pub enum Progress {
-    DidStart,
+    /// The check started, for the packages with the given `-p` flags, or for
+    /// the whole workspace.
+    DidStart {
+        packages: Option<Vec<String>>,
+    },
     DidCheckCrate(String),
     DidFinish(io::Result<()>),
     DidCancel,
 }
 
-struct Restart;
+enum Restart {
+    Workspace,
+    Packages(Vec<String>),
+    Command { command: String, args: Vec<String>, cwd: AbsPathBuf },
+}
 
 struct FlycheckActor {
     id: usize,
@@ -128,6 +160,8 @@ struct FlycheckActor {
     /// have to wrap sub-processes output handling in a thread and pass messages
     /// back over a channel.
     cargo_handle: Option<CargoHandle>,
+    /// The command of the last check, which is logged if it fails.
+    last_command: String,
 }
 
 enum Event {
@@ -142,7 +176,14 @@ impl FlycheckActor {
         config: FlycheckConfig,
         workspace_root: AbsPathBuf,
     ) -> FlycheckActor {
-        FlycheckActor { id, sender, config, workspace_root, cargo_handle: None }
+        FlycheckActor {
+            id,
+            sender,
+            config,
+            workspace_root,
+            cargo_handle: None,
+            last_command: String::new(),
+        }
     }
     fn progress(&self, progress: Progress) {
         self.send(Message::Progress { id: self.id, progress });
@@ -157,17 +198,43 @@ impl FlycheckActor {
     fn run(mut self, inbox: Receiver<Restart>) {
         while let Some(event) = self.next_event(&inbox) {
             match event {
-                Event::Restart(Restart) => {
-                    while let Ok(Restart) = inbox.recv_timeout(Duration::from_millis(50)) {}
+                Event::Restart(mut restart) => {
+                    while let Ok(next) = inbox.recv_timeout(Duration::from_millis(50)) {
+                        restart = match (restart, next) {
+                            (Restart::Packages(mut packages), Restart::Packages(more)) => {
+                                packages.extend(more);
+                                packages.sort();
+                                packages.dedup();
+                                Restart::Packages(packages)
+                            }
+                            (Restart::Workspace, _) | (_, Restart::Workspace) => Restart::Workspace,
+                            (_, next) => next,
+                        };
+                    }
 
                     self.cancel_check_process();
 
-                    let mut command = self.check_command();
+                    let (mut command, packages) = match restart {
+                        Restart::Packages(packages)
+                            if matches!(self.config, FlycheckConfig::CargoCommand { .. }) =>
+                        {
+                            (self.check_command(Some(&packages)), Some(packages))
+                        }
+                        Restart::Workspace | Restart::Packages(_) => {
+                            (self.check_command(None), None)
+                        }
+                        Restart::Command { command, args, cwd } => {
+                            let mut cmd = Command::new(command);
+                            cmd.args(args).current_dir(cwd);
+                            (cmd, None)
+                        }
+                    };
                     log::info!("restart flycheck {:?}", command);
+                    self.last_command = format!("{:?}", command);
                     command.stdout(Stdio::piped()).stderr(Stdio::null()).stdin(Stdio::null());
                     if let Ok(child) = command.spawn().map(JodChild) {
                         self.cargo_handle = Some(CargoHandle::spawn(child));
-                        self.progress(Progress::DidStart);
+                        self.progress(Progress::DidStart { packages });
                     }
                 }
                 Event::CheckEvent(None) => {
@@ -177,8 +244,8 @@ impl FlycheckActor {
                     let res = cargo_handle.join();
                     if res.is_err() {
                         log::error!(
-                            "Flycheck failed to run the following command: {:?}",
-                            self.check_command()
+                            "Flycheck failed to run the following command: {}",
+                            self.last_command
                         )
                     }
                     self.progress(Progress::DidFinish(res));
@@ -188,10 +255,11 @@ impl FlycheckActor {
                         self.progress(Progress::DidCheckCrate(msg.target.name));
                     }
 
-                    CargoMessage::Diagnostic(msg) => {
+                    CargoMessage::Diagnostic { package_id, diagnostic } => {
                         self.send(Message::AddDiagnostic {
                             workspace_root: self.workspace_root.clone(),
-                            diagnostic: msg,
+                            package_id,
+                            diagnostic,
                         });
                     }
                 },
@@ -205,7 +273,9 @@ impl FlycheckActor {
             self.progress(Progress::DidCancel);
         }
     }
-    fn check_command(&self) -> Command {
+    /// Builds the check command, for the packages with the given `-p` flags or
+    /// for the whole workspace.
+    fn check_command(&self, packages: Option<&[String]>) -> Command {
         let mut cmd = match &self.config {
             FlycheckConfig::CargoCommand {
                 command,
@@ -219,7 +289,17 @@ impl FlycheckActor {
                 let mut cmd = Command::new(toolchain::cargo());
                 cmd.arg(command);
                 cmd.current_dir(&self.workspace_root);
-                cmd.args(&["--workspace", "--message-format=json", "--manifest-path"])
+                match packages {
+                    Some(packages) => {
+                        for package in packages {
+                            cmd.args(["-p", package]);
+                        }
+                    }
+                    None => {
+                        cmd.arg("--workspace");
+                    }
+                }
+                cmd.args(["--message-format=json", "--manifest-path"])
                     .arg(self.workspace_root.join("Cargo.toml").as_os_str());
 
                 if let Some(target) = target_triple {
@@ -336,9 +416,13 @@ impl CargoActor {
                         cargo_metadata::Message::CompilerArtifact(artifact) if !artifact.fresh => {
                             self.sender.send(CargoMessage::CompilerArtifact(artifact)).unwrap()
                         }
-                        cargo_metadata::Message::CompilerMessage(msg) => {
-                            self.sender.send(CargoMessage::Diagnostic(msg.message)).unwrap()
-                        }
+                        cargo_metadata::Message::CompilerMessage(msg) => self
+                            .sender
+                            .send(CargoMessage::Diagnostic {
+                                package_id: Some(msg.package_id.repr),
+                                diagnostic: msg.message,
+                            })
+                            .unwrap(),
 
                         cargo_metadata::Message::CompilerArtifact(_)
                         | cargo_metadata::Message::BuildScriptExecuted(_)
@@ -346,9 +430,10 @@ impl CargoActor {
                         | cargo_metadata::Message::TextLine(_)
                         | _ => (),
                     },
-                    JsonMessage::Rustc(message) => {
-                        self.sender.send(CargoMessage::Diagnostic(message)).unwrap()
-                    }
+                    JsonMessage::Rustc(message) => self
+                        .sender
+                        .send(CargoMessage::Diagnostic { package_id: None, diagnostic: message })
+                        .unwrap(),
                 }
             }
         }
@@ -358,7 +443,7 @@ impl CargoActor {
 
 enum CargoMessage {
     CompilerArtifact(cargo_metadata::Artifact),
-    Diagnostic(Diagnostic),
+    Diagnostic { package_id: Option<String>, diagnostic: Diagnostic },
 }
 
 #[derive(Deserialize)]
@@ -367,3 +452,42 @@ enum JsonMessage {
     Cargo(cargo_metadata::Message),
     Rustc(Diagnostic),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_command_for_packages() {
+        let workspace_root = AbsPathBuf::assert(std::env::current_dir().unwrap());
+        let config = FlycheckConfig::CargoCommand {
+            command: "check".to_string(),
+            target_triple: None,
+            all_targets: true,
+            no_default_features: false,
+            all_features: false,
+            features: Vec::new(),
+            extra_args: Vec::new(),
+        };
+        let actor = FlycheckActor::new(0, Box::new(|_| ()), config, workspace_root.clone());
+
+        let packages = ["foo".to_string(), "bar".to_string()];
+        let command = actor.check_command(Some(&packages));
+        let manifest_path = workspace_root.join("Cargo.toml");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "check",
+                "-p",
+                "foo",
+                "-p",
+                "bar",
+                "--message-format=json",
+                "--manifest-path",
+                manifest_path.as_os_str().to_str().unwrap(),
+                "--all-targets",
+            ]
+        );
+    }
+}
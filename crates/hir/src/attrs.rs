@@ -1,6 +1,6 @@
 //! Attributes & documentation for hir types.
 use hir_def::{
-    attr::{AttrsWithOwner, Documentation},
+    attr::{AttrsWithOwner, Deprecation, Documentation, MustUse},
     path::ModPath,
     per_ns::PerNs,
     resolver::HasResolver,
@@ -18,6 +18,8 @@ use crate::{
 pub trait HasAttrs {
     fn attrs(self, db: &dyn HirDatabase) -> AttrsWithOwner;
     fn docs(self, db: &dyn HirDatabase) -> Option<Documentation>;
+    fn deprecation(self, db: &dyn HirDatabase) -> Option<Deprecation>;
+    fn must_use(self, db: &dyn HirDatabase) -> Option<MustUse>;
     fn resolve_doc_path(
         self,
         db: &dyn HirDatabase,
@@ -44,6 +46,14 @@ macro_rules! impl_has_attrs {
                 let def = AttrDefId::$def_id(self.into());
                 db.attrs(def).docs()
             }
+            fn deprecation(self, db: &dyn HirDatabase) -> Option<Deprecation> {
+                let def = AttrDefId::$def_id(self.into());
+                db.attrs(def).deprecation()
+            }
+            fn must_use(self, db: &dyn HirDatabase) -> Option<MustUse> {
+                let def = AttrDefId::$def_id(self.into());
+                db.attrs(def).must_use()
+            }
             fn resolve_doc_path(self, db: &dyn HirDatabase, link: &str, ns: Option<Namespace>) -> Option<ModuleDef> {
                 let def = AttrDefId::$def_id(self.into());
                 resolve_doc_path(db, def, link, ns).map(ModuleDef::from)
@@ -76,6 +86,12 @@ macro_rules! impl_has_attrs_enum {
             fn docs(self, db: &dyn HirDatabase) -> Option<Documentation> {
                 $enum::$variant(self).docs(db)
             }
+            fn deprecation(self, db: &dyn HirDatabase) -> Option<Deprecation> {
+                $enum::$variant(self).deprecation(db)
+            }
+            fn must_use(self, db: &dyn HirDatabase) -> Option<MustUse> {
+                $enum::$variant(self).must_use(db)
+            }
             fn resolve_doc_path(self, db: &dyn HirDatabase, link: &str, ns: Option<Namespace>) -> Option<ModuleDef> {
                 $enum::$variant(self).resolve_doc_path(db, link, ns)
             }
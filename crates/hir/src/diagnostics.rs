@@ -9,6 +9,10 @@ use hir_def::path::ModPath;
 use hir_expand::{name::Name, HirFileId, InFile};
 use syntax::{ast, AstPtr, SyntaxNodePtr, TextRange};
 
+pub use hir_ty::CastError;
+
+use crate::{AssocItem, GenericDef, Local, Trait, Type};
+
 macro_rules! diagnostics {
     ($($diag:ident,)*) => {
         pub enum AnyDiagnostic {$(
@@ -29,21 +33,28 @@ diagnostics![
     BreakOutsideOfLoop,
     InactiveCode,
     IncorrectCase,
+    InvalidCast,
     MacroError,
     MismatchedArgCount,
     MissingFields,
     MissingMatchArms,
     MissingOkOrSomeInTailExpr,
+    MissingTraitItems,
     MissingUnsafe,
     NoSuchField,
+    PossiblyUninitialized,
     RemoveThisSemicolon,
     ReplaceFilterMapNextWithFindMap,
+    TraitItemNotMember,
     UnimplementedBuiltinMacro,
+    UnreachableCode,
     UnresolvedExternCrate,
     UnresolvedImport,
     UnresolvedMacroCall,
     UnresolvedModule,
     UnresolvedProcMacro,
+    UnsatisfiedTraitBound,
+    UseOfMovedValue,
 ];
 
 #[derive(Debug)]
@@ -144,6 +155,41 @@ pub struct MissingOkOrSomeInTailExpr {
     pub required: String,
 }
 
+#[derive(Debug)]
+pub struct UnsatisfiedTraitBound {
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    /// The bound as written in a where clause, like `Foo: Trait<u32>`.
+    pub bound: String,
+    pub ty: Type,
+    pub trait_: Trait,
+    /// The item whose where clauses require the bound.
+    pub origin: GenericDef,
+}
+
+#[derive(Debug)]
+pub struct InvalidCast {
+    /// The cast expression.
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    pub error: CastError,
+    pub expr_ty: Type,
+    pub cast_ty: Type,
+}
+
+#[derive(Debug)]
+pub struct MissingTraitItems {
+    pub impl_: InFile<AstPtr<ast::Impl>>,
+    pub trait_: Trait,
+    /// The trait items without a default that the impl doesn't define.
+    pub missing: Vec<AssocItem>,
+}
+
+#[derive(Debug)]
+pub struct TraitItemNotMember {
+    pub item: InFile<AstPtr<ast::AssocItem>>,
+    pub name: Name,
+    pub trait_: Trait,
+}
+
 #[derive(Debug)]
 pub struct MissingMatchArms {
     pub file: HirFileId,
@@ -151,4 +197,25 @@ pub struct MissingMatchArms {
     pub arms: AstPtr<ast::MatchArmList>,
 }
 
+#[derive(Debug)]
+pub struct UseOfMovedValue {
+    /// The use, or the binding that the value is moved into.
+    pub node: InFile<SyntaxNodePtr>,
+    pub local: Local,
+    pub is_borrow: bool,
+}
+
+#[derive(Debug)]
+pub struct PossiblyUninitialized {
+    pub node: InFile<SyntaxNodePtr>,
+    pub local: Local,
+    pub is_borrow: bool,
+}
+
+#[derive(Debug)]
+pub struct UnreachableCode {
+    /// The first unreachable statement or tail expression of a block.
+    pub expr: InFile<AstPtr<ast::Expr>>,
+}
+
 pub use hir_ty::diagnostics::IncorrectCase;
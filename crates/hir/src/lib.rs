@@ -37,9 +37,9 @@ use arrayvec::ArrayVec;
 use base_db::{CrateDisplayName, CrateId, Edition, FileId};
 use either::Either;
 use hir_def::{
-    adt::{ReprKind, VariantData},
-    body::{BodyDiagnostic, SyntheticSyntax},
-    expr::{BindingAnnotation, LabelId, Pat, PatId},
+    adt::{ReprOptions, VariantData},
+    body::{BodyDiagnostic, BodySourceMap, SyntheticSyntax},
+    expr::{BindingAnnotation, ExprId, LabelId, Pat, PatId},
     item_tree::ItemTreeNode,
     lang_item::LangItemTarget,
     nameres,
@@ -48,8 +48,8 @@ use hir_def::{
     src::HasSource as _,
     AdtId, AssocContainerId, AssocItemId, AssocItemLoc, AttrDefId, ConstId, ConstParamId,
     DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule, ImplId, LifetimeParamId,
-    LocalEnumVariantId, LocalFieldId, Lookup, ModuleId, StaticId, StructId, TraitId, TypeAliasId,
-    TypeParamId, UnionId,
+    LocalEnumVariantId, LocalFieldId, Lookup, ModuleDefId, ModuleId, StaticId, StructId, TraitId,
+    TypeAliasId, TypeParamId, UnionId,
 };
 use hir_expand::{name::name, MacroCallKind, MacroDefId, MacroDefKind};
 use hir_ty::{
@@ -57,6 +57,7 @@ use hir_ty::{
     consteval::ConstExt,
     could_unify,
     diagnostics::BodyValidationDiagnostic,
+    layout::layout_of_ty,
     method_resolution::{self, TyFingerprint},
     primitive::UintTy,
     subst_prefix,
@@ -69,7 +70,7 @@ use hir_ty::{
 use itertools::Itertools;
 use nameres::diagnostics::DefDiagnosticKind;
 use once_cell::unsync::Lazy;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use stdx::{format_to, impl_from};
 use syntax::{
     ast::{self, AttrsOwner, NameOwner},
@@ -82,11 +83,12 @@ use crate::db::{DefDatabase, HirDatabase};
 pub use crate::{
     attrs::{HasAttrs, Namespace},
     diagnostics::{
-        AnyDiagnostic, BreakOutsideOfLoop, InactiveCode, IncorrectCase, MacroError,
-        MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkOrSomeInTailExpr,
-        MissingUnsafe, NoSuchField, RemoveThisSemicolon, ReplaceFilterMapNextWithFindMap,
-        UnimplementedBuiltinMacro, UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall,
-        UnresolvedModule, UnresolvedProcMacro,
+        AnyDiagnostic, BreakOutsideOfLoop, CastError, InactiveCode, IncorrectCase, InvalidCast,
+        MacroError, MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkOrSomeInTailExpr,
+        MissingTraitItems, MissingUnsafe, NoSuchField, PossiblyUninitialized, RemoveThisSemicolon,
+        ReplaceFilterMapNextWithFindMap, TraitItemNotMember, UnimplementedBuiltinMacro,
+        UnreachableCode, UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall,
+        UnresolvedModule, UnresolvedProcMacro, UnsatisfiedTraitBound, UseOfMovedValue,
     },
     has_source::HasSource,
     semantics::{PathResolution, Semantics, SemanticsScope},
@@ -105,7 +107,7 @@ pub use {
     cfg::{CfgAtom, CfgExpr, CfgOptions},
     hir_def::{
         adt::StructKind,
-        attr::{Attr, Attrs, AttrsWithOwner, Documentation},
+        attr::{Attr, Attrs, AttrsWithOwner, Deprecation, Documentation, MustUse},
         find_path::PrefixKind,
         import_map,
         item_scope::ItemInNs, // FIXME: don't re-export ItemInNs, as it uses raw ids.
@@ -118,7 +120,10 @@ pub use {
         name::{known, Name},
         ExpandResult, HirFileId, InFile, MacroFile, Origin,
     },
-    hir_ty::display::HirDisplay,
+    hir_ty::{
+        display::HirDisplay,
+        layout::{Layout, LayoutError},
+    },
 };
 
 // These are negative re-exports: pub using these names is forbidden, they
@@ -188,6 +193,10 @@ impl Crate {
         db.crate_graph()[self.id].display_name.clone()
     }
 
+    pub fn version(self, db: &dyn HirDatabase) -> Option<String> {
+        db.crate_graph()[self.id].version.clone()
+    }
+
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
@@ -235,6 +244,10 @@ impl Crate {
     pub fn potential_cfg(&self, db: &dyn HirDatabase) -> CfgOptions {
         db.crate_graph()[self.id].potential_cfg_options.clone()
     }
+
+    pub fn features(&self, db: &dyn HirDatabase) -> FxHashMap<String, Vec<String>> {
+        db.crate_graph()[self.id].features.clone()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -317,6 +330,31 @@ impl ModuleDef {
         }
     }
 
+    /// The deprecation of this item, or of the trait it belongs to if it is
+    /// an associated item.
+    pub fn deprecation(self, db: &dyn HirDatabase) -> Option<Deprecation> {
+        let deprecation = match self {
+            ModuleDef::Module(it) => it.deprecation(db),
+            ModuleDef::Function(it) => it.deprecation(db),
+            ModuleDef::Adt(it) => it.deprecation(db),
+            ModuleDef::Variant(it) => it.deprecation(db),
+            ModuleDef::Const(it) => it.deprecation(db),
+            ModuleDef::Static(it) => it.deprecation(db),
+            ModuleDef::Trait(it) => it.deprecation(db),
+            ModuleDef::TypeAlias(it) => it.deprecation(db),
+            ModuleDef::BuiltinType(_) => None,
+        };
+        deprecation.or_else(|| {
+            let assoc = match self {
+                ModuleDef::Function(it) => it.as_assoc_item(db),
+                ModuleDef::Const(it) => it.as_assoc_item(db),
+                ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+                _ => None,
+            }?;
+            assoc.containing_trait_or_trait_impl(db)?.deprecation(db)
+        })
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase) -> Vec<AnyDiagnostic> {
         let id = match self {
             ModuleDef::Adt(it) => match it {
@@ -610,6 +648,7 @@ impl Module {
         }
 
         for impl_def in self.impl_defs(db) {
+            impl_def.diagnostics(db, acc);
             for item in impl_def.items(db) {
                 if let AssocItem::Function(f) = item {
                     f.diagnostics(db, acc);
@@ -628,6 +667,21 @@ impl Module {
         def_map[self.id.local_id].scope.impls().map(Impl::from).collect()
     }
 
+    /// Returns the leaves of the use trees of the private imports of this
+    /// module, and of the block expressions in its items, that name resolution
+    /// never goes through.
+    pub fn unused_imports(self, db: &dyn HirDatabase) -> Vec<InFile<ast::UseTree>> {
+        hir_ty::diagnostics::unused_imports(db, self.id)
+            .into_iter()
+            .map(|it| {
+                let file_id = it.import.file_id();
+                let tree = it.import.item_tree(db.upcast());
+                let ast = tree[it.import.value].use_tree_to_ast(db.upcast(), file_id, it.use_tree);
+                InFile::new(file_id, ast)
+            })
+            .collect()
+    }
+
     /// Finds a path that can be used to refer to the given item from within
     /// this module, if possible.
     pub fn find_use_path(self, db: &dyn DefDatabase, item: impl Into<ItemInNs>) -> Option<ModPath> {
@@ -681,6 +735,22 @@ impl Field {
     pub fn parent_def(&self, _db: &dyn HirDatabase) -> VariantDef {
         self.parent
     }
+
+    pub fn layout(&self, db: &dyn HirDatabase) -> Result<Arc<Layout>, LayoutError> {
+        layout_of_ty(db, &self.ty(db).ty)
+    }
+
+    /// Returns the offset of the field in its struct or union. Enum variants
+    /// don't have a fixed layout of their own, so their fields have none.
+    pub fn offset(&self, db: &dyn HirDatabase) -> Option<u64> {
+        let adt = match self.parent {
+            VariantDef::Struct(it) => Adt::from(it),
+            VariantDef::Union(it) => Adt::from(it),
+            VariantDef::Variant(_) => return None,
+        };
+        let layout = adt.layout(db).ok()?;
+        layout.field_offsets.get(u32::from(self.id.into_raw()) as usize).copied()
+    }
 }
 
 impl HasVisibility for Field {
@@ -719,7 +789,7 @@ impl Struct {
         Type::from_def(db, self.id.lookup(db.upcast()).container.krate(), self.id)
     }
 
-    pub fn repr(self, db: &dyn HirDatabase) -> Option<ReprKind> {
+    pub fn repr(self, db: &dyn HirDatabase) -> Option<ReprOptions> {
         db.struct_data(self.id).repr.clone()
     }
 
@@ -879,6 +949,13 @@ impl Adt {
             Adt::Enum(e) => e.name(db),
         }
     }
+
+    /// Computes the memory layout of the ADT, which fails if it depends on
+    /// the ADT's type parameters.
+    pub fn layout(self, db: &dyn HirDatabase) -> Result<Arc<Layout>, LayoutError> {
+        let id = AdtId::from(self);
+        db.layout_of_adt(id, TyBuilder::type_params_subst(db, id))
+    }
 }
 
 impl HasVisibility for Adt {
@@ -1069,6 +1146,34 @@ impl Function {
                         .expect("break outside of loop in synthetic syntax");
                     acc.push(BreakOutsideOfLoop { expr }.into())
                 }
+                hir_ty::InferenceDiagnostic::UnsatisfiedTraitBound { expr, trait_ref, origin } => {
+                    if let Ok(expr) = source_map.expr_syntax(*expr) {
+                        let ty = trait_ref.self_type_parameter(&Interner);
+                        acc.push(
+                            UnsatisfiedTraitBound {
+                                expr,
+                                bound: trait_ref.display(db).to_string(),
+                                ty: Type::new(db, krate, self.id, ty),
+                                trait_: trait_ref.hir_trait_id().into(),
+                                origin: (*origin).into(),
+                            }
+                            .into(),
+                        )
+                    }
+                }
+                hir_ty::InferenceDiagnostic::InvalidCast { expr, error, expr_ty, cast_ty } => {
+                    if let Ok(expr) = source_map.expr_syntax(*expr) {
+                        acc.push(
+                            InvalidCast {
+                                expr,
+                                error: *error,
+                                expr_ty: Type::new(db, krate, self.id, expr_ty.clone()),
+                                cast_ty: Type::new(db, krate, self.id, cast_ty.clone()),
+                            }
+                            .into(),
+                        )
+                    }
+                }
             }
         }
 
@@ -1203,6 +1308,27 @@ impl Function {
                         Err(SyntheticSyntax) => (),
                     }
                 }
+                BodyValidationDiagnostic::UseOfMovedValue { node, binding, is_borrow } => {
+                    if let Some(node) = body_node_syntax(&source_map, node) {
+                        let local = Local { parent: self.id.into(), pat_id: binding };
+                        acc.push(UseOfMovedValue { node, local, is_borrow }.into());
+                    }
+                }
+                BodyValidationDiagnostic::PossiblyUninitialized { node, binding, is_borrow } => {
+                    if let Some(node) = body_node_syntax(&source_map, node) {
+                        let local = Local { parent: self.id.into(), pat_id: binding };
+                        acc.push(PossiblyUninitialized { node, local, is_borrow }.into());
+                    }
+                }
+                BodyValidationDiagnostic::UnreachableCode { expr } => {
+                    // Code that macros expand to is often unreachable on
+                    // purpose, like the tail of `unimplemented!()`.
+                    if let Ok(expr) = source_map.expr_syntax(expr) {
+                        if expr.file_id.call_node(db.upcast()).is_none() {
+                            acc.push(UnreachableCode { expr }.into());
+                        }
+                    }
+                }
             }
         }
 
@@ -1406,6 +1532,31 @@ impl Trait {
     pub fn is_unsafe(&self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).is_unsafe
     }
+
+    /// Returns the traits named in the supertrait bounds of this trait.
+    pub fn direct_supertraits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        hir_ty::direct_super_traits(db.upcast(), self.id).into_iter().map(Trait::from).collect()
+    }
+
+    /// Returns the traits which name this trait in their supertrait bounds,
+    /// looking through the crates that can see it.
+    pub fn direct_subtraits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        let krate = self.module(db).krate();
+        let mut res = Vec::new();
+        for Crate { id } in krate.transitive_reverse_dependencies(db) {
+            let def_map = db.crate_def_map(id);
+            for (_, module) in def_map.modules() {
+                for decl in module.scope.declarations() {
+                    if let ModuleDefId::TraitId(trait_) = decl {
+                        if hir_ty::direct_super_traits(db.upcast(), trait_).contains(&self.id) {
+                            res.push(trait_.into());
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
 }
 
 impl HasVisibility for Trait {
@@ -1569,6 +1720,23 @@ impl AsAssocItem for ModuleDef {
         }
     }
 }
+
+/// The syntax of an expression or pattern of a body, unless it's synthetic.
+fn body_node_syntax(
+    source_map: &BodySourceMap,
+    node: Either<ExprId, PatId>,
+) -> Option<InFile<SyntaxNodePtr>> {
+    match node {
+        Either::Left(expr) => {
+            source_map.expr_syntax(expr).ok().map(|it| it.map(|it| it.syntax_node_ptr()))
+        }
+        Either::Right(pat) => source_map
+            .pat_syntax(pat)
+            .ok()
+            .map(|it| it.map(|it| it.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()))),
+    }
+}
+
 fn as_assoc_item<ID, DEF, CTOR, AST>(db: &dyn HirDatabase, ctor: CTOR, id: ID) -> Option<AssocItem>
 where
     ID: Lookup<Data = AssocItemLoc<AST>>,
@@ -1610,6 +1778,15 @@ impl AssocItem {
         }
     }
 
+    /// Whether this item has a default value, which trait impls may omit.
+    pub fn has_default(self, db: &dyn HirDatabase) -> bool {
+        match self {
+            AssocItem::Function(it) => it.has_body(db),
+            AssocItem::Const(it) => db.const_data(it.id).has_body,
+            AssocItem::TypeAlias(it) => db.type_alias_data(it.id).type_ref.is_some(),
+        }
+    }
+
     pub fn containing_trait(self, db: &dyn HirDatabase) -> Option<Trait> {
         match self.container(db) {
             AssocItemContainer::Trait(t) => Some(t),
@@ -2022,6 +2199,67 @@ impl Impl {
         self.id.lookup(db.upcast()).container.into()
     }
 
+    /// Checks that the items of a trait impl match the ones of the trait.
+    pub fn diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
+        let trait_ = match self.trait_(db) {
+            Some(it) if !self.is_negative(db) => it,
+            _ => return,
+        };
+        let src = match self.source(db) {
+            // Builtin derives expand to empty impls, and the user can't edit
+            // the impls other macros generate anyway.
+            Some(it) if it.file_id.call_node(db.upcast()).is_none() => it,
+            _ => return,
+        };
+        let impl_items = self.items(db);
+        let trait_items = trait_.items(db);
+        let is_same_item = |a: AssocItem, b: AssocItem| {
+            let same_kind = matches!(
+                (a, b),
+                (AssocItem::Function(_), AssocItem::Function(_))
+                    | (AssocItem::Const(_), AssocItem::Const(_))
+                    | (AssocItem::TypeAlias(_), AssocItem::TypeAlias(_))
+            );
+            same_kind && a.name(db).is_some() && a.name(db) == b.name(db)
+        };
+
+        // Items of macro calls that failed to expand would be reported as missing.
+        let has_macro_calls = src.value.assoc_item_list().map_or(false, |it| {
+            it.assoc_items().any(|it| matches!(it, ast::AssocItem::MacroCall(_)))
+        });
+        let missing: Vec<_> = trait_items
+            .iter()
+            .copied()
+            .filter(|&item| !item.has_default(db))
+            .filter(|&item| !impl_items.iter().any(|&it| is_same_item(it, item)))
+            .collect();
+        if !missing.is_empty() && !has_macro_calls {
+            let impl_ = src.as_ref().map(AstPtr::new);
+            acc.push(MissingTraitItems { impl_, trait_, missing }.into());
+        }
+
+        for &item in &impl_items {
+            if trait_items.iter().any(|&it| is_same_item(item, it)) {
+                continue;
+            }
+            let name = match item.name(db) {
+                Some(it) => it,
+                None => continue,
+            };
+            let item = match item {
+                AssocItem::Function(it) => it.source(db).map(|it| it.map(ast::AssocItem::Fn)),
+                AssocItem::Const(it) => it.source(db).map(|it| it.map(ast::AssocItem::Const)),
+                AssocItem::TypeAlias(it) => {
+                    it.source(db).map(|it| it.map(ast::AssocItem::TypeAlias))
+                }
+            };
+            if let Some(item) = item {
+                let item = item.as_ref().map(AstPtr::new);
+                acc.push(TraitItemNotMember { item, name, trait_ }.into());
+            }
+        }
+    }
+
     pub fn is_builtin_derive(self, db: &dyn HirDatabase) -> Option<InFile<ast::Attr>> {
         let src = self.source(db)?;
         let item = src.file_id.is_builtin_derive(db.upcast())?;
@@ -2253,11 +2491,15 @@ impl Type {
 
         let adt = adt_id.into();
         match adt {
-            Adt::Struct(s) => matches!(s.repr(db), Some(ReprKind::Packed)),
+            Adt::Struct(s) => s.repr(db).map_or(false, |it| it.is_packed()),
             _ => false,
         }
     }
 
+    pub fn is_array(&self) -> bool {
+        matches!(&self.ty.kind(&Interner), TyKind::Array(..))
+    }
+
     pub fn is_raw_ptr(&self) -> bool {
         matches!(&self.ty.kind(&Interner), TyKind::Raw(..))
     }
@@ -2472,6 +2714,17 @@ impl Type {
         })
     }
 
+    /// Returns the `#[must_use]` attribute that applies to values of this type:
+    /// the one on its ADT, or the one on a trait of an `impl Trait` or
+    /// `dyn Trait` type.
+    pub fn must_use(&self, db: &dyn HirDatabase) -> Option<MustUse> {
+        if let Some(adt) = self.as_adt() {
+            return adt.must_use(db);
+        }
+        let traits = self.as_impl_traits(db).or_else(|| self.as_dyn_trait().map(|it| vec![it]))?;
+        traits.into_iter().find_map(|it| it.must_use(db))
+    }
+
     pub fn as_associated_type_parent_trait(&self, db: &dyn HirDatabase) -> Option<Trait> {
         self.ty.associated_type_parent_trait(db).map(Into::into)
     }
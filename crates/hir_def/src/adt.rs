@@ -14,6 +14,7 @@ use tt::{Delimiter, DelimiterKind, Leaf, Subtree, TokenTree};
 
 use crate::{
     body::{CfgExpander, LowerCtx},
+    builtin_type::{BuiltinInt, BuiltinUint},
     db::DefDatabase,
     intern::Interned,
     item_tree::{AttrOwner, Field, Fields, ItemTree, ModItem, RawVisibilityId},
@@ -31,7 +32,7 @@ use cfg::CfgOptions;
 pub struct StructData {
     pub name: Name,
     pub variant_data: Arc<VariantData>,
-    pub repr: Option<ReprKind>,
+    pub repr: Option<ReprOptions>,
     pub visibility: RawVisibility,
 }
 
@@ -39,6 +40,7 @@ pub struct StructData {
 pub struct EnumData {
     pub name: Name,
     pub variants: Arena<EnumVariantData>,
+    pub repr: Option<ReprOptions>,
     pub visibility: RawVisibility,
 }
 
@@ -63,10 +65,34 @@ pub struct FieldData {
     pub visibility: RawVisibility,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ReprKind {
-    Packed,
-    Other,
+/// The options of the `#[repr]` attributes of an ADT.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReprOptions {
+    pub c: bool,
+    pub transparent: bool,
+    /// The type of the discriminant of an enum, like `u8` for `#[repr(u8)]`.
+    pub int: Option<Either<BuiltinInt, BuiltinUint>>,
+    /// The maximum alignment of the fields, 1 for `#[repr(packed)]`.
+    pub pack: Option<u64>,
+    /// The minimum alignment, from `#[repr(align(N))]`.
+    pub align: Option<u64>,
+}
+
+impl ReprOptions {
+    pub fn is_packed(&self) -> bool {
+        self.pack.is_some()
+    }
+
+    fn merge(&mut self, other: ReprOptions) {
+        self.c |= other.c;
+        self.transparent |= other.transparent;
+        self.int = other.int.or(self.int);
+        self.pack = match (self.pack, other.pack) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.align = self.align.max(other.align);
+    }
 }
 
 fn repr_from_value(
@@ -74,21 +100,55 @@ fn repr_from_value(
     krate: CrateId,
     item_tree: &ItemTree,
     of: AttrOwner,
-) -> Option<ReprKind> {
-    item_tree.attrs(db, krate, of).by_key("repr").tt_values().find_map(parse_repr_tt)
+) -> Option<ReprOptions> {
+    let mut res: Option<ReprOptions> = None;
+    for repr in item_tree.attrs(db, krate, of).by_key("repr").tt_values().filter_map(parse_repr_tt)
+    {
+        res.get_or_insert_with(Default::default).merge(repr);
+    }
+    res
 }
 
-fn parse_repr_tt(tt: &Subtree) -> Option<ReprKind> {
+fn parse_repr_tt(tt: &Subtree) -> Option<ReprOptions> {
     match tt.delimiter {
         Some(Delimiter { kind: DelimiterKind::Parenthesis, .. }) => {}
         _ => return None,
     }
 
-    let mut it = tt.token_trees.iter();
-    match it.next()? {
-        TokenTree::Leaf(Leaf::Ident(ident)) if ident.text == "packed" => Some(ReprKind::Packed),
-        _ => Some(ReprKind::Other),
+    let mut res = ReprOptions::default();
+    let mut tts = tt.token_trees.iter().peekable();
+    while let Some(tt) = tts.next() {
+        let ident = match tt {
+            TokenTree::Leaf(Leaf::Ident(ident)) => ident,
+            _ => continue,
+        };
+        // The argument of `packed(N)` and `align(N)`. Only powers of two are
+        // valid, anything else (like a zero) is ignored.
+        let arg = match tts.peek() {
+            Some(TokenTree::Subtree(arg)) => {
+                tts.next();
+                match arg.token_trees.first() {
+                    Some(TokenTree::Leaf(Leaf::Literal(lit))) => lit.text.parse::<u64>().ok(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+        .filter(|it| it.is_power_of_two());
+        match &*ident.text {
+            "C" => res.c = true,
+            "transparent" => res.transparent = true,
+            "packed" => res.pack = Some(arg.unwrap_or(1)),
+            "align" => res.align = arg,
+            int => {
+                res.int = BuiltinInt::from_suffix(int)
+                    .map(Either::Left)
+                    .or_else(|| BuiltinUint::from_suffix(int).map(Either::Right))
+                    .or(res.int)
+            }
+        }
     }
+    Some(res)
 }
 
 impl StructData {
@@ -132,6 +192,7 @@ impl EnumData {
         let loc = e.lookup(db);
         let krate = loc.container.krate;
         let item_tree = loc.id.item_tree(db);
+        let repr = repr_from_value(db, krate, &item_tree, ModItem::from(loc.id.value).into());
         let cfg_options = db.crate_graph()[krate].cfg_options.clone();
 
         let enum_ = &item_tree[loc.id.value];
@@ -158,6 +219,7 @@ impl EnumData {
         Arc::new(EnumData {
             name: enum_.name.clone(),
             variants,
+            repr,
             visibility: item_tree[enum_.visibility].clone(),
         })
     }
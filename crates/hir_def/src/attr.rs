@@ -6,7 +6,7 @@ use std::{
     sync::Arc,
 };
 
-use base_db::CrateId;
+use base_db::{CrateId, Edition};
 use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_expand::{hygiene::Hygiene, name::AsName, AstId, InFile};
@@ -31,6 +31,19 @@ use crate::{
     VariantId,
 };
 
+/// The contents of a `#[deprecated]` attribute.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Deprecation {
+    pub since: Option<SmolStr>,
+    pub note: Option<SmolStr>,
+}
+
+/// The contents of a `#[must_use]` attribute.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MustUse {
+    pub note: Option<SmolStr>,
+}
+
 /// Holds documentation
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Documentation(String);
@@ -255,6 +268,60 @@ impl Attrs {
         }
     }
 
+    /// Parses `#[deprecated]`, `#[deprecated = "note"]` and
+    /// `#[deprecated(since = "..", note = "..")]`, as well as the
+    /// `#[rustc_deprecated]` attribute used by the standard library.
+    pub fn deprecation(&self) -> Option<Deprecation> {
+        let attr = self
+            .by_key("deprecated")
+            .attrs()
+            .chain(self.by_key("rustc_deprecated").attrs())
+            .next()?;
+        let mut deprecation = Deprecation::default();
+        match attr.input.as_deref() {
+            None => {}
+            Some(AttrInput::Literal(note)) => deprecation.note = Some(note.clone()),
+            Some(AttrInput::TokenTree(args)) => {
+                let mut tts = args.token_trees.iter();
+                while let Some(tt) = tts.next() {
+                    let key = match tt {
+                        tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => &ident.text,
+                        _ => continue,
+                    };
+                    match (tts.next(), tts.next()) {
+                        (
+                            Some(tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct {
+                                char: '=', ..
+                            }))),
+                            Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))),
+                        ) => {
+                            let value = match unquote_str(lit) {
+                                Some(it) => it,
+                                None => continue,
+                            };
+                            match key.as_str() {
+                                "since" => deprecation.since = Some(value),
+                                "note" | "reason" => deprecation.note = Some(value),
+                                _ => {}
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+        Some(deprecation)
+    }
+
+    pub fn must_use(&self) -> Option<MustUse> {
+        let attr = self.by_key("must_use").attrs().next()?;
+        let note = match attr.input.as_deref() {
+            Some(AttrInput::Literal(note)) => Some(note.clone()),
+            _ => None,
+        };
+        Some(MustUse { note })
+    }
+
     pub fn docs(&self) -> Option<Documentation> {
         let docs = self.by_key("doc").attrs().flat_map(|attr| match attr.input.as_deref()? {
             AttrInput::Literal(s) => Some(s),
@@ -685,8 +752,13 @@ impl Attr {
         hygiene: &Hygiene,
         id: AttrId,
     ) -> Option<Attr> {
-        let (parse, _) =
-            mbe::token_tree_to_syntax_node(tt, hir_expand::FragmentKind::MetaItem).ok()?;
+        // Meta items contain no edition-dependent syntax.
+        let (parse, _) = mbe::token_tree_to_syntax_node(
+            tt,
+            hir_expand::FragmentKind::MetaItem,
+            Edition::CURRENT,
+        )
+        .ok()?;
         let ast = ast::Meta::cast(parse.syntax_node())?;
 
         Self::from_src(db, ast, hygiene, id)
@@ -773,6 +845,12 @@ impl<'a> AttrQuery<'a> {
     }
 }
 
+fn unquote_str(lit: &tt::Literal) -> Option<SmolStr> {
+    let lit = ast::make::tokens::literal(&lit.to_string());
+    let token = ast::String::cast(lit)?;
+    token.value().map(SmolStr::new)
+}
+
 fn attrs_from_ast<N>(src: AstId<N>, db: &dyn DefDatabase) -> RawAttrs
 where
     N: ast::AttrsOwner,
@@ -244,6 +244,7 @@ pub struct ConstData {
     pub name: Option<Name>,
     pub type_ref: Interned<TypeRef>,
     pub visibility: RawVisibility,
+    pub has_body: bool,
 }
 
 impl ConstData {
@@ -256,6 +257,7 @@ impl ConstData {
             name: konst.name.clone(),
             type_ref: konst.type_ref.clone(),
             visibility: item_tree[konst.visibility].clone(),
+            has_body: konst.has_body,
         })
     }
 }
@@ -5,6 +5,7 @@ use std::collections::hash_map::Entry;
 
 use base_db::CrateId;
 use hir_expand::{name::Name, AstId, MacroCallId, MacroDefKind};
+use la_arena::Idx;
 use once_cell::sync::Lazy;
 use profile::Count;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -12,14 +13,50 @@ use stdx::format_to;
 use syntax::ast;
 
 use crate::{
-    db::DefDatabase, per_ns::PerNs, visibility::Visibility, AdtId, BuiltinType, ConstId, ImplId,
-    LocalModuleId, MacroDefId, ModuleDefId, ModuleId, TraitId,
+    db::DefDatabase, item_tree, item_tree::ItemTreeId, per_ns::PerNs, visibility::Visibility,
+    AdtId, BuiltinType, ConstId, ImplId, LocalModuleId, MacroDefId, ModuleDefId, ModuleId, TraitId,
 };
 
 #[derive(Copy, Clone)]
 pub(crate) enum ImportType {
-    Glob,
-    Named,
+    /// A glob import of the scope of a module, or of the variants of an enum.
+    Glob(Option<ModuleId>),
+    /// A named import, or a declaration if there is no `use` item.
+    Named(Option<ImportId>),
+}
+
+impl ImportType {
+    fn origin(self) -> Option<ImportOrigin> {
+        match self {
+            ImportType::Glob(module) => module.map(ImportOrigin::Glob),
+            ImportType::Named(import) => import.map(ImportOrigin::Named),
+        }
+    }
+}
+
+/// A single import of a `use` item, like `b` in `use a::{b, c};`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ImportId {
+    pub import: ItemTreeId<item_tree::Import>,
+    pub use_tree: Idx<ast::UseTree>,
+}
+
+/// How an import brought a name into an `ItemScope`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImportOrigin {
+    /// The name was imported by a named import.
+    Named(ImportId),
+    /// The name was imported by a glob import of the scope of this module.
+    Glob(ModuleId),
+}
+
+/// The imports the names of an `ItemScope` come from, per namespace.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ImportOrigins {
+    types: FxHashMap<Name, ImportOrigin>,
+    values: FxHashMap<Name, ImportOrigin>,
+    macros: FxHashMap<Name, ImportOrigin>,
+    unnamed_traits: FxHashMap<TraitId, ImportOrigin>,
 }
 
 #[derive(Debug, Default)]
@@ -61,6 +98,12 @@ pub struct ItemScope {
     // be all resolved to the last one defined if shadowing happens.
     legacy_macros: FxHashMap<Name, MacroDefId>,
     attr_macros: FxHashMap<AstId<ast::Item>, MacroCallId>,
+
+    /// The imports that brought the imported names into this scope, to tell
+    /// which imports name resolution goes through.
+    import_origins: ImportOrigins,
+    /// The resolved `use` imports of this scope, including glob imports.
+    imports: FxHashSet<ImportId>,
 }
 
 pub(crate) static BUILTIN_SCOPE: Lazy<FxHashMap<Name, PerNs>> = Lazy::new(|| {
@@ -191,8 +234,69 @@ impl ItemScope {
         self.unnamed_trait_imports.get(&tr).copied()
     }
 
-    pub(crate) fn push_unnamed_trait(&mut self, tr: TraitId, vis: Visibility) {
+    pub(crate) fn push_unnamed_trait(&mut self, tr: TraitId, vis: Visibility, import: ImportType) {
         self.unnamed_trait_imports.insert(tr, vis);
+        match import.origin() {
+            Some(origin) => self.import_origins.unnamed_traits.insert(tr, origin),
+            None => self.import_origins.unnamed_traits.remove(&tr),
+        };
+    }
+
+    pub(crate) fn record_import(&mut self, import: ImportId) {
+        self.imports.insert(import);
+    }
+
+    /// The resolved `use` imports of this scope.
+    pub fn imports(&self) -> impl Iterator<Item = ImportId> + '_ {
+        self.imports.iter().copied()
+    }
+
+    /// The imports that brought `name` into this scope, in any namespace.
+    pub fn import_origins(&self, name: &Name) -> impl Iterator<Item = ImportOrigin> + '_ {
+        let origins = &self.import_origins;
+        let types = origins.types.get(name).into_iter();
+        types.chain(origins.values.get(name)).chain(origins.macros.get(name)).copied()
+    }
+
+    /// The imports that brought `tr` into this scope, by name or as `_`.
+    pub fn trait_import_origins(&self, tr: TraitId) -> impl Iterator<Item = ImportOrigin> + '_ {
+        let by_name = self.types.iter().filter_map(move |(name, &(def, _))| match def {
+            ModuleDefId::TraitId(it) if it == tr => self.import_origins.types.get(name).copied(),
+            _ => None,
+        });
+        by_name.chain(self.import_origins.unnamed_traits.get(&tr).copied())
+    }
+
+    /// The named imports of this scope, with what each of them brought into it.
+    pub fn named_imports(&self) -> FxHashMap<ImportId, PerNs> {
+        let mut res: FxHashMap<ImportId, PerNs> = FxHashMap::default();
+        let origins = &self.import_origins;
+        for (name, origin) in &origins.types {
+            if let ImportOrigin::Named(import) = origin {
+                res.entry(*import).or_insert_with(PerNs::none).types =
+                    self.types.get(name).copied();
+            }
+        }
+        for (name, origin) in &origins.values {
+            if let ImportOrigin::Named(import) = origin {
+                res.entry(*import).or_insert_with(PerNs::none).values =
+                    self.values.get(name).copied();
+            }
+        }
+        for (name, origin) in &origins.macros {
+            if let ImportOrigin::Named(import) = origin {
+                res.entry(*import).or_insert_with(PerNs::none).macros =
+                    self.macros.get(name).copied();
+            }
+        }
+        for (&tr, origin) in &origins.unnamed_traits {
+            if let (ImportOrigin::Named(import), Some(&vis)) =
+                (origin, self.unnamed_trait_imports.get(&tr))
+            {
+                res.entry(*import).or_insert_with(PerNs::none).types = Some((tr.into(), vis));
+            }
+        }
+        res
     }
 
     pub(crate) fn push_res_with_import(
@@ -212,13 +316,13 @@ impl ItemScope {
                 $def_import_type:ident
             ) => {{
                 let existing = $this.$field.entry($lookup.1.clone());
-                match (existing, $def.$field) {
+                let inserted = match (existing, $def.$field) {
                     (Entry::Vacant(entry), Some(_)) => {
                         match $def_import_type {
-                            ImportType::Glob => {
+                            ImportType::Glob(_) => {
                                 $glob_imports.$field.insert($lookup.clone());
                             }
-                            ImportType::Named => {
+                            ImportType::Named(_) => {
                                 $glob_imports.$field.remove(&$lookup);
                             }
                         }
@@ -227,10 +331,11 @@ impl ItemScope {
                             entry.insert(fld);
                         }
                         $changed = true;
+                        true
                     }
                     (Entry::Occupied(mut entry), Some(_))
                         if $glob_imports.$field.contains(&$lookup)
-                            && matches!($def_import_type, ImportType::Named) =>
+                            && matches!($def_import_type, ImportType::Named(_)) =>
                     {
                         cov_mark::hit!(import_shadowed);
                         $glob_imports.$field.remove(&$lookup);
@@ -238,8 +343,19 @@ impl ItemScope {
                             entry.insert(fld);
                         }
                         $changed = true;
+                        true
+                    }
+                    _ => false,
+                };
+                if inserted {
+                    match $def_import_type.origin() {
+                        Some(origin) => {
+                            $this.import_origins.$field.insert($lookup.1.clone(), origin);
+                        }
+                        None => {
+                            $this.import_origins.$field.remove(&$lookup.1);
+                        }
                     }
-                    _ => {}
                 }
             }};
         }
@@ -325,6 +441,8 @@ impl ItemScope {
             unnamed_trait_imports,
             legacy_macros,
             attr_macros,
+            import_origins,
+            imports,
         } = self;
         types.shrink_to_fit();
         values.shrink_to_fit();
@@ -336,6 +454,11 @@ impl ItemScope {
         unnamed_trait_imports.shrink_to_fit();
         legacy_macros.shrink_to_fit();
         attr_macros.shrink_to_fit();
+        import_origins.types.shrink_to_fit();
+        import_origins.values.shrink_to_fit();
+        import_origins.macros.shrink_to_fit();
+        import_origins.unnamed_traits.shrink_to_fit();
+        imports.shrink_to_fit();
     }
 }
 
@@ -641,6 +641,8 @@ pub struct Const {
     pub name: Option<Name>,
     pub visibility: RawVisibilityId,
     pub type_ref: Interned<TypeRef>,
+    /// Whether the const has a value, which only trait consts can lack.
+    pub has_body: bool,
     pub ast_id: FileAstId<ast::Const>,
 }
 
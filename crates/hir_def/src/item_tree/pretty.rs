@@ -315,7 +315,7 @@ impl<'a> Printer<'a> {
                 wln!(self, "}}");
             }
             ModItem::Const(it) => {
-                let Const { name, visibility, type_ref, ast_id: _ } = &self.tree[it];
+                let Const { name, visibility, type_ref, has_body: _, ast_id: _ } = &self.tree[it];
                 self.print_visibility(*visibility);
                 w!(self, "const ");
                 match name {
@@ -502,9 +502,12 @@ impl<'a> Printer<'a> {
                 self.print_type_ref(elem);
                 w!(self, "]");
             }
-            TypeRef::Fn(args_and_ret, varargs) => {
+            TypeRef::Fn(args_and_ret, varargs, bound_lifetimes) => {
                 let (ret, args) =
                     args_and_ret.split_last().expect("TypeRef::Fn is missing return type");
+                if !bound_lifetimes.is_empty() {
+                    w!(self, "for<{}> ", bound_lifetimes.iter().format(", "));
+                }
                 w!(self, "fn(");
                 for (i, arg) in args.iter().enumerate() {
                     if i != 0 {
@@ -283,6 +283,7 @@ struct S {
     b: <Fully as Qualified>::Syntax,
     c: <TypeAnchored>::Path::<'a>,
     d: dyn for<'a> Trait<'a>,
+    e: for<'a> fn(&'a u8) -> &'a u8,
 }
         "#,
         expect![[r#"
@@ -291,6 +292,7 @@ struct S {
                 pub(self) b: Qualified<Self=Fully>::Syntax,
                 pub(self) c: <TypeAnchored>::Path<'a>,
                 pub(self) d: dyn for<'a> Trait<'a>,
+                pub(self) e: for<'a> fn(&'a u8) -> &'a u8,
             }
         "#]],
     )
@@ -28,7 +28,7 @@ use crate::{
     db::DefDatabase,
     derive_macro_as_call_id,
     intern::Interned,
-    item_scope::{ImportType, PerNsGlobImports},
+    item_scope::{ImportId, ImportType, PerNsGlobImports},
     item_tree::{
         self, Fields, FileItemTreeId, ImportKind, ItemTree, ItemTreeId, MacroCall, MacroDef,
         MacroRules, Mod, ModItem, ModKind,
@@ -601,7 +601,7 @@ impl DefCollector<'_> {
                 self.def_map.root,
                 &[(Some(name), PerNs::macros(macro_, Visibility::Public))],
                 Visibility::Public,
-                ImportType::Named,
+                ImportType::Named(None),
             );
         }
     }
@@ -630,7 +630,12 @@ impl DefCollector<'_> {
     ) {
         let vis =
             self.def_map.resolve_visibility(self.db, module_id, vis).unwrap_or(Visibility::Public);
-        self.update(module_id, &[(Some(name), PerNs::macros(macro_, vis))], vis, ImportType::Named);
+        self.update(
+            module_id,
+            &[(Some(name), PerNs::macros(macro_, vis))],
+            vis,
+            ImportType::Named(None),
+        );
     }
 
     /// Define a proc macro
@@ -642,7 +647,7 @@ impl DefCollector<'_> {
             self.def_map.root,
             &[(Some(name), PerNs::macros(macro_, Visibility::Public))],
             Visibility::Public,
-            ImportType::Named,
+            ImportType::Named(None),
         );
     }
 
@@ -770,6 +775,14 @@ impl DefCollector<'_> {
             .def_map
             .resolve_visibility(self.db, module_id, &directive.import.visibility)
             .unwrap_or(Visibility::Public);
+        let import_id = match import.source {
+            ImportSource::Import { id, use_tree } => {
+                let import_id = ImportId { import: id, use_tree };
+                self.def_map.modules[module_id].scope.record_import(import_id);
+                Some(import_id)
+            }
+            ImportSource::ExternCrate(_) => None,
+        };
 
         match import.kind {
             ImportKind::Plain | ImportKind::TypeOnly => {
@@ -799,7 +812,7 @@ impl DefCollector<'_> {
                     }
                 }
 
-                self.update(module_id, &[(name, def)], vis, ImportType::Named);
+                self.update(module_id, &[(name, def)], vis, ImportType::Named(import_id));
             }
             ImportKind::Glob => {
                 log::debug!("glob import: {:?}", import);
@@ -826,7 +839,7 @@ impl DefCollector<'_> {
                                 .filter(|(_, res)| !res.is_none())
                                 .collect::<Vec<_>>();
 
-                            self.update(module_id, &items, vis, ImportType::Glob);
+                            self.update(module_id, &items, vis, ImportType::Glob(Some(m)));
                         } else {
                             // glob import from same crate => we do an initial
                             // import, and then need to propagate any further
@@ -858,7 +871,7 @@ impl DefCollector<'_> {
                                 .filter(|(_, res)| !res.is_none())
                                 .collect::<Vec<_>>();
 
-                            self.update(module_id, &items, vis, ImportType::Glob);
+                            self.update(module_id, &items, vis, ImportType::Glob(Some(m)));
                             // record the glob import in case we add further items
                             let glob = self.glob_imports.entry(m.local_id).or_default();
                             if !glob.iter().any(|(mid, _)| *mid == module_id) {
@@ -888,7 +901,7 @@ impl DefCollector<'_> {
                                 (Some(name), res)
                             })
                             .collect::<Vec<_>>();
-                        self.update(module_id, &resolutions, vis, ImportType::Glob);
+                        self.update(module_id, &resolutions, vis, ImportType::Glob(None));
                     }
                     Some(d) => {
                         log::debug!("glob import {:?} from non-module/enum {:?}", import, d);
@@ -967,7 +980,11 @@ impl DefCollector<'_> {
 
                     if should_update {
                         changed = true;
-                        self.def_map.modules[module_id].scope.push_unnamed_trait(tr, vis);
+                        self.def_map.modules[module_id].scope.push_unnamed_trait(
+                            tr,
+                            vis,
+                            import_type,
+                        );
                     }
                 }
             }
@@ -989,12 +1006,13 @@ impl DefCollector<'_> {
             .cloned()
             .collect::<Vec<_>>();
 
+        let glob_origin = self.def_map.module_id(module_id);
         for (glob_importing_module, glob_import_vis) in glob_imports {
             self.update_recursive(
                 glob_importing_module,
                 resolutions,
                 glob_import_vis,
-                ImportType::Glob,
+                ImportType::Glob(Some(glob_origin)),
                 depth + 1,
             );
         }
@@ -1526,7 +1544,7 @@ impl ModCollector<'_, '_> {
                     self.module_id,
                     &[(Some(name.clone()), PerNs::from_def(id, vis, has_constructor))],
                     vis,
-                    ImportType::Named,
+                    ImportType::Named(None),
                 )
             }
         }
@@ -1642,7 +1660,7 @@ impl ModCollector<'_, '_> {
             self.module_id,
             &[(Some(name), PerNs::from_def(def, vis, false))],
             vis,
-            ImportType::Named,
+            ImportType::Named(None),
         );
         res
     }
@@ -15,7 +15,7 @@ use crate::{
     expr::{ExprId, LabelId, PatId},
     generics::GenericParams,
     intern::Interned,
-    item_scope::{BuiltinShadowMode, BUILTIN_SCOPE},
+    item_scope::{BuiltinShadowMode, ImportId, ImportOrigin, ItemScope, BUILTIN_SCOPE},
     nameres::DefMap,
     path::{ModPath, PathKind},
     per_ns::PerNs,
@@ -370,6 +370,90 @@ impl Resolver {
         traits
     }
 
+    /// Calls `cb` with the named imports that name resolution goes through to
+    /// resolve the module path `path`.
+    pub fn imports_for_path(
+        &self,
+        db: &dyn DefDatabase,
+        path: &ModPath,
+        cb: &mut dyn FnMut(ImportId),
+    ) {
+        let (def_map, module) = match self.module_scope() {
+            Some(it) => it,
+            None => return,
+        };
+        let segments = path.segments();
+        let first_name = match segments.first() {
+            Some(it) => it,
+            None => return,
+        };
+        let origins_in = |name: &Name| {
+            let name = name.clone();
+            move |scope: &ItemScope| scope.import_origins(&name).collect::<Vec<_>>()
+        };
+        match path.kind {
+            PathKind::Plain => {
+                let origins_in = origins_in(first_name);
+                def_map.with_ancestor_maps(db, module, &mut |def_map, module| {
+                    let scope = &def_map[module].scope;
+                    if scope.get(first_name).is_none() {
+                        return None;
+                    }
+                    named_imports_behind(db, origins_in(scope), &origins_in, cb);
+                    Some(())
+                });
+            }
+            PathKind::Super(_) | PathKind::Crate => {
+                let prefix = ModPath::from_segments(path.kind.clone(), None);
+                if let Some(ModuleDefId::ModuleId(m)) =
+                    self.resolve_module_path_in_items(db, &prefix).take_types()
+                {
+                    let origins_in = origins_in(first_name);
+                    let def_map = m.def_map(db);
+                    named_imports_behind(
+                        db,
+                        origins_in(&def_map[m.local_id].scope),
+                        &origins_in,
+                        cb,
+                    );
+                }
+            }
+            PathKind::Abs | PathKind::DollarCrate(_) => {}
+        }
+
+        for (idx, name) in segments.iter().enumerate().skip(1) {
+            let prefix = ModPath::from_segments(path.kind.clone(), segments[..idx].iter().cloned());
+            let m = match self.resolve_module_path_in_items(db, &prefix).take_types() {
+                Some(ModuleDefId::ModuleId(it)) => it,
+                _ => break,
+            };
+            if m.krate != def_map.krate() {
+                break;
+            }
+            let origins_in = origins_in(name);
+            let def_map = m.def_map(db);
+            named_imports_behind(db, origins_in(&def_map[m.local_id].scope), &origins_in, cb);
+        }
+    }
+
+    /// Calls `cb` with the named imports that bring `trait_` into scope.
+    pub fn imports_for_trait(
+        &self,
+        db: &dyn DefDatabase,
+        trait_: TraitId,
+        cb: &mut dyn FnMut(ImportId),
+    ) {
+        let (def_map, module) = match self.module_scope() {
+            Some(it) => it,
+            None => return,
+        };
+        let origins_in = |scope: &ItemScope| scope.trait_import_origins(trait_).collect::<Vec<_>>();
+        def_map.with_ancestor_maps(db, module, &mut |def_map, module| {
+            named_imports_behind(db, origins_in(&def_map[module].scope), &origins_in, cb);
+            None::<()>
+        });
+    }
+
     fn module_scope(&self) -> Option<(&DefMap, LocalModuleId)> {
         self.scopes.iter().rev().find_map(|scope| match scope {
             Scope::ModuleScope(m) => Some((&*m.def_map, m.module_id)),
@@ -495,6 +579,29 @@ impl Scope {
     }
 }
 
+/// Calls `cb` with the named imports behind `origins`, following glob imports
+/// back to the scopes they import from.
+fn named_imports_behind(
+    db: &dyn DefDatabase,
+    origins: Vec<ImportOrigin>,
+    origins_in: &dyn Fn(&ItemScope) -> Vec<ImportOrigin>,
+    cb: &mut dyn FnMut(ImportId),
+) {
+    let mut stack = origins;
+    let mut seen = FxHashSet::default();
+    while let Some(origin) = stack.pop() {
+        match origin {
+            ImportOrigin::Named(import) => cb(import),
+            ImportOrigin::Glob(module) => {
+                if seen.insert(module) {
+                    let def_map = module.def_map(db);
+                    stack.extend(origins_in(&def_map[module.local_id].scope));
+                }
+            }
+        }
+    }
+}
+
 // needs arbitrary_self_types to be a method... or maybe move to the def?
 pub fn resolver_for_expr(db: &dyn DefDatabase, owner: DefWithBodyId, expr_id: ExprId) -> Resolver {
     let scopes = db.expr_scopes(owner);
@@ -88,8 +88,9 @@ pub enum TypeRef {
     // expression that is further lowered later in hir_ty.
     Array(Box<TypeRef>, ConstScalar),
     Slice(Box<TypeRef>),
-    /// A fn pointer. Last element of the vector is the return type.
-    Fn(Vec<TypeRef>, bool /*varargs*/),
+    /// A fn pointer. Last element of the vector is the return type, the
+    /// lifetimes are the ones bound by a `for<'a>` binder.
+    Fn(Vec<TypeRef>, bool /*varargs*/, Box<[Name]>),
     // For
     ImplTrait(Vec<Interned<TypeBound>>),
     DynTrait(Vec<Interned<TypeBound>>),
@@ -185,10 +186,17 @@ impl TypeRef {
                     Vec::new()
                 };
                 params.push(ret_ty);
-                TypeRef::Fn(params, is_varargs)
+                TypeRef::Fn(params, is_varargs, Box::default())
+            }
+            ast::Type::ForType(inner) => {
+                let mut ty = TypeRef::from_ast_opt(ctx, inner.ty());
+                // Only fn pointers can be higher-ranked, for other types the
+                // inner type is close enough for our purposes.
+                if let TypeRef::Fn(_, _, bound_lifetimes) = &mut ty {
+                    *bound_lifetimes = lifetimes_from_generic_params(inner.generic_param_list());
+                }
+                ty
             }
-            // for types are close enough for our purposes to the inner type for now...
-            ast::Type::ForType(inner) => TypeRef::from_ast_opt(ctx, inner.ty()),
             ast::Type::ImplTraitType(inner) => {
                 TypeRef::ImplTrait(type_bounds_from_ast(ctx, inner.type_bound_list()))
             }
@@ -223,7 +231,7 @@ impl TypeRef {
         fn go(type_ref: &TypeRef, f: &mut impl FnMut(&TypeRef)) {
             f(type_ref);
             match type_ref {
-                TypeRef::Fn(types, _) | TypeRef::Tuple(types) => {
+                TypeRef::Fn(types, ..) | TypeRef::Tuple(types) => {
                     types.iter().for_each(|t| go(t, f))
                 }
                 TypeRef::RawPtr(type_ref, _)
@@ -298,13 +306,7 @@ impl TypeBound {
                 lower_path_type(path_type).map(TypeBound::Path).unwrap_or(TypeBound::Error)
             }
             ast::TypeBoundKind::ForType(for_type) => {
-                let lt_refs = match for_type.generic_param_list() {
-                    Some(gpl) => gpl
-                        .lifetime_params()
-                        .flat_map(|lp| lp.lifetime().map(|lt| Name::new_lifetime(&lt)))
-                        .collect(),
-                    None => Box::default(),
-                };
+                let lt_refs = lifetimes_from_generic_params(for_type.generic_param_list());
                 let path = for_type.ty().and_then(|ty| match ty {
                     ast::Type::PathType(path_type) => lower_path_type(path_type),
                     _ => None,
@@ -382,3 +384,13 @@ impl ConstScalar {
         .unwrap_or(ConstScalar::Unknown)
     }
 }
+
+fn lifetimes_from_generic_params(params: Option<ast::GenericParamList>) -> Box<[Name]> {
+    match params {
+        Some(gpl) => gpl
+            .lifetime_params()
+            .flat_map(|lp| lp.lifetime().map(|lt| Name::new_lifetime(&lt)))
+            .collect(),
+        None => Box::default(),
+    }
+}
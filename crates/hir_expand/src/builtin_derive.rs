@@ -5,7 +5,7 @@ use log::debug;
 use parser::FragmentKind;
 use syntax::{
     ast::{self, AstNode, GenericParamsOwner, ModuleItemOwner, NameOwner},
-    match_ast,
+    match_ast, Edition,
 };
 
 use crate::{db::AstDatabase, name, quote, AstId, CrateId, MacroCallId, MacroDefId, MacroDefKind};
@@ -71,8 +71,8 @@ struct BasicAdtInfo {
     type_params: usize,
 }
 
-fn parse_adt(tt: &tt::Subtree) -> Result<BasicAdtInfo, mbe::ExpandError> {
-    let (parsed, token_map) = mbe::token_tree_to_syntax_node(tt, FragmentKind::Items)?; // FragmentKind::Items doesn't parse attrs?
+fn parse_adt(tt: &tt::Subtree, edition: Edition) -> Result<BasicAdtInfo, mbe::ExpandError> {
+    let (parsed, token_map) = mbe::token_tree_to_syntax_node(tt, FragmentKind::Items, edition)?; // FragmentKind::Items doesn't parse attrs?
     let macro_items = ast::MacroItems::cast(parsed.syntax_node()).ok_or_else(|| {
         debug!("derive node didn't parse");
         mbe::ExpandError::UnexpectedToken
@@ -148,10 +148,12 @@ fn make_type_args(n: usize, bound: Vec<tt::TokenTree>) -> Vec<tt::TokenTree> {
 }
 
 fn expand_simple_derive(
+    db: &dyn AstDatabase,
+    id: MacroCallId,
     tt: &tt::Subtree,
     trait_path: tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
-    let info = parse_adt(tt)?;
+    let info = parse_adt(tt, id.edition(db))?;
     let name = info.name;
     let trait_path_clone = trait_path.token_trees.clone();
     let bound = (quote! { : ##trait_path_clone }).token_trees;
@@ -188,7 +190,7 @@ fn copy_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::marker::Copy })
+    expand_simple_derive(db, id, tt, quote! { #krate::marker::Copy })
 }
 
 fn clone_expand(
@@ -197,7 +199,7 @@ fn clone_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::clone::Clone })
+    expand_simple_derive(db, id, tt, quote! { #krate::clone::Clone })
 }
 
 fn default_expand(
@@ -206,7 +208,7 @@ fn default_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::default::Default })
+    expand_simple_derive(db, id, tt, quote! { #krate::default::Default })
 }
 
 fn debug_expand(
@@ -215,7 +217,7 @@ fn debug_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::fmt::Debug })
+    expand_simple_derive(db, id, tt, quote! { #krate::fmt::Debug })
 }
 
 fn hash_expand(
@@ -224,7 +226,7 @@ fn hash_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::hash::Hash })
+    expand_simple_derive(db, id, tt, quote! { #krate::hash::Hash })
 }
 
 fn eq_expand(
@@ -233,7 +235,7 @@ fn eq_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::Eq })
+    expand_simple_derive(db, id, tt, quote! { #krate::cmp::Eq })
 }
 
 fn partial_eq_expand(
@@ -242,7 +244,7 @@ fn partial_eq_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::PartialEq })
+    expand_simple_derive(db, id, tt, quote! { #krate::cmp::PartialEq })
 }
 
 fn ord_expand(
@@ -251,7 +253,7 @@ fn ord_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::Ord })
+    expand_simple_derive(db, id, tt, quote! { #krate::cmp::Ord })
 }
 
 fn partial_ord_expand(
@@ -260,7 +262,7 @@ fn partial_ord_expand(
     tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::PartialOrd })
+    expand_simple_derive(db, id, tt, quote! { #krate::cmp::PartialOrd })
 }
 
 #[cfg(test)]
@@ -404,6 +404,7 @@ fn relative_file(
     let path = AnchoredPath { anchor: call_site, path: path_str };
     let res = db
         .resolve_path(path)
+        .or_else(|| resolve_in_dependencies(db, call_id, path_str))
         .ok_or_else(|| mbe::ExpandError::Other(format!("failed to load file `{}`", path_str)))?;
     // Prevent include itself
     if res == call_site && !allow_recursion {
@@ -413,6 +414,24 @@ fn relative_file(
     }
 }
 
+/// Resolves an absolute `path_str`, like a file generated into the `OUT_DIR` of
+/// a dependency, in the source roots of the dependencies of the calling crate.
+fn resolve_in_dependencies(
+    db: &dyn AstDatabase,
+    call_id: MacroCallId,
+    path_str: &str,
+) -> Option<FileId> {
+    if !path_str.starts_with('/') && !std::path::Path::new(path_str).is_absolute() {
+        return None;
+    }
+    let krate = db.lookup_intern_macro(call_id).krate;
+    let graph = db.crate_graph();
+    graph[krate].dependencies.iter().find_map(|dep| {
+        let anchor = graph[dep.crate_id].root_file_id;
+        db.resolve_path(AnchoredPath { anchor, path: path_str })
+    })
+}
+
 fn parse_string(tt: &tt::Subtree) -> Result<String, mbe::ExpandError> {
     tt.token_trees
         .get(0)
@@ -475,10 +494,8 @@ fn include_str_expand(
         Err(e) => return ExpandResult::only_err(e),
     };
 
-    // FIXME: we're not able to read excluded files (which is most of them because
-    // it's unusual to `include_str!` a Rust file), but we can return an empty string.
-    // Ideally, we'd be able to offer a precise expansion if the user asks for macro
-    // expansion.
+    // Only Rust files are loaded, so most files (it's unusual to `include_str!` a
+    // Rust file) can't be read. Return an empty string for them instead.
     let file_id = match relative_file(db, arg_id, &path, true) {
         Ok(file_id) => file_id,
         Err(_) => {
@@ -156,8 +156,12 @@ pub fn expand_speculative(
 
     let fragment_kind = macro_fragment_kind(db, actual_macro_call);
 
-    let (node, tmap_2) =
-        mbe::token_tree_to_syntax_node(&speculative_expansion.value, fragment_kind).ok()?;
+    let (node, tmap_2) = mbe::token_tree_to_syntax_node(
+        &speculative_expansion.value,
+        fragment_kind,
+        actual_macro_call.edition(db),
+    )
+    .ok()?;
 
     let token_id = macro_def.map_id_down(token_id);
     let range = tmap_2.range_by_token(token_id, token_to_map.kind())?;
@@ -217,7 +221,8 @@ fn parse_macro_expansion(
     log::debug!("expanded = {}", tt.as_debug_string());
     log::debug!("kind = {:?}", fragment_kind);
 
-    let (parse, rev_token_map) = match mbe::token_tree_to_syntax_node(&tt, fragment_kind) {
+    let edition = macro_file.macro_call_id.edition(db);
+    let (parse, rev_token_map) = match mbe::token_tree_to_syntax_node(&tt, fragment_kind, edition) {
         Ok(it) => it,
         Err(err) => {
             log::debug!(
@@ -274,12 +279,13 @@ fn macro_arg_text(db: &dyn AstDatabase, id: MacroCallId) -> Option<GreenNode> {
 }
 
 fn macro_def(db: &dyn AstDatabase, id: MacroDefId) -> Option<Arc<TokenExpander>> {
+    let edition = db.crate_graph()[id.krate].edition;
     match id.kind {
         MacroDefKind::Declarative(ast_id) => match ast_id.to_node(db) {
             ast::Macro::MacroRules(macro_rules) => {
                 let arg = macro_rules.token_tree()?;
                 let (tt, def_site_token_map) = mbe::ast_to_token_tree(&arg);
-                let mac = match mbe::MacroRules::parse(&tt) {
+                let mac = match mbe::MacroRules::parse(&tt, edition) {
                     Ok(it) => it,
                     Err(err) => {
                         let name = macro_rules.name().map(|n| n.to_string()).unwrap_or_default();
@@ -292,7 +298,7 @@ fn macro_def(db: &dyn AstDatabase, id: MacroDefId) -> Option<Arc<TokenExpander>>
             ast::Macro::MacroDef(macro_def) => {
                 let arg = macro_def.body()?;
                 let (tt, def_site_token_map) = mbe::ast_to_token_tree(&arg);
-                let mac = match mbe::MacroDef::parse(&tt) {
+                let mac = match mbe::MacroDef::parse(&tt, edition) {
                     Ok(it) => it,
                     Err(err) => {
                         let name = macro_def.name().map(|n| n.to_string()).unwrap_or_default();
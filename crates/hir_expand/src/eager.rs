@@ -130,8 +130,10 @@ pub fn expand_eager_macro(
     });
     let arg_file_id = arg_id;
 
-    let parsed_args =
-        diagnostic_sink.result(mbe::token_tree_to_syntax_node(&parsed_args, FragmentKind::Expr))?.0;
+    let edition = db.crate_graph()[krate].edition;
+    let parsed_args = diagnostic_sink
+        .result(mbe::token_tree_to_syntax_node(&parsed_args, FragmentKind::Expr, edition))?
+        .0;
     let result = eager_macro_recur(
         db,
         InFile::new(arg_file_id.as_file(), parsed_args.syntax_node()),
@@ -25,7 +25,7 @@ pub use parser::FragmentKind;
 use std::hash::Hash;
 use std::sync::Arc;
 
-use base_db::{impl_intern_key, salsa, CrateId, FileId, FileRange};
+use base_db::{impl_intern_key, salsa, CrateId, Edition, FileId, FileRange};
 use syntax::{
     algo::skip_trivia_token,
     ast::{self, AstNode},
@@ -323,6 +323,13 @@ impl MacroCallId {
     pub fn as_file(self) -> HirFileId {
         MacroFile { macro_call_id: self }.into()
     }
+
+    /// The edition of the crate this macro is called from, which is used to
+    /// parse the macro's expansion.
+    pub(crate) fn edition(self, db: &dyn db::AstDatabase) -> Edition {
+        let krate = db.lookup_intern_macro(self).krate;
+        db.crate_graph()[krate].edition
+    }
 }
 
 /// ExpansionInfo mainly describes how to map text range between src and expanded macro
@@ -3,6 +3,7 @@ mod expr;
 mod match_check;
 mod unsafe_check;
 mod decl_check;
+mod unused_imports;
 
 use std::fmt;
 
@@ -18,6 +19,7 @@ pub use crate::diagnostics::{
         record_literal_missing_fields, record_pattern_missing_fields, BodyValidationDiagnostic,
     },
     unsafe_check::missing_unsafe,
+    unused_imports::unused_imports,
 };
 
 pub fn validate_module_item(
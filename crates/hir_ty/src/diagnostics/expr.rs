@@ -17,6 +17,7 @@ use crate::{
         self,
         usefulness::{compute_match_usefulness, expand_pattern, MatchCheckCtx, PatternArena},
     },
+    mir::{self, BorrowckError, MirSpan},
     AdtId, InferenceResult, Interner, TyExt, TyKind,
 };
 
@@ -50,6 +51,19 @@ pub enum BodyValidationDiagnostic {
     MissingMatchArms {
         match_expr: ExprId,
     },
+    UseOfMovedValue {
+        node: Either<ExprId, PatId>,
+        binding: PatId,
+        is_borrow: bool,
+    },
+    PossiblyUninitialized {
+        node: Either<ExprId, PatId>,
+        binding: PatId,
+        is_borrow: bool,
+    },
+    UnreachableCode {
+        expr: ExprId,
+    },
 }
 
 impl BodyValidationDiagnostic {
@@ -118,6 +132,68 @@ impl ExprValidator {
                 self.validate_missing_tail_expr(body.body_expr, *id);
             }
         }
+        self.validate_control_flow(db, &body);
+    }
+
+    /// Reports the diagnostics that depend on the order in which things
+    /// happen, using the MIR of the body. Bodies that can't be lowered, like
+    /// ones with syntax errors, are skipped.
+    fn validate_control_flow(&mut self, db: &dyn HirDatabase, body: &Body) {
+        let mir = match db.mir_body(self.owner) {
+            Ok(it) => it,
+            Err(_) => return,
+        };
+
+        // Type mismatches hint at types that were inferred wrongly, which
+        // could make us believe something is moved when it's copied.
+        let has_type_mismatches = self.infer.expr_type_mismatches().next().is_some()
+            || self.infer.pat_type_mismatches().next().is_some();
+        let borrowck_errors = if has_type_mismatches { Vec::new() } else { mir::borrowck(&mir) };
+        for error in borrowck_errors {
+            let (span, binding, is_borrow, moved) = match error {
+                BorrowckError::UseOfMovedValue { span, binding, is_borrow } => {
+                    (span, binding, is_borrow, true)
+                }
+                BorrowckError::PossiblyUninitialized { span, binding, is_borrow } => {
+                    (span, binding, is_borrow, false)
+                }
+            };
+            let node = match span {
+                MirSpan::ExprId(it) => Either::Left(it),
+                MirSpan::PatId(it) => Either::Right(it),
+                MirSpan::Unknown => continue,
+            };
+            self.diagnostics.push(if moved {
+                BodyValidationDiagnostic::UseOfMovedValue { node, binding, is_borrow }
+            } else {
+                BodyValidationDiagnostic::PossiblyUninitialized { node, binding, is_borrow }
+            });
+        }
+
+        // Only the first unreachable statement of each block is reported.
+        let reachable = mir.reachable_blocks();
+        let is_reachable = |expr| mir.expr_blocks.get(expr).map(|it| reachable.contains(it));
+        for (id, expr) in body.exprs.iter() {
+            let (statements, tail) = match expr {
+                Expr::Block { statements, tail, .. } if is_reachable(id) == Some(true) => {
+                    (statements, tail)
+                }
+                _ => continue,
+            };
+            let exprs = statements
+                .iter()
+                .filter_map(|it| match it {
+                    Statement::Let { initializer, .. } => *initializer,
+                    Statement::Expr { expr, .. } => Some(*expr),
+                })
+                .chain(*tail);
+            for expr in exprs {
+                if is_reachable(expr) == Some(false) {
+                    self.diagnostics.push(BodyValidationDiagnostic::UnreachableCode { expr });
+                    break;
+                }
+            }
+        }
     }
 
     fn check_for_filter_map_next(&mut self, db: &dyn HirDatabase) {
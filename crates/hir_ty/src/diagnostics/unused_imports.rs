@@ -0,0 +1,430 @@
+//! Finds the named imports of a module that name resolution never goes
+//! through.
+//!
+//! This walks the signatures and bodies of the items of the module and of its
+//! descendants, which can use the imports of the module through `super::` paths
+//! and glob imports, and records the imports the paths resolve through, as well
+//! as the ones bringing the traits of resolved methods and associated items
+//! into scope. Bodies include the expansions of macro calls, so uses inside of
+//! them are seen as well.
+//!
+//! Imports that can't be proven unused are never reported: the ones of macros,
+//! whose uses aren't tracked, and all of them if the module has a macro call
+//! that failed to expand.
+
+use hir_def::{
+    body::BodyDiagnostic,
+    expr::{Expr, Pat, PatId, Statement},
+    generics::{WherePredicate, WherePredicateTypeTarget},
+    item_scope::ImportId,
+    nameres::diagnostics::DefDiagnosticKind,
+    path::{GenericArg, GenericArgs, ModPath, Path, PathKind},
+    resolver::{resolver_for_expr, HasResolver, Resolver},
+    type_ref::{TypeBound, TypeRef},
+    AdtId, AssocContainerId, AssocItemId, DefWithBodyId, GenericDefId, ImplId, Lookup, ModuleDefId,
+    ModuleId, TraitId, VariantId,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{db::HirDatabase, from_chalk_trait_id};
+
+/// Returns the private named imports of `module`, and of the block scopes in
+/// its items, that name resolution never goes through.
+pub fn unused_imports(db: &dyn HirDatabase, module: ModuleId) -> Vec<ImportId> {
+    let _p = profile::span("unused_imports");
+    let mut collector = UsedImports {
+        db,
+        candidates: Vec::new(),
+        used: FxHashSet::default(),
+        has_unexpanded_macros: false,
+    };
+    collector.walk_module(module, true);
+    if collector.has_unexpanded_macros {
+        return Vec::new();
+    }
+    let UsedImports { candidates, used, .. } = collector;
+    candidates.into_iter().filter(|it| !used.contains(it)).collect()
+}
+
+struct UsedImports<'a> {
+    db: &'a dyn HirDatabase,
+    /// The imports that could be unused, from the scopes of the module.
+    candidates: Vec<ImportId>,
+    used: FxHashSet<ImportId>,
+    /// Whether the module has macro calls that failed to expand, which could
+    /// use any import.
+    has_unexpanded_macros: bool,
+}
+
+impl UsedImports<'_> {
+    /// Walks the items of `module`. The imports of the modules the walk starts
+    /// from are candidates, while the ones of descendants only count as uses.
+    fn walk_module(&mut self, module: ModuleId, is_candidate: bool) {
+        let db = self.db;
+        let def_map = module.def_map(db.upcast());
+        let scope = &def_map[module.local_id].scope;
+        if is_candidate {
+            let has_unexpanded_macros = def_map.diagnostics().iter().any(|it| {
+                it.in_module == module.local_id
+                    && matches!(
+                        it.kind,
+                        DefDiagnosticKind::UnresolvedMacroCall { .. }
+                            | DefDiagnosticKind::UnresolvedProcMacro { .. }
+                            | DefDiagnosticKind::MacroError { .. }
+                    )
+            });
+            self.has_unexpanded_macros |= has_unexpanded_macros;
+
+            let private = hir_def::visibility::Visibility::Module(module);
+            for (import, def) in scope.named_imports() {
+                // FIXME: track the uses of macros as well.
+                if def.macros.is_some() {
+                    continue;
+                }
+                let is_private =
+                    [def.types, def.values].iter().flatten().all(|&(_, vis)| vis == private);
+                if is_private {
+                    self.candidates.push(import);
+                }
+            }
+        }
+
+        // `use super::Item;` and the like go through other scopes.
+        let resolver = module.resolver(db.upcast());
+        for import in scope.imports() {
+            let tree = import.import.item_tree(db.upcast());
+            tree[import.import.value].use_tree.expand(|idx, path, _, _| {
+                if idx == import.use_tree {
+                    self.walk_mod_path(&resolver, &path);
+                }
+            });
+        }
+
+        for def in scope.declarations() {
+            match def {
+                ModuleDefId::ModuleId(child) => self.walk_module(child, false),
+                ModuleDefId::FunctionId(it) => {
+                    let data = db.function_data(it);
+                    let resolver = it.resolver(db.upcast());
+                    self.walk_generics(&resolver, it.into());
+                    for type_ref in data.params.iter().chain(Some(&data.ret_type)) {
+                        self.walk_type_ref(&resolver, type_ref);
+                    }
+                    self.walk_body(it.into(), is_candidate);
+                }
+                ModuleDefId::AdtId(it) => {
+                    let resolver = it.resolver(db.upcast());
+                    self.walk_generics(&resolver, it.into());
+                    match it {
+                        AdtId::StructId(it) => self.walk_fields(&resolver, it.into()),
+                        AdtId::UnionId(it) => self.walk_fields(&resolver, it.into()),
+                        AdtId::EnumId(it) => {
+                            for (local_id, _) in db.enum_data(it).variants.iter() {
+                                let variant = hir_def::EnumVariantId { parent: it, local_id };
+                                self.walk_fields(&resolver, variant.into());
+                            }
+                        }
+                    }
+                }
+                ModuleDefId::ConstId(it) => {
+                    let resolver = it.resolver(db.upcast());
+                    self.walk_type_ref(&resolver, &db.const_data(it).type_ref);
+                    self.walk_body(it.into(), is_candidate);
+                }
+                ModuleDefId::StaticId(it) => {
+                    let resolver = it.resolver(db.upcast());
+                    self.walk_type_ref(&resolver, &db.static_data(it).type_ref);
+                    self.walk_body(it.into(), is_candidate);
+                }
+                ModuleDefId::TraitId(it) => {
+                    let resolver = it.resolver(db.upcast());
+                    self.walk_generics(&resolver, it.into());
+                    for &(_, item) in db.trait_data(it).items.iter() {
+                        self.walk_assoc_item(item, is_candidate);
+                    }
+                }
+                ModuleDefId::TypeAliasId(it) => self.walk_assoc_item(it.into(), is_candidate),
+                ModuleDefId::EnumVariantId(_) | ModuleDefId::BuiltinType(_) => {}
+            }
+        }
+        for it in scope.impls() {
+            self.walk_impl(it, is_candidate);
+        }
+        for it in scope.unnamed_consts() {
+            let resolver = it.resolver(db.upcast());
+            self.walk_type_ref(&resolver, &db.const_data(it).type_ref);
+            self.walk_body(it.into(), is_candidate);
+        }
+    }
+
+    fn walk_impl(&mut self, impl_: ImplId, is_candidate: bool) {
+        let db = self.db;
+        let data = db.impl_data(impl_);
+        let resolver = impl_.resolver(db.upcast());
+        self.walk_generics(&resolver, impl_.into());
+        self.walk_type_ref(&resolver, &data.self_ty);
+        if let Some(trait_ref) = &data.target_trait {
+            self.walk_path(&resolver, &trait_ref.path);
+        }
+        for &item in data.items.iter() {
+            self.walk_assoc_item(item, is_candidate);
+        }
+    }
+
+    fn walk_assoc_item(&mut self, item: AssocItemId, is_candidate: bool) {
+        let db = self.db;
+        match item {
+            AssocItemId::FunctionId(it) => {
+                let data = db.function_data(it);
+                let resolver = it.resolver(db.upcast());
+                self.walk_generics(&resolver, it.into());
+                for type_ref in data.params.iter().chain(Some(&data.ret_type)) {
+                    self.walk_type_ref(&resolver, type_ref);
+                }
+                self.walk_body(it.into(), is_candidate);
+            }
+            AssocItemId::ConstId(it) => {
+                let resolver = it.resolver(db.upcast());
+                self.walk_type_ref(&resolver, &db.const_data(it).type_ref);
+                self.walk_body(it.into(), is_candidate);
+            }
+            AssocItemId::TypeAliasId(it) => {
+                let data = db.type_alias_data(it);
+                let resolver = it.resolver(db.upcast());
+                self.walk_generics(&resolver, it.into());
+                if let Some(type_ref) = &data.type_ref {
+                    self.walk_type_ref(&resolver, type_ref);
+                }
+                for bound in data.bounds.iter() {
+                    self.walk_type_bound(&resolver, bound);
+                }
+            }
+        }
+    }
+
+    fn walk_fields(&mut self, resolver: &Resolver, variant: VariantId) {
+        let data = variant.variant_data(self.db.upcast());
+        for (_, field) in data.fields().iter() {
+            self.walk_type_ref(resolver, &field.type_ref);
+        }
+    }
+
+    fn walk_generics(&mut self, resolver: &Resolver, def: GenericDefId) {
+        let params = self.db.generic_params(def);
+        for (_, param) in params.types.iter() {
+            if let Some(default) = &param.default {
+                self.walk_type_ref(resolver, default);
+            }
+        }
+        for (_, param) in params.consts.iter() {
+            self.walk_type_ref(resolver, &param.ty);
+        }
+        for predicate in params.where_predicates.iter() {
+            match predicate {
+                WherePredicate::TypeBound { target, bound }
+                | WherePredicate::ForLifetime { target, bound, .. } => {
+                    if let WherePredicateTypeTarget::TypeRef(type_ref) = target {
+                        self.walk_type_ref(resolver, type_ref);
+                    }
+                    self.walk_type_bound(resolver, bound);
+                }
+                WherePredicate::Lifetime { .. } => {}
+            }
+        }
+    }
+
+    fn walk_body(&mut self, def: DefWithBodyId, is_candidate: bool) {
+        let db = self.db;
+        let (body, source_map) = db.body_with_source_map(def);
+        if is_candidate {
+            self.has_unexpanded_macros |= source_map.diagnostics().iter().any(|it| {
+                matches!(
+                    it,
+                    BodyDiagnostic::MacroError { .. }
+                        | BodyDiagnostic::UnresolvedProcMacro { .. }
+                        | BodyDiagnostic::UnresolvedMacroCall { .. }
+                )
+            });
+        }
+
+        let root_resolver = def.resolver(db.upcast());
+        for &param in body.params.iter() {
+            self.walk_pat(&root_resolver, &body, param);
+        }
+
+        let infer = db.infer(def);
+        for (expr_id, expr) in body.exprs.iter() {
+            let resolver = || resolver_for_expr(db.upcast(), def, expr_id);
+            match expr {
+                Expr::Path(path) => self.walk_path(&resolver(), path),
+                Expr::RecordLit { path: Some(path), .. } => self.walk_path(&resolver(), path),
+                Expr::Cast { type_ref, .. } => self.walk_type_ref(&resolver(), type_ref),
+                Expr::MethodCall { method_name, generic_args, .. } => {
+                    let resolver = resolver();
+                    if let Some(generic_args) = generic_args {
+                        self.walk_generic_args(&resolver, generic_args);
+                    }
+                    match infer.method_resolution(expr_id) {
+                        Some((func, _)) => self.walk_assoc_item_use(&resolver, func.into()),
+                        None => {
+                            // The method could come from any trait in scope.
+                            for trait_ in resolver.traits_in_scope(db.upcast()) {
+                                let data = db.trait_data(trait_);
+                                if data.items.iter().any(|(name, item)| {
+                                    name == method_name
+                                        && matches!(item, AssocItemId::FunctionId(_))
+                                }) {
+                                    self.walk_trait_use(&resolver, trait_);
+                                }
+                            }
+                        }
+                    }
+                }
+                Expr::Lambda { args, arg_types, ret_type, .. } => {
+                    let resolver = resolver();
+                    for type_ref in arg_types.iter().chain(Some(ret_type)).flatten() {
+                        self.walk_type_ref(&resolver, type_ref);
+                    }
+                    for &arg in args {
+                        self.walk_pat(&resolver, &body, arg);
+                    }
+                }
+                Expr::For { pat, .. } => self.walk_pat(&resolver(), &body, *pat),
+                Expr::Match { arms, .. } => {
+                    let resolver = resolver();
+                    for arm in arms {
+                        self.walk_pat(&resolver, &body, arm.pat);
+                    }
+                }
+                Expr::Block { id, statements, .. } => {
+                    let resolver = resolver();
+                    for statement in statements {
+                        if let Statement::Let { pat, type_ref, .. } = statement {
+                            if let Some(type_ref) = type_ref {
+                                self.walk_type_ref(&resolver, type_ref);
+                            }
+                            self.walk_pat(&resolver, &body, *pat);
+                        }
+                    }
+                    if let Some(block_def_map) = db.block_def_map(*id) {
+                        let block = block_def_map.module_id(block_def_map.root());
+                        self.walk_module(block, is_candidate);
+                    }
+                }
+                _ => {}
+            }
+            if let Some(item) = infer.assoc_resolutions_for_expr(expr_id) {
+                self.walk_assoc_item_use(&resolver(), item);
+            }
+        }
+        for (pat_id, _) in body.pats.iter() {
+            if let Some(item) = infer.assoc_resolutions_for_pat(pat_id) {
+                self.walk_assoc_item_use(&root_resolver, item);
+            }
+        }
+    }
+
+    fn walk_pat(&mut self, resolver: &Resolver, body: &hir_def::body::Body, pat: PatId) {
+        match &body[pat] {
+            Pat::Path(path) => self.walk_path(resolver, path),
+            Pat::Record { path: Some(path), .. } | Pat::TupleStruct { path: Some(path), .. } => {
+                self.walk_path(resolver, path)
+            }
+            // This is a path if it resolves to a constant or a unit struct.
+            Pat::Bind { name, subpat: None, .. } => self.walk_mod_path(
+                resolver,
+                &ModPath::from_segments(PathKind::Plain, Some(name.clone())),
+            ),
+            _ => {}
+        }
+        body[pat].walk_child_pats(|it| self.walk_pat(resolver, body, it));
+    }
+
+    /// Records the imports of the trait of `item`, for a method call or a path
+    /// resolving to it.
+    fn walk_assoc_item_use(&mut self, resolver: &Resolver, item: AssocItemId) {
+        let db = self.db.upcast();
+        let container = match item {
+            AssocItemId::FunctionId(it) => it.lookup(db).container,
+            AssocItemId::ConstId(it) => it.lookup(db).container,
+            AssocItemId::TypeAliasId(it) => it.lookup(db).container,
+        };
+        let trait_ = match container {
+            AssocContainerId::TraitId(it) => it,
+            AssocContainerId::ImplId(it) => match self.db.impl_trait(it) {
+                Some(trait_ref) => from_chalk_trait_id(trait_ref.skip_binders().trait_id),
+                None => return,
+            },
+            AssocContainerId::ModuleId(_) => return,
+        };
+        self.walk_trait_use(resolver, trait_);
+    }
+
+    fn walk_trait_use(&mut self, resolver: &Resolver, trait_: TraitId) {
+        let used = &mut self.used;
+        resolver.imports_for_trait(self.db.upcast(), trait_, &mut |it| {
+            used.insert(it);
+        });
+    }
+
+    fn walk_type_ref(&mut self, resolver: &Resolver, type_ref: &TypeRef) {
+        match type_ref {
+            TypeRef::Path(path) => self.walk_path(resolver, path),
+            TypeRef::Fn(types, ..) | TypeRef::Tuple(types) => {
+                types.iter().for_each(|it| self.walk_type_ref(resolver, it))
+            }
+            TypeRef::RawPtr(inner, _)
+            | TypeRef::Reference(inner, ..)
+            | TypeRef::Array(inner, _)
+            | TypeRef::Slice(inner) => self.walk_type_ref(resolver, inner),
+            TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
+                bounds.iter().for_each(|it| self.walk_type_bound(resolver, it))
+            }
+            // FIXME: lower type macros.
+            TypeRef::Macro(_) => self.has_unexpanded_macros = true,
+            TypeRef::Never | TypeRef::Placeholder | TypeRef::Error => {}
+        }
+    }
+
+    fn walk_type_bound(&mut self, resolver: &Resolver, bound: &TypeBound) {
+        match bound {
+            TypeBound::Path(path) | TypeBound::ForLifetime(_, path) => {
+                self.walk_path(resolver, path)
+            }
+            TypeBound::Lifetime(_) | TypeBound::Error => {}
+        }
+    }
+
+    fn walk_path(&mut self, resolver: &Resolver, path: &Path) {
+        if let Some(type_anchor) = path.type_anchor() {
+            self.walk_type_ref(resolver, type_anchor);
+        }
+        self.walk_mod_path(resolver, path.mod_path());
+        for segment in path.segments().iter() {
+            if let Some(generic_args) = segment.args_and_bindings {
+                self.walk_generic_args(resolver, generic_args);
+            }
+        }
+    }
+
+    fn walk_generic_args(&mut self, resolver: &Resolver, generic_args: &GenericArgs) {
+        for arg in &generic_args.args {
+            if let GenericArg::Type(type_ref) = arg {
+                self.walk_type_ref(resolver, type_ref);
+            }
+        }
+        for binding in &generic_args.bindings {
+            if let Some(type_ref) = &binding.type_ref {
+                self.walk_type_ref(resolver, type_ref);
+            }
+            binding.bounds.iter().for_each(|it| self.walk_type_bound(resolver, it));
+        }
+    }
+
+    fn walk_mod_path(&mut self, resolver: &Resolver, path: &ModPath) {
+        let used = &mut self.used;
+        resolver.imports_for_path(self.db.upcast(), path, &mut |it| {
+            used.insert(it);
+        });
+    }
+}
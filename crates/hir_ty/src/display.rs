@@ -979,8 +979,11 @@ impl HirDisplay for TypeRef {
                 inner.hir_fmt(f)?;
                 write!(f, "]")?;
             }
-            TypeRef::Fn(tys, is_varargs) => {
+            TypeRef::Fn(tys, is_varargs, bound_lifetimes) => {
                 // FIXME: Function pointer qualifiers.
+                if !bound_lifetimes.is_empty() {
+                    write!(f, "for<{}> ", bound_lifetimes.iter().format(", "))?;
+                }
                 write!(f, "fn(")?;
                 f.write_joined(&tys[..tys.len() - 1], ", ")?;
                 if *is_varargs {
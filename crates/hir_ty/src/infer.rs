@@ -25,8 +25,8 @@ use hir_def::{
     path::{path, Path},
     resolver::{HasResolver, Resolver, TypeNs},
     type_ref::TypeRef,
-    AdtId, AssocItemId, DefWithBodyId, EnumVariantId, FieldId, FunctionId, HasModule, Lookup,
-    TraitId, TypeAliasId, VariantId,
+    AdtId, AssocItemId, DefWithBodyId, EnumVariantId, FieldId, FunctionId, GenericDefId, HasModule,
+    Lookup, TraitId, TypeAliasId, VariantId,
 };
 use hir_expand::name::name;
 use la_arena::ArenaMap;
@@ -37,7 +37,8 @@ use syntax::SmolStr;
 use crate::{
     db::HirDatabase, fold_tys, infer::coerce::CoerceMany, lower::ImplTraitLoweringMode,
     to_assoc_type_id, AliasEq, AliasTy, DomainGoal, Goal, InEnvironment, Interner, ProjectionTy,
-    Substitution, TraitEnvironment, TraitRef, Ty, TyBuilder, TyExt, TyKind,
+    Substitution, TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder, TyExt, TyKind, TypeWalk,
+    WhereClause,
 };
 
 // This lint has a false positive here. See the link below for details.
@@ -46,6 +47,7 @@ use crate::{
 #[allow(unreachable_pub)]
 pub use unify::could_unify;
 pub(crate) use unify::unify;
+use unify::ObligationOrigin;
 
 mod unify;
 mod path;
@@ -53,6 +55,9 @@ mod expr;
 mod pat;
 mod coerce;
 mod closure;
+mod cast;
+
+pub use cast::CastError;
 
 /// The entry point of type inference.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
@@ -122,6 +127,8 @@ pub(crate) type InferResult<T> = Result<InferOk<T>, TypeError>;
 pub enum InferenceDiagnostic {
     NoSuchField { expr: ExprId },
     BreakOutsideOfLoop { expr: ExprId },
+    UnsatisfiedTraitBound { expr: ExprId, trait_ref: TraitRef, origin: GenericDefId },
+    InvalidCast { expr: ExprId, error: CastError, expr_ty: Ty, cast_ty: Ty },
 }
 
 /// A mismatch between an expected and an inferred type.
@@ -344,6 +351,7 @@ struct InferenceContext<'a> {
     return_ty: Ty,
     diverges: Diverges,
     breakables: Vec<BreakableContext>,
+    deferred_cast_checks: Vec<cast::CastCheck>,
 }
 
 #[derive(Clone, Debug)]
@@ -380,6 +388,7 @@ impl<'a> InferenceContext<'a> {
             resolver,
             diverges: Diverges::Maybe,
             breakables: Vec::new(),
+            deferred_cast_checks: Vec::new(),
         }
     }
 
@@ -393,6 +402,8 @@ impl<'a> InferenceContext<'a> {
 
         // make sure diverging type variables are marked as such
         self.table.propagate_diverging_flag();
+        self.report_unfulfilled_obligations();
+        self.check_casts();
         let mut result = std::mem::take(&mut self.result);
         for ty in result.type_of_expr.values_mut() {
             *ty = self.table.resolve_completely(ty.clone());
@@ -416,6 +427,45 @@ impl<'a> InferenceContext<'a> {
         result
     }
 
+    /// Reports the trait bounds that don't hold, unless unknown or opaque types
+    /// are involved as the bound might hold once they are known.
+    fn report_unfulfilled_obligations(&mut self) {
+        // Implicit `Sized` bounds aren't part of the trait environment yet.
+        let sized_trait = self.resolve_lang_item("sized").and_then(|it| it.as_trait());
+        for (goal, origin) in std::mem::take(&mut self.table.unfulfilled_obligations) {
+            let trait_ref = match goal.goal.data(&Interner) {
+                chalk_ir::GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(
+                    trait_ref,
+                ))) => trait_ref.clone(),
+                _ => continue,
+            };
+            if Some(trait_ref.hir_trait_id()) == sized_trait {
+                continue;
+            }
+            let trait_ref = self.table.resolve_completely(trait_ref);
+            let mut is_known = true;
+            trait_ref.walk(&mut |ty| {
+                if matches!(
+                    ty.kind(&Interner),
+                    TyKind::Error
+                        | TyKind::InferenceVar(..)
+                        | TyKind::BoundVar(_)
+                        | TyKind::Alias(_)
+                        | TyKind::OpaqueType(..)
+                ) {
+                    is_known = false;
+                }
+            });
+            if is_known {
+                self.push_diagnostic(InferenceDiagnostic::UnsatisfiedTraitBound {
+                    expr: origin.expr,
+                    trait_ref,
+                    origin: origin.def,
+                });
+            }
+        }
+    }
+
     fn write_expr_ty(&mut self, expr: ExprId, ty: Ty) {
         self.result.type_of_expr.insert(expr, ty);
     }
@@ -493,6 +543,10 @@ impl<'a> InferenceContext<'a> {
         self.table.register_obligation(o.cast(&Interner));
     }
 
+    fn push_obligation_with_origin(&mut self, o: DomainGoal, origin: Option<ObligationOrigin>) {
+        self.table.register_obligation_with_origin(o.cast(&Interner), origin);
+    }
+
     fn unify(&mut self, ty1: &Ty, ty2: &Ty) -> bool {
         self.table.unify(ty1, ty2)
     }
@@ -0,0 +1,353 @@
+//! Type checking of `as` casts.
+//!
+//! Casts are checked once the whole body has been inferred, as the types
+//! involved often aren't known yet when the cast expression is visited.
+//!
+//! See <https://doc.rust-lang.org/reference/expressions/operator-expr.html#type-cast-expressions>
+//! and `librustc_typeck/check/cast.rs`.
+
+use chalk_ir::{Mutability, Scalar, TyVariableKind, UintTy};
+use hir_def::{expr::ExprId, AdtId, EnumId, TraitId};
+
+use crate::{
+    db::HirDatabase,
+    infer::{InferenceContext, InferenceDiagnostic},
+    Interner, Ty, TyExt, TyKind, TypeWalk,
+};
+
+/// A cast whose validity is checked after inference.
+#[derive(Clone, Debug)]
+pub(super) struct CastCheck {
+    /// The cast expression.
+    pub(super) expr: ExprId,
+    pub(super) expr_ty: Ty,
+    pub(super) cast_ty: Ty,
+}
+
+/// The reason an `as` cast is invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CastError {
+    /// A cast from or to a type that isn't a number, a raw pointer, a function
+    /// pointer or a fieldless enum.
+    NonScalar,
+    /// A cast to `bool`.
+    CastToBool,
+    /// A cast to `char` from anything but `u8`.
+    CastToChar,
+    /// A cast of a reference to an integer, which has to go through a raw
+    /// pointer.
+    NeedViaPtr,
+    /// A cast of a fat pointer to an integer, which has to go through a thin
+    /// pointer.
+    NeedViaThinPtr,
+    /// A cast of a `bool`, a `char` or an enum to a float, which has to go
+    /// through an integer.
+    NeedViaInt,
+    /// A cast of a thin pointer to a fat pointer.
+    SizedUnsizedCast,
+    /// A cast of an integer to a fat pointer.
+    IntToFatCast,
+    /// A cast between fat pointers with different kinds of metadata.
+    DifferingKinds,
+    /// Any other invalid cast between primitive types.
+    IllegalCast,
+}
+
+/// The types that can take part in a cast that isn't a coercion.
+#[derive(Clone, Debug)]
+enum CastTy {
+    Int(IntTy),
+    Float,
+    FnPtr,
+    Ptr(Ty),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IntTy {
+    U8,
+    Bool,
+    Char,
+    /// A fieldless enum.
+    CEnum,
+    Other,
+}
+
+/// The metadata of a pointer to a type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PointerKind {
+    Thin,
+    /// A slice or a `str`.
+    Length,
+    /// A trait object with the given principal trait.
+    Vtable(Option<TraitId>),
+    Unknown,
+}
+
+impl PointerKind {
+    fn is_fat(self) -> bool {
+        matches!(self, PointerKind::Length | PointerKind::Vtable(_))
+    }
+}
+
+impl<'a> InferenceContext<'a> {
+    pub(super) fn check_casts(&mut self) {
+        for cast in std::mem::take(&mut self.deferred_cast_checks) {
+            // `&T as *const U` is first tried as a coercion, which unifies `T`
+            // and `U`, or the element type of `T` and `U` for arrays.
+            let expr_ty = self.table.resolve_ty_shallow(&cast.expr_ty);
+            let cast_ty = self.table.resolve_ty_shallow(&cast.cast_ty);
+            if let (TyKind::Ref(_, _, expr_inner), TyKind::Raw(_, cast_inner)) =
+                (expr_ty.kind(&Interner), cast_ty.kind(&Interner))
+            {
+                if !self.table.unify(expr_inner, cast_inner) {
+                    let expr_inner = self.table.resolve_ty_shallow(expr_inner);
+                    if let TyKind::Array(elem, _) = expr_inner.kind(&Interner) {
+                        self.table.unify(elem, cast_inner);
+                    }
+                }
+            }
+
+            // Integer literals cast to `char` are `u8`s.
+            let is_int_var = matches!(
+                self.table.resolve_ty_shallow(&cast.expr_ty).kind(&Interner),
+                TyKind::InferenceVar(_, TyVariableKind::Integer)
+            );
+            let expr_ty = self.table.resolve_completely(cast.expr_ty);
+            let cast_ty = self.table.resolve_completely(cast.cast_ty);
+            if is_int_var && matches!(cast_ty.kind(&Interner), TyKind::Scalar(Scalar::Char)) {
+                continue;
+            }
+            if let Err(error) = check_cast(self.db, &expr_ty, &cast_ty) {
+                self.push_diagnostic(InferenceDiagnostic::InvalidCast {
+                    expr: cast.expr,
+                    error,
+                    expr_ty,
+                    cast_ty,
+                });
+            }
+        }
+    }
+}
+
+fn check_cast(db: &dyn HirDatabase, expr_ty: &Ty, cast_ty: &Ty) -> Result<(), CastError> {
+    if !is_known(expr_ty) || !is_known(cast_ty) || expr_ty == cast_ty {
+        return Ok(());
+    }
+
+    match (expr_ty.kind(&Interner), cast_ty.kind(&Interner)) {
+        (TyKind::Never, _) => return Ok(()),
+        // Function items and closures coerce to function pointers.
+        (TyKind::FnDef(..) | TyKind::Closure(..) | TyKind::Function(_), TyKind::Function(_)) => {
+            return Ok(())
+        }
+        (TyKind::Ref(m_expr, _, expr_inner), TyKind::Raw(m_cast, cast_inner)) => {
+            return check_ref_ptr_cast(db, *m_expr, expr_inner, *m_cast, cast_inner);
+        }
+        (TyKind::Ref(..), _) => {
+            return match as_cast_ty(db, cast_ty) {
+                Some(CastTy::Int(IntTy::U8 | IntTy::Other)) => Err(CastError::NeedViaPtr),
+                Some(_) => Err(CastError::NonScalar),
+                // This might be a coercion.
+                None => Ok(()),
+            };
+        }
+        _ => {}
+    }
+
+    let (t_from, t_cast) = match (as_cast_ty(db, expr_ty), as_cast_ty(db, cast_ty)) {
+        (Some(t_from), Some(t_cast)) => (t_from, t_cast),
+        (Some(_), None) => {
+            // Primitive types don't coerce to anything but themselves.
+            return if is_sized_non_primitive(cast_ty) {
+                Err(CastError::NonScalar)
+            } else {
+                Ok(())
+            };
+        }
+        (None, Some(_)) => {
+            return if is_sized_non_primitive(expr_ty) {
+                Err(CastError::NonScalar)
+            } else {
+                Ok(())
+            };
+        }
+        (None, None) => return Ok(()),
+    };
+
+    match (t_from, t_cast) {
+        (_, CastTy::Int(IntTy::CEnum) | CastTy::FnPtr) => Err(CastError::NonScalar),
+        (_, CastTy::Int(IntTy::Bool)) => Err(CastError::CastToBool),
+        (CastTy::Int(IntTy::U8), CastTy::Int(IntTy::Char)) => Ok(()),
+        (_, CastTy::Int(IntTy::Char)) => Err(CastError::CastToChar),
+        (CastTy::Int(IntTy::Bool | IntTy::CEnum | IntTy::Char), CastTy::Float) => {
+            Err(CastError::NeedViaInt)
+        }
+        (
+            CastTy::Int(IntTy::Bool | IntTy::CEnum | IntTy::Char) | CastTy::Float,
+            CastTy::Ptr(..),
+        )
+        | (CastTy::Ptr(..) | CastTy::FnPtr, CastTy::Float) => Err(CastError::IllegalCast),
+        (CastTy::Ptr(expr_inner), CastTy::Ptr(cast_inner)) => {
+            check_ptr_ptr_cast(db, &expr_inner, &cast_inner)
+        }
+        (CastTy::Ptr(expr_inner), CastTy::Int(_)) => {
+            if pointer_kind(db, &expr_inner).is_fat() {
+                Err(CastError::NeedViaThinPtr)
+            } else {
+                Ok(())
+            }
+        }
+        (CastTy::Int(_), CastTy::Ptr(cast_inner)) => {
+            if pointer_kind(db, &cast_inner).is_fat() {
+                Err(CastError::IntToFatCast)
+            } else {
+                Ok(())
+            }
+        }
+        (CastTy::FnPtr, CastTy::Ptr(cast_inner)) => {
+            if pointer_kind(db, &cast_inner).is_fat() {
+                Err(CastError::IllegalCast)
+            } else {
+                Ok(())
+            }
+        }
+        (CastTy::FnPtr, CastTy::Int(_))
+        | (CastTy::Int(_) | CastTy::Float, CastTy::Int(_) | CastTy::Float) => Ok(()),
+    }
+}
+
+/// `&T as *const U` is only valid as a coercion, or as a cast of a reference
+/// to an array to a pointer to its elements.
+fn check_ref_ptr_cast(
+    db: &dyn HirDatabase,
+    m_expr: Mutability,
+    expr_inner: &Ty,
+    m_cast: Mutability,
+    cast_inner: &Ty,
+) -> Result<(), CastError> {
+    if m_expr == Mutability::Not && m_cast == Mutability::Mut {
+        return Err(CastError::IllegalCast);
+    }
+    if expr_inner == cast_inner {
+        return Ok(());
+    }
+    if let TyKind::Array(elem, _) = expr_inner.kind(&Interner) {
+        if elem == cast_inner {
+            return Ok(());
+        }
+    }
+    match pointer_kind(db, cast_inner) {
+        PointerKind::Thin => Err(CastError::IllegalCast),
+        // This might be an unsizing coercion.
+        PointerKind::Length | PointerKind::Vtable(_) | PointerKind::Unknown => Ok(()),
+    }
+}
+
+fn check_ptr_ptr_cast(
+    db: &dyn HirDatabase,
+    expr_inner: &Ty,
+    cast_inner: &Ty,
+) -> Result<(), CastError> {
+    match (pointer_kind(db, expr_inner), pointer_kind(db, cast_inner)) {
+        (_, PointerKind::Thin | PointerKind::Unknown) | (PointerKind::Unknown, _) => Ok(()),
+        (PointerKind::Thin, _) => Err(CastError::SizedUnsizedCast),
+        (PointerKind::Length, PointerKind::Length) => Ok(()),
+        (PointerKind::Vtable(expr_trait), PointerKind::Vtable(cast_trait))
+            if expr_trait == cast_trait =>
+        {
+            Ok(())
+        }
+        _ => Err(CastError::DifferingKinds),
+    }
+}
+
+fn as_cast_ty(db: &dyn HirDatabase, ty: &Ty) -> Option<CastTy> {
+    let cast_ty = match ty.kind(&Interner) {
+        TyKind::Scalar(Scalar::Bool) => CastTy::Int(IntTy::Bool),
+        TyKind::Scalar(Scalar::Char) => CastTy::Int(IntTy::Char),
+        TyKind::Scalar(Scalar::Uint(UintTy::U8)) => CastTy::Int(IntTy::U8),
+        TyKind::Scalar(Scalar::Int(_) | Scalar::Uint(_)) => CastTy::Int(IntTy::Other),
+        TyKind::Scalar(Scalar::Float(_)) => CastTy::Float,
+        TyKind::Adt(chalk_ir::AdtId(AdtId::EnumId(it)), _) if is_fieldless(db, *it) => {
+            CastTy::Int(IntTy::CEnum)
+        }
+        TyKind::Raw(_, inner) => CastTy::Ptr(inner.clone()),
+        TyKind::FnDef(..) | TyKind::Function(_) => CastTy::FnPtr,
+        _ => return None,
+    };
+    Some(cast_ty)
+}
+
+fn is_fieldless(db: &dyn HirDatabase, enum_: EnumId) -> bool {
+    db.enum_data(enum_).variants.iter().all(|(_, it)| it.variant_data.fields().is_empty())
+}
+
+/// Whether `ty` is a type that no cast nor coercion can turn a primitive type
+/// into, or the other way around.
+fn is_sized_non_primitive(ty: &Ty) -> bool {
+    matches!(
+        ty.kind(&Interner),
+        TyKind::Adt(..) | TyKind::Tuple(..) | TyKind::Array(..) | TyKind::Ref(..)
+    )
+}
+
+fn pointer_kind(db: &dyn HirDatabase, ty: &Ty) -> PointerKind {
+    pointer_kind_rec(db, ty, 0)
+}
+
+fn pointer_kind_rec(db: &dyn HirDatabase, ty: &Ty, depth: usize) -> PointerKind {
+    // Guard against (invalid) structs that contain themselves.
+    if depth > 16 {
+        return PointerKind::Unknown;
+    }
+    match ty.kind(&Interner) {
+        TyKind::Slice(_) | TyKind::Str => PointerKind::Length,
+        TyKind::Dyn(_) => PointerKind::Vtable(ty.dyn_trait()),
+        // Only the last field of a struct or a tuple can be unsized.
+        TyKind::Adt(chalk_ir::AdtId(AdtId::StructId(it)), subst) => {
+            let variant_data = &db.struct_data(*it).variant_data;
+            match variant_data.fields().iter().last() {
+                Some((idx, _)) => {
+                    let field_types = db.field_types((*it).into());
+                    let field_ty = field_types[idx].clone().substitute(&Interner, subst);
+                    pointer_kind_rec(db, &field_ty, depth + 1)
+                }
+                None => PointerKind::Thin,
+            }
+        }
+        TyKind::Tuple(_, subst) => match subst.iter(&Interner).last() {
+            Some(last) => last
+                .ty(&Interner)
+                .map_or(PointerKind::Unknown, |it| pointer_kind_rec(db, it, depth + 1)),
+            None => PointerKind::Thin,
+        },
+        TyKind::Adt(..)
+        | TyKind::Scalar(_)
+        | TyKind::Raw(..)
+        | TyKind::Ref(..)
+        | TyKind::Array(..)
+        | TyKind::FnDef(..)
+        | TyKind::Function(_)
+        | TyKind::Never
+        | TyKind::Foreign(_) => PointerKind::Thin,
+        // Type parameters can be `?Sized`.
+        _ => PointerKind::Unknown,
+    }
+}
+
+fn is_known(ty: &Ty) -> bool {
+    let mut is_known = true;
+    ty.walk(&mut |ty| {
+        if matches!(
+            ty.kind(&Interner),
+            TyKind::Error
+                | TyKind::InferenceVar(..)
+                | TyKind::Alias(_)
+                | TyKind::OpaqueType(..)
+                | TyKind::Placeholder(_)
+        ) {
+            is_known = false;
+        }
+    });
+    is_known
+}
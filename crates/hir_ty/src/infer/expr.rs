@@ -14,13 +14,12 @@ use hir_def::{
     AssocContainerId, FieldId, Lookup,
 };
 use hir_expand::name::{name, Name};
-use stdx::always;
 use syntax::ast::RangeOp;
 
 use crate::{
     autoderef::{self, Autoderef},
     consteval,
-    infer::coerce::CoerceMany,
+    infer::{coerce::CoerceMany, unify::ObligationOrigin},
     lower::lower_to_chalk_mutability,
     mapping::from_chalk,
     method_resolution, op,
@@ -33,8 +32,8 @@ use crate::{
 };
 
 use super::{
-    find_breakable, BindingMode, BreakableContext, Diverges, Expectation, InferenceContext,
-    InferenceDiagnostic, TypeMismatch,
+    cast::CastCheck, find_breakable, BindingMode, BreakableContext, Diverges, Expectation,
+    InferenceContext, InferenceDiagnostic, TypeMismatch,
 };
 
 impl<'a> InferenceContext<'a> {
@@ -339,7 +338,7 @@ impl<'a> InferenceContext<'a> {
                     }
                     None => (Vec::new(), self.err_ty()),
                 };
-                self.register_obligations_for_call(&callee_ty);
+                self.register_obligations_for_call(tgt_expr, &callee_ty);
                 self.check_call_arguments(args, &param_tys);
                 self.normalize_associated_types_in(ret_ty)
             }
@@ -555,9 +554,13 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Cast { expr, type_ref } => {
                 // FIXME: propagate the "castable to" expectation (and find a test case that shows this is necessary)
-                let _inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
+                let inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
                 let cast_ty = self.make_ty(type_ref);
-                // FIXME check the cast...
+                self.deferred_cast_checks.push(CastCheck {
+                    expr: tgt_expr,
+                    expr_ty: inner_ty,
+                    cast_ty: cast_ty.clone(),
+                });
                 cast_ty
             }
             Expr::Ref { expr, rawness, mutability } => {
@@ -934,7 +937,7 @@ impl<'a> InferenceContext<'a> {
             ),
         };
         let method_ty = method_ty.substitute(&Interner, &substs);
-        self.register_obligations_for_call(&method_ty);
+        self.register_obligations_for_call(tgt_expr, &method_ty);
         let (expected_receiver_ty, param_tys, ret_ty) = match method_ty.callable_sig(self.db) {
             Some(sig) => {
                 if !sig.params().is_empty() {
@@ -1016,36 +1019,43 @@ impl<'a> InferenceContext<'a> {
         Substitution::from_iter(&Interner, substs)
     }
 
-    fn register_obligations_for_call(&mut self, callable_ty: &Ty) {
+    fn register_obligations_for_call(&mut self, expr: ExprId, callable_ty: &Ty) {
         let callable_ty = self.resolve_ty_shallow(callable_ty);
         if let TyKind::FnDef(fn_def, parameters) = callable_ty.kind(&Interner) {
             let def: CallableDefId = from_chalk(self.db, *fn_def);
             let generic_predicates = self.db.generic_predicates(def.into());
+            let trait_ = match def {
+                CallableDefId::FunctionId(f) => match f.lookup(self.db.upcast()).container {
+                    AssocContainerId::TraitId(trait_) => Some(trait_),
+                    _ => None,
+                },
+                CallableDefId::StructId(_) | CallableDefId::EnumVariantId(_) => None,
+            };
+            // Method resolution may fall back to trait methods whose bounds
+            // don't hold, so only the bounds of other callables are reported.
+            let origin = match trait_ {
+                Some(_) => None,
+                None => Some(ObligationOrigin { expr, def: def.into() }),
+            };
             for predicate in generic_predicates.iter() {
-                let (predicate, binders) = predicate
-                    .clone()
-                    .substitute(&Interner, parameters)
-                    .into_value_and_skipped_binders();
-                always!(binders.len(&Interner) == 0); // quantified where clauses not yet handled
-                self.push_obligation(predicate.cast(&Interner));
+                // Higher-ranked lifetimes are erased like all other lifetimes;
+                // `forall` goals would keep producing fresh lifetime variables.
+                let predicate = crate::erase_bound_lifetimes(
+                    predicate.clone().substitute(&Interner, parameters),
+                );
+                self.push_obligation_with_origin(predicate.cast(&Interner), origin);
             }
             // add obligation for trait implementation, if this is a trait method
-            match def {
-                CallableDefId::FunctionId(f) => {
-                    if let AssocContainerId::TraitId(trait_) = f.lookup(self.db.upcast()).container
-                    {
-                        // construct a TraitRef
-                        let substs = crate::subst_prefix(
-                            &*parameters,
-                            generics(self.db.upcast(), trait_.into()).len(),
-                        );
-                        self.push_obligation(
-                            TraitRef { trait_id: to_chalk_trait_id(trait_), substitution: substs }
-                                .cast(&Interner),
-                        );
-                    }
-                }
-                CallableDefId::StructId(_) | CallableDefId::EnumVariantId(_) => {}
+            if let Some(trait_) = trait_ {
+                // construct a TraitRef
+                let substs = crate::subst_prefix(
+                    parameters,
+                    generics(self.db.upcast(), trait_.into()).len(),
+                );
+                self.push_obligation(
+                    TraitRef { trait_id: to_chalk_trait_id(trait_), substitution: substs }
+                        .cast(&Interner),
+                );
             }
         }
     }
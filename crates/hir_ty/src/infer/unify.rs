@@ -8,6 +8,7 @@ use chalk_ir::{
 };
 use chalk_solve::infer::ParameterEnaVariableExt;
 use ena::unify::UnifyKey;
+use hir_def::{expr::ExprId, GenericDefId};
 
 use super::{InferOk, InferResult, InferenceContext, TypeError};
 use crate::{
@@ -134,13 +135,24 @@ pub(crate) struct TypeVariableData {
 
 type ChalkInferenceTable = chalk_solve::infer::InferenceTable<Interner>;
 
+/// The expression and the item that required an obligation, used to report it
+/// when it can't be fulfilled.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ObligationOrigin {
+    pub(crate) expr: ExprId,
+    /// The item whose where clauses require the obligation.
+    pub(crate) def: GenericDefId,
+}
+
 #[derive(Clone)]
 pub(crate) struct InferenceTable<'a> {
     pub(crate) db: &'a dyn HirDatabase,
     pub(crate) trait_env: Arc<TraitEnvironment>,
     var_unification_table: ChalkInferenceTable,
     type_variable_table: Vec<TypeVariableData>,
-    pending_obligations: Vec<Canonicalized<InEnvironment<Goal>>>,
+    pending_obligations: Vec<(Canonicalized<InEnvironment<Goal>>, Option<ObligationOrigin>)>,
+    /// Obligations with an origin that were found to have no solution.
+    pub(crate) unfulfilled_obligations: Vec<(InEnvironment<Goal>, ObligationOrigin)>,
 }
 
 impl<'a> InferenceTable<'a> {
@@ -151,6 +163,7 @@ impl<'a> InferenceTable<'a> {
             var_unification_table: ChalkInferenceTable::new(),
             type_variable_table: Vec::new(),
             pending_obligations: Vec::new(),
+            unfulfilled_obligations: Vec::new(),
         }
     }
 
@@ -337,18 +350,31 @@ impl<'a> InferenceTable<'a> {
 
     pub(crate) fn register_obligation(&mut self, goal: Goal) {
         let in_env = InEnvironment::new(&self.trait_env.env, goal);
-        self.register_obligation_in_env(in_env)
+        self.register_obligation_in_env(in_env, None)
     }
 
-    fn register_obligation_in_env(&mut self, goal: InEnvironment<Goal>) {
+    pub(crate) fn register_obligation_with_origin(
+        &mut self,
+        goal: Goal,
+        origin: Option<ObligationOrigin>,
+    ) {
+        let in_env = InEnvironment::new(&self.trait_env.env, goal);
+        self.register_obligation_in_env(in_env, origin)
+    }
+
+    fn register_obligation_in_env(
+        &mut self,
+        goal: InEnvironment<Goal>,
+        origin: Option<ObligationOrigin>,
+    ) {
         let canonicalized = self.canonicalize(goal);
-        if !self.try_resolve_obligation(&canonicalized) {
-            self.pending_obligations.push(canonicalized);
+        if !self.try_resolve_obligation(&canonicalized, origin) {
+            self.pending_obligations.push((canonicalized, origin));
         }
     }
 
     pub(crate) fn register_infer_ok<T>(&mut self, infer_ok: InferOk<T>) {
-        infer_ok.goals.into_iter().for_each(|goal| self.register_obligation_in_env(goal));
+        infer_ok.goals.into_iter().for_each(|goal| self.register_obligation_in_env(goal, None));
     }
 
     pub(crate) fn resolve_obligations_as_possible(&mut self) {
@@ -358,9 +384,9 @@ impl<'a> InferenceTable<'a> {
         while changed {
             changed = false;
             mem::swap(&mut self.pending_obligations, &mut obligations);
-            for canonicalized in obligations.drain(..) {
+            for (canonicalized, origin) in obligations.drain(..) {
                 if !self.check_changed(&canonicalized) {
-                    self.pending_obligations.push(canonicalized);
+                    self.pending_obligations.push((canonicalized, origin));
                     continue;
                 }
                 changed = true;
@@ -369,7 +395,7 @@ impl<'a> InferenceTable<'a> {
                     canonicalized.value.value,
                     &Interner,
                 );
-                self.register_obligation_in_env(uncanonical);
+                self.register_obligation_in_env(uncanonical, origin);
             }
         }
     }
@@ -397,6 +423,7 @@ impl<'a> InferenceTable<'a> {
     fn try_resolve_obligation(
         &mut self,
         canonicalized: &Canonicalized<InEnvironment<Goal>>,
+        origin: Option<ObligationOrigin>,
     ) -> bool {
         let solution = self.db.trait_solve(self.trait_env.krate, canonicalized.value.clone());
 
@@ -421,7 +448,14 @@ impl<'a> InferenceTable<'a> {
                 false
             }
             None => {
-                // FIXME obligation cannot be fulfilled => diagnostic
+                if let Some(origin) = origin {
+                    let goal = chalk_ir::Substitute::apply(
+                        &canonicalized.free_vars,
+                        canonicalized.value.value.clone(),
+                        &Interner,
+                    );
+                    self.unfulfilled_obligations.push((goal, origin));
+                }
                 true
             }
         }
@@ -0,0 +1,456 @@
+//! Computes the memory layout of types: their size, alignment, field offsets
+//! and niches.
+//!
+//! This mirrors what rustc does for the common cases, but doesn't try to be
+//! exhaustive: types whose layout we can't determine produce a
+//! [`LayoutError`] instead of a wrong answer.
+
+use std::{cmp, sync::Arc};
+
+use chalk_ir::{FloatTy, IntTy, UintTy};
+use either::Either;
+use hir_def::{
+    adt::ReprOptions,
+    builtin_type::{BuiltinInt, BuiltinUint},
+    src::HasChildSource,
+    type_ref::ConstScalar,
+    AdtId, AttrDefId, EnumId, EnumVariantId, StructId, UnionId, VariantId,
+};
+
+use crate::{
+    db::HirDatabase, ConcreteConst, ConstValue, Interner, Scalar, Substitution, Ty, TyKind,
+};
+
+// FIXME: take the pointer width from the target of the crate.
+const POINTER_SIZE: u64 = 8;
+
+/// The memory layout of a sized type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+    /// The offsets of the fields of a struct, union or tuple, in declaration
+    /// order. Empty for every other type.
+    pub field_offsets: Vec<u64>,
+    /// The scalar with the most invalid bit patterns, which enclosing enums can
+    /// use to store their discriminant.
+    pub largest_niche: Option<Niche>,
+}
+
+/// A scalar in a type that can't hold every value its size allows, like a
+/// `bool` or a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Niche {
+    pub offset: u64,
+    pub size: u64,
+    /// The valid values are `start..=end`, wrapping around if `start > end`.
+    pub start: u128,
+    pub end: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The layout depends on generic parameters.
+    HasPlaceholder,
+    /// The type is unsized, like `str` or `[T]`.
+    Unsized,
+    /// The type contains itself and so has infinite size.
+    Recursive,
+    /// The type is unknown, or its layout isn't supported.
+    Unknown,
+}
+
+impl Layout {
+    fn zst() -> Layout {
+        Layout { size: 0, align: 1, field_offsets: Vec::new(), largest_niche: None }
+    }
+
+    fn scalar(size: u64, valid_range: Option<(u128, u128)>) -> Layout {
+        Layout {
+            size,
+            align: size,
+            field_offsets: Vec::new(),
+            largest_niche: valid_range.map(|(start, end)| Niche { offset: 0, size, start, end }),
+        }
+    }
+}
+
+impl Niche {
+    fn max_value(&self) -> u128 {
+        u128::MAX >> (128 - self.size * 8)
+    }
+
+    /// The number of invalid values of the scalar.
+    pub fn available(&self) -> u128 {
+        let max = self.max_value();
+        max - (self.end.wrapping_sub(self.start) & max)
+    }
+
+    /// Uses `count` of the invalid values, returning the niche that remains.
+    fn reserve(self, count: u128) -> Option<Niche> {
+        if self.available() < count {
+            return None;
+        }
+        Some(Niche { end: self.end.wrapping_add(count) & self.max_value(), ..self })
+    }
+}
+
+pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty) -> Result<Arc<Layout>, LayoutError> {
+    let layout = match ty.kind(&Interner) {
+        TyKind::Adt(chalk_ir::AdtId(def), subst) => return db.layout_of_adt(*def, subst.clone()),
+        TyKind::Scalar(scalar) => match scalar {
+            Scalar::Bool => Layout::scalar(1, Some((0, 1))),
+            Scalar::Char => Layout::scalar(4, Some((0, 0x10FFFF))),
+            Scalar::Int(it) => Layout::scalar(int_size(*it), None),
+            Scalar::Uint(it) => Layout::scalar(uint_size(*it), None),
+            Scalar::Float(FloatTy::F32) => Layout::scalar(4, None),
+            Scalar::Float(FloatTy::F64) => Layout::scalar(8, None),
+        },
+        TyKind::Tuple(_, subst) => {
+            let fields = subst
+                .iter(&Interner)
+                .map(|it| layout_of_ty(db, it.assert_ty_ref(&Interner)))
+                .collect::<Result<Vec<_>, _>>()?;
+            univariant(&fields, &ReprOptions::default(), None)
+        }
+        TyKind::Array(elem, len) => {
+            let len = match len.data(&Interner).value {
+                ConstValue::Concrete(ConcreteConst { interned: ConstScalar::Usize(len) }) => len,
+                _ => return Err(LayoutError::Unknown),
+            };
+            let elem = layout_of_ty(db, elem)?;
+            Layout {
+                size: elem.size * len,
+                align: elem.align,
+                field_offsets: Vec::new(),
+                largest_niche: if len == 0 { None } else { elem.largest_niche },
+            }
+        }
+        TyKind::Ref(_, _, pointee) => pointer(db, pointee, true)?,
+        TyKind::Raw(_, pointee) => pointer(db, pointee, false)?,
+        TyKind::Function(_) => Layout::scalar(POINTER_SIZE, Some((1, u64::MAX as u128))),
+        TyKind::FnDef(..) | TyKind::Never => Layout::zst(),
+        TyKind::Str | TyKind::Slice(_) | TyKind::Dyn(_) => return Err(LayoutError::Unsized),
+        TyKind::Placeholder(_) | TyKind::BoundVar(_) => return Err(LayoutError::HasPlaceholder),
+        TyKind::Alias(_)
+        | TyKind::AssociatedType(..)
+        | TyKind::OpaqueType(..)
+        | TyKind::Closure(..)
+        | TyKind::Generator(..)
+        | TyKind::GeneratorWitness(..)
+        | TyKind::Foreign(_)
+        | TyKind::InferenceVar(..)
+        | TyKind::Error => return Err(LayoutError::Unknown),
+    };
+    Ok(Arc::new(layout))
+}
+
+pub(crate) fn layout_of_adt_query(
+    db: &dyn HirDatabase,
+    def: AdtId,
+    subst: Substitution,
+) -> Result<Arc<Layout>, LayoutError> {
+    let layout = match def {
+        AdtId::StructId(it) => layout_of_struct(db, it, &subst)?,
+        AdtId::UnionId(it) => layout_of_union(db, it, &subst)?,
+        AdtId::EnumId(it) => layout_of_enum(db, it, &subst)?,
+    };
+    Ok(Arc::new(layout))
+}
+
+pub(crate) fn layout_of_adt_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &AdtId,
+    _subst: &Substitution,
+) -> Result<Arc<Layout>, LayoutError> {
+    Err(LayoutError::Recursive)
+}
+
+fn field_layouts(
+    db: &dyn HirDatabase,
+    variant: VariantId,
+    subst: &Substitution,
+) -> Result<Vec<Arc<Layout>>, LayoutError> {
+    db.field_types(variant)
+        .iter()
+        .map(|(_, ty)| layout_of_ty(db, &ty.clone().substitute(&Interner, subst)))
+        .collect()
+}
+
+fn layout_of_struct(
+    db: &dyn HirDatabase,
+    it: StructId,
+    subst: &Substitution,
+) -> Result<Layout, LayoutError> {
+    let repr = db.struct_data(it).repr.clone().unwrap_or_default();
+    let mut layout = univariant(&field_layouts(db, it.into(), subst)?, &repr, None);
+
+    // `NonNull` and the `NonZero` integers declare their niche with these
+    // attributes.
+    let attrs = db.attrs(AttrDefId::AdtId(it.into()));
+    let bound = |name: &'static str| -> Option<u128> {
+        let tt = attrs.by_key(name).tt_values().next()?;
+        match tt.token_trees.first()? {
+            tt::TokenTree::Leaf(tt::Leaf::Literal(lit)) => lit.text.parse().ok(),
+            _ => None,
+        }
+    };
+    let (start, end) = (
+        bound("rustc_layout_scalar_valid_range_start"),
+        bound("rustc_layout_scalar_valid_range_end"),
+    );
+    if (start.is_some() || end.is_some()) && matches!(layout.size, 1 | 2 | 4 | 8 | 16) {
+        let max = u128::MAX >> (128 - layout.size * 8);
+        layout.largest_niche = Some(Niche {
+            offset: 0,
+            size: layout.size,
+            start: start.unwrap_or(0),
+            end: end.unwrap_or(max),
+        });
+    }
+    Ok(layout)
+}
+
+fn layout_of_union(
+    db: &dyn HirDatabase,
+    it: UnionId,
+    subst: &Substitution,
+) -> Result<Layout, LayoutError> {
+    let repr = db.union_data(it).repr.clone().unwrap_or_default();
+    let fields = field_layouts(db, it.into(), subst)?;
+    let align = fields.iter().map(|it| field_align(it, &repr)).fold(min_align(&repr), cmp::max);
+    let size = fields.iter().map(|it| it.size).max().unwrap_or(0);
+    Ok(Layout {
+        size: align_to(size, align),
+        align,
+        field_offsets: vec![0; fields.len()],
+        largest_niche: None,
+    })
+}
+
+fn layout_of_enum(
+    db: &dyn HirDatabase,
+    it: EnumId,
+    subst: &Substitution,
+) -> Result<Layout, LayoutError> {
+    let data = db.enum_data(it);
+    // FIXME: take explicit discriminants into account when sizing the tag.
+    if it.child_source(db.upcast()).value.values().any(|variant| variant.expr().is_some()) {
+        return Err(LayoutError::Unknown);
+    }
+    let repr = data.repr.clone().unwrap_or_default();
+    let variants = data
+        .variants
+        .iter()
+        .map(|(local_id, _)| {
+            field_layouts(db, EnumVariantId { parent: it, local_id }.into(), subst)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Uninhabited enums take no space.
+    if variants.is_empty() {
+        return Ok(Layout::zst());
+    }
+    let is_rust_repr = !repr.c && repr.int.is_none();
+    if is_rust_repr && variants.len() == 1 {
+        let mut layout = univariant(&variants[0], &repr, None);
+        layout.field_offsets.clear();
+        return Ok(layout);
+    }
+    if is_rust_repr {
+        if let Some(layout) = niche_filling(&variants, &repr) {
+            return Ok(layout);
+        }
+    }
+    Ok(tagged(&variants, &repr))
+}
+
+/// Stores the discriminant in the invalid values of a field, which works when
+/// all variants but one are zero-sized, like for `Option<&T>`.
+fn niche_filling(variants: &[Vec<Arc<Layout>>], repr: &ReprOptions) -> Option<Layout> {
+    let mut dataful = variants.iter().filter(|fields| fields.iter().any(|it| it.size > 0));
+    let fields = dataful.next()?;
+    if dataful.next().is_some() {
+        return None;
+    }
+    let mut layout = univariant(fields, repr, None);
+    layout.largest_niche = Some(layout.largest_niche?.reserve(variants.len() as u128 - 1)?);
+    layout.align =
+        variants.iter().flatten().map(|it| field_align(it, repr)).fold(layout.align, cmp::max);
+    layout.size = align_to(layout.size, layout.align);
+    layout.field_offsets.clear();
+    Some(layout)
+}
+
+/// Stores the discriminant in a tag in front of the fields.
+fn tagged(variants: &[Vec<Arc<Layout>>], repr: &ReprOptions) -> Layout {
+    let max_discr = variants.len() as u128 - 1;
+    let tag_size = match repr.int {
+        Some(Either::Left(it)) => builtin_int_size(it),
+        Some(Either::Right(it)) => builtin_uint_size(it),
+        None if repr.c => 4,
+        None => {
+            [1, 2, 4, 8].iter().copied().find(|&size| max_discr >> (size * 8) == 0).unwrap_or(16)
+        }
+    };
+    let tag_niche = Some(Niche { offset: 0, size: tag_size, start: 0, end: max_discr });
+
+    if repr.c {
+        // `#[repr(C)]` enums are a tag followed by a union of the variants.
+        let variants: Vec<_> = variants.iter().map(|it| univariant(it, repr, None)).collect();
+        let union_align = variants.iter().map(|it| it.align).fold(1, cmp::max);
+        let union_size = variants.iter().map(|it| it.size).max().unwrap_or(0);
+        let align = cmp::max(tag_size, union_align);
+        let size = align_to(align_to(tag_size, union_align) + union_size, align);
+        return Layout { size, align, field_offsets: Vec::new(), largest_niche: tag_niche };
+    }
+
+    // With a primitive representation, each variant is laid out like a
+    // `#[repr(C)]` struct starting with the tag.
+    let variant_repr = ReprOptions { c: repr.int.is_some(), ..repr.clone() };
+    let variants: Vec<_> =
+        variants.iter().map(|it| univariant(it, &variant_repr, Some(tag_size))).collect();
+    let align = variants.iter().map(|it| it.align).fold(tag_size, cmp::max);
+    let size = variants.iter().map(|it| it.size).max().unwrap_or(tag_size);
+    Layout {
+        size: align_to(size, align),
+        align,
+        field_offsets: Vec::new(),
+        largest_niche: tag_niche,
+    }
+}
+
+/// Lays out the fields of a struct, tuple or enum variant one after another,
+/// after a tag of `prefix_size` bytes if there is one.
+fn univariant(fields: &[Arc<Layout>], repr: &ReprOptions, prefix_size: Option<u64>) -> Layout {
+    let mut order: Vec<usize> = (0..fields.len()).collect();
+    // Without a fixed representation, fields are reordered to minimize
+    // padding: zero-sized fields first, then by decreasing alignment. After a
+    // tag, increasing alignment lets small fields fill the space next to it.
+    if !repr.c && repr.int.is_none() {
+        match prefix_size {
+            None => order.sort_by_key(|&i| {
+                (fields[i].size > 0, cmp::Reverse(field_align(&fields[i], repr)))
+            }),
+            Some(_) => order.sort_by_key(|&i| field_align(&fields[i], repr)),
+        }
+    }
+
+    let mut offset = prefix_size.unwrap_or(0);
+    let mut align = cmp::max(min_align(repr), prefix_size.unwrap_or(1));
+    let mut field_offsets = vec![0; fields.len()];
+    let mut largest_niche: Option<Niche> = None;
+    for i in order {
+        let field = &fields[i];
+        let field_align = field_align(field, repr);
+        offset = align_to(offset, field_align);
+        align = cmp::max(align, field_align);
+        field_offsets[i] = offset;
+        if let Some(niche) = field.largest_niche {
+            if largest_niche.map_or(true, |it| niche.available() > it.available()) {
+                largest_niche = Some(Niche { offset: offset + niche.offset, ..niche });
+            }
+        }
+        offset += field.size;
+    }
+    Layout { size: align_to(offset, align), align, field_offsets, largest_niche }
+}
+
+fn pointer(db: &dyn HirDatabase, pointee: &Ty, non_null: bool) -> Result<Layout, LayoutError> {
+    // Pointers to unsized types also store a length or a vtable.
+    let size = if is_unsized(db, pointee, 0)? { 2 * POINTER_SIZE } else { POINTER_SIZE };
+    Ok(Layout {
+        size,
+        align: POINTER_SIZE,
+        field_offsets: Vec::new(),
+        largest_niche: non_null.then(|| Niche {
+            offset: 0,
+            size: POINTER_SIZE,
+            start: 1,
+            end: u64::MAX as u128,
+        }),
+    })
+}
+
+fn is_unsized(db: &dyn HirDatabase, ty: &Ty, depth: usize) -> Result<bool, LayoutError> {
+    // Only the last field of a struct can be unsized, so this only recurses
+    // infinitely for types that are infinitely sized.
+    if depth > 64 {
+        return Err(LayoutError::Recursive);
+    }
+    match ty.kind(&Interner) {
+        TyKind::Str | TyKind::Slice(_) | TyKind::Dyn(_) => Ok(true),
+        TyKind::Adt(chalk_ir::AdtId(AdtId::StructId(it)), subst) => {
+            let field_types = db.field_types((*it).into());
+            match field_types.iter().last() {
+                Some((_, ty)) => {
+                    is_unsized(db, &ty.clone().substitute(&Interner, subst), depth + 1)
+                }
+                None => Ok(false),
+            }
+        }
+        TyKind::Tuple(_, subst) => match subst.iter(&Interner).last() {
+            Some(ty) => is_unsized(db, ty.assert_ty_ref(&Interner), depth + 1),
+            None => Ok(false),
+        },
+        TyKind::Placeholder(_) | TyKind::BoundVar(_) => Err(LayoutError::HasPlaceholder),
+        _ => Ok(false),
+    }
+}
+
+fn field_align(field: &Layout, repr: &ReprOptions) -> u64 {
+    repr.pack.map_or(field.align, |pack| cmp::min(field.align, pack))
+}
+
+fn min_align(repr: &ReprOptions) -> u64 {
+    repr.align.unwrap_or(1)
+}
+
+fn align_to(offset: u64, align: u64) -> u64 {
+    (offset + align - 1) / align * align
+}
+
+fn int_size(it: IntTy) -> u64 {
+    match it {
+        IntTy::Isize => POINTER_SIZE,
+        IntTy::I8 => 1,
+        IntTy::I16 => 2,
+        IntTy::I32 => 4,
+        IntTy::I64 => 8,
+        IntTy::I128 => 16,
+    }
+}
+
+fn uint_size(it: UintTy) -> u64 {
+    match it {
+        UintTy::Usize => POINTER_SIZE,
+        UintTy::U8 => 1,
+        UintTy::U16 => 2,
+        UintTy::U32 => 4,
+        UintTy::U64 => 8,
+        UintTy::U128 => 16,
+    }
+}
+
+fn builtin_int_size(it: BuiltinInt) -> u64 {
+    match it {
+        BuiltinInt::Isize => POINTER_SIZE,
+        BuiltinInt::I8 => 1,
+        BuiltinInt::I16 => 2,
+        BuiltinInt::I32 => 4,
+        BuiltinInt::I64 => 8,
+        BuiltinInt::I128 => 16,
+    }
+}
+
+fn builtin_uint_size(it: BuiltinUint) -> u64 {
+    match it {
+        BuiltinUint::Usize => POINTER_SIZE,
+        BuiltinUint::U8 => 1,
+        BuiltinUint::U16 => 2,
+        BuiltinUint::U32 => 4,
+        BuiltinUint::U64 => 8,
+        BuiltinUint::U128 => 16,
+    }
+}
@@ -22,7 +22,9 @@ mod walk;
 pub mod db;
 pub mod diagnostics;
 pub mod display;
+pub mod layout;
 pub mod method_resolution;
+pub mod mir;
 pub mod primitive;
 pub mod traits;
 
@@ -49,7 +51,7 @@ use crate::{db::HirDatabase, utils::generics};
 pub use autoderef::autoderef;
 pub use builder::TyBuilder;
 pub use chalk_ext::*;
-pub use infer::{could_unify, InferenceDiagnostic, InferenceResult};
+pub use infer::{could_unify, CastError, InferenceDiagnostic, InferenceResult};
 pub use interner::Interner;
 pub use lower::{
     associated_type_shorthand_candidates, callable_item_sig, CallableDefId, ImplTraitLoweringMode,
@@ -61,7 +63,7 @@ pub use mapping::{
     to_foreign_def_id, to_placeholder_idx,
 };
 pub use traits::TraitEnvironment;
-pub use utils::all_super_traits;
+pub use utils::{all_super_traits, direct_super_traits};
 pub use walk::TypeWalk;
 
 pub use chalk_ir::{
@@ -141,6 +143,19 @@ where
     Binders::empty(&Interner, value.shifted_in_from(&Interner, DebruijnIndex::ONE))
 }
 
+/// Instantiates the lifetimes bound by a higher-ranked `for<'a>` binder with
+/// `'static`, which is what all other lifetimes are lowered to as well.
+pub(crate) fn erase_bound_lifetimes<T>(value: Binders<T>) -> T
+where
+    T: Fold<Interner, Result = T> + HasInterner<Interner = Interner>,
+{
+    let lifetimes = Substitution::from_iter(
+        &Interner,
+        value.binders.iter(&Interner).map(|_| static_lifetime().cast(&Interner)),
+    );
+    value.substitute(&Interner, &lifetimes)
+}
+
 pub(crate) fn make_only_type_binders<T: HasInterner<Interner = Interner>>(
     num_vars: usize,
     value: T,
@@ -191,12 +206,8 @@ impl CallableSig {
 
     pub fn from_fn_ptr(fn_ptr: &FnPointer) -> CallableSig {
         CallableSig {
-            // FIXME: what to do about lifetime params? -> return PolyFnSig
-            params_and_return: fn_ptr
-                .substitution
-                .clone()
-                .shifted_out_to(&Interner, DebruijnIndex::ONE)
-                .expect("unexpected lifetime vars in fn ptr")
+            // FIXME: keep the lifetime params around? -> return PolyFnSig
+            params_and_return: erase_bound_lifetimes(fn_ptr.clone().into_binders(&Interner))
                 .0
                 .as_slice(&Interner)
                 .iter()
@@ -18,7 +18,7 @@ use hir_def::{
     generics::{TypeParamProvenance, WherePredicate, WherePredicateTypeTarget},
     path::{GenericArg, Path, PathSegment, PathSegments},
     resolver::{HasResolver, Resolver, TypeNs},
-    type_ref::{TraitRef as HirTraitRef, TypeBound, TypeRef},
+    type_ref::{LifetimeRef, TraitRef as HirTraitRef, TypeBound, TypeRef},
     AdtId, AssocContainerId, AssocItemId, ConstId, ConstParamId, EnumId, EnumVariantId, FunctionId,
     GenericDefId, HasModule, ImplId, LocalFieldId, Lookup, StaticId, StructId, TraitId,
     TypeAliasId, TypeParamId, UnionId, VariantId,
@@ -38,9 +38,10 @@ use crate::{
         all_super_trait_refs, associated_type_by_name_including_super_traits, generics, Generics,
     },
     AliasEq, AliasTy, Binders, BoundVar, CallableSig, DebruijnIndex, DynTy, FnPointer, FnSig,
-    FnSubst, ImplTraitId, Interner, PolyFnSig, ProjectionTy, QuantifiedWhereClause,
-    QuantifiedWhereClauses, ReturnTypeImplTrait, ReturnTypeImplTraits, Substitution,
-    TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder, TyKind, WhereClause,
+    FnSubst, ImplTraitId, Interner, Lifetime, LifetimeData, PolyFnSig, ProjectionTy,
+    QuantifiedWhereClause, QuantifiedWhereClauses, ReturnTypeImplTrait, ReturnTypeImplTraits,
+    Substitution, TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder, TyKind, VariableKinds,
+    WhereClause,
 };
 
 #[derive(Debug)]
@@ -65,6 +66,10 @@ pub struct TyLoweringContext<'a> {
     /// Splitting this up would be a possible fix.
     opaque_type_data: RefCell<Vec<ReturnTypeImplTrait>>,
     expander: RefCell<Option<Expander>>,
+    /// The lifetimes bound by the `for<'a>` binders we're in, innermost last.
+    /// Each one comes with the value of `in_binders` right inside of its
+    /// binder and its index in there.
+    bound_lifetimes: RefCell<Vec<(DebruijnIndex, usize, Name)>>,
 }
 
 impl<'a> TyLoweringContext<'a> {
@@ -83,6 +88,7 @@ impl<'a> TyLoweringContext<'a> {
             type_param_mode,
             opaque_type_data,
             expander: RefCell::new(None),
+            bound_lifetimes: RefCell::new(Vec::new()),
         }
     }
 
@@ -93,17 +99,29 @@ impl<'a> TyLoweringContext<'a> {
     ) -> T {
         let opaque_ty_data_vec = self.opaque_type_data.replace(Vec::new());
         let expander = self.expander.replace(None);
+        // The `for<'a>` binders we're in are out of reach if we don't nest in
+        // them, like in the bounds of `impl Trait`.
+        let keep_bound_lifetimes = debruijn.depth() >= self.in_binders.depth();
+        let bound_lifetimes = if keep_bound_lifetimes {
+            self.bound_lifetimes.replace(Vec::new())
+        } else {
+            Vec::new()
+        };
         let new_ctx = Self {
             in_binders: debruijn,
             impl_trait_counter: Cell::new(self.impl_trait_counter.get()),
             opaque_type_data: RefCell::new(opaque_ty_data_vec),
             expander: RefCell::new(expander),
+            bound_lifetimes: RefCell::new(bound_lifetimes),
             ..*self
         };
         let result = f(&new_ctx);
         self.impl_trait_counter.set(new_ctx.impl_trait_counter.get());
         self.opaque_type_data.replace(new_ctx.opaque_type_data.into_inner());
         self.expander.replace(new_ctx.expander.into_inner());
+        if keep_bound_lifetimes {
+            self.bound_lifetimes.replace(new_ctx.bound_lifetimes.into_inner());
+        }
         result
     }
 
@@ -115,6 +133,24 @@ impl<'a> TyLoweringContext<'a> {
         self.with_debruijn(self.in_binders.shifted_in_from(debruijn), f)
     }
 
+    /// Lowers something inside of a `for<'a, ...>` binder, which binds
+    /// `lifetimes`. An empty binder is the same as `with_shifted_in`.
+    fn with_bound_lifetimes<T>(
+        &self,
+        lifetimes: &[Name],
+        f: impl FnOnce(&TyLoweringContext) -> T,
+    ) -> T {
+        self.with_shifted_in(DebruijnIndex::ONE, |ctx| {
+            let outer_len = ctx.bound_lifetimes.borrow().len();
+            ctx.bound_lifetimes.borrow_mut().extend(
+                lifetimes.iter().enumerate().map(|(idx, name)| (ctx.in_binders, idx, name.clone())),
+            );
+            let result = f(ctx);
+            ctx.bound_lifetimes.borrow_mut().truncate(outer_len);
+            result
+        })
+    }
+
     pub fn with_impl_trait_mode(self, impl_trait_mode: ImplTraitLoweringMode) -> Self {
         Self { impl_trait_mode, ..self }
     }
@@ -184,19 +220,19 @@ impl<'a> TyLoweringContext<'a> {
                 let inner_ty = self.lower_ty(inner);
                 TyKind::Slice(inner_ty).intern(&Interner)
             }
-            TypeRef::Reference(inner, _, mutability) => {
+            TypeRef::Reference(inner, lifetime, mutability) => {
                 let inner_ty = self.lower_ty(inner);
-                let lifetime = static_lifetime();
+                let lifetime = self.lower_lifetime(lifetime.as_ref());
                 TyKind::Ref(lower_to_chalk_mutability(*mutability), lifetime, inner_ty)
                     .intern(&Interner)
             }
             TypeRef::Placeholder => TyKind::Error.intern(&Interner),
-            TypeRef::Fn(params, is_varargs) => {
-                let substs = self.with_shifted_in(DebruijnIndex::ONE, |ctx| {
+            TypeRef::Fn(params, is_varargs, bound_lifetimes) => {
+                let substs = self.with_bound_lifetimes(bound_lifetimes, |ctx| {
                     Substitution::from_iter(&Interner, params.iter().map(|tr| ctx.lower_ty(tr)))
                 });
                 TyKind::Function(FnPointer {
-                    num_binders: 0, // FIXME lower `for<'a> fn()` correctly
+                    num_binders: bound_lifetimes.len(),
                     sig: FnSig { abi: (), safety: Safety::Safe, variadic: *is_varargs },
                     substitution: FnSubst(substs),
                 })
@@ -347,6 +383,22 @@ impl<'a> TyLoweringContext<'a> {
         (ty, res)
     }
 
+    /// Lifetimes bound by a `for<'a>` binder we're in become bound variables,
+    /// all others are `'static`.
+    fn lower_lifetime(&self, lifetime: Option<&LifetimeRef>) -> Lifetime {
+        let bound_lifetimes = self.bound_lifetimes.borrow();
+        let bound = lifetime.and_then(|lifetime| {
+            bound_lifetimes.iter().rev().find(|(_, _, name)| *name == lifetime.name)
+        });
+        match bound {
+            Some((binder, idx, _)) => {
+                let debruijn = DebruijnIndex::new(self.in_binders.depth() - binder.depth());
+                LifetimeData::BoundVar(BoundVar::new(debruijn, *idx)).intern(&Interner)
+            }
+            None => static_lifetime(),
+        }
+    }
+
     /// This is only for `generic_predicates_for_param`, where we can't just
     /// lower the self types of the predicates since that could lead to cycles.
     /// So we just check here if the `type_ref` resolves to a generic param, and which.
@@ -741,33 +793,34 @@ impl<'a> TyLoweringContext<'a> {
         where_predicate: &'a WherePredicate,
         ignore_bindings: bool,
     ) -> impl Iterator<Item = QuantifiedWhereClause> + 'a {
-        match where_predicate {
-            WherePredicate::ForLifetime { target, bound, .. }
-            | WherePredicate::TypeBound { target, bound } => {
-                let self_ty = match target {
-                    WherePredicateTypeTarget::TypeRef(type_ref) => self.lower_ty(type_ref),
-                    WherePredicateTypeTarget::TypeParam(param_id) => {
-                        let generic_def = self.resolver.generic_def().expect("generics in scope");
-                        let generics = generics(self.db.upcast(), generic_def);
-                        let param_id =
-                            hir_def::TypeParamId { parent: generic_def, local_id: *param_id };
-                        let placeholder = to_placeholder_idx(self.db, param_id);
-                        match self.type_param_mode {
-                            TypeParamLoweringMode::Placeholder => TyKind::Placeholder(placeholder),
-                            TypeParamLoweringMode::Variable => {
-                                let idx = generics.param_idx(param_id).expect("matching generics");
-                                TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, idx))
-                            }
-                        }
-                        .intern(&Interner)
+        let (where_lifetimes, target, bound): (&[Name], _, _) = match where_predicate {
+            WherePredicate::ForLifetime { lifetimes, target, bound } => (lifetimes, target, bound),
+            WherePredicate::TypeBound { target, bound } => (&[], target, bound),
+            WherePredicate::Lifetime { .. } => return vec![].into_iter(),
+        };
+        // The self type is inside of the binder of `for<'a> &'a T: Trait`.
+        let self_ty = match target {
+            WherePredicateTypeTarget::TypeRef(type_ref) => {
+                self.with_bound_lifetimes(where_lifetimes, |ctx| ctx.lower_ty(type_ref))
+            }
+            WherePredicateTypeTarget::TypeParam(param_id) => {
+                let generic_def = self.resolver.generic_def().expect("generics in scope");
+                let generics = generics(self.db.upcast(), generic_def);
+                let param_id = hir_def::TypeParamId { parent: generic_def, local_id: *param_id };
+                let placeholder = to_placeholder_idx(self.db, param_id);
+                match self.type_param_mode {
+                    TypeParamLoweringMode::Placeholder => TyKind::Placeholder(placeholder),
+                    TypeParamLoweringMode::Variable => {
+                        let idx = generics.param_idx(param_id).expect("matching generics");
+                        TyKind::BoundVar(BoundVar::new(DebruijnIndex::ONE, idx))
                     }
-                };
-                self.lower_type_bound(bound, self_ty, ignore_bindings)
-                    .collect::<Vec<_>>()
-                    .into_iter()
+                }
+                .intern(&Interner)
             }
-            WherePredicate::Lifetime { .. } => vec![].into_iter(),
-        }
+        };
+        self.lower_type_bound_in_binder(bound, where_lifetimes, self_ty, ignore_bindings)
+            .collect::<Vec<_>>()
+            .into_iter()
     }
 
     pub(crate) fn lower_type_bound(
@@ -776,33 +829,51 @@ impl<'a> TyLoweringContext<'a> {
         self_ty: Ty,
         ignore_bindings: bool,
     ) -> impl Iterator<Item = QuantifiedWhereClause> + 'a {
-        let mut bindings = None;
-        let trait_ref = match bound {
-            TypeBound::Path(path) => {
-                bindings = self.lower_trait_ref_from_path(path, Some(self_ty));
-                bindings.clone().map(WhereClause::Implemented).map(crate::wrap_empty_binders)
-            }
-            TypeBound::ForLifetime(_, path) => {
-                // FIXME Don't silently drop the hrtb lifetimes here
-                bindings = self.lower_trait_ref_from_path(path, Some(self_ty));
-                bindings.clone().map(WhereClause::Implemented).map(crate::wrap_empty_binders)
+        self.lower_type_bound_in_binder(bound, &[], self_ty.shifted_in(&Interner), ignore_bindings)
+    }
+
+    /// Lowers `bound` into where clauses, each inside of a binder for the
+    /// lifetimes of `for<'a> T: Trait<'a>` and `T: for<'a> Trait<'a>`. The
+    /// `self_ty` has to be inside of that binder already.
+    fn lower_type_bound_in_binder(
+        &'a self,
+        bound: &'a TypeBound,
+        where_lifetimes: &'a [Name],
+        self_ty: Ty,
+        ignore_bindings: bool,
+    ) -> impl Iterator<Item = QuantifiedWhereClause> + 'a {
+        let (lifetimes, path): (Vec<Name>, _) = match bound {
+            TypeBound::Path(path) => (where_lifetimes.to_vec(), Some(path)),
+            // Both binders at once are an error, but nothing we should trip
+            // over.
+            TypeBound::ForLifetime(lifetimes, path) => {
+                (where_lifetimes.iter().chain(lifetimes.iter()).cloned().collect(), Some(path))
             }
-            TypeBound::Lifetime(_) => None,
-            TypeBound::Error => None,
+            TypeBound::Lifetime(_) | TypeBound::Error => (Vec::new(), None),
         };
-        trait_ref.into_iter().chain(
-            bindings
-                .into_iter()
-                .filter(move |_| !ignore_bindings)
-                .flat_map(move |tr| self.assoc_type_bindings_from_type_bound(bound, tr)),
+        let trait_ref = path.and_then(|path| {
+            self.with_bound_lifetimes(&lifetimes, |ctx| {
+                ctx.lower_trait_ref_from_path(path, Some(self_ty))
+            })
+        });
+        let implemented = trait_ref
+            .clone()
+            .map(|tr| make_lifetime_binders(lifetimes.len(), WhereClause::Implemented(tr)));
+        implemented.into_iter().chain(
+            trait_ref.into_iter().filter(move |_| !ignore_bindings).flat_map(move |tr| {
+                self.assoc_type_bindings_from_type_bound(bound, &lifetimes, tr)
+            }),
         )
     }
 
+    /// Lowers the associated type bindings of `bound`. Like `trait_ref`, they
+    /// are inside of the binder of `lifetimes`.
     fn assoc_type_bindings_from_type_bound(
         &'a self,
         bound: &'a TypeBound,
+        lifetimes: &[Name],
         trait_ref: TraitRef,
-    ) -> impl Iterator<Item = QuantifiedWhereClause> + 'a {
+    ) -> SmallVec<[QuantifiedWhereClause; 1]> {
         let last_segment = match bound {
             TypeBound::Path(path) | TypeBound::ForLifetime(_, path) => path.segments().last(),
             TypeBound::Error | TypeBound::Lifetime(_) => None,
@@ -811,7 +882,7 @@ impl<'a> TyLoweringContext<'a> {
             .into_iter()
             .flat_map(|segment| segment.args_and_bindings.into_iter())
             .flat_map(|args_and_bindings| args_and_bindings.bindings.iter())
-            .flat_map(move |binding| {
+            .flat_map(|binding| {
                 let found = associated_type_by_name_including_super_traits(
                     self.db,
                     trait_ref.clone(),
@@ -829,20 +900,26 @@ impl<'a> TyLoweringContext<'a> {
                     binding.type_ref.as_ref().map_or(0, |_| 1) + binding.bounds.len(),
                 );
                 if let Some(type_ref) = &binding.type_ref {
-                    let ty = self.lower_ty(type_ref);
+                    let ty = self.with_bound_lifetimes(lifetimes, |ctx| ctx.lower_ty(type_ref));
                     let alias_eq =
                         AliasEq { alias: AliasTy::Projection(projection_ty.clone()), ty };
-                    preds.push(crate::wrap_empty_binders(WhereClause::AliasEq(alias_eq)));
-                }
-                for bound in &binding.bounds {
-                    preds.extend(self.lower_type_bound(
-                        bound,
-                        TyKind::Alias(AliasTy::Projection(projection_ty.clone())).intern(&Interner),
-                        false,
+                    preds.push(make_lifetime_binders(
+                        lifetimes.len(),
+                        WhereClause::AliasEq(alias_eq),
                     ));
                 }
+                // FIXME: bounds on projections that refer to higher-ranked
+                // lifetimes are dropped.
+                let projection =
+                    TyKind::Alias(AliasTy::Projection(projection_ty)).intern(&Interner);
+                if let Ok(projection) = projection.shifted_out(&Interner) {
+                    for bound in &binding.bounds {
+                        preds.extend(self.lower_type_bound(bound, projection.clone(), false));
+                    }
+                }
                 preds
             })
+            .collect()
     }
 
     fn lower_impl_trait(&self, bounds: &[Interned<TypeBound>]) -> ReturnTypeImplTrait {
@@ -903,14 +980,11 @@ pub fn associated_type_shorthand_candidates<R>(
         ),
         TypeNs::GenericParam(param_id) => {
             let predicates = db.generic_predicates_for_param(param_id);
-            let res = predicates.iter().find_map(|pred| match pred.skip_binders().skip_binders() {
-                // FIXME: how to correctly handle higher-ranked bounds here?
-                WhereClause::Implemented(tr) => search(
-                    tr.clone()
-                        .shifted_out_to(&Interner, DebruijnIndex::ONE)
-                        .expect("FIXME unexpected higher-ranked trait bound"),
-                ),
-                _ => None,
+            let res = predicates.iter().find_map(|pred| {
+                match crate::erase_bound_lifetimes(pred.skip_binders().clone()) {
+                    WhereClause::Implemented(tr) => search(tr),
+                    _ => None,
+                }
             });
             if let res @ Some(_) = res {
                 return res;
@@ -1386,3 +1460,16 @@ pub(crate) fn lower_to_chalk_mutability(m: hir_def::type_ref::Mutability) -> Mut
 fn make_binders<T: HasInterner<Interner = Interner>>(generics: &Generics, value: T) -> Binders<T> {
     crate::make_only_type_binders(generics.len(), value)
 }
+
+fn make_lifetime_binders<T: HasInterner<Interner = Interner>>(
+    num_lifetimes: usize,
+    value: T,
+) -> Binders<T> {
+    Binders::new(
+        VariableKinds::from_iter(
+            &Interner,
+            (0..num_lifetimes).map(|_| chalk_ir::VariableKind::Lifetime),
+        ),
+        value,
+    )
+}
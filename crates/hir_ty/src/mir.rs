@@ -0,0 +1,220 @@
+//! A MIR-like control-flow graph of a body, for diagnostics that depend on the
+//! order in which things happen, like uses of moved values.
+//!
+//! This is much simpler than rustc's MIR, as nothing is compiled from it: it
+//! records which places are read, moved, borrowed and (re)initialized, and how
+//! control flows between them, but lumps together the details of computations
+//! that don't matter for that.
+
+mod borrowck;
+mod lower;
+
+use hir_def::{
+    expr::{BinaryOp, ExprId, PatId, UnaryOp},
+    type_ref::Mutability,
+    FieldId, VariantId,
+};
+use la_arena::{Arena, ArenaMap, Idx};
+use rustc_hash::FxHashSet;
+use stdx::impl_from;
+
+use crate::Ty;
+
+pub use self::{
+    borrowck::{borrowck, BorrowckError},
+    lower::MirLowerError,
+};
+pub(crate) use lower::mir_body_query;
+
+pub type LocalId = Idx<Local>;
+pub type BasicBlockId = Idx<BasicBlock>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirBody {
+    pub basic_blocks: Arena<BasicBlock>,
+    pub locals: Arena<Local>,
+    pub start_block: BasicBlockId,
+    /// The local that holds the value of the body when it returns.
+    pub return_local: LocalId,
+    /// The locals holding the arguments, which are initialized when the body
+    /// starts. The parameter patterns are bound from them.
+    pub param_locals: Vec<LocalId>,
+    /// The local of each binding. The bindings of the alternatives of an or
+    /// pattern share one local.
+    pub binding_locals: ArenaMap<PatId, LocalId>,
+    /// The block in which the evaluation of each lowered expression starts.
+    pub expr_blocks: ArenaMap<ExprId, BasicBlockId>,
+}
+
+impl MirBody {
+    /// The blocks that control can reach from the start of the body.
+    pub fn reachable_blocks(&self) -> FxHashSet<BasicBlockId> {
+        let mut reachable = FxHashSet::default();
+        let mut stack = vec![self.start_block];
+        while let Some(block) = stack.pop() {
+            if reachable.insert(block) {
+                let terminator = self.basic_blocks[block].terminator.as_ref();
+                stack.extend(terminator.into_iter().flat_map(|it| it.kind.successors()));
+            }
+        }
+        reachable
+    }
+}
+
+/// A variable or a temporary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Local {
+    pub ty: Ty,
+    /// The pattern that declares the local, or `None` for temporaries.
+    pub binding: Option<PatId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub statements: Vec<Statement>,
+    /// How control leaves the block. This is only `None` while lowering.
+    pub terminator: Option<Terminator>,
+}
+
+/// The part of the body a statement or terminator was lowered from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MirSpan {
+    ExprId(ExprId),
+    PatId(PatId),
+    Unknown,
+}
+impl_from!(ExprId, PatId for MirSpan);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: MirSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatementKind {
+    Assign(Place, Rvalue),
+    /// Reads a place without using its value, like the scrutinee of a `match`
+    /// that only has wildcard arms.
+    FakeRead(Place),
+    /// Starts the scope of a local, which is uninitialized until it's
+    /// assigned to.
+    StorageLive(LocalId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Terminator {
+    pub kind: TerminatorKind,
+    pub span: MirSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminatorKind {
+    Goto {
+        target: BasicBlockId,
+    },
+    /// Branches on a value, like the condition of an `if` or the discriminant
+    /// of a `match`. Which target is taken for which value isn't recorded.
+    SwitchInt {
+        discr: Operand,
+        targets: Vec<BasicBlockId>,
+    },
+    /// Calls a function. Desugared calls, like the `Iterator::next` calls of a
+    /// `for` loop, call a constant of unknown type.
+    Call {
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Place,
+        /// Where to continue after the call, or `None` if it never returns.
+        target: Option<BasicBlockId>,
+    },
+    Return,
+    Unreachable,
+}
+
+impl TerminatorKind {
+    pub fn successors(&self) -> Vec<BasicBlockId> {
+        match self {
+            TerminatorKind::Goto { target } => vec![*target],
+            TerminatorKind::SwitchInt { targets, .. } => targets.clone(),
+            TerminatorKind::Call { target, .. } => target.iter().copied().collect(),
+            TerminatorKind::Return | TerminatorKind::Unreachable => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Place {
+    pub local: LocalId,
+    pub projection: Vec<ProjectionElem>,
+}
+
+impl Place {
+    fn project(&self, elem: ProjectionElem) -> Place {
+        let mut projection = self.projection.clone();
+        projection.push(elem);
+        Place { local: self.local, projection }
+    }
+}
+
+impl From<LocalId> for Place {
+    fn from(local: LocalId) -> Place {
+        Place { local, projection: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProjectionElem {
+    Deref,
+    Field(FieldId),
+    TupleField(usize),
+    Index(LocalId),
+    /// An element of a slice pattern.
+    ConstantIndex {
+        offset: usize,
+        from_end: bool,
+    },
+    /// The `rest @ ..` part of a slice pattern.
+    Subslice {
+        from: usize,
+        to: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    /// Reads the place without invalidating it.
+    Copy(Place),
+    /// Moves the value out of the place, which can't be used again until it's
+    /// reinitialized.
+    Move(Place),
+    Constant(Ty),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rvalue {
+    Use(Operand),
+    /// Takes a reference or a raw pointer to the place.
+    Ref(Mutability, Place),
+    BinaryOp(BinaryOp, Operand, Operand),
+    UnaryOp(UnaryOp, Operand),
+    Cast(Operand, Ty),
+    Aggregate(AggregateKind, Vec<Operand>),
+    /// An array of the same value, like `[0; 32]`.
+    Repeat(Operand),
+    Discriminant(Place),
+    /// Moves the value into a new box, like `box value`.
+    Box(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregateKind {
+    Tuple,
+    Array,
+    /// A struct, union or enum variant, with the operands in the order of the
+    /// fields.
+    Adt(VariantId),
+    /// A closure or an async block, with the operands reading the captured
+    /// variables.
+    Closure,
+}
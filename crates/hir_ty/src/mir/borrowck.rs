@@ -0,0 +1,246 @@
+//! Finds uses of moved and possibly-uninitialized variables, by computing
+//! which locals and fields may be moved out of or uninitialized at each point
+//! of the body.
+//!
+//! Unlike rustc, this doesn't check borrows against later writes or moves:
+//! references aren't tracked at all.
+
+use hir_def::expr::PatId;
+use la_arena::ArenaMap;
+use rustc_hash::FxHashSet;
+
+use crate::TyExt;
+
+use super::{
+    BasicBlockId, Local, LocalId, MirBody, MirSpan, Operand, Place, ProjectionElem, Rvalue,
+    StatementKind, TerminatorKind,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BorrowckError {
+    UseOfMovedValue { span: MirSpan, binding: PatId, is_borrow: bool },
+    PossiblyUninitialized { span: MirSpan, binding: PatId, is_borrow: bool },
+}
+
+pub fn borrowck(body: &MirBody) -> Vec<BorrowckError> {
+    let _p = profile::span("borrowck");
+    let mut entry_states: ArenaMap<BasicBlockId, State> = ArenaMap::default();
+    let mut initial = State::default();
+    for (local, _) in body.locals.iter() {
+        if !body.param_locals.contains(&local) {
+            initial.maybe_uninit.insert(local);
+        }
+    }
+    entry_states.insert(body.start_block, initial);
+
+    let mut worklist = vec![body.start_block];
+    while let Some(block) = worklist.pop() {
+        let mut state = entry_states[block].clone();
+        let mut checker = Checker { body, errors: None };
+        checker.visit_block(block, &mut state);
+        let terminator = body.basic_blocks[block].terminator.as_ref();
+        let successors = terminator.into_iter().flat_map(|it| it.kind.successors());
+        for succ in successors {
+            let changed = match entry_states.get_mut(succ) {
+                Some(succ_state) => succ_state.join(&state),
+                None => {
+                    entry_states.insert(succ, state.clone());
+                    true
+                }
+            };
+            if changed && !worklist.contains(&succ) {
+                worklist.push(succ);
+            }
+        }
+    }
+
+    // Now that the states are known, report the errors, once per variable.
+    let mut checker = Checker { body, errors: Some((Vec::new(), FxHashSet::default())) };
+    for (block, _) in body.basic_blocks.iter() {
+        if let Some(state) = entry_states.get(block) {
+            checker.visit_block(block, &mut state.clone());
+        }
+    }
+    checker.errors.map(|(errors, _)| errors).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    maybe_uninit: FxHashSet<LocalId>,
+    /// The places that may have been moved out of. Only moves out of locals
+    /// and their fields are tracked.
+    maybe_moved: FxHashSet<Place>,
+}
+
+impl State {
+    fn join(&mut self, other: &State) -> bool {
+        let len = self.maybe_uninit.len() + self.maybe_moved.len();
+        self.maybe_uninit.extend(other.maybe_uninit.iter().copied());
+        self.maybe_moved.extend(other.maybe_moved.iter().cloned());
+        len != self.maybe_uninit.len() + self.maybe_moved.len()
+    }
+}
+
+struct Checker<'a> {
+    body: &'a MirBody,
+    /// The errors found so far and the locals they were reported for, or
+    /// `None` while the states are being computed.
+    errors: Option<(Vec<BorrowckError>, FxHashSet<LocalId>)>,
+}
+
+impl Checker<'_> {
+    fn visit_block(&mut self, block: BasicBlockId, state: &mut State) {
+        let block = &self.body.basic_blocks[block];
+        for statement in &block.statements {
+            let span = statement.span;
+            match &statement.kind {
+                StatementKind::Assign(place, rvalue) => {
+                    self.visit_rvalue(rvalue, span, state);
+                    self.write(place, span, state);
+                }
+                StatementKind::FakeRead(place) => self.read(place, span, false, state),
+                StatementKind::StorageLive(local) => {
+                    state.maybe_uninit.insert(*local);
+                    state.maybe_moved.retain(|it| it.local != *local);
+                }
+            }
+        }
+        let terminator = match &block.terminator {
+            Some(it) => it,
+            None => return,
+        };
+        let span = terminator.span;
+        match &terminator.kind {
+            TerminatorKind::SwitchInt { discr, .. } => self.visit_operand(discr, span, state),
+            TerminatorKind::Call { func, args, destination, .. } => {
+                self.visit_operand(func, span, state);
+                for arg in args {
+                    self.visit_operand(arg, span, state);
+                }
+                self.write(destination, span, state);
+            }
+            TerminatorKind::Goto { .. } | TerminatorKind::Return | TerminatorKind::Unreachable => {}
+        }
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue, span: MirSpan, state: &mut State) {
+        match rvalue {
+            Rvalue::Use(operand)
+            | Rvalue::UnaryOp(_, operand)
+            | Rvalue::Cast(operand, _)
+            | Rvalue::Repeat(operand)
+            | Rvalue::Box(operand) => self.visit_operand(operand, span, state),
+            Rvalue::BinaryOp(_, lhs, rhs) => {
+                self.visit_operand(lhs, span, state);
+                self.visit_operand(rhs, span, state);
+            }
+            Rvalue::Aggregate(_, operands) => {
+                for operand in operands {
+                    self.visit_operand(operand, span, state);
+                }
+            }
+            Rvalue::Ref(_, place) => self.read(place, span, true, state),
+            Rvalue::Discriminant(place) => self.read(place, span, false, state),
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &Operand, span: MirSpan, state: &mut State) {
+        match operand {
+            Operand::Copy(place) => self.read(place, span, false, state),
+            Operand::Move(place) => {
+                self.read(place, span, false, state);
+                if let Some(path) = move_path(place) {
+                    state.maybe_moved.insert(path);
+                }
+            }
+            Operand::Constant(_) => {}
+        }
+    }
+
+    fn read(&mut self, place: &Place, span: MirSpan, is_borrow: bool, state: &State) {
+        self.read_indices(place, span, state);
+        if state.maybe_uninit.contains(&place.local) {
+            self.report(place.local, |binding| BorrowckError::PossiblyUninitialized {
+                span,
+                binding,
+                is_borrow,
+            });
+            return;
+        }
+        let path = tracked_prefix(place);
+        let is_moved = state.maybe_moved.iter().any(|moved| {
+            moved.local == path.local
+                && (moved.projection.starts_with(&path.projection)
+                    || path.projection.starts_with(&moved.projection))
+        });
+        if is_moved {
+            self.report(place.local, |binding| BorrowckError::UseOfMovedValue {
+                span,
+                binding,
+                is_borrow,
+            });
+        }
+    }
+
+    fn write(&mut self, place: &Place, span: MirSpan, state: &mut State) {
+        self.read_indices(place, span, state);
+        if place.projection.is_empty() {
+            state.maybe_uninit.remove(&place.local);
+            state.maybe_moved.retain(|it| it.local != place.local);
+        } else if let Some(path) = move_path(place) {
+            // Assigning to a field reinitializes everything that was moved
+            // out of it.
+            state.maybe_moved.retain(|it| {
+                it.local != path.local || !it.projection.starts_with(&path.projection)
+            });
+        } else {
+            // Writing through a reference reads the reference.
+            self.read(&tracked_prefix(place), span, false, state);
+        }
+    }
+
+    fn read_indices(&mut self, place: &Place, span: MirSpan, state: &State) {
+        for elem in &place.projection {
+            if let ProjectionElem::Index(local) = elem {
+                self.read(&(*local).into(), span, false, state);
+            }
+        }
+    }
+
+    fn report(&mut self, local: LocalId, f: impl FnOnce(PatId) -> BorrowckError) {
+        let (errors, reported) = match &mut self.errors {
+            Some(it) => it,
+            None => return,
+        };
+        // Locals of unknown type are likely in code with other errors.
+        let binding = match &self.body.locals[local] {
+            Local { binding: Some(it), ty } if !ty.is_unknown() => *it,
+            _ => return,
+        };
+        if reported.insert(local) {
+            errors.push(f(binding));
+        }
+    }
+}
+
+/// The part of the place up to the first projection that leaves the memory
+/// of the local, like a dereference.
+fn tracked_prefix(place: &Place) -> Place {
+    let projection = place
+        .projection
+        .iter()
+        .take_while(|it| matches!(it, ProjectionElem::Field(_) | ProjectionElem::TupleField(_)))
+        .cloned()
+        .collect();
+    Place { local: place.local, projection }
+}
+
+/// The place if moves out of it are tracked.
+fn move_path(place: &Place) -> Option<Place> {
+    let path = tracked_prefix(place);
+    if path.projection.len() == place.projection.len() {
+        Some(path)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,1330 @@
+//! Lowers a body to its MIR-like control-flow graph, using the inference
+//! results to resolve methods, fields and the types of moved values.
+
+use std::{collections::hash_map::Entry, sync::Arc};
+
+use base_db::CrateId;
+use hir_def::{
+    body::Body,
+    expr::{
+        Array, BinaryOp, BindingAnnotation, Expr, ExprId, MatchArm, Pat, PatId,
+        Statement as HirStatement, UnaryOp,
+    },
+    resolver::{resolver_for_expr, ResolveValueResult, ValueNs},
+    type_ref::Mutability,
+    DefWithBodyId, FieldId, HasModule, LocalFieldId, TraitId,
+};
+use hir_expand::name::Name;
+use la_arena::{Arena, ArenaMap};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    db::HirDatabase,
+    infer::{Adjust, Adjustment, AutoBorrow},
+    method_resolution, static_lifetime, Canonical, CanonicalVarKinds, InferenceResult, Interner,
+    TraitEnvironment, Ty, TyExt, TyKind, TypeWalk,
+};
+
+use super::{
+    AggregateKind, BasicBlock, BasicBlockId, Local, LocalId, MirBody, MirSpan, Operand, Place,
+    ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MirLowerError {
+    /// The body has syntax errors.
+    Missing,
+    UnresolvedName,
+    UnresolvedField,
+    /// A `break` or `continue` outside of a loop.
+    BreakOutsideOfLoop,
+    /// The body uses something the lowering doesn't support yet.
+    Unsupported(&'static str),
+}
+
+type Result<T> = std::result::Result<T, MirLowerError>;
+
+/// Unwraps the result of lowering an expression, returning `Ok(None)` if the
+/// expression diverges.
+macro_rules! unwrap_or_diverge {
+    ($e:expr) => {
+        match $e {
+            Some(it) => it,
+            None => return Ok(None),
+        }
+    };
+}
+
+pub(crate) fn mir_body_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Result<Arc<MirBody>> {
+    let _p = profile::span("mir_body_query");
+    let body = db.body(def);
+    let infer = db.infer(def);
+    let krate = def.module(db.upcast()).krate();
+    let trait_env = def
+        .as_generic_def_id()
+        .map_or_else(|| Arc::new(TraitEnvironment::empty(krate)), |it| db.trait_environment(it));
+
+    let mut basic_blocks = Arena::new();
+    let start_block = basic_blocks.alloc(BasicBlock { statements: Vec::new(), terminator: None });
+    let mut locals = Arena::new();
+    let return_local = locals.alloc(Local { ty: infer[body.body_expr].clone(), binding: None });
+    let mut ctx = MirLowerCtx {
+        db,
+        owner: def,
+        body: &body,
+        infer: &infer,
+        krate,
+        trait_env,
+        copy_trait: db.lang_item(krate, "copy".into()).and_then(|it| it.as_trait()),
+        is_copy_cache: FxHashMap::default(),
+        breakables: Vec::new(),
+        result: MirBody {
+            basic_blocks,
+            locals,
+            start_block,
+            return_local,
+            param_locals: Vec::new(),
+            binding_locals: ArenaMap::default(),
+            expr_blocks: ArenaMap::default(),
+        },
+    };
+
+    for &param in &body.params {
+        let local = ctx.new_temp(infer[param].clone());
+        ctx.result.param_locals.push(local);
+        ctx.declare_bindings(start_block, param);
+        ctx.bind_pattern(start_block, param, local.into(), BindMode::Move)?;
+    }
+    if let Some(end) = ctx.lower_expr_to_place(body.body_expr, return_local.into(), start_block)? {
+        ctx.set_terminator(end, TerminatorKind::Return, MirSpan::Unknown);
+    }
+    Ok(Arc::new(ctx.result))
+}
+
+struct MirLowerCtx<'a> {
+    db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: &'a Body,
+    infer: &'a InferenceResult,
+    krate: CrateId,
+    trait_env: Arc<TraitEnvironment>,
+    copy_trait: Option<TraitId>,
+    is_copy_cache: FxHashMap<Ty, bool>,
+    /// The loops and labeled blocks around the expression being lowered.
+    breakables: Vec<BreakableContext>,
+    result: MirBody,
+}
+
+struct BreakableContext {
+    label: Option<Name>,
+    /// Where `continue` jumps to, or `None` for labeled blocks.
+    begin: Option<BasicBlockId>,
+    /// Where `break` jumps to, created on the first `break`.
+    end: Option<BasicBlockId>,
+    /// Where `break` stores its value.
+    place: Place,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BindMode {
+    /// Moves the non-`Copy` values into the bindings.
+    Move,
+    /// Initializes the bindings without moving, for match guards, which only
+    /// see the bindings by reference.
+    NoMove,
+    /// The bindings borrow the matched place, because of a default binding
+    /// mode.
+    Ref,
+}
+
+impl MirLowerCtx<'_> {
+    fn lower_expr_to_some_operand(
+        &mut self,
+        expr_id: ExprId,
+        current: BasicBlockId,
+    ) -> Result<Option<(Operand, BasicBlockId)>> {
+        let (temp, current) = unwrap_or_diverge!(self.lower_expr_to_temp(expr_id, current)?);
+        Ok(Some((Operand::Move(temp.into()), current)))
+    }
+
+    fn lower_expr_to_temp(
+        &mut self,
+        expr_id: ExprId,
+        current: BasicBlockId,
+    ) -> Result<Option<(LocalId, BasicBlockId)>> {
+        let temp = self.new_temp(self.expr_ty_after_adjustments(expr_id));
+        let current =
+            unwrap_or_diverge!(self.lower_expr_to_place(expr_id, temp.into(), current)?);
+        Ok(Some((temp, current)))
+    }
+
+    fn lower_expr_as_place(
+        &mut self,
+        current: BasicBlockId,
+        expr_id: ExprId,
+    ) -> Result<Option<(Place, BasicBlockId)>> {
+        let infer = self.infer;
+        let adjustments = infer.expr_adjustments.get(&expr_id).map_or(&[][..], |it| &**it);
+        self.lower_expr_as_place_with_adjust(current, expr_id, adjustments)
+    }
+
+    fn lower_expr_as_place_with_adjust(
+        &mut self,
+        current: BasicBlockId,
+        expr_id: ExprId,
+        adjustments: &[Adjustment],
+    ) -> Result<Option<(Place, BasicBlockId)>> {
+        let (last, rest) = match adjustments.split_last() {
+            Some(it) => it,
+            None => return self.lower_expr_as_place_without_adjust(current, expr_id),
+        };
+        let (place, current) =
+            unwrap_or_diverge!(self.lower_expr_as_place_with_adjust(current, expr_id, rest)?);
+        let span = expr_id.into();
+        let place = match last.kind {
+            Adjust::Deref(None) => place.project(ProjectionElem::Deref),
+            Adjust::Deref(Some(_)) => {
+                // An overloaded deref calls `Deref::deref(&place)` and
+                // dereferences the result.
+                let ty =
+                    TyKind::Ref(crate::Mutability::Not, static_lifetime(), last.target.clone())
+                        .intern(&Interner);
+                let temp = self.new_temp(ty);
+                self.push_assignment(
+                    current,
+                    temp.into(),
+                    Rvalue::Ref(Mutability::Shared, place),
+                    span,
+                );
+                Place::from(temp).project(ProjectionElem::Deref)
+            }
+            Adjust::Borrow(AutoBorrow::Ref(m)) | Adjust::Borrow(AutoBorrow::RawPtr(m)) => {
+                let temp = self.new_temp(last.target.clone());
+                let rvalue = Rvalue::Ref(from_chalk_mutability(m), place);
+                self.push_assignment(current, temp.into(), rvalue, span);
+                temp.into()
+            }
+            Adjust::Pointer(_) | Adjust::NeverToAny => {
+                let ty =
+                    rest.last().map_or_else(|| self.infer[expr_id].clone(), |it| it.target.clone());
+                let temp = self.new_temp(last.target.clone());
+                let operand = self.operand_for_place(place, &ty);
+                self.push_assignment(current, temp.into(), Rvalue::Use(operand), span);
+                temp.into()
+            }
+        };
+        Ok(Some((place, current)))
+    }
+
+    fn lower_expr_as_place_without_adjust(
+        &mut self,
+        current: BasicBlockId,
+        expr_id: ExprId,
+    ) -> Result<Option<(Place, BasicBlockId)>> {
+        self.record_expr_block(expr_id, current);
+        match &self.body.exprs[expr_id] {
+            Expr::Path(path) => {
+                if let Some(local) = self.resolve_local(expr_id, path)? {
+                    return Ok(Some((local.into(), current)));
+                }
+            }
+            Expr::Field { expr, name } => {
+                let (place, current) =
+                    unwrap_or_diverge!(self.lower_expr_as_place(current, *expr)?);
+                let elem = match self.infer.field_resolution(expr_id) {
+                    Some(field) => ProjectionElem::Field(field),
+                    None => ProjectionElem::TupleField(
+                        name.as_tuple_index().ok_or(MirLowerError::UnresolvedField)?,
+                    ),
+                };
+                return Ok(Some((place.project(elem), current)));
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                let (place, current) =
+                    unwrap_or_diverge!(self.lower_expr_as_place(current, *expr)?);
+                let ty = self.expr_ty_after_adjustments(*expr);
+                if ty.as_reference_or_ptr().is_some() {
+                    return Ok(Some((place.project(ProjectionElem::Deref), current)));
+                }
+                // `Box` and overloaded derefs both read the place, which is
+                // all that matters here.
+                let temp = self.new_temp(TyKind::Error.intern(&Interner));
+                let rvalue = Rvalue::Ref(Mutability::Shared, place);
+                self.push_assignment(current, temp.into(), rvalue, expr_id.into());
+                return Ok(Some((Place::from(temp).project(ProjectionElem::Deref), current)));
+            }
+            Expr::Index { base, index } => {
+                let (place, current) =
+                    unwrap_or_diverge!(self.lower_expr_as_place(current, *base)?);
+                let (index, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_temp(*index, current)?);
+                return Ok(Some((place.project(ProjectionElem::Index(index)), current)));
+            }
+            _ => {}
+        }
+        let temp = self.new_temp(self.infer[expr_id].clone());
+        let current = unwrap_or_diverge!(self.lower_expr_to_place_without_adjust(
+            expr_id,
+            temp.into(),
+            current
+        )?);
+        Ok(Some((temp.into(), current)))
+    }
+
+    fn lower_expr_to_place(
+        &mut self,
+        expr_id: ExprId,
+        place: Place,
+        current: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>> {
+        let infer = self.infer;
+        match infer.expr_adjustments.get(&expr_id).map(|it| &**it) {
+            None | Some([]) | Some([Adjustment { kind: Adjust::NeverToAny, .. }]) => {
+                self.lower_expr_to_place_without_adjust(expr_id, place, current)
+            }
+            Some(adjustments) => {
+                let (p, current) = unwrap_or_diverge!(self.lower_expr_as_place_with_adjust(
+                    current,
+                    expr_id,
+                    adjustments
+                )?);
+                let ty = self.expr_ty_after_adjustments(expr_id);
+                let operand = self.operand_for_place(p, &ty);
+                self.push_assignment(current, place, Rvalue::Use(operand), expr_id.into());
+                Ok(Some(current))
+            }
+        }
+    }
+
+    fn lower_expr_to_place_without_adjust(
+        &mut self,
+        expr_id: ExprId,
+        place: Place,
+        current: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>> {
+        self.record_expr_block(expr_id, current);
+        let span = expr_id.into();
+        match &self.body.exprs[expr_id] {
+            Expr::Missing => Err(MirLowerError::Missing),
+            Expr::Path(path) => {
+                let operand = match self.resolve_local(expr_id, path)? {
+                    Some(local) => self.operand_for_place(local.into(), &self.infer[expr_id]),
+                    None => Operand::Constant(self.infer[expr_id].clone()),
+                };
+                self.push_assignment(current, place, Rvalue::Use(operand), span);
+                Ok(Some(current))
+            }
+            Expr::Field { .. } | Expr::Index { .. } | Expr::UnaryOp { op: UnaryOp::Deref, .. } => {
+                let (p, current) =
+                    unwrap_or_diverge!(self.lower_expr_as_place_without_adjust(current, expr_id)?);
+                let operand = self.operand_for_place(p, &self.infer[expr_id]);
+                self.push_assignment(current, place, Rvalue::Use(operand), span);
+                Ok(Some(current))
+            }
+            Expr::Literal(_) | Expr::Const { .. } => {
+                let operand = Operand::Constant(self.infer[expr_id].clone());
+                self.push_assignment(current, place, Rvalue::Use(operand), span);
+                Ok(Some(current))
+            }
+            Expr::If { condition, then_branch, else_branch } => {
+                let (discr, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*condition, current)?);
+                let then_block = self.new_basic_block();
+                let else_block = self.new_basic_block();
+                let targets = vec![then_block, else_block];
+                self.set_terminator(current, TerminatorKind::SwitchInt { discr, targets }, span);
+                let then_end = self.lower_expr_to_place(*then_branch, place.clone(), then_block)?;
+                let else_end = match else_branch {
+                    Some(else_branch) => {
+                        self.lower_expr_to_place(*else_branch, place, else_block)?
+                    }
+                    None => {
+                        self.push_unit(else_block, place, span);
+                        Some(else_block)
+                    }
+                };
+                Ok(self.merge_blocks(&[then_end, else_end], span))
+            }
+            Expr::Block { statements, tail, label, .. } => match label {
+                Some(label) => {
+                    let label = self.body.labels[*label].name.clone();
+                    self.breakables.push(BreakableContext {
+                        label: Some(label),
+                        begin: None,
+                        end: None,
+                        place: place.clone(),
+                    });
+                    let end = self.lower_block_to_place(statements, *tail, place, current)?;
+                    let breakable = self.breakables.pop().unwrap();
+                    Ok(self.merge_blocks(&[end, breakable.end], span))
+                }
+                None => self.lower_block_to_place(statements, *tail, place, current),
+            },
+            Expr::Loop { body, label } => {
+                let begin = self.new_basic_block();
+                self.set_goto(current, begin, span);
+                self.breakables.push(BreakableContext {
+                    label: label.map(|it| self.body.labels[it].name.clone()),
+                    begin: Some(begin),
+                    end: None,
+                    place,
+                });
+                let unit = self.new_unit_temp();
+                if let Some(end) = self.lower_expr_to_place(*body, unit.into(), begin)? {
+                    self.set_goto(end, begin, span);
+                }
+                Ok(self.breakables.pop().unwrap().end)
+            }
+            Expr::While { condition, body, label } => {
+                let begin = self.new_basic_block();
+                self.set_goto(current, begin, span);
+                let end = self.new_basic_block();
+                self.breakables.push(BreakableContext {
+                    label: label.map(|it| self.body.labels[it].name.clone()),
+                    begin: Some(begin),
+                    end: Some(end),
+                    place: place.clone(),
+                });
+                if let Some((discr, current)) =
+                    self.lower_expr_to_some_operand(*condition, begin)?
+                {
+                    let body_block = self.new_basic_block();
+                    let targets = vec![body_block, end];
+                    self.set_terminator(
+                        current,
+                        TerminatorKind::SwitchInt { discr, targets },
+                        span,
+                    );
+                    let unit = self.new_unit_temp();
+                    if let Some(body_end) =
+                        self.lower_expr_to_place(*body, unit.into(), body_block)?
+                    {
+                        self.set_goto(body_end, begin, span);
+                    }
+                }
+                self.breakables.pop();
+                self.push_unit(end, place, span);
+                Ok(Some(end))
+            }
+            Expr::For { iterable, pat, body, label } => {
+                // Desugared to calls of `IntoIterator::into_iter` and
+                // `Iterator::next`, whose result is matched against `pat`.
+                let (iterable, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*iterable, current)?);
+                let iter = self.new_temp(TyKind::Error.intern(&Interner));
+                let begin = self.new_basic_block();
+                self.set_terminator(
+                    current,
+                    TerminatorKind::Call {
+                        func: unknown_operand(),
+                        args: vec![iterable],
+                        destination: iter.into(),
+                        target: Some(begin),
+                    },
+                    span,
+                );
+                let end = self.new_basic_block();
+                self.breakables.push(BreakableContext {
+                    label: label.map(|it| self.body.labels[it].name.clone()),
+                    begin: Some(begin),
+                    end: Some(end),
+                    place: place.clone(),
+                });
+                let iter_ref = self.new_temp(TyKind::Error.intern(&Interner));
+                self.push_assignment(
+                    begin,
+                    iter_ref.into(),
+                    Rvalue::Ref(Mutability::Mut, iter.into()),
+                    span,
+                );
+                let item = self.new_temp(TyKind::Error.intern(&Interner));
+                let after_next = self.new_basic_block();
+                self.set_terminator(
+                    begin,
+                    TerminatorKind::Call {
+                        func: unknown_operand(),
+                        args: vec![Operand::Move(iter_ref.into())],
+                        destination: item.into(),
+                        target: Some(after_next),
+                    },
+                    span,
+                );
+                let body_block = self.new_basic_block();
+                let discr = Operand::Copy(item.into());
+                let targets = vec![body_block, end];
+                self.set_terminator(after_next, TerminatorKind::SwitchInt { discr, targets }, span);
+                self.declare_bindings(body_block, *pat);
+                self.bind_pattern(body_block, *pat, item.into(), BindMode::Move)?;
+                let unit = self.new_unit_temp();
+                if let Some(body_end) = self.lower_expr_to_place(*body, unit.into(), body_block)? {
+                    self.set_goto(body_end, begin, span);
+                }
+                self.breakables.pop();
+                self.push_unit(end, place, span);
+                Ok(Some(end))
+            }
+            Expr::Call { callee, args } => {
+                // Calling a closure through a variable only borrows it, unless
+                // it's an `FnOnce`, which we don't bother to tell apart. The
+                // callee is read before the arguments are evaluated.
+                let (func, current) =
+                    unwrap_or_diverge!(self.lower_expr_as_place(current, *callee)?);
+                let temp = self.new_temp(self.expr_ty_after_adjustments(*callee));
+                let rvalue = Rvalue::Use(Operand::Copy(func));
+                self.push_assignment(current, temp.into(), rvalue, (*callee).into());
+                self.lower_call(
+                    Operand::Move(temp.into()),
+                    Vec::new(),
+                    args,
+                    place,
+                    current,
+                    expr_id,
+                )
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                let (func, subst) = match self.infer.method_resolution(expr_id) {
+                    Some(it) => it,
+                    None => {
+                        // We don't know whether the receiver is moved or
+                        // borrowed, so it's only read.
+                        let (p, current) =
+                            unwrap_or_diverge!(self.lower_expr_as_place(current, *receiver)?);
+                        let receiver = Operand::Copy(p);
+                        return self.lower_call(
+                            unknown_operand(),
+                            vec![receiver],
+                            args,
+                            place,
+                            current,
+                            expr_id,
+                        );
+                    }
+                };
+                let sig =
+                    self.db.callable_item_signature(func.into()).substitute(&Interner, &subst);
+                let self_param = sig.params().first().and_then(|it| it.as_reference_or_ptr());
+                let receiver_is_ref = self.infer[*receiver].as_reference_or_ptr().is_some();
+                let (receiver_op, current) = match self_param {
+                    Some((_, _, m)) if !receiver_is_ref => {
+                        // The receiver is borrowed automatically.
+                        let (p, current) =
+                            unwrap_or_diverge!(self.lower_expr_as_place(current, *receiver)?);
+                        let temp = self.new_temp(sig.params()[0].clone());
+                        let rvalue = Rvalue::Ref(from_chalk_mutability(m), p);
+                        self.push_assignment(current, temp.into(), rvalue, (*receiver).into());
+                        (Operand::Move(temp.into()), current)
+                    }
+                    Some(_) => {
+                        // The receiver reference is reborrowed.
+                        let (p, current) =
+                            unwrap_or_diverge!(self.lower_expr_as_place(current, *receiver)?);
+                        (Operand::Copy(p), current)
+                    }
+                    None => {
+                        unwrap_or_diverge!(self.lower_expr_to_some_operand(*receiver, current)?)
+                    }
+                };
+                let func_ty = self.db.value_ty(func.into()).substitute(&Interner, &subst);
+                let func = Operand::Constant(func_ty);
+                self.lower_call(func, vec![receiver_op], args, place, current, expr_id)
+            }
+            Expr::Match { expr, arms } => self.lower_match(expr_id, *expr, arms, place, current),
+            Expr::Break { expr, label } => {
+                let idx = self.find_breakable(label.as_ref(), false)?;
+                let current = match expr {
+                    Some(expr) => {
+                        let place = self.breakables[idx].place.clone();
+                        unwrap_or_diverge!(self.lower_expr_to_place(*expr, place, current)?)
+                    }
+                    None => current,
+                };
+                let end = match self.breakables[idx].end {
+                    Some(end) => end,
+                    None => {
+                        let end = self.new_basic_block();
+                        self.breakables[idx].end = Some(end);
+                        end
+                    }
+                };
+                self.set_goto(current, end, span);
+                Ok(None)
+            }
+            Expr::Continue { label } => {
+                let idx = self.find_breakable(label.as_ref(), true)?;
+                let begin = self.breakables[idx].begin.ok_or(MirLowerError::BreakOutsideOfLoop)?;
+                self.set_goto(current, begin, span);
+                Ok(None)
+            }
+            Expr::Return { expr } => {
+                let current = match expr {
+                    Some(expr) => {
+                        let place = self.result.return_local.into();
+                        unwrap_or_diverge!(self.lower_expr_to_place(*expr, place, current)?)
+                    }
+                    None => current,
+                };
+                self.set_terminator(current, TerminatorKind::Return, span);
+                Ok(None)
+            }
+            Expr::Yield { .. } => Err(MirLowerError::Unsupported("yield")),
+            Expr::TryBlock { .. } => Err(MirLowerError::Unsupported("try block")),
+            Expr::RecordLit { fields, spread, .. } => {
+                let variant = self
+                    .infer
+                    .variant_resolution_for_expr(expr_id)
+                    .ok_or(MirLowerError::UnresolvedName)?;
+                let variant_data = variant.variant_data(self.db.upcast());
+                let mut operands: FxHashMap<LocalFieldId, Operand> = FxHashMap::default();
+                let mut current = current;
+                for field in fields {
+                    let local_id =
+                        variant_data.field(&field.name).ok_or(MirLowerError::UnresolvedField)?;
+                    let (operand, c) =
+                        unwrap_or_diverge!(self.lower_expr_to_some_operand(field.expr, current)?);
+                    current = c;
+                    operands.insert(local_id, operand);
+                }
+                if let Some(spread) = spread {
+                    // The fields that aren't given are moved out of the base.
+                    let (base, c) = unwrap_or_diverge!(self.lower_expr_as_place(current, *spread)?);
+                    current = c;
+                    let subst = match self.infer[expr_id].kind(&Interner) {
+                        TyKind::Adt(_, subst) => subst.clone(),
+                        _ => return Err(MirLowerError::UnresolvedName),
+                    };
+                    let field_types = self.db.field_types(variant);
+                    for (local_id, _) in variant_data.fields().iter() {
+                        if let Entry::Vacant(entry) = operands.entry(local_id) {
+                            let ty = field_types[local_id].clone().substitute(&Interner, &subst);
+                            let field = FieldId { parent: variant, local_id };
+                            let p = base.project(ProjectionElem::Field(field));
+                            entry.insert(self.operand_for_place(p, &ty));
+                        }
+                    }
+                }
+                let operands = variant_data
+                    .fields()
+                    .iter()
+                    .filter_map(|(local_id, _)| operands.remove(&local_id))
+                    .collect();
+                let rvalue = Rvalue::Aggregate(AggregateKind::Adt(variant), operands);
+                self.push_assignment(current, place, rvalue, span);
+                Ok(Some(current))
+            }
+            Expr::Await { expr } => {
+                let (operand, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                self.lower_call(unknown_operand(), vec![operand], &[], place, current, expr_id)
+            }
+            Expr::Try { expr } => {
+                // Desugared to a call of `Try::branch`, which either continues
+                // with the output or returns the residual.
+                let (operand, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                let branch = self.new_temp(TyKind::Error.intern(&Interner));
+                let after_branch = self.new_basic_block();
+                self.set_terminator(
+                    current,
+                    TerminatorKind::Call {
+                        func: unknown_operand(),
+                        args: vec![operand],
+                        destination: branch.into(),
+                        target: Some(after_branch),
+                    },
+                    span,
+                );
+                let continue_block = self.new_basic_block();
+                let return_block = self.new_basic_block();
+                let discr = Operand::Copy(branch.into());
+                let targets = vec![continue_block, return_block];
+                self.set_terminator(
+                    after_branch,
+                    TerminatorKind::SwitchInt { discr, targets },
+                    span,
+                );
+                self.set_terminator(return_block, TerminatorKind::Return, span);
+                let rvalue = Rvalue::Use(Operand::Move(branch.into()));
+                self.push_assignment(continue_block, place, rvalue, span);
+                Ok(Some(continue_block))
+            }
+            Expr::Cast { expr, .. } => {
+                let (operand, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                let rvalue = Rvalue::Cast(operand, self.infer[expr_id].clone());
+                self.push_assignment(current, place, rvalue, span);
+                Ok(Some(current))
+            }
+            Expr::Ref { expr, mutability, .. } => {
+                let (p, current) = unwrap_or_diverge!(self.lower_expr_as_place(current, *expr)?);
+                self.push_assignment(current, place, Rvalue::Ref(*mutability, p), span);
+                Ok(Some(current))
+            }
+            Expr::Box { expr } => {
+                let (operand, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                self.push_assignment(current, place, Rvalue::Box(operand), span);
+                Ok(Some(current))
+            }
+            Expr::UnaryOp { expr, op } => {
+                let (operand, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                self.push_assignment(current, place, Rvalue::UnaryOp(*op, operand), span);
+                Ok(Some(current))
+            }
+            Expr::BinaryOp { lhs, rhs, op } => {
+                let op = op.ok_or(MirLowerError::Missing)?;
+                self.lower_binary_op(expr_id, op, *lhs, *rhs, place, current)
+            }
+            Expr::Range { lhs, rhs, .. } => {
+                let mut operands = Vec::new();
+                let mut current = current;
+                for expr in lhs.iter().chain(rhs) {
+                    let (operand, c) =
+                        unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                    operands.push(operand);
+                    current = c;
+                }
+                let kind = match self.infer[expr_id].as_adt() {
+                    Some((hir_def::AdtId::StructId(it), _)) => AggregateKind::Adt(it.into()),
+                    _ => AggregateKind::Tuple,
+                };
+                self.push_assignment(current, place, Rvalue::Aggregate(kind, operands), span);
+                Ok(Some(current))
+            }
+            Expr::Lambda { body, .. } | Expr::Async { body } => {
+                // The captured variables are read when the closure is created.
+                // They might be moved into it, but that's not tracked.
+                let mut captures = Vec::new();
+                self.collect_captures(*body, &mut captures);
+                let operands = captures.into_iter().map(|it| Operand::Copy(it.into())).collect();
+                let rvalue = Rvalue::Aggregate(AggregateKind::Closure, operands);
+                self.push_assignment(current, place, rvalue, span);
+                Ok(Some(current))
+            }
+            Expr::Tuple { exprs } | Expr::Array(Array::ElementList(exprs)) => {
+                let kind = match &self.body.exprs[expr_id] {
+                    Expr::Tuple { .. } => AggregateKind::Tuple,
+                    _ => AggregateKind::Array,
+                };
+                let mut operands = Vec::new();
+                let mut current = current;
+                for expr in exprs {
+                    let (operand, c) =
+                        unwrap_or_diverge!(self.lower_expr_to_some_operand(*expr, current)?);
+                    operands.push(operand);
+                    current = c;
+                }
+                self.push_assignment(current, place, Rvalue::Aggregate(kind, operands), span);
+                Ok(Some(current))
+            }
+            Expr::Array(Array::Repeat { initializer, .. }) => {
+                let (operand, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(*initializer, current)?);
+                self.push_assignment(current, place, Rvalue::Repeat(operand), span);
+                Ok(Some(current))
+            }
+            Expr::Unsafe { body } => self.lower_expr_to_place(*body, place, current),
+            Expr::MacroStmts { tail } => self.lower_expr_to_place(*tail, place, current),
+        }
+    }
+
+    fn lower_block_to_place(
+        &mut self,
+        statements: &[HirStatement],
+        tail: Option<ExprId>,
+        place: Place,
+        mut current: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>> {
+        for statement in statements {
+            // Code after a diverging statement goes into a block without
+            // predecessors, which is how unreachable code is found.
+            let end = match statement {
+                HirStatement::Let { pat, initializer, .. } => {
+                    self.declare_bindings(current, *pat);
+                    match initializer {
+                        Some(initializer) => {
+                            match self.lower_expr_as_place(current, *initializer)? {
+                                Some((init, current)) => {
+                                    let span = (*initializer).into();
+                                    self.push_statement(
+                                        current,
+                                        StatementKind::FakeRead(init.clone()),
+                                        span,
+                                    );
+                                    self.bind_pattern(current, *pat, init, BindMode::Move)?;
+                                    Some(current)
+                                }
+                                None => None,
+                            }
+                        }
+                        None => Some(current),
+                    }
+                }
+                HirStatement::Expr { expr, .. } => {
+                    let temp = self.new_temp(self.expr_ty_after_adjustments(*expr));
+                    self.lower_expr_to_place(*expr, temp.into(), current)?
+                }
+            };
+            current = match end {
+                Some(it) => it,
+                None => self.new_basic_block(),
+            };
+        }
+        match tail {
+            Some(tail) => self.lower_expr_to_place(tail, place, current),
+            None => {
+                self.push_unit(current, place, MirSpan::Unknown);
+                Ok(Some(current))
+            }
+        }
+    }
+
+    fn lower_call(
+        &mut self,
+        func: Operand,
+        mut operands: Vec<Operand>,
+        args: &[ExprId],
+        destination: Place,
+        mut current: BasicBlockId,
+        expr_id: ExprId,
+    ) -> Result<Option<BasicBlockId>> {
+        for arg in args {
+            let (operand, c) = unwrap_or_diverge!(self.lower_expr_to_some_operand(*arg, current)?);
+            operands.push(operand);
+            current = c;
+        }
+        let target =
+            if self.infer[expr_id].is_never() { None } else { Some(self.new_basic_block()) };
+        self.set_terminator(
+            current,
+            TerminatorKind::Call { func, args: operands, destination, target },
+            expr_id.into(),
+        );
+        Ok(target)
+    }
+
+    fn lower_match(
+        &mut self,
+        expr_id: ExprId,
+        scrutinee: ExprId,
+        arms: &[MatchArm],
+        place: Place,
+        current: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>> {
+        let span = expr_id.into();
+        let (scrutinee_place, current) =
+            unwrap_or_diverge!(self.lower_expr_as_place(current, scrutinee)?);
+        let discr = self.new_temp(TyKind::Error.intern(&Interner));
+        let rvalue = Rvalue::Discriminant(scrutinee_place.clone());
+        self.push_assignment(current, discr.into(), rvalue, scrutinee.into());
+        if arms.is_empty() {
+            // Only matches on uninhabited types diverge. Other empty matches
+            // are errors, and are treated as if they did nothing.
+            let ty = self.expr_ty_after_adjustments(scrutinee);
+            let is_uninhabited = ty.is_never()
+                || matches!(ty.as_adt(), Some((hir_def::AdtId::EnumId(it), _))
+                    if self.db.enum_data(it).variants.is_empty());
+            if is_uninhabited {
+                self.set_terminator(current, TerminatorKind::Unreachable, span);
+                return Ok(None);
+            }
+            return Ok(Some(current));
+        }
+
+        // The arms are tried in order. Which patterns match isn't modelled, so
+        // every arm may be taken, except that the last one is taken if no
+        // other one was, as matches are exhaustive.
+        let mut test_block = current;
+        let mut ends = Vec::new();
+        for (i, arm) in arms.iter().enumerate() {
+            let mut arm_block = self.new_basic_block();
+            let next_test = if i + 1 < arms.len() { Some(self.new_basic_block()) } else { None };
+            match next_test {
+                Some(next_test) => {
+                    let discr = Operand::Copy(discr.into());
+                    let targets = vec![arm_block, next_test];
+                    self.set_terminator(
+                        test_block,
+                        TerminatorKind::SwitchInt { discr, targets },
+                        span,
+                    );
+                }
+                None => self.set_goto(test_block, arm_block, span),
+            }
+            self.declare_bindings(arm_block, arm.pat);
+            if let Some(guard) = arm.guard {
+                self.bind_pattern(arm_block, arm.pat, scrutinee_place.clone(), BindMode::NoMove)?;
+                match self.lower_expr_to_some_operand(guard, arm_block)? {
+                    Some((discr, after_guard)) => {
+                        let body_block = self.new_basic_block();
+                        let targets = std::iter::once(body_block).chain(next_test).collect();
+                        self.set_terminator(
+                            after_guard,
+                            TerminatorKind::SwitchInt { discr, targets },
+                            span,
+                        );
+                        arm_block = body_block;
+                    }
+                    None => {
+                        if let Some(next_test) = next_test {
+                            test_block = next_test;
+                        }
+                        continue;
+                    }
+                }
+            }
+            self.bind_pattern(arm_block, arm.pat, scrutinee_place.clone(), BindMode::Move)?;
+            ends.push(self.lower_expr_to_place(arm.expr, place.clone(), arm_block)?);
+            if let Some(next_test) = next_test {
+                test_block = next_test;
+            }
+        }
+        Ok(self.merge_blocks(&ends, span))
+    }
+
+    fn lower_binary_op(
+        &mut self,
+        expr_id: ExprId,
+        op: BinaryOp,
+        lhs: ExprId,
+        rhs: ExprId,
+        place: Place,
+        current: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>> {
+        let span = expr_id.into();
+        match op {
+            BinaryOp::Assignment { op: arith_op } => {
+                if !self.is_place_expr(lhs) {
+                    return Err(MirLowerError::Unsupported("destructuring assignment"));
+                }
+                let (rhs, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(rhs, current)?);
+                let (lhs, current) = unwrap_or_diverge!(self.lower_expr_as_place(current, lhs)?);
+                let rvalue = match arith_op {
+                    Some(arith_op) => Rvalue::BinaryOp(
+                        BinaryOp::ArithOp(arith_op),
+                        Operand::Copy(lhs.clone()),
+                        rhs,
+                    ),
+                    None => Rvalue::Use(rhs),
+                };
+                self.push_assignment(current, lhs, rvalue, span);
+                self.push_unit(current, place, span);
+                Ok(Some(current))
+            }
+            BinaryOp::LogicOp(_) => {
+                let (discr, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(lhs, current)?);
+                let rhs_block = self.new_basic_block();
+                let short_circuit = self.new_basic_block();
+                let targets = vec![rhs_block, short_circuit];
+                self.set_terminator(current, TerminatorKind::SwitchInt { discr, targets }, span);
+                let constant = Operand::Constant(self.infer[expr_id].clone());
+                self.push_assignment(short_circuit, place.clone(), Rvalue::Use(constant), span);
+                let rhs_end = self.lower_expr_to_place(rhs, place, rhs_block)?;
+                Ok(self.merge_blocks(&[rhs_end, Some(short_circuit)], span))
+            }
+            BinaryOp::CmpOp(_) => {
+                // Comparisons take their operands by reference.
+                let (lhs, current) = unwrap_or_diverge!(self.lower_expr_as_place(current, lhs)?);
+                let (rhs, current) = unwrap_or_diverge!(self.lower_expr_as_place(current, rhs)?);
+                let rvalue = Rvalue::BinaryOp(op, Operand::Copy(lhs), Operand::Copy(rhs));
+                self.push_assignment(current, place, rvalue, span);
+                Ok(Some(current))
+            }
+            BinaryOp::ArithOp(_) => {
+                let (lhs, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(lhs, current)?);
+                let (rhs, current) =
+                    unwrap_or_diverge!(self.lower_expr_to_some_operand(rhs, current)?);
+                self.push_assignment(current, place, Rvalue::BinaryOp(op, lhs, rhs), span);
+                Ok(Some(current))
+            }
+        }
+    }
+
+    /// Allocates the locals of the bindings of the pattern, which start out
+    /// uninitialized.
+    fn declare_bindings(&mut self, block: BasicBlockId, pat: PatId) {
+        let body = self.body;
+        match &body.pats[pat] {
+            Pat::Bind { subpat, .. } => {
+                let local = self
+                    .result
+                    .locals
+                    .alloc(Local { ty: self.infer[pat].clone(), binding: Some(pat) });
+                self.result.binding_locals.insert(pat, local);
+                self.push_statement(block, StatementKind::StorageLive(local), pat.into());
+                if let Some(subpat) = subpat {
+                    self.declare_bindings(block, *subpat);
+                }
+            }
+            Pat::Or(alternatives) => {
+                let (first, rest) = match alternatives.split_first() {
+                    Some(it) => it,
+                    None => return,
+                };
+                self.declare_bindings(block, *first);
+                // All alternatives bind the same names, to the same locals.
+                let mut locals = FxHashMap::default();
+                for (name, binding) in self.bindings(*first) {
+                    if let Some(&local) = self.result.binding_locals.get(binding) {
+                        locals.insert(name, local);
+                    }
+                }
+                for &alternative in rest {
+                    for (name, binding) in self.bindings(alternative) {
+                        match locals.get(&name) {
+                            Some(&local) => self.result.binding_locals.insert(binding, local),
+                            None => self.declare_bindings(block, binding),
+                        }
+                    }
+                }
+            }
+            it => it.walk_child_pats(|it| self.declare_bindings(block, it)),
+        }
+    }
+
+    fn bindings(&self, pat: PatId) -> Vec<(Name, PatId)> {
+        let mut res = Vec::new();
+        let mut stack = vec![pat];
+        while let Some(pat) = stack.pop() {
+            let pat_data = &self.body.pats[pat];
+            if let Pat::Bind { name, .. } = pat_data {
+                res.push((name.clone(), pat));
+            }
+            pat_data.walk_child_pats(|it| stack.push(it));
+        }
+        res
+    }
+
+    /// Initializes the bindings of the pattern from the matched place.
+    fn bind_pattern(
+        &mut self,
+        block: BasicBlockId,
+        pat: PatId,
+        mut place: Place,
+        mut mode: BindMode,
+    ) -> Result<()> {
+        if let Some(adjustments) = self.infer.pat_adjustments.get(&pat) {
+            for _ in adjustments {
+                place = place.project(ProjectionElem::Deref);
+            }
+            if !adjustments.is_empty() {
+                mode = BindMode::Ref;
+            }
+        }
+        let body = self.body;
+        match &body.pats[pat] {
+            Pat::Missing
+            | Pat::Wild
+            | Pat::Lit(_)
+            | Pat::Range { .. }
+            | Pat::Path(_)
+            | Pat::ConstBlock(_) => {}
+            Pat::Bind { mode: annotation, subpat, .. } => {
+                let local =
+                    *self.result.binding_locals.get(pat).ok_or(MirLowerError::UnresolvedName)?;
+                let rvalue = match annotation {
+                    BindingAnnotation::Ref => Rvalue::Ref(Mutability::Shared, place.clone()),
+                    BindingAnnotation::RefMut => Rvalue::Ref(Mutability::Mut, place.clone()),
+                    _ if mode == BindMode::Ref => Rvalue::Ref(Mutability::Shared, place.clone()),
+                    _ if mode == BindMode::NoMove => Rvalue::Use(Operand::Copy(place.clone())),
+                    _ => Rvalue::Use(self.operand_for_place(place.clone(), &self.infer[pat])),
+                };
+                self.push_assignment(block, local.into(), rvalue, pat.into());
+                if let Some(subpat) = subpat {
+                    self.bind_pattern(block, *subpat, place, mode)?;
+                }
+            }
+            Pat::Tuple { args, ellipsis } => {
+                let arity = match self.infer[pat].kind(&Interner) {
+                    TyKind::Tuple(arity, _) => *arity,
+                    _ => return Err(MirLowerError::UnresolvedField),
+                };
+                for (arg, idx) in positional_fields(args, *ellipsis, arity) {
+                    self.bind_pattern(
+                        block,
+                        arg,
+                        place.project(ProjectionElem::TupleField(idx)),
+                        mode,
+                    )?;
+                }
+            }
+            Pat::TupleStruct { args, ellipsis, .. } => {
+                let variant = self
+                    .infer
+                    .variant_resolution_for_pat(pat)
+                    .ok_or(MirLowerError::UnresolvedName)?;
+                let fields: Vec<_> = variant
+                    .variant_data(self.db.upcast())
+                    .fields()
+                    .iter()
+                    .map(|(id, _)| id)
+                    .collect();
+                for (arg, idx) in positional_fields(args, *ellipsis, fields.len()) {
+                    let local_id = *fields.get(idx).ok_or(MirLowerError::UnresolvedField)?;
+                    let field = FieldId { parent: variant, local_id };
+                    self.bind_pattern(
+                        block,
+                        arg,
+                        place.project(ProjectionElem::Field(field)),
+                        mode,
+                    )?;
+                }
+            }
+            Pat::Record { args, .. } => {
+                let variant = self
+                    .infer
+                    .variant_resolution_for_pat(pat)
+                    .ok_or(MirLowerError::UnresolvedName)?;
+                let variant_data = variant.variant_data(self.db.upcast());
+                for arg in args {
+                    let local_id =
+                        variant_data.field(&arg.name).ok_or(MirLowerError::UnresolvedField)?;
+                    let field = FieldId { parent: variant, local_id };
+                    self.bind_pattern(
+                        block,
+                        arg.pat,
+                        place.project(ProjectionElem::Field(field)),
+                        mode,
+                    )?;
+                }
+            }
+            Pat::Slice { prefix, slice, suffix } => {
+                for (offset, &pat) in prefix.iter().enumerate() {
+                    let elem = ProjectionElem::ConstantIndex { offset, from_end: false };
+                    self.bind_pattern(block, pat, place.project(elem), mode)?;
+                }
+                if let Some(slice) = slice {
+                    let elem = ProjectionElem::Subslice { from: prefix.len(), to: suffix.len() };
+                    self.bind_pattern(block, *slice, place.project(elem), mode)?;
+                }
+                for (i, &pat) in suffix.iter().enumerate() {
+                    let elem =
+                        ProjectionElem::ConstantIndex { offset: suffix.len() - i, from_end: true };
+                    self.bind_pattern(block, pat, place.project(elem), mode)?;
+                }
+            }
+            Pat::Ref { pat, .. } => {
+                // `&pat` resets the default binding mode.
+                let mode = if mode == BindMode::Ref { BindMode::Move } else { mode };
+                self.bind_pattern(block, *pat, place.project(ProjectionElem::Deref), mode)?;
+            }
+            Pat::Box { inner } => {
+                self.bind_pattern(block, *inner, place.project(ProjectionElem::Deref), mode)?;
+            }
+            // All alternatives bind the same locals, so binding them from the
+            // first one is enough.
+            Pat::Or(alternatives) => {
+                if let Some(&first) = alternatives.first() {
+                    self.bind_pattern(block, first, place, mode)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects the variables of the body that are used in a closure.
+    fn collect_captures(&self, expr_id: ExprId, captures: &mut Vec<LocalId>) {
+        if let Expr::Path(path) = &self.body.exprs[expr_id] {
+            let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr_id);
+            if let Some(ValueNs::LocalBinding(pat)) =
+                resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path())
+            {
+                // Variables declared in the closure don't have a local.
+                if let Some(&local) = self.result.binding_locals.get(pat) {
+                    if !captures.contains(&local) {
+                        captures.push(local);
+                    }
+                }
+            }
+        }
+        self.body.exprs[expr_id].walk_child_exprs(|it| self.collect_captures(it, captures));
+    }
+
+    fn resolve_local(
+        &self,
+        expr_id: ExprId,
+        path: &hir_def::path::Path,
+    ) -> Result<Option<LocalId>> {
+        let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr_id);
+        match resolver.resolve_path_in_value_ns(self.db.upcast(), path.mod_path()) {
+            Some(ResolveValueResult::ValueNs(ValueNs::LocalBinding(pat))) => {
+                match self.result.binding_locals.get(pat) {
+                    Some(&local) => Ok(Some(local)),
+                    None => Err(MirLowerError::UnresolvedName),
+                }
+            }
+            // Unresolved paths are treated like constants.
+            Some(_) | None => Ok(None),
+        }
+    }
+
+    fn is_place_expr(&self, expr_id: ExprId) -> bool {
+        match &self.body.exprs[expr_id] {
+            Expr::Path(path) => matches!(self.resolve_local(expr_id, path), Ok(Some(_))),
+            Expr::Field { .. } | Expr::Index { .. } | Expr::UnaryOp { op: UnaryOp::Deref, .. } => {
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn find_breakable(&self, label: Option<&Name>, is_continue: bool) -> Result<usize> {
+        let idx = match label {
+            Some(label) => self.breakables.iter().rposition(|it| it.label.as_ref() == Some(label)),
+            // Unlabeled `break`s and `continue`s only target loops.
+            None => self.breakables.iter().rposition(|it| it.begin.is_some()),
+        };
+        match idx {
+            Some(idx) if !is_continue || self.breakables[idx].begin.is_some() => Ok(idx),
+            _ => Err(MirLowerError::BreakOutsideOfLoop),
+        }
+    }
+
+    fn operand_for_place(&mut self, place: Place, ty: &Ty) -> Operand {
+        if self.is_copy(ty) {
+            Operand::Copy(place)
+        } else {
+            Operand::Move(place)
+        }
+    }
+
+    fn is_copy(&mut self, ty: &Ty) -> bool {
+        // Moves of types we know little about, like unnormalized projections
+        // and closures, aren't tracked, as they'd only lead to false
+        // positives. The same goes for `&mut` references, which are usually
+        // reborrowed rather than moved.
+        match ty.kind(&Interner) {
+            TyKind::Alias(_) | TyKind::AssociatedType(..) | TyKind::Closure(..) => return true,
+            TyKind::Ref(..)
+            | TyKind::Raw(..)
+            | TyKind::Scalar(_)
+            | TyKind::FnDef(..)
+            | TyKind::Function(_)
+            | TyKind::Never
+            | TyKind::Error => return true,
+            _ => {}
+        }
+        let mut has_unknown = false;
+        ty.walk(&mut |it| has_unknown |= it.is_unknown());
+        if has_unknown {
+            return true;
+        }
+        let copy_trait = match self.copy_trait {
+            Some(it) => it,
+            None => return true,
+        };
+        if let Some(&it) = self.is_copy_cache.get(ty) {
+            return it;
+        }
+        let canonical =
+            Canonical { value: ty.clone(), binders: CanonicalVarKinds::empty(&Interner) };
+        let is_copy = method_resolution::implements_trait(
+            &canonical,
+            self.db,
+            self.trait_env.clone(),
+            self.krate,
+            copy_trait,
+        );
+        self.is_copy_cache.insert(ty.clone(), is_copy);
+        is_copy
+    }
+
+    fn expr_ty_after_adjustments(&self, expr_id: ExprId) -> Ty {
+        match self.infer.expr_adjustments.get(&expr_id).and_then(|it| it.last()) {
+            Some(adjustment) => adjustment.target.clone(),
+            None => self.infer[expr_id].clone(),
+        }
+    }
+
+    fn record_expr_block(&mut self, expr_id: ExprId, block: BasicBlockId) {
+        if self.result.expr_blocks.get(expr_id).is_none() {
+            self.result.expr_blocks.insert(expr_id, block);
+        }
+    }
+
+    fn new_basic_block(&mut self) -> BasicBlockId {
+        self.result.basic_blocks.alloc(BasicBlock { statements: Vec::new(), terminator: None })
+    }
+
+    fn new_temp(&mut self, ty: Ty) -> LocalId {
+        self.result.locals.alloc(Local { ty, binding: None })
+    }
+
+    fn new_unit_temp(&mut self) -> LocalId {
+        self.new_temp(TyKind::Tuple(0, crate::Substitution::empty(&Interner)).intern(&Interner))
+    }
+
+    fn push_statement(&mut self, block: BasicBlockId, kind: StatementKind, span: MirSpan) {
+        self.result.basic_blocks[block].statements.push(Statement { kind, span });
+    }
+
+    fn push_assignment(
+        &mut self,
+        block: BasicBlockId,
+        place: Place,
+        rvalue: Rvalue,
+        span: MirSpan,
+    ) {
+        self.push_statement(block, StatementKind::Assign(place, rvalue), span);
+    }
+
+    fn push_unit(&mut self, block: BasicBlockId, place: Place, span: MirSpan) {
+        let rvalue = Rvalue::Aggregate(AggregateKind::Tuple, Vec::new());
+        self.push_assignment(block, place, rvalue, span);
+    }
+
+    fn set_terminator(&mut self, block: BasicBlockId, kind: TerminatorKind, span: MirSpan) {
+        self.result.basic_blocks[block].terminator = Some(Terminator { kind, span });
+    }
+
+    fn set_goto(&mut self, from: BasicBlockId, target: BasicBlockId, span: MirSpan) {
+        self.set_terminator(from, TerminatorKind::Goto { target }, span);
+    }
+
+    /// Joins the control flow of the blocks that don't diverge.
+    fn merge_blocks(
+        &mut self,
+        blocks: &[Option<BasicBlockId>],
+        span: MirSpan,
+    ) -> Option<BasicBlockId> {
+        let mut blocks = blocks.iter().flatten().copied();
+        let first = blocks.next()?;
+        let mut blocks = blocks.peekable();
+        if blocks.peek().is_none() {
+            return Some(first);
+        }
+        let merged = self.new_basic_block();
+        self.set_goto(first, merged, span);
+        for block in blocks {
+            self.set_goto(block, merged, span);
+        }
+        Some(merged)
+    }
+}
+
+fn unknown_operand() -> Operand {
+    Operand::Constant(TyKind::Error.intern(&Interner))
+}
+
+fn from_chalk_mutability(m: crate::Mutability) -> Mutability {
+    match m {
+        crate::Mutability::Not => Mutability::Shared,
+        crate::Mutability::Mut => Mutability::Mut,
+    }
+}
+
+/// Pairs the subpatterns of a tuple or tuple struct pattern with the indices
+/// of the fields they match, taking `..` into account.
+fn positional_fields(
+    args: &[PatId],
+    ellipsis: Option<usize>,
+    arity: usize,
+) -> impl Iterator<Item = (PatId, usize)> + '_ {
+    args.iter().enumerate().map(move |(i, &arg)| match ellipsis {
+        Some(ellipsis) if i >= ellipsis => (arg, arity.saturating_sub(args.len() - i)),
+        _ => (arg, i),
+    })
+}
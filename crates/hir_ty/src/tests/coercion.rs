@@ -559,3 +559,40 @@ fn test() {
         "#,
     );
 }
+
+#[test]
+fn cast_ref_to_ptr_unifies_pointee() {
+    check_types(
+        r#"
+fn test() {
+    let a = 5;
+    let p = &a as *const u8;
+    a;
+  //^ u8
+    let b = [1, 2];
+    let q = &b as *const u16;
+    b;
+  //^ [u16; 2]
+}
+"#,
+    );
+}
+
+#[test]
+fn coerce_fn_item_to_higher_ranked_fn_ptr() {
+    check_types(
+        r#"
+struct S;
+fn id<'a>(s: &'a S) -> &'a S { s }
+fn test(g: for<'a> fn(&'a S) -> &'a S) {
+    g(&S);
+  //^^^^^ &S
+    let f: for<'a> fn(&'a S) -> &'a S = id;
+    f;
+  //^ fn(&S) -> &S
+    f(&S);
+  //^^^^^ &S
+}
+"#,
+    );
+}
@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use base_db::fixture::WithFixture;
+use hir_def::{AdtId, ModuleDefId};
+
+use crate::{
+    db::HirDatabase,
+    layout::{Layout, LayoutError},
+    test_db::TestDB,
+    TyBuilder,
+};
+
+/// Computes the layout of the type named `Goal` in the root module.
+fn eval_goal(ra_fixture: &str) -> Result<Arc<Layout>, LayoutError> {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let def_map = module.def_map(&db);
+    let adt = def_map[module.local_id]
+        .scope
+        .entries()
+        .find_map(|(name, def)| match def.take_types()? {
+            ModuleDefId::AdtId(it) if name.to_string() == "Goal" => Some(it),
+            _ => None,
+        })
+        .expect("no `Goal` type in the fixture");
+    let subst = match adt {
+        AdtId::StructId(it) => TyBuilder::type_params_subst(&db, it),
+        AdtId::UnionId(it) => TyBuilder::type_params_subst(&db, it),
+        AdtId::EnumId(it) => TyBuilder::type_params_subst(&db, it),
+    };
+    db.layout_of_adt(adt, subst)
+}
+
+#[track_caller]
+fn check_size_and_align(ra_fixture: &str, size: u64, align: u64) {
+    let layout = eval_goal(ra_fixture).unwrap();
+    assert_eq!((size, align), (layout.size, layout.align));
+}
+
+#[track_caller]
+fn check_fail(ra_fixture: &str, e: LayoutError) {
+    assert_eq!(Err(e), eval_goal(ra_fixture));
+}
+
+#[test]
+fn reordered_fields() {
+    check_size_and_align("struct Goal { a: u8, b: u32, c: u8 }", 8, 4);
+    check_size_and_align("struct Goal(u8, (), u64, u16);", 16, 8);
+    check_size_and_align("struct Goal { a: [u16; 3], b: (u8, u32) }", 16, 4);
+
+    let layout = eval_goal("struct Goal { a: u8, b: u32, c: u16 }").unwrap();
+    assert_eq!(vec![6, 0, 4], layout.field_offsets);
+}
+
+#[test]
+fn repr_attributes() {
+    check_size_and_align("#[repr(C)] struct Goal { a: u8, b: u32, c: u8 }", 12, 4);
+    check_size_and_align("#[repr(packed)] struct Goal { a: u8, b: u32, c: u8 }", 6, 1);
+    check_size_and_align("#[repr(C, packed(2))] struct Goal { a: u8, b: u32 }", 6, 2);
+    check_size_and_align("#[repr(align(16))] struct Goal(u8);", 16, 16);
+    check_size_and_align("#[repr(transparent)] struct Goal(u32, ());", 4, 4);
+
+    let layout = eval_goal("#[repr(C)] struct Goal { a: u8, b: u32, c: u16 }").unwrap();
+    assert_eq!(vec![0, 4, 8], layout.field_offsets);
+}
+
+#[test]
+fn invalid_repr_arguments() {
+    check_size_and_align("#[repr(packed(0))] struct Goal { a: u8, b: u32 }", 5, 1);
+    check_size_and_align("#[repr(packed(3))] struct Goal { a: u8, b: u32 }", 5, 1);
+    check_size_and_align("#[repr(align(0))] struct Goal(u8);", 1, 1);
+    check_size_and_align("#[repr(C, align(0))] struct Goal(u16, u8);", 4, 2);
+}
+
+#[test]
+fn primitives_and_pointers() {
+    check_size_and_align("struct Goal(bool, char, i128);", 32, 16);
+    check_size_and_align("struct Goal<'a>(&'a u8, *const u8);", 16, 8);
+    check_size_and_align("struct Goal<'a>(&'a str, &'a [u8], fn());", 40, 8);
+    check_size_and_align("struct Goal(!, fn());", 8, 8);
+    check_size_and_align("struct Goal { a: [u8; 0], b: (), c: [(); 5] }", 0, 1);
+    check_size_and_align("struct Inner; struct Goal<'a>(&'a Inner);", 8, 8);
+    check_size_and_align("struct Inner([u8]); struct Goal<'a>(&'a Inner);", 16, 8);
+}
+
+#[test]
+fn unions() {
+    check_size_and_align("union Goal { a: u8, b: u32 }", 4, 4);
+    check_size_and_align("union Goal { a: [u8; 5], b: u16 }", 6, 2);
+}
+
+#[test]
+fn fieldless_enums() {
+    check_size_and_align("enum Goal {}", 0, 1);
+    check_size_and_align("enum Goal { A }", 0, 1);
+    check_size_and_align("enum Goal { A, B, C }", 1, 1);
+    check_size_and_align("#[repr(C)] enum Goal { A, B }", 4, 4);
+    check_size_and_align("#[repr(u16)] enum Goal { A, B }", 2, 2);
+    check_size_and_align("#[repr(i64)] enum Goal { A }", 8, 8);
+}
+
+#[test]
+fn explicit_discriminants() {
+    check_fail("enum Goal { A = 1000, B }", LayoutError::Unknown);
+    check_fail("#[repr(u8)] enum Goal { A(u8) = 1, B }", LayoutError::Unknown);
+}
+
+#[test]
+fn enums_with_data() {
+    check_size_and_align("enum Goal { A(u8), B(u32) }", 8, 4);
+    check_size_and_align("enum Goal { A(u8, u16), B }", 4, 2);
+    check_size_and_align("#[repr(C)] enum Goal { A(u8), B(u32) }", 8, 4);
+    check_size_and_align("#[repr(u8)] enum Goal { A(u16, u8), B }", 6, 2);
+}
+
+#[test]
+fn niche_optimization() {
+    check_size_and_align(
+        r#"
+//- minicore: option
+struct Goal<'a> { a: Option<&'a u8>, b: Option<bool>, c: Option<Option<bool>> }
+"#,
+        16,
+        8,
+    );
+    check_size_and_align("enum Goal { A(char), B, C }", 4, 4);
+    check_size_and_align("enum Goal { A(u32), B }", 8, 4);
+    check_size_and_align(
+        r#"
+//- minicore: option
+#[rustc_layout_scalar_valid_range_start(1)]
+struct NonZero(u32);
+struct Goal(Option<NonZero>);
+"#,
+        4,
+        4,
+    );
+    check_size_and_align("enum Bools { A(bool), B } enum Goal { A(Bools), B, C }", 1, 1);
+    check_size_and_align("enum Goal { A(bool), B, C, D }", 1, 1);
+}
+
+#[test]
+fn generic_fields() {
+    check_size_and_align("struct Goal<T> { a: u32, b: fn() -> T }", 16, 8);
+    check_fail("struct Goal<T> { a: u32, b: T }", LayoutError::HasPlaceholder);
+    check_size_and_align("struct Inner<T>(T, u8); struct Goal(Inner<u32>);", 8, 4);
+}
+
+#[test]
+fn unsupported_types() {
+    check_fail("struct Goal(Goal);", LayoutError::Recursive);
+    check_fail("struct Goal { a: u8, b: str }", LayoutError::Unsized);
+}
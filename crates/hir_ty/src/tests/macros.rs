@@ -887,6 +887,33 @@ fn bar() -> u32 {0}
     );
 }
 
+#[test]
+fn infer_builtin_macros_include_from_dependency_source_root() {
+    check_types(
+        r#"
+//- /main.rs crate:main deps:gen env:OUT_DIR=/gen/out
+#[rustc_builtin_macro]
+macro_rules! include {() => {}}
+
+#[rustc_builtin_macro]
+macro_rules! concat {() => {}}
+
+#[rustc_builtin_macro]
+macro_rules! env {() => {}}
+
+include!(concat!(env!("OUT_DIR"), "/foo.rs"));
+
+fn main() {
+    bar();
+} //^^^^^ u32
+
+//- /gen/lib.rs new_source_root: crate:gen
+//- /gen/out/foo.rs
+fn bar() -> u32 {0}
+"#,
+    );
+}
+
 #[test]
 fn infer_builtin_macros_include_concat_with_bad_env_should_failed() {
     check_types(
@@ -1308,3 +1335,33 @@ fn infinitely_recursive_macro_type() {
         "#]],
     );
 }
+
+#[test]
+fn infer_macro_pat_fragment_2021() {
+    check_types(
+        r#"
+//- /main.rs crate:main edition:2021
+macro_rules! matches_any {
+    ($e:expr, $p:pat) => { match $e { $p => true, _ => false } };
+}
+fn test() {
+    let x = matches_any!(1, 1 | 2);
+    x;
+} //^ bool
+"#,
+    );
+}
+
+#[test]
+fn infer_2018_keywords_as_identifiers_in_2015() {
+    check_types(
+        r#"
+//- /main.rs crate:main edition:2015
+fn async() -> u8 { 0 }
+fn test() {
+    let try = async();
+    try;
+} //^^^ u8
+"#,
+    );
+}
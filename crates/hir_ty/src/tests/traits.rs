@@ -3515,3 +3515,60 @@ fn test() {
 "#,
     );
 }
+
+#[test]
+fn higher_ranked_fn_bounds() {
+    check_types(
+        r#"
+//- minicore: fn, coerce_unsized
+struct S;
+impl S { fn len(&self) -> usize { 0 } }
+fn id<'a>(s: &'a S) -> &'a S { s }
+
+fn call<F: for<'a> Fn(&'a S) -> &'a S>(f: F) -> F { f }
+fn take<F>(f: F) where F: for<'a> Fn(&'a S) -> usize {}
+fn take2<F>(f: F) where for<'a> F: Fn(&'a S) -> &'a S {}
+
+fn test<F: for<'a> Fn(&'a S) -> &'a S>(f: F) {
+    f(&S).len();
+  //^^^^^^^^^^^ usize
+    take(|s| s.len());
+        //^ &S
+    take2(|s| s);
+         //^ &S
+    call(id)(&S);
+  //^^^^^^^^^^^^ &S
+    let g = call(|s| s);
+    g(&S);
+  //^^^^^ &S
+    let d: &dyn for<'a> Fn(&'a S) -> &'a S = &|s| s;
+    d(&S).len();
+  //^^^^^^^^^^^ usize
+}
+"#,
+    );
+}
+
+#[test]
+fn higher_ranked_trait_bounds_with_assoc_types() {
+    check_types(
+        r#"
+trait Tr<'a> { type Assoc; fn get(&self) -> Self::Assoc; }
+trait Sup: for<'a> Tr<'a, Assoc = u8> {}
+
+fn shorthand<T: for<'a> Tr<'a>>(t: T) {
+    let x: T::Assoc = t.get();
+    x;
+  //^ Tr::Assoc<T>
+}
+fn binding<T>(t: T) where for<'b> T: Tr<'b, Assoc = u32> {
+    t.get();
+  //^^^^^^^ u32
+}
+fn supertrait<T: Sup>(t: T) {
+    t.get();
+  //^^^^^^^ u8
+}
+"#,
+    );
+}
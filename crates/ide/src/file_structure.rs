@@ -105,7 +105,10 @@ fn structure_node(node: &SyntaxNode) -> Option<StructureNode> {
             node_range: node.syntax().text_range(),
             kind,
             detail,
-            deprecated: node.attrs().filter_map(|x| x.simple_name()).any(|x| x == "deprecated"),
+            deprecated: node
+                .attrs()
+                .filter_map(|x| x.simple_name())
+                .any(|x| x == "deprecated" || x == "rustc_deprecated"),
         })
     }
 
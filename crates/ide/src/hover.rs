@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use either::Either;
 use hir::{AsAssocItem, HasAttrs, HasSource, HirDisplay, Semantics};
 use ide_db::{
@@ -426,11 +428,27 @@ fn hover_for_definition(
             },
             it.attrs(db).docs(),
         ),
-        Definition::Field(def) => label_and_docs(db, def),
+        Definition::Field(def) => label_and_layout_info_and_docs(db, def, |&it| {
+            let layout = it.layout(db).ok()?;
+            let mut res = format!("size = {}, align = {}", layout.size, layout.align);
+            if let Some(offset) = it.offset(db) {
+                format_to!(res, ", offset = {}", offset);
+            }
+            Some(res)
+        }),
         Definition::ModuleDef(it) => match it {
             hir::ModuleDef::Module(it) => label_and_docs(db, it),
             hir::ModuleDef::Function(it) => label_and_docs(db, it),
-            hir::ModuleDef::Adt(it) => label_and_docs(db, it),
+            hir::ModuleDef::Adt(it) => label_and_layout_info_and_docs(db, it, |&it| {
+                let layout = it.layout(db).ok()?;
+                let mut res = format!("size = {}, align = {}", layout.size, layout.align);
+                if let Some(niches) =
+                    layout.largest_niche.map(|it| it.available()).filter(|&it| it > 0)
+                {
+                    format_to!(res, ", niches = {}", niches);
+                }
+                Some(res)
+            }),
             hir::ModuleDef::Variant(it) => label_and_docs(db, it),
             hir::ModuleDef::Const(it) => label_and_docs(db, it),
             hir::ModuleDef::Static(it) => label_and_docs(db, it),
@@ -450,11 +468,25 @@ fn hover_for_definition(
         Definition::Label(it) => return Some(Markup::fenced_block(&it.name(db))),
     };
 
-    return hover_markup(
-        docs.filter(|_| config.documentation.is_some()).map(Into::into),
-        label,
-        mod_path,
-    );
+    let docs = docs.filter(|_| config.documentation.is_some()).map(String::from);
+    let docs = match def.deprecation(db) {
+        Some(deprecation) => {
+            let mut buf = String::from("**Deprecated**");
+            if let Some(since) = &deprecation.since {
+                format_to!(buf, " since {}", since);
+            }
+            if let Some(note) = &deprecation.note {
+                format_to!(buf, ": {}", note);
+            }
+            if let Some(docs) = docs {
+                format_to!(buf, "\n\n{}", docs);
+            }
+            Some(buf)
+        }
+        None => docs,
+    };
+
+    return hover_markup(docs, label, mod_path);
 
     fn label_and_docs<D>(db: &RootDatabase, def: D) -> (String, Option<hir::Documentation>)
     where
@@ -464,6 +496,24 @@ fn hover_for_definition(
         let docs = def.attrs(db).docs();
         (label, docs)
     }
+
+    fn label_and_layout_info_and_docs<D, E, V>(
+        db: &RootDatabase,
+        def: D,
+        layout_extractor: E,
+    ) -> (String, Option<hir::Documentation>)
+    where
+        D: HasAttrs + HirDisplay,
+        E: Fn(&D) -> Option<V>,
+        V: Display,
+    {
+        let label = match layout_extractor(&def) {
+            Some(layout) => format!("{} // {}", def.display(db), layout),
+            None => def.display(db).to_string(),
+        };
+        let docs = def.attrs(db).docs();
+        (label, docs)
+    }
 }
 
 fn hover_for_local(it: hir::Local, db: &RootDatabase) -> Option<Markup> {
@@ -827,6 +877,59 @@ fn main() { }
         );
     }
 
+    #[test]
+    fn hover_shows_deprecation() {
+        check(
+            r#"
+/// Does foo.
+#[deprecated(since = "1.2.0", note = "use `bar` instead")]
+pub fn foo() {}
+
+fn main() { foo$0(); }
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                pub fn foo()
+                ```
+
+                ---
+
+                **Deprecated** since 1.2.0: use `bar` instead
+
+                Does foo.
+            "#]],
+        );
+        check(
+            r#"
+#[deprecated]
+pub struct Foo;
+
+fn main() { Foo$0; }
+"#,
+            expect![[r#"
+                *Foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                pub struct Foo // size = 0, align = 1
+                ```
+
+                ---
+
+                **Deprecated**
+            "#]],
+        );
+    }
+
     #[test]
     fn hover_shows_fn_doc_attr_raw_string() {
         check(
@@ -854,6 +957,79 @@ fn main() { }
         );
     }
 
+    #[test]
+    fn hover_shows_struct_layout() {
+        check(
+            r#"
+#[repr(C)]
+struct Foo$0 { a: u8, b: u32, c: bool }
+"#,
+            expect![[r#"
+                *Foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                struct Foo // size = 12, align = 4, niches = 254
+                ```
+            "#]],
+        );
+        check(
+            r#"
+struct Foo { a: u8, b$0: u32, c: u16 }
+"#,
+            expect![[r#"
+                *b*
+
+                ```rust
+                test::Foo
+                ```
+
+                ```rust
+                b: u32 // size = 4, align = 4, offset = 0
+                ```
+            "#]],
+        );
+        check(
+            r#"
+enum Foo$0 { A(u32), B }
+"#,
+            expect![[r#"
+                *Foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                enum Foo // size = 8, align = 4, niches = 254
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_no_layout_for_generic_fields() {
+        check(
+            r#"
+struct Foo<T> { a: u8, b$0: T }
+"#,
+            expect![[r#"
+                *b*
+
+                ```rust
+                test::Foo
+                ```
+
+                ```rust
+                b: T
+                ```
+            "#]],
+        );
+    }
+
     #[test]
     fn hover_shows_struct_field_info() {
         // Hovering over the field when instantiating
@@ -873,7 +1049,7 @@ fn main() {
                 ```
 
                 ```rust
-                field_a: u32
+                field_a: u32 // size = 4, align = 4, offset = 0
                 ```
             "#]],
         );
@@ -895,7 +1071,7 @@ fn main() {
                 ```
 
                 ```rust
-                field_a: u32
+                field_a: u32 // size = 4, align = 4, offset = 0
                 ```
             "#]],
         );
@@ -1702,7 +1878,7 @@ fn foo() { let bar = Ba$0r; }
                 ```
 
                 ```rust
-                struct Bar
+                struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1738,7 +1914,7 @@ fn foo() { let bar = Ba$0r; }
                 ```
 
                 ```rust
-                struct Bar
+                struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1767,7 +1943,7 @@ fn foo() { let bar = Ba$0r; }
                 ```
 
                 ```rust
-                struct Bar
+                struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1795,7 +1971,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1821,7 +1997,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1851,7 +2027,7 @@ pub struct Bar {
                 ```
 
                 ```rust
-                field: ()
+                field: () // size = 0, align = 1, offset = 0
                 ```
 
                 ---
@@ -1879,7 +2055,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1909,7 +2085,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1935,7 +2111,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1961,7 +2137,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -1988,7 +2164,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -2014,7 +2190,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -2040,7 +2216,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -2068,7 +2244,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -2089,7 +2265,7 @@ pub trait Foo {
 /// [buzz]: Foo::buzz
 pub struct B$0ar
 "#,
-            expect![[r#"
+            expect![[r##"
                 *Bar*
 
                 ```rust
@@ -2097,13 +2273,13 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
 
                 [Foo](https://docs.rs/test/*/test/trait.Foo.html#tymethod.buzz)
-            "#]],
+            "##]],
         );
     }
 
@@ -2123,7 +2299,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -2150,7 +2326,7 @@ pub struct B$0ar
                 ```
 
                 ```rust
-                pub struct Bar
+                pub struct Bar // size = 0, align = 1
                 ```
 
                 ---
@@ -2204,7 +2380,7 @@ struct S {
                 ```
 
                 ```rust
-                field: i32
+                field: i32 // size = 4, align = 4, offset = 0
                 ```
 
                 ---
@@ -3029,8 +3205,8 @@ fn foo() {
                                     file_id: FileId(
                                         1,
                                     ),
-                                    full_range: 251..433,
-                                    focus_range: 290..296,
+                                    full_range: 253..507,
+                                    focus_range: 364..370,
                                     name: "Future",
                                     kind: Trait,
                                     description: "pub trait Future",
@@ -3937,7 +4113,7 @@ pub fn gimme() -> theitem::TheItem {
                 ```
 
                 ```rust
-                pub struct TheItem
+                pub struct TheItem // size = 0, align = 1
                 ```
 
                 ---
@@ -4042,7 +4218,7 @@ mod string {
                 ```
 
                 ```rust
-                struct String
+                struct String // size = 0, align = 1
                 ```
 
                 ---
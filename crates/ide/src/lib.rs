@@ -40,6 +40,7 @@ mod inlay_hints;
 mod join_lines;
 mod markdown_remove;
 mod matching_brace;
+mod moniker;
 mod move_item;
 mod parent_module;
 mod references;
@@ -49,6 +50,7 @@ mod ssr;
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod type_hierarchy;
 mod typing;
 mod view_crate_graph;
 mod view_hir;
@@ -81,6 +83,7 @@ pub use crate::{
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
     join_lines::JoinLinesConfig,
     markup::Markup,
+    moniker::{MonikerIdentifier, MonikerKind, MonikerResult, PackageInformation},
     move_item::Direction,
     prime_caches::PrimeCachesProgress,
     references::ReferenceSearchResult,
@@ -93,7 +96,7 @@ pub use crate::{
 };
 pub use hir::{Documentation, Semantics};
 pub use ide_assists::{
-    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, SingleResolve,
+    Assist, AssistCommand, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, SingleResolve,
 };
 pub use ide_completion::{
     CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit,
@@ -219,8 +222,10 @@ impl Analysis {
             file_id,
             Edition::CURRENT,
             None,
+            None,
             cfg_options.clone(),
             cfg_options,
+            Default::default(),
             Env::default(),
             Default::default(),
         );
@@ -454,6 +459,32 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Computes type hierarchy candidates for the given file position.
+    pub fn type_hierarchy(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy(db, position))
+    }
+
+    /// Computes the supertraits of a trait, or the traits implemented by a type.
+    pub fn supertypes(&self, position: FilePosition) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::supertypes(db, position))
+    }
+
+    /// Computes the subtraits and implementors of a trait.
+    pub fn subtypes(&self, position: FilePosition) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::subtypes(db, position))
+    }
+
+    /// Computes the monikers of the definition at the given position.
+    pub fn moniker(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<RangeInfo<Vec<MonikerResult>>>> {
+        self.with_db(|db| moniker::moniker(db, position))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancellable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -586,11 +617,19 @@ impl Analysis {
 
         self.with_db(|db| {
             let diagnostic_assists = if include_fixes {
-                ide_diagnostics::diagnostics(db, diagnostics_config, &resolve, frange.file_id)
-                    .into_iter()
-                    .flat_map(|it| it.fixes.unwrap_or_default())
-                    .filter(|it| it.target.intersect(frange.range).is_some())
-                    .collect()
+                let mut res: Vec<_> =
+                    ide_diagnostics::diagnostics(db, diagnostics_config, &resolve, frange.file_id)
+                        .into_iter()
+                        .flat_map(|it| it.fixes.unwrap_or_default())
+                        .filter(|it| it.target.intersect(frange.range).is_some())
+                        .collect();
+                res.extend(ide_diagnostics::migrate_to_edition_2021(
+                    db,
+                    diagnostics_config,
+                    &resolve,
+                    frange,
+                ));
+                res
             } else {
                 Vec::new()
             };
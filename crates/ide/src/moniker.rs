@@ -0,0 +1,303 @@
+//! Computes monikers: identifiers of definitions that are stable across
+//! projects, for linking references in one repository to the definitions in
+//! another one.
+
+use std::fmt;
+
+use hir::{
+    db::DefDatabase, AsAssocItem, AssocItemContainer, Crate, ItemInNs, ModuleDef, PrefixKind,
+    Semantics,
+};
+use ide_db::{
+    defs::{Definition, NameClass, NameRefClass},
+    RootDatabase,
+};
+use syntax::{ast, AstNode};
+
+use crate::{FilePosition, RangeInfo};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonikerResult {
+    pub identifier: MonikerIdentifier,
+    pub kind: MonikerKind,
+    pub package_information: PackageInformation,
+}
+
+/// The crate of a definition and the path to it from the crate root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonikerIdentifier {
+    pub crate_name: String,
+    pub path: Vec<String>,
+}
+
+impl fmt::Display for MonikerIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.crate_name)?;
+        for segment in &self.path {
+            write!(f, "::{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonikerKind {
+    /// The definition belongs to another crate than the one the moniker was
+    /// requested from.
+    Import,
+    /// The definition belongs to the crate the moniker was requested from.
+    Export,
+}
+
+/// The package a definition belongs to, as declared in its project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInformation {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+// Feature: Moniker
+//
+// Computes an identifier of the definition under the cursor made of its
+// package, version and path, so that code intelligence tools can link its uses
+// across repositories.
+pub(crate) fn moniker(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<MonikerResult>>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let current_crate = sema.to_module_def(position.file_id)?.krate();
+    let node = sema.find_node_at_offset_with_descend(file.syntax(), position.offset)?;
+    let def = match &node {
+        ast::NameLike::Name(name) => match NameClass::classify(&sema, name)? {
+            NameClass::Definition(it) | NameClass::ConstReference(it) => it,
+            NameClass::PatFieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+        },
+        ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(&sema, name_ref)? {
+            NameRefClass::Definition(it) => it,
+            NameRefClass::FieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+        },
+        ast::NameLike::Lifetime(_) => return None,
+    };
+    let moniker = def_to_moniker(db, def, current_crate)?;
+    Some(RangeInfo::new(node.syntax().text_range(), vec![moniker]))
+}
+
+pub(crate) fn def_to_moniker(
+    db: &RootDatabase,
+    def: Definition,
+    from_crate: Crate,
+) -> Option<MonikerResult> {
+    let krate = def.module(db)?.krate();
+    let display_name = krate.display_name(db)?;
+    let path = def_path(db, def, krate)?;
+    Some(MonikerResult {
+        identifier: MonikerIdentifier { crate_name: display_name.to_string(), path },
+        kind: if krate == from_crate { MonikerKind::Export } else { MonikerKind::Import },
+        package_information: PackageInformation {
+            name: display_name.canonical_name().to_string(),
+            version: krate.version(db),
+        },
+    })
+}
+
+/// Returns the path to `def` from the root of `krate`, its crate. Locals,
+/// generic parameters and labels don't have one.
+fn def_path(db: &RootDatabase, def: Definition, krate: Crate) -> Option<Vec<String>> {
+    match def {
+        Definition::Macro(it) => {
+            path_from_root(db, krate, it.into()).or_else(|| Some(vec![it.name(db)?.to_string()]))
+        }
+        Definition::Field(it) => {
+            let mut path = module_def_path(db, krate, it.parent_def(db).into())?;
+            path.push(it.name(db).to_string());
+            Some(path)
+        }
+        Definition::ModuleDef(ModuleDef::Module(it)) if it.parent(db).is_none() => Some(Vec::new()),
+        Definition::ModuleDef(ModuleDef::BuiltinType(_)) => None,
+        Definition::ModuleDef(it) => {
+            assoc_item_path(db, krate, it).or_else(|| module_def_path(db, krate, it))
+        }
+        Definition::SelfType(_)
+        | Definition::Local(_)
+        | Definition::GenericParam(_)
+        | Definition::Label(_) => None,
+    }
+}
+
+/// Returns the path to an associated item through its trait, or through the
+/// type of its impl. Items of trait impls are qualified by both, as different
+/// traits can have items of the same name.
+fn assoc_item_path(db: &RootDatabase, krate: Crate, def: ModuleDef) -> Option<Vec<String>> {
+    let assoc = match def {
+        ModuleDef::Function(it) => it.as_assoc_item(db),
+        ModuleDef::Const(it) => it.as_assoc_item(db),
+        ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+        _ => None,
+    }?;
+    let mut path = match assoc.container(db) {
+        AssocItemContainer::Trait(trait_) => module_def_path(db, krate, trait_.into())?,
+        AssocItemContainer::Impl(imp) => {
+            let adt = imp.self_ty(db).as_adt()?;
+            let mut path = module_def_path(db, krate, adt.into())?;
+            if let Some(trait_) = imp.trait_(db) {
+                path.push(trait_.name(db).to_string());
+            }
+            path
+        }
+    };
+    path.push(def.name(db)?.to_string());
+    Some(path)
+}
+
+/// Items that can't be named from the crate root, like private ones, are
+/// identified by the path to their definition.
+fn module_def_path(db: &RootDatabase, krate: Crate, def: ModuleDef) -> Option<Vec<String>> {
+    path_from_root(db, krate, def.into())
+        .or_else(|| Some(def.canonical_path(db)?.split("::").map(|it| it.to_string()).collect()))
+}
+
+/// Prefers the path other crates import the item with, then the one used
+/// within its crate.
+fn path_from_root(db: &RootDatabase, krate: Crate, item: ItemInNs) -> Option<Vec<String>> {
+    if let Some(path) = db.import_map(krate.into()).path_of(item) {
+        return Some(path.segments.iter().map(|it| it.to_string()).collect());
+    }
+    let path = krate.root_module(db).find_use_path_prefixed(db, item, PrefixKind::Plain)?;
+    Some(path.segments().iter().map(|it| it.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    use super::MonikerKind;
+
+    #[track_caller]
+    fn check(ra_fixture: &str, identifier: &str, package: &str, kind: MonikerKind) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let monikers = analysis.moniker(position).unwrap().unwrap().info;
+        assert_eq!(monikers.len(), 1);
+        let moniker = &monikers[0];
+        assert_eq!(moniker.identifier.to_string(), identifier);
+        let info = &moniker.package_information;
+        let actual_package = match &info.version {
+            Some(version) => format!("{}@{}", info.name, version),
+            None => info.name.clone(),
+        };
+        assert_eq!(actual_package, package);
+        assert_eq!(moniker.kind, kind);
+    }
+
+    #[track_caller]
+    fn check_no_moniker(ra_fixture: &str) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        assert!(analysis.moniker(position).unwrap().is_none());
+    }
+
+    #[test]
+    fn local_definition() {
+        check(
+            r#"
+//- /lib.rs crate:foo@0.1.0
+pub mod module {
+    pub struct Struct$0;
+}
+"#,
+            "foo::module::Struct",
+            "foo@0.1.0",
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn dependency_reexport() {
+        check(
+            r#"
+//- /main.rs crate:main deps:foo
+fn f(_: foo::Str$0uct) {}
+//- /foo/lib.rs crate:foo@1.2.3
+mod inner {
+    pub struct Struct;
+}
+pub use inner::Struct;
+"#,
+            "foo::Struct",
+            "foo@1.2.3",
+            MonikerKind::Import,
+        );
+    }
+
+    #[test]
+    fn private_item() {
+        check(
+            r#"
+//- /lib.rs crate:foo
+mod inner {
+    fn func$0() {}
+}
+"#,
+            "foo::inner::func",
+            "foo",
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn trait_and_impl_items() {
+        check(
+            r#"
+//- /lib.rs crate:foo
+pub trait Trait {
+    fn func$0();
+}
+"#,
+            "foo::Trait::func",
+            "foo",
+            MonikerKind::Export,
+        );
+        check(
+            r#"
+//- /lib.rs crate:foo
+pub trait Trait {
+    fn func();
+}
+pub struct S;
+impl Trait for S {
+    fn func$0() {}
+}
+"#,
+            "foo::S::Trait::func",
+            "foo",
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn field() {
+        check(
+            r#"
+//- /lib.rs crate:foo
+pub struct S {
+    pub field$0: u32,
+}
+"#,
+            "foo::S::field",
+            "foo",
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn no_moniker_for_locals() {
+        check_no_moniker(
+            r#"
+//- /lib.rs crate:foo
+fn f() {
+    let local$0 = 92;
+}
+"#,
+        );
+    }
+}
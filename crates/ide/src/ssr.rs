@@ -45,6 +45,7 @@ pub(crate) fn ssr_assists(
             group: Some(GroupLabel("Apply SSR".into())),
             target: comment_range,
             source_change,
+            command: None,
         };
 
         ssr_assists.push(assist);
@@ -140,6 +141,7 @@ mod tests {
                         is_snippet: false,
                     },
                 ),
+                command: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_file_assist);
@@ -186,6 +188,7 @@ mod tests {
                         is_snippet: false,
                     },
                 ),
+                command: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_workspace_assist);
@@ -225,6 +228,7 @@ mod tests {
                 ),
                 target: 10..21,
                 source_change: None,
+                command: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_file_assist);
@@ -244,6 +248,7 @@ mod tests {
                 ),
                 target: 10..21,
                 source_change: None,
+                command: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_workspace_assist);
@@ -134,8 +134,9 @@ pub struct HlRange {
 // consuming:: Emitted for locals that are being consumed when use in a function call.
 // controlFlow:: Emitted for control-flow related tokens, this includes the `?` operator.
 // declaration:: Emitted for names of definitions, like `foo` in `fn foo() {}`.
+// deprecated:: Emitted for items marked with `#[deprecated]`, and for the items of deprecated traits.
 // documentation:: Emitted for documentation comments.
-// injected:: Emitted for doc-string injected highlighting like rust source blocks in documentation.
+// injected:: Emitted for doc-string injected highlighting like rust source blocks in documentation, and for languages like regex or SQL embedded in string literals.
 // intraDocLink:: Emitted for intra doc links in doc-strings.
 // library:: Emitted for items that are defined outside of the current crate.
 // public:: Emitted for items that are from the current crate and are `pub`.
@@ -351,6 +352,7 @@ fn traverse(
 
         if let Some(string) = element_to_highlight.as_token().cloned().and_then(ast::String::cast) {
             highlight_format_string(hl, &string, range);
+            inject::injected_language(hl, &string, range);
             // Highlight escape sequences
             if let Some(char_ranges) = string.char_ranges() {
                 for (piece_range, _) in char_ranges.iter().filter(|(_, char)| char.is_ok()) {
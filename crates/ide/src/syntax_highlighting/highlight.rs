@@ -493,6 +493,9 @@ fn highlight_def(db: &RootDatabase, krate: Option<hir::Crate>, def: Definition)
         (false, _, true) => h |= HlMod::Public,
         _ => {}
     }
+    if def.deprecation(db).is_some() {
+        h |= HlMod::Deprecated;
+    }
 
     h
 }
@@ -534,6 +537,9 @@ fn highlight_method_call(
     } else if is_public {
         h |= HlMod::Public;
     }
+    if hir::ModuleDef::from(func).deprecation(sema.db).is_some() {
+        h |= HlMod::Deprecated;
+    }
 
     if let Some(self_param) = func.self_param(sema.db) {
         match self_param.access(sema.db) {
@@ -1,10 +1,18 @@
-//! "Recursive" Syntax highlighting for code in doctests and fixtures.
+//! "Recursive" Syntax highlighting for code in doctests and fixtures, and for
+//! other languages embedded in string literals.
 
 use std::mem;
 
 use either::Either;
 use hir::{InFile, Semantics};
-use ide_db::{call_info::ActiveParameter, helpers::rust_doc::is_rust_fence, SymbolKind};
+use ide_db::{
+    call_info::ActiveParameter,
+    helpers::{
+        injection::{self, TokenKind},
+        rust_doc::is_rust_fence,
+    },
+    SymbolKind,
+};
 use syntax::{
     ast::{self, AstNode, IsString},
     AstToken, NodeOrToken, SyntaxNode, SyntaxToken, TextRange, TextSize,
@@ -12,7 +20,7 @@ use syntax::{
 
 use crate::{
     doc_links::{doc_attributes, extract_definitions_from_markdown, resolve_doc_path_for_def},
-    Analysis, HlMod, HlRange, HlTag, RootDatabase,
+    Analysis, HlMod, HlOperator, HlPunct, HlRange, HlTag, RootDatabase,
 };
 
 use super::{highlights::Highlights, injector::Injector};
@@ -211,6 +219,38 @@ pub(super) fn doc_comment(
     }
 }
 
+/// Highlights the contents of string literals in languages like regex or SQL.
+pub(super) fn injected_language(hl: &mut Highlights, string: &ast::String, range: TextRange) {
+    let language = match injection::injected_language(string) {
+        Some(it) => it,
+        None => return,
+    };
+    let chars = match injection::string_chars(string) {
+        Some(it) => it,
+        None => return,
+    };
+    for (piece_range, kind) in injection::lex(language, &chars) {
+        let tag = match kind {
+            TokenKind::Keyword => HlTag::Keyword,
+            TokenKind::Operator => HlTag::Operator(HlOperator::Other),
+            TokenKind::Escape => HlTag::EscapeSequence,
+            TokenKind::String => HlTag::StringLiteral,
+            TokenKind::Number => HlTag::NumericLiteral,
+            TokenKind::Comment => HlTag::Comment,
+            TokenKind::Bracket => HlTag::Punctuation(HlPunct::Bracket),
+            TokenKind::Brace => HlTag::Punctuation(HlPunct::Brace),
+            TokenKind::Parenthesis => HlTag::Punctuation(HlPunct::Parenthesis),
+            TokenKind::Comma => HlTag::Punctuation(HlPunct::Comma),
+            TokenKind::Colon => HlTag::Punctuation(HlPunct::Colon),
+        };
+        hl.add(HlRange {
+            range: piece_range + range.start(),
+            highlight: tag | HlMod::Injected,
+            binding_hash: None,
+        });
+    }
+}
+
 fn find_doc_string_in_attr(attr: &hir::Attr, it: &ast::Attr) -> Option<ast::String> {
     match it.expr() {
         // #[doc = lit]
@@ -771,6 +771,38 @@ impl t for foo {
     )
 }
 
+#[test]
+fn test_deprecated_highlighting() {
+    check_highlighting(
+        r#"
+#[deprecated]
+struct Old;
+
+#[deprecated(since = "1.2.0", note = "use `Trait2`")]
+trait Trait {
+    fn method(&self) {}
+}
+impl Trait for Old {}
+
+struct New;
+impl New {
+    #[deprecated = "use `New::new2`"]
+    fn new() -> New { New }
+    fn new2() -> New { New }
+}
+
+fn main() {
+    let old = Old;
+    old.method();
+    New::new();
+    New::new2();
+}
+"#,
+        expect_file!["./test_data/highlight_deprecated.html"],
+        false,
+    );
+}
+
 #[test]
 fn test_injection() {
     check_highlighting(
@@ -790,6 +822,29 @@ fn foo() {
     );
 }
 
+#[test]
+fn test_language_injection() {
+    check_highlighting(
+        r##"
+mod regex {
+    pub struct Regex;
+    impl Regex {
+        pub fn new(re: &str) -> Regex { Regex }
+    }
+}
+fn main() {
+    let re = regex::Regex::new(r"^(\d{2,4})[a-z-]+?$");
+    // language=sql
+    let query = "SELECT name FROM users WHERE id = $1";
+    // language=json
+    let json = "{\"a\": [1, true]}";
+}
+"##,
+        expect_file!["./test_data/language_injection.html"],
+        false,
+    );
+}
+
 /// Highlights the code given by the `ra_fixture` argument, renders the
 /// result as HTML, and compares it with the HTML file given as `snapshot`.
 /// Note that the `snapshot` file is overwritten by the rendered HTML.
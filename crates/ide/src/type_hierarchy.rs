@@ -0,0 +1,214 @@
+//! Entry point for type-hierarchy
+
+use hir::{Impl, Semantics};
+use ide_db::{
+    defs::{Definition, NameClass, NameRefClass},
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{ast, AstNode};
+
+use crate::{display::TryToNav, FilePosition, NavigationTarget, RangeInfo};
+
+// Feature: Type Hierarchy
+//
+// Shows the supertraits of a trait and the traits implemented by a type, as
+// well as the subtraits and implementors of a trait.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Show Type Hierarchy**
+// |===
+pub(crate) fn type_hierarchy(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let sema = Semantics::new(db);
+    let (range, def) = type_at(&sema, position)?;
+    let nav = def.try_to_nav(db)?;
+    Some(RangeInfo { range, info: vec![nav] })
+}
+
+pub(crate) fn supertypes(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let (_, def) = type_at(&sema, position)?;
+    let navs = match def {
+        hir::ModuleDef::Trait(trait_) => {
+            trait_.direct_supertraits(db).into_iter().filter_map(|it| it.try_to_nav(db)).collect()
+        }
+        hir::ModuleDef::Adt(adt) => implemented_traits(db, adt.ty(db)),
+        hir::ModuleDef::TypeAlias(alias) => implemented_traits(db, alias.ty(db)),
+        _ => return None,
+    };
+    Some(navs)
+}
+
+pub(crate) fn subtypes(db: &RootDatabase, position: FilePosition) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let (_, def) = type_at(&sema, position)?;
+    let trait_ = match def {
+        hir::ModuleDef::Trait(it) => it,
+        hir::ModuleDef::Adt(_) | hir::ModuleDef::TypeAlias(_) => return Some(Vec::new()),
+        _ => return None,
+    };
+    let subtraits = trait_.direct_subtraits(db).into_iter().filter_map(|it| it.try_to_nav(db));
+    // Implementors are shown as their type where it has a definition, and as
+    // the impl block otherwise.
+    let implementors = Impl::all_for_trait(db, trait_).into_iter().filter_map(|imp| {
+        match imp.self_ty(db).as_adt() {
+            Some(adt) => adt.try_to_nav(db),
+            None => imp.try_to_nav(db),
+        }
+    });
+    Some(subtraits.chain(implementors).unique().collect())
+}
+
+fn implemented_traits(db: &RootDatabase, ty: hir::Type) -> Vec<NavigationTarget> {
+    Impl::all_for_type(db, ty)
+        .into_iter()
+        .filter_map(|imp| imp.trait_(db))
+        .unique()
+        .filter_map(|it| it.try_to_nav(db))
+        .collect()
+}
+
+fn type_at(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+) -> Option<(syntax::TextRange, hir::ModuleDef)> {
+    let file = sema.parse(position.file_id);
+    let node = sema.find_node_at_offset_with_descend(file.syntax(), position.offset)?;
+    let def = match &node {
+        ast::NameLike::Name(name) => match NameClass::classify(sema, name)? {
+            NameClass::Definition(it) | NameClass::ConstReference(it) => it,
+            NameClass::PatFieldShorthand { .. } => return None,
+        },
+        ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(sema, name_ref)? {
+            NameRefClass::Definition(it) => it,
+            NameRefClass::FieldShorthand { .. } => return None,
+        },
+        ast::NameLike::Lifetime(_) => return None,
+    };
+    match def {
+        Definition::ModuleDef(
+            def
+            @ (hir::ModuleDef::Trait(_) | hir::ModuleDef::Adt(_) | hir::ModuleDef::TypeAlias(_)),
+        ) => Some((node.syntax().text_range(), def)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::base_db::FilePosition;
+
+    use crate::fixture;
+
+    fn check_hierarchy(
+        ra_fixture: &str,
+        expected: &str,
+        expected_supertypes: &[&str],
+        expected_subtypes: &[&str],
+    ) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+
+        let mut navs = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        let nav = navs.pop().unwrap();
+        nav.assert_match(expected);
+
+        let item_pos =
+            FilePosition { file_id: nav.file_id, offset: nav.focus_or_full_range().start() };
+        let supertypes = analysis.supertypes(item_pos).unwrap().unwrap();
+        assert_eq!(supertypes.len(), expected_supertypes.len());
+        for (nav, expected) in supertypes.iter().zip(expected_supertypes) {
+            nav.assert_match(expected);
+        }
+
+        let subtypes = analysis.subtypes(item_pos).unwrap().unwrap();
+        assert_eq!(subtypes.len(), expected_subtypes.len());
+        for (nav, expected) in subtypes.iter().zip(expected_subtypes) {
+            nav.assert_match(expected);
+        }
+    }
+
+    #[test]
+    fn trait_hierarchy() {
+        check_hierarchy(
+            r#"
+trait A {}
+trait B$0: A {}
+trait C: B {}
+struct S;
+impl B for S {}
+"#,
+            "B Trait FileId(0) 11..24 17..18",
+            &["A Trait FileId(0) 0..10 6..7"],
+            &["C Trait FileId(0) 25..38 31..32", "S Struct FileId(0) 39..48 46..47"],
+        );
+    }
+
+    #[test]
+    fn supertraits_in_where_clause() {
+        check_hierarchy(
+            r#"
+trait A {}
+trait B {}
+trait C$0 where Self: A + B {}
+"#,
+            "C Trait FileId(0) 22..50 28..29",
+            &["A Trait FileId(0) 0..10 6..7", "B Trait FileId(0) 11..21 17..18"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn type_implemented_traits() {
+        check_hierarchy(
+            r#"
+trait A {}
+trait B {}
+struct S;
+impl A for S {}
+impl B for S {}
+impl S {}
+fn f(s: S$0) {}
+"#,
+            "S Struct FileId(0) 22..31 29..30",
+            &["A Trait FileId(0) 0..10 6..7", "B Trait FileId(0) 11..21 17..18"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn implementor_without_definition() {
+        check_hierarchy(
+            r#"
+trait A$0 {}
+impl A for &'static str {}
+"#,
+            "A Trait FileId(0) 0..10 6..7",
+            &[],
+            &["impl Impl FileId(0) 11..37 22..34"],
+        );
+    }
+
+    #[test]
+    fn subtraits_in_dependent_crate() {
+        check_hierarchy(
+            r#"
+//- /main.rs crate:main deps:lib
+trait B: lib::A {}
+//- /lib.rs crate:lib
+pub trait A$0 {}
+"#,
+            "A Trait FileId(1) 0..14 10..11",
+            &[],
+            &["B Trait FileId(0) 0..18 6..7"],
+        );
+    }
+}
@@ -22,7 +22,7 @@ use ide_db::{
 use syntax::{
     algo::find_node_at_offset,
     ast::{self, edit::IndentLevel, AstToken},
-    AstNode, Parse, SourceFile,
+    AstNode, Edition, Parse, SourceFile,
     SyntaxKind::{self, FIELD_EXPR, METHOD_CALL_EXPR},
     TextRange, TextSize,
 };
@@ -66,12 +66,14 @@ pub(crate) fn on_char_typed(
     if !stdx::always!(file.tree().syntax().text().char_at(position.offset) == Some(char_typed)) {
         return None;
     }
-    let edit = on_char_typed_inner(file, position.offset, char_typed)?;
+    let edition = db.file_edition(position.file_id);
+    let edit = on_char_typed_inner(file, edition, position.offset, char_typed)?;
     Some(SourceChange::from_text_edit(position.file_id, edit))
 }
 
 fn on_char_typed_inner(
     file: &Parse<SourceFile>,
+    edition: Edition,
     offset: TextSize,
     char_typed: char,
 ) -> Option<TextEdit> {
@@ -82,14 +84,18 @@ fn on_char_typed_inner(
         '.' => on_dot_typed(&file.tree(), offset),
         '=' => on_eq_typed(&file.tree(), offset),
         '>' => on_arrow_typed(&file.tree(), offset),
-        '{' => on_opening_brace_typed(file, offset),
+        '{' => on_opening_brace_typed(file, edition, offset),
         _ => unreachable!(),
     }
 }
 
 /// Inserts a closing `}` when the user types an opening `{`, wrapping an existing expression in a
 /// block, or a part of a `use` item.
-fn on_opening_brace_typed(file: &Parse<SourceFile>, offset: TextSize) -> Option<TextEdit> {
+fn on_opening_brace_typed(
+    file: &Parse<SourceFile>,
+    edition: Edition,
+    offset: TextSize,
+) -> Option<TextEdit> {
     if !stdx::always!(file.tree().syntax().text().char_at(offset) == Some('{')) {
         return None;
     }
@@ -104,7 +110,7 @@ fn on_opening_brace_typed(file: &Parse<SourceFile>, offset: TextSize) -> Option<
     if !stdx::always!(range.len() == TextSize::of('{')) {
         return None;
     }
-    let file = file.reparse(&Indel::delete(range));
+    let file = file.reparse(&Indel::delete(range), edition);
 
     if let Some(edit) = brace_expr(&file.tree(), offset) {
         return Some(edit);
@@ -245,7 +251,7 @@ mod tests {
         let edit = TextEdit::insert(offset, char_typed.to_string());
         edit.apply(&mut before);
         let parse = SourceFile::parse(&before);
-        on_char_typed_inner(&parse, offset, char_typed).map(|it| {
+        on_char_typed_inner(&parse, Edition::CURRENT, offset, char_typed).map(|it| {
             it.apply(&mut before);
             before.to_string()
         })
@@ -139,7 +139,7 @@ impl Assists {
             return None;
         }
         let label = Label::new(label.into());
-        let assist = Assist { id, label, group: None, target, source_change: None };
+        let assist = Assist { id, label, group: None, target, source_change: None, command: None };
         self.add_impl(assist, f)
     }
 
@@ -155,7 +155,14 @@ impl Assists {
             return None;
         }
         let label = Label::new(label.into());
-        let assist = Assist { id, label, group: Some(group.clone()), target, source_change: None };
+        let assist = Assist {
+            id,
+            label,
+            group: Some(group.clone()),
+            target,
+            source_change: None,
+            command: None,
+        };
         self.add_impl(assist, f)
     }
 
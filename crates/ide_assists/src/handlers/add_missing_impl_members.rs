@@ -1,11 +1,9 @@
-use ide_db::traits::resolve_target_trait;
+use ide_db::traits::{add_trait_assoc_items_to_impl, resolve_target_trait};
 use syntax::ast::{self, AstNode};
 
 use crate::{
     assist_context::{AssistContext, Assists},
-    utils::{
-        add_trait_assoc_items_to_impl, filter_assoc_items, render_snippet, Cursor, DefaultMethods,
-    },
+    utils::{filter_assoc_items, render_snippet, Cursor, DefaultMethods},
     AssistId, AssistKind,
 };
 
@@ -0,0 +1,173 @@
+use ide_db::helpers::{
+    insert_use::{organize_imports as organize, ImportScope},
+    unused_imports::{remove_use_tree, unused_imports},
+};
+use syntax::{ast, AstNode};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: organize_imports
+//
+// Removes the unused imports of the file, then sorts and merges the imports
+// of the scope according to the import granularity setting.
+//
+// ```
+// mod m { pub struct A; pub struct B; }
+// use m::{B, A}$0;
+// use std::fmt;
+//
+// fn f(_: A) -> fmt::Result {}
+// ```
+// ->
+// ```
+// mod m { pub struct A; pub struct B; }
+// use std::fmt;
+//
+// use m::A;
+//
+// fn f(_: A) -> fmt::Result {}
+// ```
+pub(crate) fn organize_imports(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let use_item: ast::Use = ctx.find_node_at_offset()?;
+    let scope = ImportScope::find_insert_use_container(use_item.syntax())?;
+    let unused = unused_imports(&ctx.sema, ctx.frange.file_id);
+
+    let target = use_item.syntax().text_range();
+    acc.add(
+        AssistId("organize_imports", AssistKind::RefactorRewrite),
+        "Organize imports",
+        target,
+        |builder| {
+            let scope = match scope {
+                ImportScope::File(it) => ImportScope::File(builder.make_mut(it)),
+                ImportScope::Module(it) => ImportScope::Module(builder.make_mut(it)),
+                ImportScope::Block(it) => ImportScope::Block(builder.make_mut(it)),
+            };
+            let unused: Vec<_> = unused.into_iter().map(|tree| builder.make_mut(tree)).collect();
+            for tree in &unused {
+                remove_use_tree(tree);
+            }
+            organize(&scope, &ctx.config.insert_use);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::helpers::insert_use::ImportGranularity;
+
+    use crate::tests::{check_assist, check_assist_with_config, TEST_CONFIG};
+
+    use super::*;
+
+    #[test]
+    fn removes_unused_and_merges_imports() {
+        check_assist(
+            organize_imports,
+            r#"
+mod a { pub struct A; pub struct B; pub mod c { pub struct C; } }
+use a::c::C;
+use a::B$0;
+use a::A;
+use std::fmt;
+use std::collections::HashMap;
+
+fn f(_: A, _: C, _: fmt::Result, _: HashMap) {}
+"#,
+            r#"
+mod a { pub struct A; pub struct B; pub mod c { pub struct C; } }
+use std::{collections::HashMap, fmt};
+
+use a::{A, c::C};
+
+fn f(_: A, _: C, _: fmt::Result, _: HashMap) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn flattens_imports_with_item_granularity() {
+        let mut config = TEST_CONFIG;
+        config.insert_use.granularity = ImportGranularity::Item;
+        check_assist_with_config(
+            organize_imports,
+            config,
+            r#"
+mod a { pub struct A; pub struct B; pub mod c { pub struct C; } }
+use a::{c::{self, C}, A as Aa, B}$0;
+
+fn f(_: Aa, _: C, _: c::C) {}
+"#,
+            r#"
+mod a { pub struct A; pub struct B; pub mod c { pub struct C; } }
+use a::A as Aa;
+use a::c;
+use a::c::C;
+
+fn f(_: Aa, _: C, _: c::C) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_imports_with_attributes_and_comments() {
+        check_assist(
+            organize_imports,
+            r#"
+mod inner {
+    mod a { pub struct A; pub struct B; }
+    use self::a::B;
+    use self::a::A$0;
+    // comment
+    use self::a::B as C;
+    #[cfg(test)]
+    use self::a::A as D;
+
+    fn f(_: A, _: B, _: C) {}
+}
+"#,
+            r#"
+mod inner {
+    mod a { pub struct A; pub struct B; }
+    use self::a::{A, B};
+    // comment
+    use self::a::B as C;
+    #[cfg(test)]
+    use self::a::A as D;
+
+    fn f(_: A, _: B, _: C) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn preserves_granularity() {
+        let mut config = TEST_CONFIG;
+        config.insert_use.granularity = ImportGranularity::Preserve;
+        check_assist_with_config(
+            organize_imports,
+            config,
+            r#"
+mod a { pub struct A; pub struct B; }
+mod b { pub struct C; }
+use crate::b::C;
+use a::{A, B}$0;
+use std::fmt;
+
+fn f(_: A, _: C, _: fmt::Result) {}
+"#,
+            r#"
+mod a { pub struct A; pub struct B; }
+mod b { pub struct C; }
+use std::fmt;
+
+use a::{A};
+
+use crate::b::C;
+
+fn f(_: A, _: C, _: fmt::Result) {}
+"#,
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use hir::ModuleDef;
 use ide_db::helpers::{import_assets::NameToImport, mod_path_to_ast};
-use ide_db::items_locator;
+use ide_db::{items_locator, traits::add_trait_assoc_items_to_impl};
 use itertools::Itertools;
 use syntax::{
     ast::{self, make, AstNode, NameOwner},
@@ -10,7 +10,7 @@ use syntax::{
 use crate::{
     assist_context::{AssistBuilder, AssistContext, Assists},
     utils::{
-        add_trait_assoc_items_to_impl, filter_assoc_items, generate_trait_impl_text,
+        filter_assoc_items, gen_trait_fn_body::gen_trait_fn_body, generate_trait_impl_text,
         render_snippet, Cursor, DefaultMethods,
     },
     AssistId, AssistKind,
@@ -18,20 +18,21 @@ use crate::{
 
 // Assist: replace_derive_with_manual_impl
 //
-// Converts a `derive` impl into a manual one.
+// Converts a `derive` impl into a manual one. For the builtin derivable traits
+// the methods get the body the derive would generate.
 //
 // ```
-// # trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-// #[derive(Deb$0ug, Display)]
+// # trait Display { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
+// #[derive(Debug, Disp$0lay)]
 // struct S;
 // ```
 // ->
 // ```
-// # trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-// #[derive(Display)]
+// # trait Display { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
+// #[derive(Debug)]
 // struct S;
 //
-// impl Debug for S {
+// impl Display for S {
 //     fn fmt(&self, f: &mut Formatter) -> Result<()> {
 //         ${0:todo!()}
 //     }
@@ -111,13 +112,16 @@ fn add_assist(
         |builder| {
             let insert_pos = adt.syntax().text_range().end();
             let impl_def_with_items =
-                impl_def_from_trait(&ctx.sema, &annotated_name, trait_, trait_path);
+                impl_def_from_trait(&ctx.sema, adt, &annotated_name, trait_, trait_path);
             update_attribute(builder, input, &trait_name, attr);
             let trait_path = format!("{}", trait_path);
             match (ctx.config.snippet_cap, impl_def_with_items) {
-                (None, _) => {
+                (None, None) => {
                     builder.insert(insert_pos, generate_trait_impl_text(adt, &trait_path, ""))
                 }
+                (None, Some((impl_def, _))) => {
+                    builder.insert(insert_pos, format!("\n\n{}", impl_def))
+                }
                 (Some(cap), None) => builder.insert_snippet(
                     cap,
                     insert_pos,
@@ -149,12 +153,14 @@ fn add_assist(
 
 fn impl_def_from_trait(
     sema: &hir::Semantics<ide_db::RootDatabase>,
+    adt: &ast::Adt,
     annotated_name: &ast::Name,
     trait_: Option<hir::Trait>,
     trait_path: &ast::Path,
 ) -> Option<(ast::Impl, ast::AssocItem)> {
     let trait_ = trait_?;
     let target_scope = sema.scope(annotated_name.syntax());
+    let module = target_scope.module()?;
     let trait_items = filter_assoc_items(sema.db, &trait_.items(sema.db), DefaultMethods::No);
     if trait_items.is_empty() {
         return None;
@@ -163,6 +169,11 @@ fn impl_def_from_trait(
         make::impl_trait(trait_path.clone(), make::ext::ident_path(&annotated_name.text()));
     let (impl_def, first_assoc_item) =
         add_trait_assoc_items_to_impl(sema, trait_items, trait_, impl_def, target_scope);
+
+    // Fill in the body the derive would have generated, if we know it.
+    if let ast::AssocItem::Fn(func) = &first_assoc_item {
+        gen_trait_fn_body(sema, module, func, trait_path, adt);
+    }
     Some((impl_def, first_assoc_item))
 }
 
@@ -240,13 +251,354 @@ struct Foo {
 }
 
 impl fmt::Debug for Foo {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        ${0:todo!()}
+    $0fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Foo").field("bar", &self.bar).finish()
     }
 }
 "#,
         )
     }
+    #[test]
+    fn add_custom_impl_debug_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+mod fmt {
+    pub struct Error;
+    pub type Result = Result<(), Error>;
+    pub struct Formatter<'a>;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result;
+    }
+}
+
+#[derive(Debu$0g)]
+enum Foo {
+    Bar { baz: usize },
+    Qux(usize, String),
+    Empty,
+}
+"#,
+            r#"
+mod fmt {
+    pub struct Error;
+    pub type Result = Result<(), Error>;
+    pub struct Formatter<'a>;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result;
+    }
+}
+
+enum Foo {
+    Bar { baz: usize },
+    Qux(usize, String),
+    Empty,
+}
+
+impl fmt::Debug for Foo {
+    $0fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bar { baz } => f.debug_struct("Bar").field("baz", baz).finish(),
+            Self::Qux(arg0, arg1) => f.debug_tuple("Qux").field(arg0).field(arg1).finish(),
+            Self::Empty => f.write_str("Empty"),
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_clone() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: clone
+#[derive(Clo$0ne)]
+struct Foo {
+    bar: usize,
+    baz: String,
+}
+"#,
+            r#"
+struct Foo {
+    bar: usize,
+    baz: String,
+}
+
+impl Clone for Foo {
+    $0fn clone(&self) -> Self {
+        Self { bar: self.bar.clone(), baz: self.baz.clone() }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_clone_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: clone
+#[derive(Clo$0ne)]
+enum Foo {
+    Bar { baz: usize },
+    Qux(String),
+    Empty,
+}
+"#,
+            r#"
+enum Foo {
+    Bar { baz: usize },
+    Qux(String),
+    Empty,
+}
+
+impl Clone for Foo {
+    $0fn clone(&self) -> Self {
+        match self {
+            Self::Bar { baz } => Self::Bar { baz: baz.clone() },
+            Self::Qux(arg0) => Self::Qux(arg0.clone()),
+            Self::Empty => Self::Empty,
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_default() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: default
+#[derive(Defau$0lt)]
+struct Foo(usize, String);
+"#,
+            r#"
+struct Foo(usize, String);
+
+impl Default for Foo {
+    $0fn default() -> Self {
+        Self(Default::default(), Default::default())
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_hash() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: mem
+mod hash {
+    pub trait Hasher {}
+    pub trait Hash {
+        fn hash<H: Hasher>(&self, state: &mut H);
+    }
+}
+
+#[derive(Has$0h)]
+enum Foo {
+    Bar { baz: usize },
+    Empty,
+}
+"#,
+            r#"
+mod hash {
+    pub trait Hasher {}
+    pub trait Hash {
+        fn hash<H: Hasher>(&self, state: &mut H);
+    }
+}
+
+enum Foo {
+    Bar { baz: usize },
+    Empty,
+}
+
+impl hash::Hash for Foo {
+    $0fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        hash::Hash::hash(&core::mem::discriminant(self), state);
+        match self {
+            Self::Bar { baz } => {
+                hash::Hash::hash(baz, state);
+            }
+            _ => {}
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_hash_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+mod hash {
+    pub trait Hasher {}
+    pub trait Hash {
+        fn hash<H: Hasher>(&self, state: &mut H);
+    }
+}
+
+#[derive(Has$0h)]
+struct Foo(usize, String);
+"#,
+            r#"
+mod hash {
+    pub trait Hasher {}
+    pub trait Hash {
+        fn hash<H: Hasher>(&self, state: &mut H);
+    }
+}
+
+struct Foo(usize, String);
+
+impl hash::Hash for Foo {
+    $0fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        hash::Hash::hash(&self.0, state);
+        hash::Hash::hash(&self.1, state);
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_partial_eq() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: eq, mem
+#[derive(Partial$0Eq)]
+enum Foo {
+    Bar { baz: usize, qux: usize },
+    Quux(String),
+    Empty,
+}
+"#,
+            r#"
+enum Foo {
+    Bar { baz: usize, qux: usize },
+    Quux(String),
+    Empty,
+}
+
+impl PartialEq for Foo {
+    $0fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bar { baz: l_baz, qux: l_qux }, Self::Bar { baz: r_baz, qux: r_qux }) => l_baz == r_baz && l_qux == r_qux,
+            (Self::Quux(l_0), Self::Quux(r_0)) => l_0 == r_0,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_partial_eq_raw_field() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: eq, mem
+#[derive(Partial$0Eq)]
+enum Foo {
+    Bar { r#type: usize },
+    Empty,
+}
+"#,
+            r#"
+enum Foo {
+    Bar { r#type: usize },
+    Empty,
+}
+
+impl PartialEq for Foo {
+    $0fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bar { r#type: l_type }, Self::Bar { r#type: r_type }) => l_type == r_type,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_partial_ord() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: ord
+#[derive(Partial$0Ord)]
+struct Foo {
+    bar: usize,
+    baz: usize,
+}
+"#,
+            r#"
+struct Foo {
+    bar: usize,
+    baz: usize,
+}
+
+impl PartialOrd for Foo {
+    $0fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        match self.bar.partial_cmp(&other.bar) {
+            Some(core::cmp::Ordering::Equal) => {}
+            ord => return ord,
+        }
+        self.baz.partial_cmp(&other.baz)
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_ord_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: ord
+#[derive(O$0rd)]
+enum Foo {
+    Bar(usize),
+    Empty,
+}
+"#,
+            r#"
+enum Foo {
+    Bar(usize),
+    Empty,
+}
+
+impl Ord for Foo {
+    $0fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let variant_index = |it: &Self| match it {
+            Self::Bar(..) => 0,
+            Self::Empty => 1,
+        };
+        match (self, other) {
+            (Self::Bar(l_0), Self::Bar(r_0)) => l_0.cmp(r_0),
+            _ => variant_index(self).cmp(&variant_index(other)),
+        }
+    }
+}
+"#,
+        )
+    }
+
     #[test]
     fn add_custom_impl_all() {
         check_assist(
@@ -0,0 +1,457 @@
+use hir::Type;
+use ide_db::{call_info::ActiveParameter, helpers::term_search::term_search};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, make},
+    AstNode, SyntaxKind, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: term_search
+//
+// Replaces a `todo!()` with an expression of the expected type, built from the
+// locals, fields, methods and constructors in scope.
+//
+// ```
+// struct Name;
+// fn greet(name: Name) {}
+// fn main() {
+//     let name = Name;
+//     greet(todo$0!());
+// }
+// ```
+// ->
+// ```
+// struct Name;
+// fn greet(name: Name) {}
+// fn main() {
+//     let name = Name;
+//     greet(name);
+// }
+// ```
+pub(crate) fn term_search_hole(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let name = macro_call.path()?.segment()?.name_ref()?;
+    if name.text() != "todo" || macro_call.excl_token().is_none() {
+        return None;
+    }
+    let goal = expected_type(ctx, &macro_call)?;
+    let current_fn =
+        macro_call.syntax().ancestors().find_map(ast::Fn::cast).and_then(|it| ctx.sema.to_def(&it));
+    let scope = ctx.sema.scope(macro_call.syntax());
+    let terms = term_search(&ctx.sema, &scope, &goal, current_fn);
+
+    let range = macro_call.syntax().text_range();
+    let group = GroupLabel("Fill the hole".to_string());
+    for term in terms {
+        acc.add_group(
+            &group,
+            AssistId("term_search", AssistKind::Generate),
+            format!("Replace `todo!()` with `{}`", term),
+            range,
+            |builder| builder.replace(range, term),
+        );
+    }
+    Some(())
+}
+
+// Assist: fill_missing_arguments
+//
+// Adds the missing arguments of a call, filled with expressions of the
+// parameter types where possible and `todo!()` otherwise.
+//
+// ```
+// fn connect(host: &str, port: u16) {}
+// fn main() {
+//     let host = "localhost";
+//     connect($0);
+// }
+// ```
+// ->
+// ```
+// fn connect(host: &str, port: u16) {}
+// fn main() {
+//     let host = "localhost";
+//     connect(host, todo!());
+// }
+// ```
+pub(crate) fn fill_missing_arguments(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let arg_list = ctx.find_node_at_offset::<ast::ArgList>()?;
+    let callable = match_call(ctx, &arg_list)?;
+    let params = callable.params(ctx.db());
+    let provided = arg_list.args().count();
+    if provided >= params.len() {
+        return None;
+    }
+
+    let current_fn =
+        arg_list.syntax().ancestors().find_map(ast::Fn::cast).and_then(|it| ctx.sema.to_def(&it));
+    let scope = ctx.sema.scope(arg_list.syntax());
+    let args = params[provided..]
+        .iter()
+        .map(|(_, ty)| {
+            term_search(&ctx.sema, &scope, ty, current_fn)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| make::ext::expr_todo().to_string())
+        })
+        .join(", ");
+
+    let r_paren = arg_list.r_paren_token()?;
+    let last = r_paren
+        .siblings_with_tokens(syntax::Direction::Prev)
+        .skip(1)
+        .find(|it| !it.kind().is_trivia())?;
+    let text = match last.kind() {
+        T!['('] => args,
+        T![,] => format!(" {}", args),
+        _ => format!(", {}", args),
+    };
+    let offset = last.text_range().end();
+    acc.add(
+        AssistId("fill_missing_arguments", AssistKind::Generate),
+        "Fill missing arguments",
+        arg_list.syntax().text_range(),
+        |builder| builder.insert(offset, text),
+    )
+}
+
+fn match_call(ctx: &AssistContext, arg_list: &ast::ArgList) -> Option<hir::Callable> {
+    let call = arg_list.syntax().parent()?;
+    match call.kind() {
+        SyntaxKind::CALL_EXPR => {
+            let call = ast::CallExpr::cast(call)?;
+            ctx.sema.type_of_expr(&call.expr()?)?.as_callable(ctx.db())
+        }
+        SyntaxKind::METHOD_CALL_EXPR => {
+            ctx.sema.resolve_method_call_as_callable(&ast::MethodCallExpr::cast(call)?)
+        }
+        _ => None,
+    }
+}
+
+/// Computes the type expected in place of `macro_call`: `todo!()` itself has
+/// type `!`, so we have to look at its surroundings.
+fn expected_type(ctx: &AssistContext, macro_call: &ast::MacroCall) -> Option<Type> {
+    let parent = macro_call.syntax().parent()?;
+    let ty = if let Some(let_stmt) = ast::LetStmt::cast(parent.clone()) {
+        ctx.sema.resolve_type(&let_stmt.ty()?)
+    } else if ast::ArgList::can_cast(parent.kind()) {
+        let token = macro_call.syntax().first_token()?;
+        ActiveParameter::at_token(&ctx.sema, token).map(|it| it.ty)
+    } else if let Some(field) = ast::RecordExprField::cast(parent.clone()) {
+        ctx.sema.resolve_record_field(&field).map(|(_, _, ty)| ty)
+    } else if ast::ReturnExpr::can_cast(parent.kind()) || is_tail_of_fn(macro_call) {
+        let func = macro_call.syntax().ancestors().find_map(ast::Fn::cast)?;
+        ctx.sema.to_def(&func).map(|it| it.ret_type(ctx.db()))
+    } else {
+        None
+    };
+    ty.filter(|it| !it.is_unit() && !it.is_unknown())
+}
+
+fn is_tail_of_fn(macro_call: &ast::MacroCall) -> bool {
+    let block = match macro_call.syntax().ancestors().find_map(ast::BlockExpr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let is_tail = block.tail_expr().map_or(false, |it| it.syntax() == macro_call.syntax());
+    is_tail && block.syntax().parent().map_or(false, |it| ast::Fn::can_cast(it.kind()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn fill_let_with_local() {
+        check_assist_by_label(
+            term_search_hole,
+            r#"
+struct Config;
+fn main() {
+    let config = Config;
+    let c: Config = todo$0!();
+}
+"#,
+            r#"
+struct Config;
+fn main() {
+    let config = Config;
+    let c: Config = config;
+}
+"#,
+            "Replace `todo!()` with `config`",
+        );
+    }
+
+    #[test]
+    fn fill_argument_with_field() {
+        check_assist_by_label(
+            term_search_hole,
+            r#"
+struct Name(u8);
+struct Person { name: Name }
+fn greet(name: Name) {}
+fn main() {
+    let p = Person { name: Name(0) };
+    greet(todo$0!());
+}
+"#,
+            r#"
+struct Name(u8);
+struct Person { name: Name }
+fn greet(name: Name) {}
+fn main() {
+    let p = Person { name: Name(0) };
+    greet(p.name);
+}
+"#,
+            "Replace `todo!()` with `p.name`",
+        );
+    }
+
+    #[test]
+    fn fill_return_value_with_constructor() {
+        check_assist_by_label(
+            term_search_hole,
+            r#"
+struct Port(u16);
+struct Server { port: Port }
+impl Server {
+    fn new(port: Port) -> Server { Server { port } }
+}
+fn make(port: Port) -> Server {
+    todo$0!()
+}
+"#,
+            r#"
+struct Port(u16);
+struct Server { port: Port }
+impl Server {
+    fn new(port: Port) -> Server { Server { port } }
+}
+fn make(port: Port) -> Server {
+    Server { port }
+}
+"#,
+            "Replace `todo!()` with `Server { port }`",
+        );
+    }
+
+    #[test]
+    fn clone_field_behind_reference() {
+        check_assist_by_label(
+            term_search_hole,
+            r#"
+//- minicore: derive, clone, copy
+#[derive(Clone)]
+struct Duration(u64);
+struct Config { timeout: Duration }
+struct Client { config: Config }
+fn wait(timeout: Duration) {}
+impl Client {
+    fn run(&self) {
+        wait(todo$0!());
+    }
+}
+"#,
+            r#"
+#[derive(Clone)]
+struct Duration(u64);
+struct Config { timeout: Duration }
+struct Client { config: Config }
+fn wait(timeout: Duration) {}
+impl Client {
+    fn run(&self) {
+        wait(self.config.timeout.clone());
+    }
+}
+"#,
+            "Replace `todo!()` with `self.config.timeout.clone()`",
+        );
+    }
+
+    #[test]
+    fn borrow_mutable_local_mutably() {
+        check_assist_by_label(
+            term_search_hole,
+            r#"
+struct Buf;
+fn push(buf: &mut Buf) {}
+fn main() {
+    let mut buf = Buf;
+    push(todo$0!());
+}
+"#,
+            r#"
+struct Buf;
+fn push(buf: &mut Buf) {}
+fn main() {
+    let mut buf = Buf;
+    push(&mut buf);
+}
+"#,
+            "Replace `todo!()` with `&mut buf`",
+        );
+    }
+
+    #[test]
+    fn no_mutable_borrow_of_immutable_places() {
+        check_assist_not_applicable(
+            term_search_hole,
+            r#"
+struct Buf;
+struct Writer { buf: Buf }
+fn push(buf: &mut Buf) {}
+impl Writer {
+    fn write(&mut self) {
+        let buf = Buf;
+        push(todo$0!());
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fill_with_method_call() {
+        check_assist_by_label(
+            term_search_hole,
+            r#"
+struct Id(u8);
+struct User;
+impl User { fn id(&self) -> Id { Id(0) } }
+fn main() {
+    let user = User;
+    let id: Id = todo$0!();
+}
+"#,
+            r#"
+struct Id(u8);
+struct User;
+impl User { fn id(&self) -> Id { Id(0) } }
+fn main() {
+    let user = User;
+    let id: Id = user.id();
+}
+"#,
+            "Replace `todo!()` with `user.id()`",
+        );
+    }
+
+    #[test]
+    fn does_not_recurse() {
+        check_assist_not_applicable(
+            term_search_hole,
+            r#"
+struct Id(u32);
+fn id() -> Id {
+    todo$0!()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_expected_type() {
+        check_assist_not_applicable(
+            term_search_hole,
+            r#"
+fn main() {
+    let x = 92;
+    todo$0!();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fill_missing_arguments_with_locals() {
+        check_assist(
+            fill_missing_arguments,
+            r#"
+struct Host;
+struct Port;
+fn connect(host: &Host, port: Port, retries: u8) {}
+fn main() {
+    let host = Host;
+    let port = Port;
+    connect($0);
+}
+"#,
+            r#"
+struct Host;
+struct Port;
+fn connect(host: &Host, port: Port, retries: u8) {}
+fn main() {
+    let host = Host;
+    let port = Port;
+    connect(&host, port, todo!());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fill_missing_mutable_reference_argument() {
+        check_assist(
+            fill_missing_arguments,
+            r#"
+struct Buf;
+fn push(buf: &mut Buf, byte: u8) {}
+fn main() {
+    let mut buf = Buf;
+    push($0);
+}
+"#,
+            r#"
+struct Buf;
+fn push(buf: &mut Buf, byte: u8) {}
+fn main() {
+    let mut buf = Buf;
+    push(&mut buf, todo!());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fill_remaining_method_arguments() {
+        check_assist(
+            fill_missing_arguments,
+            r#"
+struct Port;
+struct Server;
+impl Server { fn listen(&self, host: u8, port: Port) {} }
+fn main(server: Server, port: Port) {
+    server.listen(1,$0);
+}
+"#,
+            r#"
+struct Port;
+struct Server;
+impl Server { fn listen(&self, host: u8, port: Port) {} }
+fn main(server: Server, port: Port) {
+    server.listen(1, port);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_all_arguments() {
+        check_assist_not_applicable(
+            fill_missing_arguments,
+            r#"
+fn f(a: u8) {}
+fn main() {
+    f(1$0);
+}
+"#,
+        );
+    }
+}
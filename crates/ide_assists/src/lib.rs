@@ -24,7 +24,7 @@ pub(crate) use crate::assist_context::{AssistContext, Assists};
 
 pub use assist_config::AssistConfig;
 pub use ide_db::assists::{
-    Assist, AssistId, AssistKind, AssistResolveStrategy, GroupLabel, SingleResolve,
+    Assist, AssistCommand, AssistId, AssistKind, AssistResolveStrategy, GroupLabel, SingleResolve,
 };
 
 /// Return all the assists applicable at the given position.
@@ -94,6 +94,7 @@ mod handlers {
     mod move_bounds;
     mod move_guard;
     mod move_module_to_file;
+    mod organize_imports;
     mod pull_assignment_up;
     mod qualify_path;
     mod raw_string;
@@ -110,6 +111,7 @@ mod handlers {
     mod replace_qualified_name_with_use;
     mod replace_string_with_char;
     mod split_import;
+    mod term_search;
     mod toggle_ignore;
     mod unmerge_use;
     mod unwrap_block;
@@ -164,6 +166,7 @@ mod handlers {
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
             move_module_to_file::move_module_to_file,
+            organize_imports::organize_imports,
             pull_assignment_up::pull_assignment_up,
             qualify_path::qualify_path,
             raw_string::add_hash,
@@ -182,6 +185,8 @@ mod handlers {
             replace_let_with_if_let::replace_let_with_if_let,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             split_import::split_import,
+            term_search::fill_missing_arguments,
+            term_search::term_search_hole,
             toggle_ignore::toggle_ignore,
             unmerge_use::unmerge_use,
             unwrap_block::unwrap_block,
@@ -455,6 +455,27 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_fill_missing_arguments() {
+    check_doc_test(
+        "fill_missing_arguments",
+        r#####"
+fn connect(host: &str, port: u16) {}
+fn main() {
+    let host = "localhost";
+    connect($0);
+}
+"#####,
+        r#####"
+fn connect(host: &str, port: u16) {}
+fn main() {
+    let host = "localhost";
+    connect(host, todo!());
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_fix_visibility() {
     check_doc_test(
@@ -1161,6 +1182,28 @@ mod foo;
     )
 }
 
+#[test]
+fn doctest_organize_imports() {
+    check_doc_test(
+        "organize_imports",
+        r#####"
+mod m { pub struct A; pub struct B; }
+use m::{B, A}$0;
+use std::fmt;
+
+fn f(_: A) -> fmt::Result {}
+"#####,
+        r#####"
+mod m { pub struct A; pub struct B; }
+use std::fmt;
+
+use m::A;
+
+fn f(_: A) -> fmt::Result {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_pull_assignment_up() {
     check_doc_test(
@@ -1336,16 +1379,16 @@ fn doctest_replace_derive_with_manual_impl() {
     check_doc_test(
         "replace_derive_with_manual_impl",
         r#####"
-trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-#[derive(Deb$0ug, Display)]
+trait Display { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
+#[derive(Debug, Disp$0lay)]
 struct S;
 "#####,
         r#####"
-trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-#[derive(Display)]
+trait Display { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
+#[derive(Debug)]
 struct S;
 
-impl Debug for S {
+impl Display for S {
     fn fmt(&self, f: &mut Formatter) -> Result<()> {
         ${0:todo!()}
     }
@@ -1519,6 +1562,29 @@ use std::{collections::HashMap};
     )
 }
 
+#[test]
+fn doctest_term_search() {
+    check_doc_test(
+        "term_search",
+        r#####"
+struct Name;
+fn greet(name: Name) {}
+fn main() {
+    let name = Name;
+    greet(todo$0!());
+}
+"#####,
+        r#####"
+struct Name;
+fn greet(name: Name) {}
+fn main() {
+    let name = Name;
+    greet(name);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_toggle_ignore() {
     check_doc_test(
@@ -1,5 +1,6 @@
 //! Assorted functions shared by several assists.
 
+pub(crate) mod gen_trait_fn_body;
 pub(crate) mod suggest_name;
 
 use std::ops;
@@ -7,18 +8,13 @@ use std::ops;
 use hir::{Adt, HasSource, Semantics};
 use ide_db::{
     helpers::{FamousDefs, SnippetCap},
-    path_transform::PathTransform,
     RootDatabase,
 };
 use itertools::Itertools;
 use stdx::format_to;
 use syntax::{
-    ast::{
-        self,
-        edit::{self, AstNodeEdit},
-        make, ArgListOwner, AttrsOwner, GenericParamsOwner, NameOwner, TypeBoundsOwner,
-    },
-    ted, AstNode, Direction, SmolStr,
+    ast::{self, make, ArgListOwner, AttrsOwner, GenericParamsOwner, NameOwner, TypeBoundsOwner},
+    AstNode, Direction, SmolStr,
     SyntaxKind::*,
     SyntaxNode, TextSize, T,
 };
@@ -117,53 +113,6 @@ pub fn filter_assoc_items(
         .collect::<Vec<_>>()
 }
 
-pub fn add_trait_assoc_items_to_impl(
-    sema: &hir::Semantics<ide_db::RootDatabase>,
-    items: Vec<ast::AssocItem>,
-    trait_: hir::Trait,
-    impl_: ast::Impl,
-    target_scope: hir::SemanticsScope,
-) -> (ast::Impl, ast::AssocItem) {
-    let source_scope = sema.scope_for_def(trait_);
-
-    let transform = PathTransform {
-        subst: (trait_, impl_.clone()),
-        source_scope: &source_scope,
-        target_scope: &target_scope,
-    };
-
-    let items = items.into_iter().map(|assoc_item| {
-        let assoc_item = assoc_item.clone_for_update();
-        transform.apply(assoc_item.clone());
-        edit::remove_attrs_and_docs(&assoc_item).clone_subtree().clone_for_update()
-    });
-
-    let res = impl_.clone_for_update();
-
-    let assoc_item_list = res.get_or_create_assoc_item_list();
-    let mut first_item = None;
-    for item in items {
-        first_item.get_or_insert_with(|| item.clone());
-        match &item {
-            ast::AssocItem::Fn(fn_) if fn_.body().is_none() => {
-                let body = make::block_expr(None, Some(make::ext::expr_todo()))
-                    .indent(edit::IndentLevel(1));
-                ted::replace(fn_.get_or_create_body().syntax(), body.clone_for_update().syntax())
-            }
-            ast::AssocItem::TypeAlias(type_alias) => {
-                if let Some(type_bound_list) = type_alias.type_bound_list() {
-                    type_bound_list.remove()
-                }
-            }
-            _ => {}
-        }
-
-        assoc_item_list.add_item(item)
-    }
-
-    (res, first_item.unwrap())
-}
-
 #[derive(Clone, Copy, Debug)]
 pub(crate) enum Cursor<'a> {
     Replace(&'a SyntaxNode),
@@ -0,0 +1,567 @@
+//! Generates the bodies of derivable trait methods, mirroring the code the
+//! corresponding `#[derive]` would expand to.
+
+use hir::{Module, ModuleDef, Semantics};
+use ide_db::{
+    helpers::{mod_path_to_ast, FamousDefs},
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, edit::AstNodeEdit, edit::IndentLevel, make, NameOwner},
+    ted, AstNode, T,
+};
+
+/// Replaces the placeholder body of `func`, a method of the derivable trait
+/// `trait_path` implemented for `adt` in `module`, with a real implementation.
+pub(crate) fn gen_trait_fn_body(
+    sema: &Semantics<RootDatabase>,
+    module: Module,
+    func: &ast::Fn,
+    trait_path: &ast::Path,
+    adt: &ast::Adt,
+) -> Option<()> {
+    let trait_name = trait_path.segment()?.name_ref()?;
+    let fn_name = func.name()?;
+    let shape = Shape::of(adt)?;
+    let paths = CorePaths::new(sema, module);
+    let body = match (trait_name.text().as_str(), fn_name.text().as_str()) {
+        ("Clone", "clone") => gen_clone(&shape),
+        ("Debug", "fmt") => gen_debug(&shape, &param_name(func)?),
+        ("Default", "default") => gen_default(&shape)?,
+        ("Hash", "hash") => gen_hash(&shape, trait_path, &paths, &param_name(func)?)?,
+        ("PartialEq", "eq") => gen_partial_eq(&shape, &paths, &param_name(func)?)?,
+        ("PartialOrd", "partial_cmp") => {
+            gen_ord(&shape, &paths, &param_name(func)?, Ordering::Partial)?
+        }
+        ("Ord", "cmp") => gen_ord(&shape, &paths, &param_name(func)?, Ordering::Total)?,
+        _ => return None,
+    };
+    let body = body.indent(IndentLevel(1));
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+    Some(())
+}
+
+/// Paths to the items of `core` the generated bodies refer to, as seen from
+/// the module the impl is inserted into.
+struct CorePaths {
+    discriminant: Option<ast::Path>,
+    ordering: Option<ast::Path>,
+}
+
+impl CorePaths {
+    fn new(sema: &Semantics<RootDatabase>, module: Module) -> CorePaths {
+        let famous_defs = FamousDefs(sema, Some(module.krate()));
+        let find_path =
+            |def: ModuleDef| module.find_use_path(sema.db, def).as_ref().map(mod_path_to_ast);
+        CorePaths {
+            discriminant: famous_defs
+                .core_mem_discriminant()
+                .and_then(|it| find_path(ModuleDef::Function(it))),
+            ordering: famous_defs
+                .core_cmp_Ordering()
+                .and_then(|it| find_path(ModuleDef::Adt(hir::Adt::Enum(it)))),
+        }
+    }
+
+    /// `core::mem::discriminant(arg)`.
+    fn discriminant(&self, arg: &str) -> Option<ast::Expr> {
+        let path = self.discriminant.clone()?;
+        let arg = make::expr_path(make::ext::ident_path(arg));
+        Some(make::expr_call(make::expr_path(path), make::arg_list(Some(arg))))
+    }
+
+    /// `core::cmp::Ordering::Equal`.
+    fn ordering_equal(&self) -> Option<ast::Path> {
+        let ordering = self.ordering.as_ref()?;
+        Some(make::path_from_text(&format!("{}::Equal", ordering)))
+    }
+}
+
+/// The name of the first non-`self` parameter, like `f` in `Debug::fmt`.
+fn param_name(func: &ast::Fn) -> Option<String> {
+    let pat = func.param_list()?.params().next()?.pat()?;
+    Some(pat.syntax().text().to_string())
+}
+
+enum Fields {
+    Record(Vec<ast::Name>),
+    Tuple(usize),
+    Unit,
+}
+
+impl Fields {
+    fn of(field_list: Option<ast::FieldList>) -> Option<Fields> {
+        let res = match field_list {
+            Some(ast::FieldList::RecordFieldList(it)) => {
+                Fields::Record(it.fields().map(|it| it.name()).collect::<Option<_>>()?)
+            }
+            Some(ast::FieldList::TupleFieldList(it)) => Fields::Tuple(it.fields().count()),
+            None => Fields::Unit,
+        };
+        Some(res)
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Fields::Record(it) => it.is_empty(),
+            Fields::Tuple(it) => *it == 0,
+            Fields::Unit => true,
+        }
+    }
+
+    /// The fields as accessed on `receiver`, like `self.name` or `self.0`.
+    fn access(&self, receiver: &str) -> Vec<(String, ast::Expr)> {
+        self.names()
+            .into_iter()
+            .map(|name| {
+                let receiver = make::expr_path(make::ext::ident_path(receiver));
+                let expr = make::expr_field(receiver, &name);
+                (name, expr)
+            })
+            .collect()
+    }
+
+    fn names(&self) -> Vec<String> {
+        match self {
+            Fields::Record(it) => it.iter().map(|it| it.text().to_string()).collect(),
+            Fields::Tuple(it) => (0..*it).map(|it| it.to_string()).collect(),
+            Fields::Unit => Vec::new(),
+        }
+    }
+
+    /// A pattern binding all fields to locals named `{prefix}{field}`, or just
+    /// the field names for records without a prefix.
+    fn bind(&self, path: ast::Path, prefix: &str) -> (ast::Pat, Vec<(String, ast::Expr)>) {
+        let prefix = match (self, prefix) {
+            (Fields::Tuple(_), "") => "arg",
+            _ => prefix,
+        };
+        let bindings: Vec<_> = self
+            .names()
+            .into_iter()
+            .map(|name| {
+                // `l_r#type` isn't an identifier, and the prefix already
+                // keeps a raw field name from clashing with a keyword.
+                let local = match name.strip_prefix("r#") {
+                    Some(raw) if !prefix.is_empty() => format!("{}{}", prefix, raw),
+                    _ => format!("{}{}", prefix, name),
+                };
+                (name, local)
+            })
+            .collect();
+        let pat = match self {
+            Fields::Record(_) if prefix.is_empty() => {
+                let pats = bindings
+                    .iter()
+                    .map(|(_, local)| make::ident_pat(false, false, make::name(local)).into());
+                make::record_pat(path, pats).into()
+            }
+            Fields::Record(_) => {
+                let fields = bindings.iter().map(|(name, local)| {
+                    let pat = make::ident_pat(false, false, make::name(local));
+                    make::record_pat_field(make::name_ref(name), pat.into())
+                });
+                make::record_pat_with_fields(path, make::record_pat_field_list(fields)).into()
+            }
+            Fields::Tuple(_) => {
+                let pats = bindings
+                    .iter()
+                    .map(|(_, local)| make::ident_pat(false, false, make::name(local)).into());
+                make::tuple_struct_pat(path, pats).into()
+            }
+            Fields::Unit => make::path_pat(path),
+        };
+        let exprs = bindings
+            .into_iter()
+            .map(|(name, local)| (name, make::expr_path(make::ext::ident_path(&local))))
+            .collect();
+        (pat, exprs)
+    }
+
+    /// A pattern matching the variant at `path` without binding anything.
+    fn wildcard(&self, path: ast::Path) -> ast::Pat {
+        match self {
+            Fields::Record(_) => make::record_pat(path, Some(make::rest_pat().into())).into(),
+            Fields::Tuple(_) => make::tuple_struct_pat(path, Some(make::rest_pat().into())).into(),
+            Fields::Unit => make::path_pat(path),
+        }
+    }
+
+    /// Constructs a value at `path` from one expression per field.
+    fn construct(&self, path: ast::Path, values: Vec<ast::Expr>) -> ast::Expr {
+        match self {
+            Fields::Record(names) => {
+                let fields = names.iter().zip(values).map(|(name, value)| {
+                    make::record_expr_field(make::name_ref(&name.text()), Some(value))
+                });
+                make::record_expr(path, make::record_expr_field_list(fields)).into()
+            }
+            Fields::Tuple(_) => make::expr_call(make::expr_path(path), make::arg_list(values)),
+            Fields::Unit => make::expr_path(path),
+        }
+    }
+}
+
+enum Shape {
+    Struct { name: ast::Name, fields: Fields },
+    Enum { variants: Vec<(ast::Name, Fields)> },
+}
+
+impl Shape {
+    fn of(adt: &ast::Adt) -> Option<Shape> {
+        let res = match adt {
+            ast::Adt::Struct(it) => {
+                Shape::Struct { name: it.name()?, fields: Fields::of(it.field_list())? }
+            }
+            ast::Adt::Enum(it) => {
+                let variants = it
+                    .variant_list()?
+                    .variants()
+                    .map(|it| Some((it.name()?, Fields::of(it.field_list())?)))
+                    .collect::<Option<_>>()?;
+                Shape::Enum { variants }
+            }
+            ast::Adt::Union(_) => return None,
+        };
+        Some(res)
+    }
+}
+
+fn self_path() -> ast::Path {
+    make::ext::ident_path("Self")
+}
+
+fn variant_path(name: &ast::Name) -> ast::Path {
+    make::path_from_text(&format!("Self::{}", name))
+}
+
+fn method_call(receiver: ast::Expr, method: &str, args: Vec<ast::Expr>) -> ast::Expr {
+    make::expr_method_call(receiver, method, make::arg_list(args))
+}
+
+fn call(path: &str, args: Vec<ast::Expr>) -> ast::Expr {
+    make::expr_call(make::expr_path(make::path_from_text(path)), make::arg_list(args))
+}
+
+fn block(stmts: Vec<ast::Stmt>, tail: Option<ast::Expr>) -> ast::BlockExpr {
+    let stmts = stmts.into_iter().map(|it| it.indent(IndentLevel(1)));
+    make::block_expr(stmts, tail.map(|it| it.indent(IndentLevel(1))))
+}
+
+fn match_expr(scrutinee: ast::Expr, arms: Vec<(ast::Pat, ast::Expr)>) -> ast::Expr {
+    let arms = arms
+        .into_iter()
+        .map(|(pat, expr)| make::match_arm(Some(pat), None, expr.indent(IndentLevel(1))));
+    make::expr_match(scrutinee, make::match_arm_list(arms))
+}
+
+fn gen_clone(shape: &Shape) -> ast::BlockExpr {
+    let clone = |(_, expr): (String, ast::Expr)| method_call(expr, "clone", Vec::new());
+    let expr = match shape {
+        Shape::Struct { fields, .. } => {
+            let values = fields.access("self").into_iter().map(clone).collect();
+            fields.construct(self_path(), values)
+        }
+        Shape::Enum { variants } => {
+            let arms = variants
+                .iter()
+                .map(|(name, fields)| {
+                    let (pat, bindings) = fields.bind(variant_path(name), "");
+                    let values = bindings.into_iter().map(clone).collect();
+                    (pat, fields.construct(variant_path(name), values))
+                })
+                .collect();
+            match_expr(make::expr_path(make::ext::ident_path("self")), arms)
+        }
+    };
+    block(Vec::new(), Some(expr))
+}
+
+fn gen_debug(shape: &Shape, f: &str) -> ast::BlockExpr {
+    let debug = |name: &ast::Name, fields: &Fields, values: Vec<(String, ast::Expr)>| {
+        let f = make::expr_path(make::ext::ident_path(f));
+        let name = make::expr_literal(&format!("\"{}\"", name)).into();
+        match fields {
+            Fields::Record(_) => {
+                let mut expr = method_call(f, "debug_struct", vec![name]);
+                for (field, value) in values {
+                    let field = make::expr_literal(&format!("\"{}\"", field)).into();
+                    expr = method_call(expr, "field", vec![field, value]);
+                }
+                method_call(expr, "finish", Vec::new())
+            }
+            Fields::Tuple(_) => {
+                let mut expr = method_call(f, "debug_tuple", vec![name]);
+                for (_, value) in values {
+                    expr = method_call(expr, "field", vec![value]);
+                }
+                method_call(expr, "finish", Vec::new())
+            }
+            Fields::Unit => method_call(f, "write_str", vec![name]),
+        }
+    };
+    let expr = match shape {
+        Shape::Struct { name, fields } => {
+            let values = fields
+                .access("self")
+                .into_iter()
+                .map(|(field, expr)| (field, make::expr_ref(expr, false)))
+                .collect();
+            debug(name, fields, values)
+        }
+        Shape::Enum { variants } => {
+            let arms = variants
+                .iter()
+                .map(|(name, fields)| {
+                    let (pat, bindings) = fields.bind(variant_path(name), "");
+                    (pat, debug(name, fields, bindings))
+                })
+                .collect();
+            match_expr(make::expr_path(make::ext::ident_path("self")), arms)
+        }
+    };
+    block(Vec::new(), Some(expr))
+}
+
+fn gen_default(shape: &Shape) -> Option<ast::BlockExpr> {
+    // `#[derive(Default)]` doesn't support enums.
+    let fields = match shape {
+        Shape::Struct { fields, .. } => fields,
+        Shape::Enum { .. } => return None,
+    };
+    let values = fields.names().iter().map(|_| call("Default::default", Vec::new())).collect();
+    Some(block(Vec::new(), Some(fields.construct(self_path(), values))))
+}
+
+fn gen_hash(
+    shape: &Shape,
+    trait_path: &ast::Path,
+    paths: &CorePaths,
+    state: &str,
+) -> Option<ast::BlockExpr> {
+    // Calls `Hash::hash` through the trait path, so that it works without the
+    // trait being in scope.
+    let hash_path = make::path_from_text(&format!("{}::hash", trait_path));
+    let hash = |expr: ast::Expr| -> ast::Stmt {
+        let state = make::expr_path(make::ext::ident_path(state));
+        let call =
+            make::expr_call(make::expr_path(hash_path.clone()), make::arg_list(vec![expr, state]));
+        make::expr_stmt(call).into()
+    };
+    let res = match shape {
+        Shape::Struct { fields, .. } => {
+            let stmts = fields
+                .access("self")
+                .into_iter()
+                .map(|(_, expr)| hash(make::expr_ref(expr, false)))
+                .collect();
+            block(stmts, None)
+        }
+        Shape::Enum { variants } => {
+            let discriminant = paths.discriminant("self")?;
+            let mut stmts = vec![hash(make::expr_ref(discriminant, false))];
+            let mut arms: Vec<(ast::Pat, ast::Expr)> = variants
+                .iter()
+                .filter(|(_, fields)| !fields.is_empty())
+                .map(|(name, fields)| {
+                    // The bindings are references already, as `self` is matched by reference.
+                    let (pat, bindings) = fields.bind(variant_path(name), "");
+                    let stmts = bindings.into_iter().map(|(_, expr)| hash(expr)).collect();
+                    (pat, block(stmts, None).into())
+                })
+                .collect();
+            if !arms.is_empty() {
+                if arms.len() < variants.len() {
+                    arms.push((make::wildcard_pat().into(), make::expr_empty_block()));
+                }
+                let scrutinee = make::expr_path(make::ext::ident_path("self"));
+                stmts.push(make::expr_stmt(match_expr(scrutinee, arms)).into());
+            }
+            block(stmts, None)
+        }
+    };
+    Some(res)
+}
+
+fn gen_partial_eq(shape: &Shape, paths: &CorePaths, other: &str) -> Option<ast::BlockExpr> {
+    let eq = |lhs: Vec<(String, ast::Expr)>, rhs: Vec<(String, ast::Expr)>| {
+        lhs.into_iter()
+            .zip(rhs)
+            .map(|((_, lhs), (_, rhs))| make::expr_bin_op(lhs, T![==], rhs))
+            .reduce(|acc, it| make::expr_bin_op(acc, T![&&], it))
+            .unwrap_or_else(|| make::expr_literal("true").into())
+    };
+    let expr = match shape {
+        Shape::Struct { fields, .. } => eq(fields.access("self"), fields.access(other)),
+        Shape::Enum { variants } => {
+            let fallback =
+                make::expr_bin_op(paths.discriminant("self")?, T![==], paths.discriminant(other)?);
+            let mut arms: Vec<(ast::Pat, ast::Expr)> = variants
+                .iter()
+                .filter(|(_, fields)| !fields.is_empty())
+                .map(|(name, fields)| {
+                    let (l_pat, l_bindings) = fields.bind(variant_path(name), "l_");
+                    let (r_pat, r_bindings) = fields.bind(variant_path(name), "r_");
+                    (make::tuple_pat(vec![l_pat, r_pat]).into(), eq(l_bindings, r_bindings))
+                })
+                .collect();
+            if arms.is_empty() {
+                fallback
+            } else {
+                arms.push((make::wildcard_pat().into(), fallback));
+                let scrutinee = make::expr_tuple(vec![
+                    make::expr_path(make::ext::ident_path("self")),
+                    make::expr_path(make::ext::ident_path(other)),
+                ]);
+                match_expr(scrutinee, arms)
+            }
+        }
+    };
+    Some(block(Vec::new(), Some(expr)))
+}
+
+#[derive(Clone, Copy)]
+enum Ordering {
+    Partial,
+    Total,
+}
+
+impl Ordering {
+    fn method(self) -> &'static str {
+        match self {
+            Ordering::Partial => "partial_cmp",
+            Ordering::Total => "cmp",
+        }
+    }
+
+    fn equal(self, equal: &ast::Path) -> ast::Expr {
+        let equal = make::expr_path(equal.clone());
+        match self {
+            Ordering::Partial => make::expr_call(
+                make::expr_path(make::ext::ident_path("Some")),
+                make::arg_list(Some(equal)),
+            ),
+            Ordering::Total => equal,
+        }
+    }
+
+    fn equal_pat(self, equal: &ast::Path) -> ast::Pat {
+        let equal = equal.clone();
+        match self {
+            Ordering::Partial => {
+                make::tuple_struct_pat(make::ext::ident_path("Some"), Some(make::path_pat(equal)))
+                    .into()
+            }
+            Ordering::Total => make::path_pat(equal),
+        }
+    }
+
+    /// Compares the pairs lexicographically, returning early on the first
+    /// pair which isn't `equal`.
+    fn chain(self, equal: &ast::Path, pairs: Vec<(ast::Expr, ast::Expr)>) -> ast::Expr {
+        let mut cmps: Vec<ast::Expr> = pairs
+            .into_iter()
+            .map(|(lhs, rhs)| method_call(lhs, self.method(), vec![rhs]))
+            .collect();
+        let last = match cmps.pop() {
+            Some(it) => it,
+            None => return self.equal(equal),
+        };
+        if cmps.is_empty() {
+            return last;
+        }
+        let stmts = cmps
+            .into_iter()
+            .map(|cmp| {
+                let ord = make::ident_pat(false, false, make::name("ord"));
+                let ret = make::expr_return(Some(make::expr_path(make::ext::ident_path("ord"))));
+                let arms =
+                    vec![(self.equal_pat(equal), make::expr_empty_block()), (ord.into(), ret)];
+                make::expr_stmt(match_expr(cmp, arms)).into()
+            })
+            .collect();
+        block(stmts, Some(last)).into()
+    }
+}
+
+fn gen_ord(
+    shape: &Shape,
+    paths: &CorePaths,
+    other: &str,
+    ordering: Ordering,
+) -> Option<ast::BlockExpr> {
+    let equal = paths.ordering_equal()?;
+    let res = match shape {
+        Shape::Struct { fields, .. } => {
+            let pairs = fields
+                .access("self")
+                .into_iter()
+                .zip(fields.access(other))
+                .map(|((_, lhs), (_, rhs))| (lhs, make::expr_ref(rhs, false)))
+                .collect();
+            match ordering.chain(&equal, pairs) {
+                ast::Expr::BlockExpr(it) => it,
+                it => block(Vec::new(), Some(it)),
+            }
+        }
+        Shape::Enum { variants } => {
+            // Variants are ordered by their position first, like the derive does.
+            let index_arms = variants
+                .iter()
+                .enumerate()
+                .map(|(idx, (name, fields))| {
+                    (
+                        fields.wildcard(variant_path(name)),
+                        make::expr_literal(&idx.to_string()).into(),
+                    )
+                })
+                .collect();
+            let index_fn = make::expr_closure(
+                Some(make::param(
+                    make::ident_pat(false, false, make::name("it")).into(),
+                    make::ty("&Self"),
+                )),
+                match_expr(make::expr_path(make::ext::ident_path("it")), index_arms),
+            );
+            let let_stmt = make::let_stmt(
+                make::ident_pat(false, false, make::name("variant_index")).into(),
+                None,
+                Some(index_fn),
+            );
+            let index =
+                |it: &str| call("variant_index", vec![make::expr_path(make::ext::ident_path(it))]);
+            let fallback = method_call(
+                index("self"),
+                ordering.method(),
+                vec![make::expr_ref(index(other), false)],
+            );
+
+            let mut arms: Vec<(ast::Pat, ast::Expr)> = variants
+                .iter()
+                .filter(|(_, fields)| !fields.is_empty())
+                .map(|(name, fields)| {
+                    let (l_pat, l_bindings) = fields.bind(variant_path(name), "l_");
+                    let (r_pat, r_bindings) = fields.bind(variant_path(name), "r_");
+                    let pairs = l_bindings
+                        .into_iter()
+                        .zip(r_bindings)
+                        .map(|((_, lhs), (_, rhs))| (lhs, rhs))
+                        .collect();
+                    (make::tuple_pat(vec![l_pat, r_pat]).into(), ordering.chain(&equal, pairs))
+                })
+                .collect();
+            let expr = if arms.is_empty() {
+                fallback
+            } else {
+                arms.push((make::wildcard_pat().into(), fallback));
+                let scrutinee = make::expr_tuple(vec![
+                    make::expr_path(make::ext::ident_path("self")),
+                    make::expr_path(make::ext::ident_path(other)),
+                ]);
+                match_expr(scrutinee, arms)
+            };
+            block(vec![let_stmt.into()], Some(expr))
+        }
+    };
+    Some(res)
+}
@@ -0,0 +1,435 @@
+//! Completes expressions producing a value of the expected type: field and
+//! method chains like `self.config.name`, `Default::default()` and associated
+//! constructor functions. With term search enabled, these are replaced by the
+//! expressions found by [`term_search`].
+
+use std::collections::VecDeque;
+
+use hir::{HasVisibility, HirDisplay, ScopeDef};
+use ide_db::{
+    helpers::{term_search::term_search, FamousDefs},
+    SymbolKind,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    item::CompletionRelevanceTypeMatch, CompletionContext, CompletionItem, CompletionItemKind,
+    CompletionKind, CompletionRelevance, Completions,
+};
+
+/// How many field accesses and method calls a chain may consist of.
+const MAX_CHAIN_LEN: usize = 3;
+/// Upper bound on the number of chains we offer, to keep the list readable.
+const MAX_CHAINS: usize = 16;
+
+pub(crate) fn complete_expected_type(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_trivial_path() || ctx.is_path_disallowed() || !ctx.expects_expression() {
+        return;
+    }
+    let expected = match &ctx.expected_type {
+        Some(it) if !it.is_unit() && !it.is_unknown() => it,
+        _ => return,
+    };
+
+    if ctx.config.enable_term_search {
+        complete_terms(acc, ctx, expected);
+        return;
+    }
+    complete_chains(acc, ctx, expected);
+    complete_default(acc, ctx, expected);
+    complete_constructors(acc, ctx, expected);
+}
+
+fn complete_terms(acc: &mut Completions, ctx: &CompletionContext, expected: &hir::Type) {
+    let current_fn = ctx.function_def.as_ref().and_then(|it| ctx.sema.to_def(it));
+    for term in term_search(&ctx.sema, &ctx.scope, expected, current_fn) {
+        // Plain names are completed already.
+        if term.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), term);
+        item.kind(CompletionItemKind::Snippet).detail(expected.display(ctx.db).to_string());
+        item.set_relevance(CompletionRelevance {
+            type_match: Some(CompletionRelevanceTypeMatch::Exact),
+            ..CompletionRelevance::default()
+        });
+        item.add_to(acc);
+    }
+}
+
+fn complete_chains(acc: &mut Completions, ctx: &CompletionContext, expected: &hir::Type) {
+    let mut found = 0;
+    let mut locals = Vec::new();
+    ctx.scope.process_all_names(&mut |name, def| {
+        if let ScopeDef::Local(local) = def {
+            locals.push((name, local));
+        }
+    });
+    for (name, local) in locals {
+        let ty = local.ty(ctx.db);
+        let mut visited = vec![ty.clone()];
+        // Plain fields of `self` are already offered by `complete_undotted_self`.
+        let skip_fields = local.is_self(ctx.db) && ctx.config.enable_self_on_the_fly;
+        // Search breadth-first, so that the shortest chains are offered before
+        // hitting `MAX_CHAINS`.
+        let mut queue = VecDeque::from(vec![(name.to_string(), ty, 0)]);
+        while let Some((path, ty, len)) = queue.pop_front() {
+            if len == MAX_CHAIN_LEN {
+                continue;
+            }
+            for (step, kind, step_ty) in chain_steps(ctx, &ty) {
+                if found == MAX_CHAINS {
+                    return;
+                }
+                let path = format!("{}.{}", path, step);
+                if &step_ty == expected {
+                    if !(skip_fields
+                        && len == 0
+                        && kind == CompletionItemKind::from(SymbolKind::Field))
+                    {
+                        add_chain(acc, ctx, &path, kind, &step_ty);
+                        found += 1;
+                    }
+                } else if !visited.contains(&step_ty) {
+                    visited.push(step_ty.clone());
+                    queue.push_back((path, step_ty, len + 1));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the visible fields and argument-less methods of `ty`.
+fn chain_steps(
+    ctx: &CompletionContext,
+    ty: &hir::Type,
+) -> Vec<(String, CompletionItemKind, hir::Type)> {
+    let db = ctx.db;
+    let module = match ctx.scope.module() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let mut res = Vec::new();
+    for (field, field_ty) in ty.strip_references().fields(db) {
+        if field.is_visible_from(db, module) {
+            res.push((field.name(db).to_string(), SymbolKind::Field.into(), field_ty));
+        }
+    }
+    for (idx, field_ty) in ty.strip_references().tuple_fields(db).into_iter().enumerate() {
+        res.push((idx.to_string(), SymbolKind::Field.into(), field_ty));
+    }
+    if let Some(krate) = ctx.krate {
+        let traits_in_scope = ctx.scope.traits_in_scope();
+        let mut seen_methods = FxHashSet::default();
+        ty.iterate_method_candidates(db, krate, &traits_in_scope, None, |_ty, func| {
+            let takes_no_args = func.method_params(db).map_or(false, |it| it.is_empty());
+            if func.self_param(db).is_some()
+                && takes_no_args
+                && func.is_visible_from(db, module)
+                && seen_methods.insert(func.name(db))
+            {
+                let ret_type = func.ret_type(db);
+                if !ret_type.is_unit() && !ret_type.contains_unknown() {
+                    res.push((
+                        format!("{}()", func.name(db)),
+                        CompletionItemKind::Method,
+                        ret_type,
+                    ));
+                }
+            }
+            None::<()>
+        });
+    }
+    res
+}
+
+fn add_chain(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    path: &str,
+    kind: CompletionItemKind,
+    ty: &hir::Type,
+) {
+    let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), path);
+    item.kind(kind).detail(ty.display(ctx.db).to_string());
+    item.set_relevance(CompletionRelevance {
+        type_match: Some(CompletionRelevanceTypeMatch::Exact),
+        exact_name_match: ctx.expected_name.as_ref().map_or(false, |name| {
+            path.rsplit('.').next().map(|it| it.trim_end_matches("()"))
+                == Some(name.text().as_str())
+        }),
+        ..CompletionRelevance::default()
+    });
+    item.add_to(acc);
+}
+
+fn complete_default(acc: &mut Completions, ctx: &CompletionContext, expected: &hir::Type) {
+    let default_trait = match FamousDefs(&ctx.sema, ctx.krate).core_default_Default() {
+        Some(it) => it,
+        None => return,
+    };
+    if !expected.impls_trait(ctx.db, default_trait, &[]) {
+        return;
+    }
+    let path = match ctx
+        .scope
+        .module()
+        .and_then(|module| module.find_use_path(ctx.db, hir::ModuleDef::Trait(default_trait)))
+    {
+        Some(it) => it,
+        None => return,
+    };
+
+    let label = format!("{}::default()", path);
+    let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label);
+    item.kind(SymbolKind::Function)
+        .detail(expected.display(ctx.db).to_string())
+        .lookup_by("default");
+    item.set_relevance(CompletionRelevance {
+        type_match: Some(CompletionRelevanceTypeMatch::Exact),
+        ..CompletionRelevance::default()
+    });
+    item.add_to(acc);
+}
+
+fn complete_constructors(acc: &mut Completions, ctx: &CompletionContext, expected: &hir::Type) {
+    let (module, krate, adt) = match (ctx.scope.module(), ctx.krate, expected.as_adt()) {
+        (Some(module), Some(krate), Some(adt)) => (module, krate, adt),
+        _ => return,
+    };
+    let adt_path = match module.find_use_path(ctx.db, hir::ModuleDef::Adt(adt)) {
+        Some(it) => it,
+        None => return,
+    };
+
+    expected.clone().iterate_assoc_items(ctx.db, krate, |item| {
+        let func = match item {
+            hir::AssocItem::Function(it) => it,
+            _ => return None,
+        };
+        if func.self_param(ctx.db).is_some() || !func.is_visible_from(ctx.db, module) {
+            return None;
+        }
+        let ret_type = func.ret_type(ctx.db);
+        let type_match = if &ret_type == expected {
+            CompletionRelevanceTypeMatch::Exact
+        } else if expected.could_unify_with(ctx.db, &ret_type) {
+            CompletionRelevanceTypeMatch::CouldUnify
+        } else {
+            return None;
+        };
+
+        let name = func.name(ctx.db);
+        let has_params = !func.assoc_fn_params(ctx.db).is_empty();
+        let call = format!("{}::{}", adt_path, name);
+        let label = if has_params { format!("{}(…)", call) } else { format!("{}()", call) };
+        let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label);
+        item.kind(SymbolKind::Function)
+            .detail(ret_type.display(ctx.db).to_string())
+            .lookup_by(name.to_string());
+        match ctx.config.snippet_cap {
+            Some(cap) if has_params => item.insert_snippet(cap, format!("{}($0)", call)),
+            _ => item.insert_text(format!("{}()", call)),
+        };
+        item.set_relevance(CompletionRelevance {
+            type_match: Some(type_match),
+            ..CompletionRelevance::default()
+        });
+        item.add_to(acc);
+        None::<()>
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{
+        tests::{
+            check_edit, filtered_completion_list, filtered_completion_list_with_config, TEST_CONFIG,
+        },
+        CompletionConfig, CompletionKind,
+    };
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = filtered_completion_list(ra_fixture, CompletionKind::Magic);
+        expect.assert_eq(&actual);
+    }
+
+    fn check_term_search(ra_fixture: &str, expect: Expect) {
+        let config = CompletionConfig { enable_term_search: true, ..TEST_CONFIG };
+        let actual =
+            filtered_completion_list_with_config(config, ra_fixture, CompletionKind::Magic);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_field_and_method_chains() {
+        check(
+            r#"
+struct Name;
+struct Config { name: Name, other: u32 }
+impl Config { fn name(&self) -> Name { Name } }
+struct Server { config: Config }
+
+fn take(name: Name) {}
+
+impl Server {
+    fn run(&self) {
+        let cfg = Config { name: Name, other: 0 };
+        take($0)
+    }
+}
+"#,
+            expect![[r#"
+                fd cfg.name           Name
+                me cfg.name()         Name
+                fd self.config.name   Name
+                me self.config.name() Name
+            "#]],
+        );
+    }
+
+    #[test]
+    fn prefers_shorter_chains_when_truncated() {
+        check(
+            r#"
+struct T;
+struct Near { t: T }
+struct Far { many: Many }
+struct Many {
+    t0: T, t1: T, t2: T, t3: T, t4: T, t5: T, t6: T, t7: T,
+    t8: T, t9: T, t10: T, t11: T, t12: T, t13: T, t14: T, t15: T,
+}
+struct Root { near: Near, far: Far }
+
+fn take(t: T) {}
+
+fn run(root: Root) {
+    take($0)
+}
+"#,
+            expect![[r#"
+                fd root.near.t       T
+                fd root.far.many.t0  T
+                fd root.far.many.t1  T
+                fd root.far.many.t2  T
+                fd root.far.many.t3  T
+                fd root.far.many.t4  T
+                fd root.far.many.t5  T
+                fd root.far.many.t6  T
+                fd root.far.many.t7  T
+                fd root.far.many.t8  T
+                fd root.far.many.t9  T
+                fd root.far.many.t10 T
+                fd root.far.many.t11 T
+                fd root.far.many.t12 T
+                fd root.far.many.t13 T
+                fd root.far.many.t14 T
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_default() {
+        check(
+            r#"
+//- minicore: default
+struct Config;
+impl Default for Config {
+    fn default() -> Self { Config }
+}
+
+fn take(config: Config) {}
+
+fn main() {
+    take($0)
+}
+"#,
+            expect![[r#"
+                fn Default::default() Config
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_constructors() {
+        check(
+            r#"
+mod config {
+    pub struct Config;
+    impl Config {
+        pub fn new(verbose: bool) -> Config { Config }
+        pub fn empty() -> Self { Config }
+        fn private() -> Config { Config }
+        pub fn other() -> u32 { 0 }
+    }
+}
+
+fn take(config: config::Config) {}
+
+fn main() {
+    take($0)
+}
+"#,
+            expect![[r#"
+                fn config::Config::new(…) Config
+                fn config::Config::empty() Config
+            "#]],
+        );
+    }
+
+    #[test]
+    fn insert_constructor_call() {
+        check_edit(
+            "new",
+            r#"
+struct Config;
+impl Config { fn new(verbose: bool) -> Config { Config } }
+
+fn take(config: Config) {}
+
+fn main() {
+    take($0)
+}
+"#,
+            r#"
+struct Config;
+impl Config { fn new(verbose: bool) -> Config { Config } }
+
+fn take(config: Config) {}
+
+fn main() {
+    take(Config::new($0))
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_terms() {
+        check_term_search(
+            r#"
+struct Port(u16);
+struct Host;
+struct Server { host: Host, port: Port }
+impl Server {
+    fn new(host: Host, port: Port) -> Server { Server { host, port } }
+}
+struct Config { port: Port }
+
+fn start(server: Server) {}
+
+fn main() {
+    let config = Config { port: Port(0) };
+    let host = Host;
+    start($0)
+}
+"#,
+            expect![[r#"
+                sn Server::new(host, config.port) Server
+                sn Server { host, port: config.port } Server
+            "#]],
+        );
+    }
+}
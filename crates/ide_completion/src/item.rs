@@ -153,6 +153,9 @@ pub struct CompletionRelevance {
     /// Basically, we want to guarantee that postfix snippets always takes
     /// precedence over everything else.
     pub exact_postfix_snippet_match: bool,
+    /// Set for items marked with `#[deprecated]`, which are ranked below the
+    /// other items of the same relevance.
+    pub is_deprecated: bool,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -177,6 +180,36 @@ pub enum CompletionRelevanceTypeMatch {
     /// }
     /// ```
     Exact,
+    /// This is set in cases like these:
+    ///
+    /// ```
+    /// fn f(spam: &String) {}
+    /// fn main {
+    ///     let foo = String::new();
+    ///     f($0) // type of local matches the type of param after `&`
+    /// }
+    /// ```
+    AfterRef,
+    /// This is set in cases like these:
+    ///
+    /// ```
+    /// fn f(spam: String) {}
+    /// fn main {
+    ///     let foo = &String::new();
+    ///     f($0) // type of local matches the type of param after `.clone()`
+    /// }
+    /// ```
+    AfterClone,
+    /// This is set in cases like these:
+    ///
+    /// ```
+    /// fn f(spam: String) {}
+    /// fn main() -> Option<()> {
+    ///     let foo = Some(String::new());
+    ///     f($0) // type of local matches the type of param after `?`
+    /// }
+    /// ```
+    AfterTry,
 }
 
 impl CompletionRelevance {
@@ -198,6 +231,11 @@ impl CompletionRelevance {
         score += match self.type_match {
             Some(CompletionRelevanceTypeMatch::Exact) => 4,
             Some(CompletionRelevanceTypeMatch::CouldUnify) => 3,
+            Some(
+                CompletionRelevanceTypeMatch::AfterRef
+                | CompletionRelevanceTypeMatch::AfterClone
+                | CompletionRelevanceTypeMatch::AfterTry,
+            ) => 2,
             None => 0,
         };
         if self.is_local {
@@ -206,14 +244,14 @@ impl CompletionRelevance {
         if self.exact_postfix_snippet_match {
             score += 100;
         }
-        score
+        score * 2 + if self.is_deprecated { 0 } else { 1 }
     }
 
     /// Returns true when the score for this threshold is above
     /// some threshold such that we think it is especially likely
     /// to be relevant.
     pub fn is_relevant(&self) -> bool {
-        self.score() > 0
+        self.score() > CompletionRelevance::default().score()
     }
 }
 
@@ -460,7 +498,7 @@ impl Builder {
             completion_kind: self.completion_kind,
             deprecated: self.deprecated,
             trigger_call_info: self.trigger_call_info.unwrap_or(false),
-            relevance: self.relevance,
+            relevance: CompletionRelevance { is_deprecated: self.deprecated, ..self.relevance },
             ref_match: self.ref_match,
             import_to_add: self.import_to_add,
         }
@@ -591,16 +629,31 @@ mod tests {
         // This test asserts that the relevance score for these items is ascending, and
         // that any items in the same vec have the same score.
         let expected_relevance_order = vec![
+            vec![CompletionRelevance { is_deprecated: true, ..CompletionRelevance::default() }],
             vec![CompletionRelevance::default()],
             vec![
                 CompletionRelevance { exact_name_match: true, ..CompletionRelevance::default() },
                 CompletionRelevance { is_local: true, ..CompletionRelevance::default() },
             ],
-            vec![CompletionRelevance {
-                exact_name_match: true,
-                is_local: true,
-                ..CompletionRelevance::default()
-            }],
+            vec![
+                CompletionRelevance {
+                    exact_name_match: true,
+                    is_local: true,
+                    ..CompletionRelevance::default()
+                },
+                CompletionRelevance {
+                    type_match: Some(CompletionRelevanceTypeMatch::AfterRef),
+                    ..CompletionRelevance::default()
+                },
+                CompletionRelevance {
+                    type_match: Some(CompletionRelevanceTypeMatch::AfterClone),
+                    ..CompletionRelevance::default()
+                },
+                CompletionRelevance {
+                    type_match: Some(CompletionRelevanceTypeMatch::AfterTry),
+                    ..CompletionRelevance::default()
+                },
+            ],
             vec![CompletionRelevance {
                 type_match: Some(CompletionRelevanceTypeMatch::CouldUnify),
                 ..CompletionRelevance::default()
@@ -625,6 +678,7 @@ mod tests {
                 type_match: None,
                 is_local: false,
                 exact_postfix_snippet_match: true,
+                is_deprecated: false,
             }],
         ];
 
@@ -43,6 +43,10 @@ pub use crate::{
 // When completing a function call, `()` are automatically inserted. If a function
 // takes arguments, the cursor is positioned inside the parenthesis.
 //
+// If the type of the expression being completed is known, expressions producing
+// a value of that type are offered and ranked first: field and method chains like
+// `self.config.name`, `Default::default()` and associated constructor functions.
+//
 // There are postfix completions, which can be triggered by typing something like
 // `foo().if`. The word after `.` determines postfix completion. Possible variants are:
 //
@@ -155,6 +159,7 @@ pub fn completions(
     completions::qualified_path::complete_qualified_path(&mut acc, &ctx);
     completions::unqualified_path::complete_unqualified_path(&mut acc, &ctx);
     completions::dot::complete_dot(&mut acc, &ctx);
+    completions::expected_type::complete_expected_type(&mut acc, &ctx);
     completions::record::complete_record(&mut acc, &ctx);
     completions::pattern::complete_pattern(&mut acc, &ctx);
     completions::postfix::complete_postfix(&mut acc, &ctx);
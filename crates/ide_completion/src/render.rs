@@ -12,7 +12,7 @@ mod builder_ext;
 
 use hir::{AsAssocItem, HasAttrs, HirDisplay};
 use ide_db::{
-    helpers::{item_name, SnippetCap},
+    helpers::{item_name, FamousDefs, SnippetCap},
     RootDatabase, SymbolKind,
 };
 use syntax::TextRange;
@@ -47,8 +47,7 @@ impl<'a> RenderContext<'a> {
     }
 
     fn is_deprecated(&self, node: impl HasAttrs) -> bool {
-        let attrs = node.attrs(self.db());
-        attrs.by_key("deprecated").exists() || attrs.by_key("rustc_deprecated").exists()
+        node.deprecation(self.db()).is_some()
     }
 
     fn is_deprecated_assoc_item(&self, as_assoc_item: impl AsAssocItem) -> bool {
@@ -299,11 +298,61 @@ fn compute_type_match(
         Some(CompletionRelevanceTypeMatch::Exact)
     } else if expected_type.could_unify_with(ctx.db, completion_ty) {
         Some(CompletionRelevanceTypeMatch::CouldUnify)
+    } else if expected_type.remove_ref().as_ref() == Some(completion_ty) {
+        Some(CompletionRelevanceTypeMatch::AfterRef)
+    } else if completion_ty.remove_ref().as_ref() == Some(expected_type)
+        && is_clone(ctx, expected_type)
+    {
+        Some(CompletionRelevanceTypeMatch::AfterClone)
+    } else if is_try_match(ctx, completion_ty, expected_type) {
+        Some(CompletionRelevanceTypeMatch::AfterTry)
     } else {
         None
     }
 }
 
+fn is_clone(ctx: &CompletionContext, ty: &hir::Type) -> bool {
+    FamousDefs(&ctx.sema, ctx.krate)
+        .core_clone_Clone()
+        .map_or(false, |clone| ty.impls_trait(ctx.db, clone, &[]))
+}
+
+/// Checks whether `completion_ty` is an `Option` or a `Result` wrapping
+/// `expected_type`, which can be unwrapped with `?` in the current function.
+/// For a `Result`, its error also has to convert into the returned error.
+fn is_try_match(
+    ctx: &CompletionContext,
+    completion_ty: &hir::Type,
+    expected_type: &hir::Type,
+) -> bool {
+    let famous_defs = FamousDefs(&ctx.sema, ctx.krate);
+    let try_enums = [famous_defs.core_option_Option(), famous_defs.core_result_Result()];
+    let completion_adt = match completion_ty.as_adt() {
+        Some(hir::Adt::Enum(it)) if try_enums.contains(&Some(it)) => it,
+        _ => return false,
+    };
+    if completion_ty.type_arguments().next().as_ref() != Some(expected_type) {
+        return false;
+    }
+    let ret_type = match ctx.function_def.as_ref().and_then(|it| ctx.sema.to_def(it)) {
+        Some(func) => func.ret_type(ctx.db),
+        None => return false,
+    };
+    if ret_type.as_adt() != Some(hir::Adt::Enum(completion_adt)) {
+        return false;
+    }
+    let errors = (completion_ty.type_arguments().nth(1), ret_type.type_arguments().nth(1));
+    match errors {
+        (Some(completion_err), Some(ret_err)) => {
+            completion_err.could_unify_with(ctx.db, &ret_err)
+                || famous_defs
+                    .core_convert_From()
+                    .map_or(false, |from| ret_err.impls_trait(ctx.db, from, &[completion_err]))
+        }
+        _ => true,
+    }
+}
+
 fn compute_exact_name_match(ctx: &CompletionContext, completion_name: &str) -> bool {
     ctx.expected_name.as_ref().map_or(false, |name| name.text() == completion_name)
 }
@@ -387,9 +436,22 @@ mod tests {
                     relevance.type_match == Some(CompletionRelevanceTypeMatch::CouldUnify),
                     "type_could_unify",
                 ),
+                (
+                    relevance.type_match == Some(CompletionRelevanceTypeMatch::AfterRef),
+                    "type_after_ref",
+                ),
+                (
+                    relevance.type_match == Some(CompletionRelevanceTypeMatch::AfterClone),
+                    "type_after_clone",
+                ),
+                (
+                    relevance.type_match == Some(CompletionRelevanceTypeMatch::AfterTry),
+                    "type_after_try",
+                ),
                 (relevance.exact_name_match, "name"),
                 (relevance.is_local, "local"),
                 (relevance.exact_postfix_snippet_match, "snippet"),
+                (relevance.is_deprecated, "deprecated"),
             ]
             .into_iter()
             .filter_map(|(cond, desc)| if cond { Some(desc) } else { None })
@@ -542,6 +604,7 @@ fn main() { let _: m::Spam = S$0 }
                             ),
                             is_local: false,
                             exact_postfix_snippet_match: false,
+                            is_deprecated: false,
                         },
                         trigger_call_info: true,
                     },
@@ -571,6 +634,7 @@ fn main() { let _: m::Spam = S$0 }
                             ),
                             is_local: false,
                             exact_postfix_snippet_match: false,
+                            is_deprecated: false,
                         },
                     },
                     CompletionItem {
@@ -624,6 +688,13 @@ fn main() { som$0 }
                         lookup: "something_deprecated",
                         detail: "fn()",
                         deprecated: true,
+                        relevance: CompletionRelevance {
+                            exact_name_match: false,
+                            type_match: None,
+                            is_local: false,
+                            exact_postfix_snippet_match: false,
+                            is_deprecated: true,
+                        },
                     },
                     CompletionItem {
                         label: "something_else_deprecated()",
@@ -636,6 +707,13 @@ fn main() { som$0 }
                         lookup: "something_else_deprecated",
                         detail: "fn()",
                         deprecated: true,
+                        relevance: CompletionRelevance {
+                            exact_name_match: false,
+                            type_match: None,
+                            is_local: false,
+                            exact_postfix_snippet_match: false,
+                            is_deprecated: true,
+                        },
                     },
                 ]
             "#]],
@@ -665,6 +743,7 @@ fn foo() { A { the$0 } }
                             ),
                             is_local: false,
                             exact_postfix_snippet_match: false,
+                            is_deprecated: true,
                         },
                     },
                 ]
@@ -1028,6 +1107,26 @@ fn f() { A { bar: b$0 }; }
         );
     }
 
+    #[test]
+    fn score_deprecated_items_lower() {
+        check_relevance(
+            r#"
+#[deprecated]
+fn bar() -> u8 { 0 }
+fn baz() -> u8 { 0 }
+#[deprecated]
+fn quux() {}
+fn f() { let _: u8 = b$0; }
+"#,
+            expect![[r#"
+                fn baz() [type]
+                fn bar() [type+deprecated]
+                fn f() []
+                fn quux() [deprecated]
+            "#]],
+        );
+    }
+
     #[test]
     fn score_method_type_and_name_match() {
         check_relevance(
@@ -1083,7 +1182,7 @@ fn main() {
 }
             "#,
             expect![[r#"
-                lc s [name+local]
+                lc s [type_after_ref+name+local]
                 lc &mut s [type+name+local]
                 st S []
                 fn main() []
@@ -1108,6 +1207,77 @@ fn main() {
         );
     }
 
+    #[test]
+    fn score_after_clone_and_try() {
+        check_relevance(
+            r#"
+//- minicore: clone, option
+struct S;
+impl Clone for S { fn clone(&self) -> S { S } }
+fn foo(s: S) {}
+fn main() -> Option<()> {
+    let by_ref = &S;
+    let wrapped = Some(S);
+    let other = 92;
+    foo($0);
+    None
+}
+            "#,
+            expect![[r#"
+                lc wrapped [type_after_try+local]
+                lc by_ref [type_after_clone+local]
+                lc other [local]
+                st S []
+                fn main() []
+                fn foo(…) []
+                md core []
+                tt Clone []
+                ev None []
+                tt Sized []
+                en Option []
+                ev Some(…) []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn score_try_only_with_convertible_error() {
+        check_relevance(
+            r#"
+//- minicore: result, from
+struct S;
+struct Error;
+struct OtherError;
+struct UnrelatedError;
+impl From<Error> for OtherError { fn from(_: Error) -> Self { OtherError } }
+fn foo(s: S) {}
+fn main() -> Result<(), OtherError> {
+    let converts: Result<S, Error> = Ok(S);
+    let unrelated: Result<S, UnrelatedError> = Ok(S);
+    foo($0);
+    Ok(())
+}
+            "#,
+            expect![[r#"
+                lc converts [type_after_try+local]
+                lc unrelated [local]
+                st UnrelatedError []
+                st Error []
+                st OtherError []
+                fn foo(…) []
+                st S []
+                fn main() []
+                md core []
+                tt Sized []
+                tt Into []
+                tt From []
+                en Result []
+                ev Err(…) []
+                ev Ok(…) []
+            "#]],
+        );
+    }
+
     #[test]
     fn suggest_deref() {
         check_relevance(
@@ -1241,9 +1411,9 @@ fn foo() {
 fn bar(t: &Foo) {}
 "#,
             expect![[r#"
-                ev Foo::A []
+                ev Foo::A [type_after_ref]
                 ev &Foo::A [type]
-                ev Foo::B []
+                ev Foo::B [type_after_ref]
                 ev &Foo::B [type]
                 en Foo []
                 fn bar(…) []
@@ -1311,6 +1481,15 @@ fn foo(f: Foo) { let _: &u32 = f.b$0 }
                             Field,
                         ),
                         detail: "u32",
+                        relevance: CompletionRelevance {
+                            exact_name_match: false,
+                            type_match: Some(
+                                AfterRef,
+                            ),
+                            is_local: false,
+                            exact_postfix_snippet_match: false,
+                            is_deprecated: false,
+                        },
                     },
                     CompletionItem {
                         label: "baz()",
@@ -1320,6 +1499,15 @@ fn foo(f: Foo) { let _: &u32 = f.b$0 }
                         kind: Method,
                         lookup: "baz",
                         detail: "fn(&self) -> u32",
+                        relevance: CompletionRelevance {
+                            exact_name_match: false,
+                            type_match: Some(
+                                AfterRef,
+                            ),
+                            is_local: false,
+                            exact_postfix_snippet_match: false,
+                            is_deprecated: false,
+                        },
                     },
                 ]
             "#]],
@@ -7,6 +7,7 @@
 
 use std::str::FromStr;
 
+use base_db::CrateId;
 use syntax::TextRange;
 
 use crate::{label::Label, source_change::SourceChange};
@@ -29,6 +30,16 @@ pub struct Assist {
     /// cumbersome, especially if you want to embed an assist into another data
     /// structure, such as a diagnostic.
     pub source_change: Option<SourceChange>,
+    /// An action the server performs when the assist is applied, for assists
+    /// which change how the code is analyzed rather than the code itself.
+    pub command: Option<AssistCommand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssistCommand {
+    /// Reloads the workspace with the given Cargo feature of the crate's
+    /// package enabled.
+    EnableCargoFeature { krate: CrateId, feature: String },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -6,8 +6,8 @@
 // FIXME: this badly needs rename/rewrite (matklad, 2020-02-06).
 
 use hir::{
-    Field, GenericParam, HasVisibility, Impl, Label, Local, MacroDef, Module, ModuleDef, Name,
-    PathResolution, Semantics, Visibility,
+    Deprecation, Field, GenericParam, HasAttrs, HasVisibility, Impl, Label, Local, MacroDef,
+    Module, ModuleDef, Name, PathResolution, Semantics, Visibility,
 };
 use syntax::{
     ast::{self, AstNode, PathSegmentKind},
@@ -91,6 +91,18 @@ impl Definition {
         };
         Some(name)
     }
+
+    pub fn deprecation(&self, db: &RootDatabase) -> Option<Deprecation> {
+        match self {
+            Definition::Macro(it) => it.deprecation(db),
+            Definition::Field(it) => it.deprecation(db),
+            Definition::ModuleDef(it) => it.deprecation(db),
+            Definition::SelfType(_)
+            | Definition::Local(_)
+            | Definition::GenericParam(_)
+            | Definition::Label(_) => None,
+        }
+    }
 }
 
 /// On a first blush, a single `ast::Name` defines a single definition at some
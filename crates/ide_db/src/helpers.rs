@@ -1,15 +1,21 @@
 //! A module with ide helpers for high-level ide features.
+pub mod injection;
 pub mod import_assets;
 pub mod insert_use;
 pub mod merge_imports;
 pub mod rust_doc;
+pub mod term_search;
+pub mod unused_imports;
 pub mod generated_lints;
 
 use std::collections::VecDeque;
 
 use base_db::FileId;
 use either::Either;
-use hir::{Crate, Enum, ItemInNs, MacroDef, Module, ModuleDef, Name, ScopeDef, Semantics, Trait};
+use hir::{
+    Crate, Enum, Function, HasAttrs, ItemInNs, MacroDef, Module, ModuleDef, Name, ScopeDef,
+    Semantics, Trait,
+};
 use syntax::{
     ast::{self, make, LoopBodyOwner},
     AstNode, SyntaxKind, SyntaxToken, TokenAtOffset, WalkEvent,
@@ -57,6 +63,18 @@ pub fn mod_path_to_ast(path: &hir::ModPath) -> ast::Path {
     make::path_from_segments(segments, is_abs)
 }
 
+/// Whether an `allow` attribute on `module` or one of its ancestors, including
+/// the inner attributes of their files, allows one of `lints`.
+pub fn is_lint_allowed_in_module(db: &RootDatabase, module: Module, lints: &[&str]) -> bool {
+    module.path_to_root(db).into_iter().any(|module| {
+        module.attrs(db).by_key("allow").tt_values().any(|tt| {
+            tt.to_string()
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|lint| lints.contains(&lint))
+        })
+    })
+}
+
 /// Iterates all `ModuleDef`s and `Impl` blocks of the given file.
 pub fn visit_file_defs(
     sema: &Semantics<RootDatabase>,
@@ -106,10 +124,18 @@ impl FamousDefs<'_, '_> {
         self.find_crate("core")
     }
 
+    pub fn core_clone_Clone(&self) -> Option<Trait> {
+        self.find_trait("core:clone:Clone")
+    }
+
     pub fn core_cmp_Ord(&self) -> Option<Trait> {
         self.find_trait("core:cmp:Ord")
     }
 
+    pub fn core_cmp_Ordering(&self) -> Option<Enum> {
+        self.find_enum("core:cmp:Ordering")
+    }
+
     pub fn core_convert_From(&self) -> Option<Trait> {
         self.find_trait("core:convert:From")
     }
@@ -146,6 +172,14 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:ops:Deref")
     }
 
+    pub fn core_ops_Drop(&self) -> Option<Trait> {
+        self.find_trait("core:ops:Drop")
+    }
+
+    pub fn core_mem_discriminant(&self) -> Option<Function> {
+        self.find_function("core:mem:discriminant")
+    }
+
     fn find_trait(&self, path: &str) -> Option<Trait> {
         match self.find_def(path)? {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
@@ -160,6 +194,13 @@ impl FamousDefs<'_, '_> {
         }
     }
 
+    fn find_function(&self, path: &str) -> Option<Function> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Function(it)) => Some(it),
+            _ => None,
+        }
+    }
+
     fn find_module(&self, path: &str) -> Option<Module> {
         match self.find_def(path)? {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Module(it)) => Some(it),
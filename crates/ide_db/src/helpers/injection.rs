@@ -0,0 +1,798 @@
+//! Detection and lexing of other languages embedded in string literals, like
+//! the regexes passed to `Regex::new`.
+//!
+//! A string is in another language when it is preceded by a `// language=sql`
+//! comment, or when it is the first argument of a known call site.
+use std::iter::successors;
+
+use syntax::{
+    ast::{self, HasFormatSpecifier, IsString},
+    AstNode, AstToken, NodeOrToken, SyntaxElement, SyntaxKind, TextRange, TextSize,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Regex,
+    Sql,
+    Json,
+}
+
+impl Language {
+    fn from_name(name: &str) -> Option<Language> {
+        let res = match &*name.to_ascii_lowercase() {
+            "regex" | "regexp" => Language::Regex,
+            "sql" => Language::Sql,
+            "json" => Language::Json,
+            _ => return None,
+        };
+        Some(res)
+    }
+}
+
+/// Functions whose first argument is in another language, as the trailing
+/// segments of their path.
+const CALL_SITES: &[(&[&str], Language)] = &[
+    (&["Regex", "new"], Language::Regex),
+    (&["RegexBuilder", "new"], Language::Regex),
+    (&["serde_json", "from_str"], Language::Json),
+];
+
+/// Macros whose first string literal is in another language, as the trailing
+/// segments of their path.
+const MACRO_CALL_SITES: &[(&[&str], Language)] = &[
+    (&["sqlx", "query"], Language::Sql),
+    (&["sqlx", "query_as"], Language::Sql),
+    (&["sqlx", "query_scalar"], Language::Sql),
+    (&["sqlx", "query_unchecked"], Language::Sql),
+    (&["sqlx", "query_as_unchecked"], Language::Sql),
+];
+
+/// Returns the language of the contents of `string`, if it's known to be
+/// something other than text.
+pub fn injected_language(string: &ast::String) -> Option<Language> {
+    commented_language(string).or_else(|| call_site(string).map(|(_, language)| language))
+}
+
+/// Returns the path of the function or macro called with `string`, if that's
+/// what makes it be in another language. Callers that need to be sure can
+/// check that it resolves to the expected crate.
+pub fn injecting_call_path(string: &ast::String) -> Option<ast::Path> {
+    if commented_language(string).is_some() {
+        return None;
+    }
+    call_site(string).map(|(path, _)| path)
+}
+
+fn commented_language(string: &ast::String) -> Option<Language> {
+    let element = match string.syntax().parent().and_then(ast::Literal::cast) {
+        Some(literal) => literal.syntax().clone().into(),
+        None => string.syntax().clone().into(),
+    };
+    language_comment(element).or_else(|| {
+        string.syntax().ancestors().take_while(|it| !ast::Fn::can_cast(it.kind())).find_map(|it| {
+            if ast::Stmt::can_cast(it.kind()) || ast::Item::can_cast(it.kind()) {
+                language_comment(it.into())
+            } else {
+                None
+            }
+        })
+    })
+}
+
+/// Returns the characters of the value of `string`, with their ranges relative
+/// to the start of the token, or `None` if the string has invalid escapes.
+pub fn string_chars(string: &ast::String) -> Option<Vec<(TextRange, char)>> {
+    if string.is_raw() {
+        let contents = string.text_range_between_quotes()?;
+        let offset = contents.start() - string.syntax().text_range().start();
+        let text = &string.text()[contents - string.syntax().text_range().start()];
+        let res = text
+            .char_indices()
+            .map(|(idx, c)| {
+                (TextRange::at(TextSize::from(idx as u32) + offset, TextSize::of(c)), c)
+            })
+            .collect();
+        return Some(res);
+    }
+    string.char_ranges()?.into_iter().map(|(range, c)| Some((range, c.ok()?))).collect()
+}
+
+fn language_comment(element: SyntaxElement) -> Option<Language> {
+    let prev = match &element {
+        NodeOrToken::Node(it) => {
+            // Leading comments of items are attached to the item itself.
+            let leading = it
+                .children_with_tokens()
+                .take_while(|it| matches!(it.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT))
+                .filter_map(NodeOrToken::into_token)
+                .filter_map(ast::Comment::cast)
+                .last();
+            if let Some(comment) = leading {
+                return comment_language(&comment);
+            }
+            it.prev_sibling_or_token()
+        }
+        NodeOrToken::Token(it) => it.prev_sibling_or_token(),
+    };
+    for sibling in successors(prev, |it| it.prev_sibling_or_token()) {
+        match sibling.kind() {
+            SyntaxKind::WHITESPACE => continue,
+            SyntaxKind::COMMENT => {
+                return comment_language(&sibling.into_token().and_then(ast::Comment::cast)?);
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn comment_language(comment: &ast::Comment) -> Option<Language> {
+    let text = comment.text();
+    let text = text.strip_prefix(comment.prefix()).unwrap_or(text);
+    let text = text.strip_suffix("*/").unwrap_or(text).trim();
+    Language::from_name(text.strip_prefix("language=")?.trim())
+}
+
+fn call_site(string: &ast::String) -> Option<(ast::Path, Language)> {
+    let parent = string.syntax().parent()?;
+    if let Some(tt) = ast::TokenTree::cast(parent.clone()) {
+        let macro_call = tt.syntax().parent().and_then(ast::MacroCall::cast)?;
+        let first_string = tt
+            .syntax()
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find_map(ast::String::cast)?;
+        if &first_string != string {
+            return None;
+        }
+        let path = macro_call.path()?;
+        return path_language(&path, MACRO_CALL_SITES).map(|language| (path, language));
+    }
+
+    let literal = ast::Literal::cast(parent)?;
+    let arg_list = literal.syntax().parent().and_then(ast::ArgList::cast)?;
+    if arg_list.args().next()?.syntax() != literal.syntax() {
+        return None;
+    }
+    let call = arg_list.syntax().parent().and_then(ast::CallExpr::cast)?;
+    let path = match call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    path_language(&path, CALL_SITES).map(|language| (path, language))
+}
+
+fn path_language(path: &ast::Path, call_sites: &[(&[&str], Language)]) -> Option<Language> {
+    let segments: Vec<_> = path
+        .segments()
+        .map(|it| it.name_ref().map(|it| it.text().to_string()).unwrap_or_default())
+        .collect();
+    call_sites.iter().find_map(|(suffix, language)| {
+        let matches = segments.len() >= suffix.len()
+            && segments[segments.len() - suffix.len()..]
+                .iter()
+                .zip(suffix.iter())
+                .all(|(segment, expected)| segment == expected);
+        if matches {
+            Some(*language)
+        } else {
+            None
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Operator,
+    Escape,
+    String,
+    Number,
+    Comment,
+    Bracket,
+    Brace,
+    Parenthesis,
+    Comma,
+    Colon,
+}
+
+/// Splits the characters of a string in `language` into the tokens worth
+/// highlighting.
+pub fn lex(language: Language, chars: &[(TextRange, char)]) -> Vec<(TextRange, TokenKind)> {
+    match language {
+        Language::Regex => parse_regex(chars).0,
+        Language::Sql => lex_sql(chars),
+        Language::Json => lex_json(chars),
+    }
+}
+
+/// Returns the first syntax error of a regex, in the syntax of the `regex`
+/// crate.
+pub fn regex_error(chars: &[(TextRange, char)]) -> Option<(TextRange, &'static str)> {
+    parse_regex(chars).1
+}
+
+fn range_of(chars: &[(TextRange, char)]) -> TextRange {
+    match (chars.first(), chars.last()) {
+        (Some(first), Some(last)) => first.0.cover(last.0),
+        _ => TextRange::default(),
+    }
+}
+
+/// The tokens of a regex, and its first error.
+type ParsedRegex = (Vec<(TextRange, TokenKind)>, Option<(TextRange, &'static str)>);
+
+fn parse_regex(chars: &[(TextRange, char)]) -> ParsedRegex {
+    let mut tokens = Vec::new();
+    // The open groups, with whether verbose mode was on outside of them.
+    let mut groups = Vec::new();
+    // Whether the previous item can be repeated.
+    let mut can_repeat = false;
+    // In verbose mode, set by the `x` flag, whitespace is ignored and `#`
+    // starts a comment.
+    let mut verbose = false;
+    let mut i = 0;
+
+    macro_rules! error {
+        ($range:expr, $msg:expr) => {
+            return (tokens, Some(($range, $msg)))
+        };
+    }
+
+    while i < chars.len() {
+        let (range, c) = chars[i];
+        if verbose && c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if verbose && c == '#' {
+            let len = chars[i..].iter().position(|&(_, c)| c == '\n').unwrap_or(chars.len() - i);
+            tokens.push((range_of(&chars[i..i + len]), TokenKind::Comment));
+            i += len;
+            continue;
+        }
+        match c {
+            '\\' => {
+                let len = match escape_len(&chars[i..]) {
+                    Ok(it) => it,
+                    Err(msg) => error!(range_of(&chars[i..(i + 2).min(chars.len())]), msg),
+                };
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Escape));
+                i += len;
+                can_repeat = true;
+                continue;
+            }
+            '[' => {
+                let len = match class_len(&chars[i..], &mut tokens) {
+                    Some(it) => it,
+                    None => error!(range, "unclosed character class"),
+                };
+                i += len;
+                can_repeat = true;
+                continue;
+            }
+            '(' => {
+                groups.push((range, verbose));
+                let mut len = 1;
+                if let Some((_, '?')) = chars.get(i + 1) {
+                    len = match group_flags_len(&chars[i..]) {
+                        Ok(it) => it,
+                        Err(msg) => error!(range_of(&chars[i..(i + 2).min(chars.len())]), msg),
+                    };
+                    let is_named = matches!(chars.get(i + 2), Some((_, 'P')) | Some((_, '<')));
+                    if !is_named {
+                        verbose = verbose_flag(&chars[i + 2..i + len - 1]).unwrap_or(verbose);
+                    }
+                    // `(?i)` only sets flags, it isn't a group.
+                    if let Some((_, ')')) = chars.get(i + len - 1) {
+                        groups.pop();
+                        tokens.push((range_of(&chars[i..i + len]), TokenKind::Parenthesis));
+                        i += len;
+                        can_repeat = false;
+                        continue;
+                    }
+                }
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Parenthesis));
+                i += len;
+                can_repeat = false;
+                continue;
+            }
+            ')' => {
+                match groups.pop() {
+                    Some((_, outer_verbose)) => verbose = outer_verbose,
+                    None => error!(range, "unopened group"),
+                }
+                tokens.push((range, TokenKind::Parenthesis));
+                can_repeat = true;
+            }
+            '|' => {
+                tokens.push((range, TokenKind::Operator));
+                can_repeat = false;
+            }
+            '*' | '+' | '?' => {
+                if !can_repeat {
+                    error!(range, "repetition operator missing expression");
+                }
+                let mut len = 1;
+                if let Some((_, '?')) = chars.get(i + 1) {
+                    len += 1;
+                }
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Operator));
+                i += len;
+                can_repeat = false;
+                continue;
+            }
+            '{' => {
+                if !can_repeat {
+                    error!(range, "repetition operator missing expression");
+                }
+                let len = match counted_repetition_len(&chars[i..]) {
+                    Ok(it) => it,
+                    Err(msg) => error!(range, msg),
+                };
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Operator));
+                i += len;
+                can_repeat = false;
+                continue;
+            }
+            '^' | '$' | '.' => {
+                tokens.push((range, TokenKind::Keyword));
+                can_repeat = true;
+            }
+            _ => can_repeat = true,
+        }
+        i += 1;
+    }
+    if let Some(&(range, _)) = groups.last() {
+        return (tokens, Some((range, "unclosed group")));
+    }
+    (tokens, None)
+}
+
+/// Returns the length of the escape sequence at the start of `chars`.
+fn escape_len(chars: &[(TextRange, char)]) -> Result<usize, &'static str> {
+    let c = match chars.get(1) {
+        Some(&(_, c)) => c,
+        None => return Err("incomplete escape sequence"),
+    };
+    match c {
+        'd' | 'D' | 'w' | 'W' | 's' | 'S' | 'b' | 'B' | 'A' | 'z' | 'n' | 'r' | 't' | 'f' | 'v'
+        | 'a' => Ok(2),
+        'x' | 'u' | 'U' | 'p' | 'P' => match chars.get(2) {
+            Some((_, '{')) => match chars[2..].iter().position(|&(_, c)| c == '}') {
+                Some(pos) => Ok(pos + 3),
+                None => Err("unclosed escape sequence"),
+            },
+            Some(_) if c == 'p' || c == 'P' => Ok(3),
+            Some(_) => {
+                let digits = match c {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8,
+                };
+                let valid = chars.len() >= digits + 2
+                    && chars[2..digits + 2].iter().all(|&(_, c)| c.is_ascii_hexdigit());
+                if valid {
+                    Ok(digits + 2)
+                } else {
+                    Err("invalid hexadecimal escape")
+                }
+            }
+            None => Err("incomplete escape sequence"),
+        },
+        '0'..='9' => Err("backreferences are not supported"),
+        '<' | '>' => Err("word boundary assertions `\\<` and `\\>` are not supported"),
+        c if c.is_ascii_punctuation() || c == ' ' => Ok(2),
+        _ => Err("unrecognized escape sequence"),
+    }
+}
+
+/// Returns the length of the character class at the start of `chars`, or
+/// `None` if it's unclosed. Pushes the tokens of the class to `tokens`.
+fn class_len(
+    chars: &[(TextRange, char)],
+    tokens: &mut Vec<(TextRange, TokenKind)>,
+) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let (range, c) = chars[i];
+        match c {
+            '\\' => {
+                let len = escape_len(&chars[i..]).unwrap_or(2).min(chars.len() - i);
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Escape));
+                i += len;
+                continue;
+            }
+            '[' => {
+                tokens.push((range, TokenKind::Bracket));
+                depth += 1;
+                if let Some((range, '^')) = chars.get(i + 1) {
+                    tokens.push((*range, TokenKind::Operator));
+                    i += 1;
+                }
+                // A `]` right after the opening bracket is a literal.
+                if let Some((_, ']')) = chars.get(i + 1) {
+                    i += 1;
+                }
+            }
+            ']' => {
+                tokens.push((range, TokenKind::Bracket));
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            '-' if i > 0 && !matches!(chars.get(i + 1), Some((_, ']')) | None) => {
+                tokens.push((range, TokenKind::Operator));
+            }
+            '&' | '~' if matches!(chars.get(i + 1), Some(&(_, next)) if next == c) => {
+                tokens.push((range_of(&chars[i..i + 2]), TokenKind::Operator));
+                i += 2;
+                continue;
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the length of the `(?...` prefix of a group, including the closing
+/// parenthesis of a flag group like `(?i)`.
+fn group_flags_len(chars: &[(TextRange, char)]) -> Result<usize, &'static str> {
+    match chars.get(2).map(|&(_, c)| c) {
+        Some('=') | Some('!') => return Err("look-around is not supported"),
+        Some('<') if matches!(chars.get(3), Some((_, '=')) | Some((_, '!'))) => {
+            return Err("look-around is not supported")
+        }
+        Some('P') | Some('<') => {
+            let start = if chars[2].1 == 'P' { 3 } else { 2 };
+            if !matches!(chars.get(start), Some((_, '<'))) {
+                return Err("invalid group name");
+            }
+            let len = match chars[start..].iter().position(|&(_, c)| c == '>') {
+                Some(it) => it,
+                None => return Err("unclosed group name"),
+            };
+            let name = &chars[start + 1..start + len];
+            if name.is_empty() || !name.iter().all(|&(_, c)| c == '_' || c.is_alphanumeric()) {
+                return Err("invalid group name");
+            }
+            return Ok(start + len + 1);
+        }
+        _ => (),
+    }
+    for (i, &(_, c)) in chars.iter().enumerate().skip(2) {
+        match c {
+            'i' | 'm' | 's' | 'U' | 'u' | 'x' | '-' => (),
+            ':' | ')' => return Ok(i + 1),
+            _ => return Err("unrecognized flag"),
+        }
+    }
+    Err("unclosed group")
+}
+
+/// Whether the flags of a group, like `x-i` in `(?x-i)`, turn verbose mode on
+/// or off.
+fn verbose_flag(flags: &[(TextRange, char)]) -> Option<bool> {
+    let mut enable = true;
+    let mut res = None;
+    for &(_, c) in flags {
+        match c {
+            '-' => enable = false,
+            'x' => res = Some(enable),
+            _ => (),
+        }
+    }
+    res
+}
+
+/// Returns the length of the counted repetition, like `{2,3}`, at the start of
+/// `chars`.
+fn counted_repetition_len(chars: &[(TextRange, char)]) -> Result<usize, &'static str> {
+    let len = match chars.iter().position(|&(_, c)| c == '}') {
+        Some(it) => it + 1,
+        None => return Err("unclosed counted repetition"),
+    };
+    let body: String = chars[1..len - 1].iter().map(|&(_, c)| c).collect();
+    let mut bounds = body.splitn(2, ',').map(|it| it.trim());
+    let min = bounds.next().and_then(|it| it.parse::<u32>().ok());
+    let max = match bounds.next() {
+        Some("") => None,
+        Some(it) => Some(it.parse::<u32>().map_err(|_| "invalid counted repetition")?),
+        None => min,
+    };
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err("invalid repetition range"),
+        (Some(_), _) => Ok(len),
+        (None, _) => Err("invalid counted repetition"),
+    }
+}
+
+const SQL_KEYWORDS: &[&str] = &[
+    "all",
+    "alter",
+    "and",
+    "as",
+    "asc",
+    "between",
+    "by",
+    "case",
+    "create",
+    "default",
+    "delete",
+    "desc",
+    "distinct",
+    "drop",
+    "else",
+    "end",
+    "exists",
+    "from",
+    "group",
+    "having",
+    "in",
+    "index",
+    "inner",
+    "insert",
+    "into",
+    "is",
+    "join",
+    "key",
+    "left",
+    "like",
+    "limit",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "outer",
+    "primary",
+    "returning",
+    "right",
+    "select",
+    "set",
+    "table",
+    "then",
+    "union",
+    "update",
+    "values",
+    "when",
+    "where",
+];
+
+fn lex_sql(chars: &[(TextRange, char)]) -> Vec<(TextRange, TokenKind)> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (range, c) = chars[i];
+        let len = match c {
+            '\'' | '"' => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .position(|&(_, it)| it == c)
+                    .map_or(chars.len() - i, |it| it + 2);
+                if c == '\'' {
+                    tokens.push((range_of(&chars[i..i + len]), TokenKind::String));
+                }
+                len
+            }
+            '-' if matches!(chars.get(i + 1), Some((_, '-'))) => {
+                let len =
+                    chars[i..].iter().position(|&(_, it)| it == '\n').unwrap_or(chars.len() - i);
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Comment));
+                len
+            }
+            '$' | '?' => {
+                let len =
+                    1 + chars[i + 1..].iter().take_while(|(_, it)| it.is_ascii_digit()).count();
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Escape));
+                len
+            }
+            '(' | ')' => {
+                tokens.push((range, TokenKind::Parenthesis));
+                1
+            }
+            ',' => {
+                tokens.push((range, TokenKind::Comma));
+                1
+            }
+            '=' | '<' | '>' | '!' | '+' | '-' | '*' | '/' | '%' | '|' => {
+                tokens.push((range, TokenKind::Operator));
+                1
+            }
+            c if c.is_ascii_digit() => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|(_, it)| it.is_ascii_digit() || *it == '.')
+                    .count();
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Number));
+                len
+            }
+            c if c == '_' || c.is_alphabetic() => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|(_, it)| *it == '_' || it.is_alphanumeric())
+                    .count();
+                let word: String = chars[i..i + len].iter().map(|&(_, c)| c).collect();
+                if SQL_KEYWORDS.contains(&&*word.to_ascii_lowercase()) {
+                    tokens.push((range_of(&chars[i..i + len]), TokenKind::Keyword));
+                }
+                len
+            }
+            _ => 1,
+        };
+        i += len;
+    }
+    tokens
+}
+
+fn lex_json(chars: &[(TextRange, char)]) -> Vec<(TextRange, TokenKind)> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (range, c) = chars[i];
+        let len = match c {
+            '"' => {
+                let mut len = 1;
+                while let Some(&(range, c)) = chars.get(i + len) {
+                    match c {
+                        '\\' => {
+                            let end = (i + len + 2).min(chars.len());
+                            tokens.push((range.cover(chars[end - 1].0), TokenKind::Escape));
+                            len = end - i;
+                        }
+                        '"' => {
+                            len += 1;
+                            break;
+                        }
+                        _ => len += 1,
+                    }
+                }
+                len
+            }
+            '{' | '}' => {
+                tokens.push((range, TokenKind::Brace));
+                1
+            }
+            '[' | ']' => {
+                tokens.push((range, TokenKind::Bracket));
+                1
+            }
+            ',' => {
+                tokens.push((range, TokenKind::Comma));
+                1
+            }
+            ':' => {
+                tokens.push((range, TokenKind::Colon));
+                1
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|(_, it)| {
+                        it.is_ascii_digit() || matches!(it, '-' | '+' | '.' | 'e' | 'E')
+                    })
+                    .count();
+                tokens.push((range_of(&chars[i..i + len]), TokenKind::Number));
+                len
+            }
+            c if c.is_ascii_alphabetic() => {
+                let len = chars[i..].iter().take_while(|(_, it)| it.is_ascii_alphabetic()).count();
+                let word: String = chars[i..i + len].iter().map(|&(_, c)| c).collect();
+                if matches!(&*word, "true" | "false" | "null") {
+                    tokens.push((range_of(&chars[i..i + len]), TokenKind::Keyword));
+                }
+                len
+            }
+            _ => 1,
+        };
+        i += len;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::{ast, AstNode, SourceFile};
+
+    use super::*;
+
+    fn string(code: &str) -> ast::String {
+        let file = SourceFile::parse(code).tree();
+        file.syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find_map(ast::String::cast)
+            .unwrap()
+    }
+
+    fn check_language(code: &str, expected: Option<Language>) {
+        assert_eq!(injected_language(&string(code)), expected);
+    }
+
+    fn check_regex_error(regex: &str, expected: Option<&str>) {
+        let code = format!("fn f() {{ Regex::new(r\"{}\"); }}", regex);
+        let chars = string_chars(&string(&code)).unwrap();
+        assert_eq!(regex_error(&chars).map(|(_, msg)| msg), expected, "{}", regex);
+    }
+
+    #[test]
+    fn detects_call_sites() {
+        check_language(r#"fn f() { regex::Regex::new(r"\d+"); }"#, Some(Language::Regex));
+        check_language(r#"fn f() { RegexBuilder::new("a").build(); }"#, Some(Language::Regex));
+        check_language(r#"fn f() { serde_json::from_str("{}"); }"#, Some(Language::Json));
+        check_language(r#"fn f() { sqlx::query!("SELECT 1"); }"#, Some(Language::Sql));
+        check_language(r#"fn f() { sqlx::query_as!(T, "SELECT 1"); }"#, Some(Language::Sql));
+        check_language(r#"fn f() { Foo::new("a"); }"#, None);
+        check_language(r#"fn f() { query!("SELECT 1"); }"#, None);
+    }
+
+    #[test]
+    fn detects_language_comments() {
+        check_language(
+            r#"
+fn f() {
+    // language=sql
+    let q = "SELECT 1";
+}
+"#,
+            Some(Language::Sql),
+        );
+        check_language(
+            r#"
+// language=regex
+const RE: &str = "a+";
+"#,
+            Some(Language::Regex),
+        );
+        check_language(r#"fn f() { g(/* language=json */ "{}"); }"#, Some(Language::Json));
+        check_language(
+            r#"
+fn f() {
+    // language=cobol
+    let q = "SELECT 1";
+}
+"#,
+            None,
+        );
+    }
+
+    #[test]
+    fn valid_regexes() {
+        for regex in [
+            r"\d+",
+            r"^[a-z_][a-z0-9_]*$",
+            r"(?P<year>\d{4})-(?<month>\d{2})",
+            r"(?i)hello|(?:bye)+?",
+            r"[[:alpha:]\]\-]",
+            r"[^]a]",
+            r"\p{Greek}\pN\x41\x{10FFFF}é",
+            r"a{2,}c{1,2}",
+            r"\.\*\{\}",
+            "(?x) a + # one or more (\n b {2} # [unclosed",
+            "(?x: a # ( \n ) (?-x: a)",
+        ] {
+            check_regex_error(regex, None);
+        }
+    }
+
+    #[test]
+    fn invalid_regexes() {
+        check_regex_error(r"(a", Some("unclosed group"));
+        check_regex_error(r"a)", Some("unopened group"));
+        check_regex_error(r"[a-z", Some("unclosed character class"));
+        check_regex_error(r"*a", Some("repetition operator missing expression"));
+        check_regex_error(r"a|+", Some("repetition operator missing expression"));
+        check_regex_error(r"a{3,2}", Some("invalid repetition range"));
+        check_regex_error(r"a{2", Some("unclosed counted repetition"));
+        check_regex_error(r"\y", Some("unrecognized escape sequence"));
+        check_regex_error(r"(a)\1", Some("backreferences are not supported"));
+        check_regex_error(r"a(?=b)", Some("look-around is not supported"));
+        check_regex_error(r"(?P<1a-b>x)", Some("invalid group name"));
+        check_regex_error(r"a\", Some("incomplete escape sequence"));
+        check_regex_error("(?x: a ) # (", Some("unclosed group"));
+    }
+}
@@ -8,7 +8,7 @@ use hir::Semantics;
 use syntax::{
     algo,
     ast::{self, make, AstNode, AttrsOwner, ModuleItemOwner, PathSegmentKind, VisibilityOwner},
-    match_ast, ted, AstToken, Direction, NodeOrToken, SyntaxNode, SyntaxToken,
+    match_ast, ted, AstToken, Direction, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken,
 };
 
 use crate::{
@@ -220,7 +220,137 @@ pub fn insert_use<'a>(scope: &ImportScope, path: ast::Path, cfg: &InsertUseConfi
     insert_use_(scope, &path, cfg.group, use_item);
 }
 
-#[derive(Eq, PartialEq, PartialOrd, Ord)]
+/// Sorts the `use` items of `scope` into groups the way [`insert_use`] does,
+/// and merges or splits them according to the granularity of `cfg`. Only the
+/// first run of consecutive `use` items without attributes is reordered, so
+/// comments and `#[cfg]`-gated imports stay where they are.
+pub fn organize_imports(scope: &ImportScope, cfg: &InsertUseConfig) {
+    let _p = profile::span("organize_imports");
+    let mut run = Vec::new();
+    for element in scope.as_syntax_node().children_with_tokens() {
+        match element {
+            NodeOrToken::Token(token) if token.kind() == SyntaxKind::WHITESPACE => continue,
+            NodeOrToken::Node(node) => match ast::Use::cast(node) {
+                // Leading comments are part of the item they precede.
+                Some(use_item)
+                    if use_item.attrs().next().is_none()
+                        && use_item
+                            .syntax()
+                            .first_token()
+                            .map_or(true, |it| it.kind() != SyntaxKind::COMMENT) =>
+                {
+                    run.push(use_item)
+                }
+                _ if run.is_empty() => continue,
+                _ => break,
+            },
+            _ if run.is_empty() => continue,
+            _ => break,
+        }
+    }
+    let (first, last) = match (run.first(), run.last()) {
+        (Some(first), Some(last)) => (first.clone(), last.clone()),
+        _ => return,
+    };
+
+    let mut uses: Vec<ast::Use> = match cfg.granularity {
+        ImportGranularity::Preserve => {
+            run.iter().map(|use_item| use_item.clone_subtree().clone_for_update()).collect()
+        }
+        _ => run.iter().flat_map(flatten_use).collect(),
+    };
+    let mb = match cfg.granularity {
+        ImportGranularity::Crate => Some(MergeBehavior::Crate),
+        ImportGranularity::Module => Some(MergeBehavior::Module),
+        ImportGranularity::Item | ImportGranularity::Preserve => None,
+    };
+    if let Some(mb) = mb {
+        let mut merged: Vec<ast::Use> = Vec::new();
+        'uses: for use_item in uses {
+            for existing in merged.iter_mut() {
+                if let Some(it) = try_merge_imports(existing, &use_item, mb) {
+                    *existing = it;
+                    continue 'uses;
+                }
+            }
+            merged.push(use_item);
+        }
+        uses = merged;
+    }
+    uses.sort_by(|lhs, rhs| {
+        let (lhs_group, lhs_path, lhs_has_tl) = use_sort_key(lhs);
+        let (rhs_group, rhs_path, rhs_has_tl) = use_sort_key(rhs);
+        lhs_group.cmp(&rhs_group).then_with(|| match (lhs_path, rhs_path) {
+            (Some(lhs), Some(rhs)) => use_tree_path_cmp(&lhs, lhs_has_tl, &rhs, rhs_has_tl),
+            (lhs, rhs) => lhs.is_some().cmp(&rhs.is_some()),
+        })
+    });
+    uses.dedup_by(|lhs, rhs| lhs.syntax().text() == rhs.syntax().text());
+
+    let indent = first
+        .syntax()
+        .prev_sibling_or_token()
+        .and_then(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+        .map(|it| it.text().rsplit('\n').next().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    let mut elements = Vec::new();
+    let mut prev_group = None;
+    for use_item in uses {
+        let (group, ..) = use_sort_key(&use_item);
+        if let Some(prev_group) = prev_group {
+            let blank_line = if cfg.group && prev_group != group { "\n" } else { "" };
+            let ws = format!("\n{}{}", blank_line, indent);
+            elements.push(make::tokens::whitespace(&ws).into());
+        }
+        elements.push(use_item.syntax().clone().into());
+        prev_group = Some(group);
+    }
+    ted::replace_all(first.syntax().clone().into()..=last.syntax().clone().into(), elements);
+}
+
+fn use_sort_key(use_item: &ast::Use) -> (ImportGroup, Option<ast::Path>, bool) {
+    let tree = use_item.use_tree();
+    let path = tree.as_ref().and_then(|it| it.path());
+    let has_tl = tree.map_or(false, |it| it.use_tree_list().is_some());
+    let group = path.as_ref().map_or(ImportGroup::ExternCrate, ImportGroup::new);
+    (group, path, has_tl)
+}
+
+/// Splits `use_item` into one `use` item per imported name.
+fn flatten_use(use_item: &ast::Use) -> Vec<ast::Use> {
+    fn flatten_tree(prefix: Option<ast::Path>, tree: &ast::UseTree, acc: &mut Vec<ast::UseTree>) {
+        let path = match (prefix, tree.path()) {
+            (Some(prefix), Some(path)) if path_is_self(&path) => Some(prefix),
+            (Some(prefix), Some(path)) => Some(make::path_concat(prefix, path)),
+            (prefix, path) => prefix.or(path),
+        };
+        match (tree.use_tree_list(), path) {
+            (Some(list), path) => {
+                list.use_trees().for_each(|tree| flatten_tree(path.clone(), &tree, acc))
+            }
+            (None, Some(path)) => {
+                acc.push(make::use_tree(path, None, tree.rename(), tree.star_token().is_some()))
+            }
+            (None, None) => acc.push(tree.clone()),
+        }
+    }
+
+    let mut trees = Vec::new();
+    if let Some(tree) = use_item.use_tree() {
+        flatten_tree(None, &tree, &mut trees);
+    }
+    trees
+        .into_iter()
+        .map(|tree| make::use_(use_item.visibility(), tree).clone_for_update())
+        .collect()
+}
+
+fn path_is_self(path: &ast::Path) -> bool {
+    path.qualifier().is_none() && path.segment().and_then(|it| it.self_token()).is_some()
+}
+
+#[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
 enum ImportGroup {
     // the order here defines the order of new group inserts
     Std,
@@ -0,0 +1,335 @@
+//! Searches for expressions of a given type, built from the locals in scope.
+//!
+//! The search works bottom-up: it starts with the locals and, in each round,
+//! derives new terms by accessing fields, calling methods whose arguments can
+//! be filled with the terms found so far and constructing values of the goal
+//! type. Every term carries a cost, so that the simplest expressions are
+//! offered first.
+use hir::{
+    Adt, AssocItem, Field, Function, HasVisibility, Module, ModuleDef, ScopeDef, Semantics,
+    SemanticsScope, StructKind, Trait, Type,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+
+use crate::{helpers::FamousDefs, RootDatabase};
+
+/// How many rounds of derivations are made.
+const DEPTH: usize = 3;
+/// How many terms of a single (non goal) type are kept, to keep the search
+/// from blowing up.
+const MAX_TERMS_PER_TYPE: usize = 2;
+/// How many terms of the goal type are returned.
+const MAX_RESULTS: usize = 8;
+
+#[derive(Debug, Clone)]
+struct Term {
+    expr: String,
+    ty: Type,
+    cost: usize,
+    /// Whether the term is a place reached through a reference, like
+    /// `self.config` in a `&self` method, which can't be moved out of.
+    behind_ref: bool,
+    /// Whether the term is a `mut` local or a field of one, which can be
+    /// borrowed mutably.
+    mutable_place: bool,
+}
+
+/// Finds expressions producing a value of type `goal` from the locals in
+/// `scope`, cheapest first.
+///
+/// `current_fn` is the function the expression is meant for. It is never
+/// called, as that would only lead to infinite recursion.
+pub fn term_search(
+    sema: &Semantics<RootDatabase>,
+    scope: &SemanticsScope,
+    goal: &Type,
+    current_fn: Option<Function>,
+) -> Vec<String> {
+    let db = sema.db;
+    let (module, krate) = match (scope.module(), scope.krate()) {
+        (Some(module), Some(krate)) => (module, krate),
+        _ => return Vec::new(),
+    };
+    if goal.is_unit() || goal.contains_unknown() {
+        return Vec::new();
+    }
+    let traits_in_scope = scope.traits_in_scope();
+    let clone_trait = FamousDefs(sema, Some(krate)).core_clone_Clone();
+    let default_trait = FamousDefs(sema, Some(krate)).core_default_Default();
+
+    let mut terms = Vec::new();
+    scope.process_all_names(&mut |name, def| {
+        if let ScopeDef::Local(local) = def {
+            terms.push(Term {
+                expr: name.to_string(),
+                ty: local.ty(db),
+                cost: 0,
+                behind_ref: false,
+                mutable_place: local.is_mut(db),
+            });
+        }
+    });
+
+    for _ in 0..DEPTH {
+        let mut new_terms = Vec::new();
+        for term in &terms {
+            let receiver = term.ty.strip_references();
+            let behind_ref = term.behind_ref || term.ty.remove_ref().is_some();
+            let mutable_place = term.mutable_place && !behind_ref;
+            for (field, ty) in receiver.fields(db) {
+                if field.is_visible_from(db, module) {
+                    new_terms.push(Term {
+                        expr: format!("{}.{}", term.expr, field.name(db)),
+                        ty,
+                        cost: term.cost + 1,
+                        behind_ref,
+                        mutable_place,
+                    });
+                }
+            }
+            for (idx, ty) in receiver.tuple_fields(db).into_iter().enumerate() {
+                new_terms.push(Term {
+                    expr: format!("{}.{}", term.expr, idx),
+                    ty,
+                    cost: term.cost + 1,
+                    behind_ref,
+                    mutable_place,
+                });
+            }
+
+            let cloned = match term.ty.remove_ref() {
+                Some(inner) => Some(inner),
+                None if is_borrowed_place(db, term) => Some(term.ty.clone()),
+                None => None,
+            };
+            if let (Some(ty), Some(clone_trait)) = (cloned, clone_trait) {
+                if ty.impls_trait(db, clone_trait, &[]) {
+                    new_terms.push(Term {
+                        expr: format!("{}.clone()", term.expr),
+                        ty,
+                        cost: term.cost + 1,
+                        behind_ref: false,
+                        mutable_place: false,
+                    });
+                }
+            }
+
+            let mut seen_methods = FxHashSet::default();
+            term.ty.iterate_method_candidates(db, krate, &traits_in_scope, None, |_ty, func| {
+                if Some(func) == current_fn
+                    || !func.is_visible_from(db, module)
+                    || !seen_methods.insert(func.name(db))
+                {
+                    return None;
+                }
+                let params = func.method_params(db)?;
+                let ret_type = func.ret_type(db);
+                if ret_type.is_unit() || ret_type.contains_unknown() {
+                    return None;
+                }
+                let (args, cost) = fill_args(db, &terms, params.iter().map(|it| it.ty()))?;
+                new_terms.push(Term {
+                    expr: format!("{}.{}({})", term.expr, func.name(db), args.join(", ")),
+                    ty: ret_type,
+                    cost: term.cost + cost + 1,
+                    behind_ref: false,
+                    mutable_place: false,
+                });
+                None::<()>
+            });
+        }
+
+        new_terms.extend(constructors(db, module, &terms, goal, current_fn, default_trait));
+
+        new_terms.sort_by_key(|it| it.cost);
+        for term in new_terms {
+            if terms.iter().any(|it| it.expr == term.expr) {
+                continue;
+            }
+            let limit =
+                if same_type(db, &term.ty, goal) { MAX_RESULTS } else { MAX_TERMS_PER_TYPE };
+            if terms.iter().filter(|it| same_type(db, &it.ty, &term.ty)).count() < limit {
+                terms.push(term);
+            }
+        }
+    }
+
+    terms
+        .iter()
+        .filter_map(|it| coerce(db, it, goal))
+        .sorted_by_key(|(expr, cost)| (*cost, expr.len()))
+        .map(|(expr, _)| expr)
+        .dedup()
+        .take(MAX_RESULTS)
+        .collect()
+}
+
+/// Builds values of the goal type: struct and enum variant literals,
+/// associated constructor functions and `Default::default()`.
+fn constructors(
+    db: &RootDatabase,
+    module: Module,
+    terms: &[Term],
+    goal: &Type,
+    current_fn: Option<Function>,
+    default_trait: Option<Trait>,
+) -> Vec<Term> {
+    let mut res = Vec::new();
+    let krate = module.krate();
+
+    if let Some(adt) = goal.as_adt() {
+        if let Some(path) = module.find_use_path(db, ModuleDef::Adt(adt)) {
+            match adt {
+                Adt::Struct(it) => {
+                    let fields = goal.fields(db);
+                    if fields.iter().all(|(field, _)| field.is_visible_from(db, module)) {
+                        res.extend(literal(
+                            db,
+                            terms,
+                            goal,
+                            path.to_string(),
+                            it.kind(db),
+                            &fields,
+                        ));
+                    }
+                }
+                // Variant fields of generic enums can't be substituted yet.
+                Adt::Enum(it) if goal.type_arguments().next().is_none() => {
+                    for variant in it.variants(db) {
+                        let fields: Vec<_> =
+                            variant.fields(db).into_iter().map(|it| (it, it.ty(db))).collect();
+                        let path = format!("{}::{}", path, variant.name(db));
+                        res.extend(literal(db, terms, goal, path, variant.kind(db), &fields));
+                    }
+                }
+                _ => {}
+            }
+
+            goal.clone().iterate_assoc_items(db, krate, |item| {
+                let func = match item {
+                    AssocItem::Function(it) => it,
+                    _ => return None,
+                };
+                if Some(func) == current_fn
+                    || func.self_param(db).is_some()
+                    || !func.is_visible_from(db, module)
+                {
+                    return None;
+                }
+                let ret_type = func.ret_type(db);
+                if !same_type(db, &ret_type, goal) {
+                    return None;
+                }
+                let params = func.assoc_fn_params(db);
+                let (args, cost) = fill_args(db, terms, params.iter().map(|it| it.ty()))?;
+                res.push(Term {
+                    expr: format!("{}::{}({})", path, func.name(db), args.join(", ")),
+                    ty: goal.clone(),
+                    cost: cost + 1,
+                    behind_ref: false,
+                    mutable_place: false,
+                });
+                None::<()>
+            });
+        }
+    }
+
+    if let Some(default_trait) = default_trait {
+        if goal.impls_trait(db, default_trait, &[]) {
+            if let Some(path) = module.find_use_path(db, ModuleDef::Trait(default_trait)) {
+                res.push(Term {
+                    expr: format!("{}::default()", path),
+                    ty: goal.clone(),
+                    cost: 1,
+                    behind_ref: false,
+                    mutable_place: false,
+                });
+            }
+        }
+    }
+
+    res
+}
+
+/// Builds a struct or variant literal, if all fields can be filled.
+fn literal(
+    db: &RootDatabase,
+    terms: &[Term],
+    goal: &Type,
+    path: String,
+    kind: StructKind,
+    fields: &[(Field, Type)],
+) -> Option<Term> {
+    let (args, cost) = fill_args(db, terms, fields.iter().map(|(_, ty)| ty))?;
+    let expr = match kind {
+        StructKind::Record => {
+            let fields = fields
+                .iter()
+                .zip(&args)
+                .map(|((field, _), arg)| {
+                    let name = field.name(db).to_string();
+                    if &name == arg {
+                        name
+                    } else {
+                        format!("{}: {}", name, arg)
+                    }
+                })
+                .join(", ");
+            format!("{} {{ {} }}", path, fields)
+        }
+        StructKind::Tuple => format!("{}({})", path, args.join(", ")),
+        StructKind::Unit => path,
+    };
+    Some(Term { expr, ty: goal.clone(), cost: cost + 1, behind_ref: false, mutable_place: false })
+}
+
+/// Fills the arguments of a call, preferring the cheapest terms.
+fn fill_args<'a>(
+    db: &RootDatabase,
+    terms: &[Term],
+    params: impl Iterator<Item = &'a Type>,
+) -> Option<(Vec<String>, usize)> {
+    let mut cost = 0;
+    let mut args = Vec::new();
+    for param in params {
+        let (arg, arg_cost) = fill_arg(db, terms, param)?;
+        args.push(arg);
+        cost += arg_cost;
+    }
+    Some((args, cost))
+}
+
+fn fill_arg(db: &RootDatabase, terms: &[Term], ty: &Type) -> Option<(String, usize)> {
+    terms.iter().filter_map(|term| coerce(db, term, ty)).min_by_key(|(_, cost)| *cost)
+}
+
+/// Uses `term` as a value of type `ty`, borrowing it if `ty` is a reference.
+/// Borrowed places are only used by value if they are `Copy`, and only
+/// mutable places are borrowed mutably.
+fn coerce(db: &RootDatabase, term: &Term, ty: &Type) -> Option<(String, usize)> {
+    if same_type(db, &term.ty, ty) && !is_borrowed_place(db, term) {
+        return Some((term.expr.clone(), term.cost));
+    }
+    let inner = ty.remove_ref()?;
+    if !same_type(db, &inner, &term.ty) {
+        return None;
+    }
+    if !ty.is_mutable_reference() {
+        Some((format!("&{}", term.expr), term.cost))
+    } else if term.mutable_place {
+        Some((format!("&mut {}", term.expr), term.cost))
+    } else {
+        None
+    }
+}
+
+/// Whether `term` is a place behind a reference that can't be moved out of.
+fn is_borrowed_place(db: &RootDatabase, term: &Term) -> bool {
+    term.behind_ref && term.ty.remove_ref().is_none() && !term.ty.is_copy(db)
+}
+
+/// Compares the types themselves, ignoring the environments they come from.
+fn same_type(db: &RootDatabase, a: &Type, b: &Type) -> bool {
+    !a.contains_unknown() && !b.contains_unknown() && a.could_unify_with(db, b)
+}
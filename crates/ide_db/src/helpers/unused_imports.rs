@@ -0,0 +1,244 @@
+//! Finds the imports of a file that name resolution never goes through.
+//!
+//! The uses are tracked by `hir`, from the resolved paths, methods and
+//! associated items of the module and its descendants, including the ones in
+//! macro expansions. Imports that can't be proven unused, like the ones of
+//! macros and glob imports, are never reported.
+use hir::{InFile, Module, ModuleSource, Semantics};
+use syntax::{
+    ast::{self, AttrsOwner},
+    AstNode, AstPtr, SyntaxNode,
+};
+
+use crate::{base_db::FileId, helpers::is_lint_allowed_in_module, RootDatabase};
+
+const LINTS: &[&str] = &["unused_imports", "unused"];
+
+/// Returns the unused leaves of the use trees of the private `use` items of
+/// `file_id`, in source order.
+pub fn unused_imports(sema: &Semantics<RootDatabase>, file_id: FileId) -> Vec<ast::UseTree> {
+    let _p = profile::span("unused_imports");
+    let db = sema.db;
+    let source_file = sema.parse(file_id);
+    let mut modules: Vec<Module> = sema.to_module_defs(file_id).collect();
+    let mut res = Vec::new();
+    while let Some(module) = modules.pop() {
+        modules.extend(
+            module.children(db).filter(|child| {
+                matches!(child.definition_source(db).value, ModuleSource::Module(_))
+            }),
+        );
+        if is_lint_allowed_in_module(db, module, LINTS) {
+            continue;
+        }
+        for InFile { file_id: tree_file_id, value: tree } in module.unused_imports(db) {
+            if tree_file_id != file_id.into() {
+                continue;
+            }
+            let tree = AstPtr::new(&tree).to_node(source_file.syntax());
+            let use_item = match tree.syntax().ancestors().find_map(ast::Use::cast) {
+                Some(it) => it,
+                None => continue,
+            };
+            if use_item.attrs().next().is_some() || is_lint_allowed(use_item.syntax()) {
+                continue;
+            }
+            res.push(tree);
+        }
+    }
+    res.sort_by_key(|it| it.syntax().text_range().start());
+    res.dedup();
+    res
+}
+
+/// Removes `tree` from its `use` item, removing the enclosing use tree lists
+/// and the item itself as they become empty. `tree` must be mutable.
+pub fn remove_use_tree(tree: &ast::UseTree) {
+    let mut tree = tree.clone();
+    while let Some(list) = tree.syntax().parent().and_then(ast::UseTreeList::cast) {
+        if list.use_trees().count() > 1 {
+            tree.remove();
+            return;
+        }
+        tree = list.parent_use_tree();
+    }
+    if let Some(use_item) = tree.syntax().parent().and_then(ast::Use::cast) {
+        use_item.remove();
+    }
+}
+
+/// Whether `#[allow(unused_imports)]` or `#[allow(unused)]` applies to `node`
+/// from one of the items containing it, up to the closest module.
+fn is_lint_allowed(node: &SyntaxNode) -> bool {
+    let is_allow_attr = |attr: ast::Attr| {
+        attr.simple_name().as_deref() == Some("allow")
+            && attr.token_tree().map_or(false, |tt| {
+                tt.syntax()
+                    .children_with_tokens()
+                    .filter_map(|it| it.into_token())
+                    .any(|token| LINTS.contains(&token.text()))
+            })
+    };
+    node.ancestors()
+        .take_while(|it| !ast::Module::can_cast(it.kind()))
+        .filter_map(ast::Item::cast)
+        .any(|item| item.attrs().any(is_allow_attr))
+}
+
+#[cfg(test)]
+mod tests {
+    use base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use hir::Semantics;
+    use stdx::format_to;
+
+    use crate::RootDatabase;
+
+    use super::unused_imports;
+
+    fn check(ra_fixture: &str, expect: expect_test::Expect) {
+        let (db, files) = RootDatabase::with_many_files(ra_fixture);
+        let file_id = files[0];
+        let sema = Semantics::new(&db);
+        let mut actual = String::new();
+        for tree in unused_imports(&sema, file_id) {
+            format_to!(actual, "{}\n", tree);
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn unused_imports_in_items_and_bodies() {
+        check(
+            r#"
+mod m {
+    pub struct A;
+    pub struct B;
+    pub fn f() {}
+    pub mod n { pub struct C; }
+    pub trait Tr { fn method(&self) {} }
+    impl Tr for A {}
+}
+use m::{A, B as Bee, f, n::{self, C}};
+use m::Tr;
+
+fn g() -> A {
+    use m::B;
+    n::C;
+    A.method();
+    A
+}
+"#,
+            expect![[r#"
+                B as Bee
+                f
+                C
+                m::B
+            "#]],
+        );
+    }
+
+    #[test]
+    fn shadowed_import_is_unused() {
+        check(
+            r#"
+mod m { pub struct S; }
+mod k { pub struct S; }
+use m::S;
+fn f() {
+    use k::S;
+    S;
+}
+"#,
+            expect![[r#"
+                m::S
+            "#]],
+        );
+    }
+
+    #[test]
+    fn imports_used_in_macros_and_attributes() {
+        check(
+            r#"
+mod m {
+    pub struct Debug;
+    pub struct S;
+    pub trait Tr { fn method(&self); }
+}
+use m::{Debug, S};
+use m::Tr as _;
+macro_rules! make { ($t:ident) => { $t } }
+#[derive(Debug)]
+struct T;
+fn f() { make!(S); }
+"#,
+            expect![[r#"
+                Debug
+                m::Tr as _
+            "#]],
+        );
+    }
+
+    #[test]
+    fn trait_used_in_macro_expansion() {
+        check(
+            r#"
+mod fmt {
+    pub trait Write { fn write_str(&mut self, s: &str); }
+    pub struct Buf;
+    impl Write for Buf { fn write_str(&mut self, _: &str) {} }
+}
+use fmt::Write;
+macro_rules! write { ($dst:expr, $s:expr) => { $dst.write_str($s) } }
+fn f(mut b: fmt::Buf) { write!(b, "x"); }
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn imports_used_by_child_modules() {
+        check(
+            r#"
+//- /main.rs
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::{S, T, U};
+mod child {
+    use super::T;
+    fn f(_: T) {}
+}
+mod tests;
+//- /tests.rs
+use super::*;
+fn g(_: S) {}
+"#,
+            expect![[r#"
+                U
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_unused_imports_when_allowed_or_reexported() {
+        check(
+            r#"
+#![allow(unused_imports)]
+mod m { pub struct S; }
+use m::S;
+"#,
+            expect![[r#""#]],
+        );
+        check(
+            r#"
+mod m { pub struct S; pub struct T; }
+pub use m::S;
+#[cfg(test)]
+use m::T;
+mod child {
+    use super::*;
+}
+"#,
+            expect![[r#""#]],
+        );
+    }
+}
@@ -1,11 +1,15 @@
 //! Functionality for obtaining data related to traits from the DB.
 
-use crate::RootDatabase;
+use crate::{path_transform::PathTransform, RootDatabase};
 use hir::Semantics;
 use rustc_hash::FxHashSet;
 use syntax::{
-    ast::{self, NameOwner},
-    AstNode,
+    ast::{
+        self,
+        edit::{self, AstNodeEdit},
+        make, NameOwner, TypeBoundsOwner,
+    },
+    ted, AstNode,
 };
 
 /// Given the `impl` block, attempts to find the trait this `impl` corresponds to.
@@ -76,5 +80,55 @@ pub fn get_missing_assoc_items(
     })
 }
 
+/// Adds the given trait items to the `impl` block, with placeholder bodies for
+/// the functions that don't have one. Returns the new `impl` block and the
+/// first added item.
+pub fn add_trait_assoc_items_to_impl(
+    sema: &Semantics<RootDatabase>,
+    items: Vec<ast::AssocItem>,
+    trait_: hir::Trait,
+    impl_: ast::Impl,
+    target_scope: hir::SemanticsScope,
+) -> (ast::Impl, ast::AssocItem) {
+    let source_scope = sema.scope_for_def(trait_);
+
+    let transform = PathTransform {
+        subst: (trait_, impl_.clone()),
+        source_scope: &source_scope,
+        target_scope: &target_scope,
+    };
+
+    let items = items.into_iter().map(|assoc_item| {
+        let assoc_item = assoc_item.clone_for_update();
+        transform.apply(assoc_item.clone());
+        edit::remove_attrs_and_docs(&assoc_item).clone_subtree().clone_for_update()
+    });
+
+    let res = impl_.clone_for_update();
+
+    let assoc_item_list = res.get_or_create_assoc_item_list();
+    let mut first_item = None;
+    for item in items {
+        first_item.get_or_insert_with(|| item.clone());
+        match &item {
+            ast::AssocItem::Fn(fn_) if fn_.body().is_none() => {
+                let body = make::block_expr(None, Some(make::ext::expr_todo()))
+                    .indent(edit::IndentLevel(1));
+                ted::replace(fn_.get_or_create_body().syntax(), body.clone_for_update().syntax())
+            }
+            ast::AssocItem::TypeAlias(type_alias) => {
+                if let Some(type_bound_list) = type_alias.type_bound_list() {
+                    type_bound_list.remove()
+                }
+            }
+            _ => {}
+        }
+
+        assoc_item_list.add_item(item)
+    }
+
+    (res, first_item.unwrap())
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,247 @@
+//! Diagnostics which help migrating a crate to Rust 2021, and the crate-wide
+//! fix applying all of them at once.
+
+use hir::{Crate, Semantics};
+use ide_db::{
+    assists::{Assist, AssistResolveStrategy},
+    base_db::{Edition, FileId, FileRange},
+    source_change::SourceChange,
+    RootDatabase,
+};
+use rustc_hash::FxHashSet;
+use syntax::{ast::AstNode, NodeOrToken};
+
+use crate::{
+    handlers::{
+        array_into_iter, non_fmt_panics,
+        rust_2021_incompatible_closure_captures as closure_captures,
+        rust_2021_prefixes_incompatible_syntax as reserved_prefixes,
+    },
+    unresolved_fix, Diagnostic, DiagnosticsConfig,
+};
+
+/// Computes the Rust 2021 migration diagnostics for a file of a crate using an
+/// older edition.
+pub(crate) fn edition_2021_diagnostics(
+    sema: &Semantics<RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+) {
+    let source_file = sema.parse(file_id);
+    for element in source_file.syntax().descendants_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                array_into_iter::array_into_iter(acc, sema, file_id, &node);
+                non_fmt_panics::non_fmt_panics(acc, sema, file_id, &node);
+                closure_captures::rust_2021_incompatible_closure_captures(
+                    acc, sema, file_id, &node,
+                );
+            }
+            NodeOrToken::Token(token) => {
+                reserved_prefixes::rust_2021_prefixes_incompatible_syntax(acc, file_id, &token);
+            }
+        }
+    }
+}
+
+/// Offers a fix migrating the whole crate to Rust 2021 if there's a migration
+/// diagnostic in `frange`. The fix bundles the fixes of all migration
+/// diagnostics of the crate into a single [`SourceChange`].
+pub fn migrate_to_edition_2021(
+    db: &RootDatabase,
+    config: &DiagnosticsConfig,
+    resolve: &AssistResolveStrategy,
+    frange: FileRange,
+) -> Option<Assist> {
+    if !config.edition_migration {
+        return None;
+    }
+    let sema = Semantics::new(db);
+    let krate = sema.to_module_def(frange.file_id)?.krate();
+    if krate.edition(db) >= Edition::Edition2021 {
+        return None;
+    }
+
+    let mut diagnostics = Vec::new();
+    edition_2021_diagnostics(&sema, &mut diagnostics, frange.file_id);
+    let target = diagnostics
+        .into_iter()
+        .filter(|it| !config.disabled.contains(it.code.as_str()))
+        .find(|it| it.range.intersect(frange.range).is_some())?
+        .range;
+
+    let mut res = unresolved_fix("migrate_to_edition_2021", "Migrate crate to Rust 2021", target);
+    if resolve.should_resolve(&res.id) {
+        res.source_change = Some(crate_migration(&sema, config, krate));
+    }
+    Some(res)
+}
+
+fn crate_migration(
+    sema: &Semantics<RootDatabase>,
+    config: &DiagnosticsConfig,
+    krate: Crate,
+) -> SourceChange {
+    let db = sema.db;
+    let mut file_ids: Vec<FileId> = {
+        let mut seen = FxHashSet::default();
+        let mut modules = vec![krate.root_module(db)];
+        while let Some(module) = modules.pop() {
+            seen.insert(module.definition_source(db).file_id.original_file(db));
+            modules.extend(module.children(db));
+        }
+        seen.into_iter().collect()
+    };
+    file_ids.sort();
+
+    let mut res = SourceChange::default();
+    for file_id in file_ids {
+        let mut diagnostics = Vec::new();
+        edition_2021_diagnostics(sema, &mut diagnostics, file_id);
+        let fixes = diagnostics
+            .into_iter()
+            .filter(|it| !config.disabled.contains(it.code.as_str()))
+            .filter_map(|it| it.fixes?.into_iter().next()?.source_change);
+        for fix in fixes {
+            // Fixes touching the same code as an earlier one are skipped as a
+            // whole, their diagnostics will show up again after the migration.
+            let is_disjoint = fix.source_file_edits.iter().all(|(file_id, edit)| {
+                res.get_source_edit(*file_id)
+                    .map_or(true, |existing| existing.clone().union(edit.clone()).is_ok())
+            });
+            if !is_disjoint {
+                continue;
+            }
+            for (file_id, edit) in fix.source_file_edits {
+                match res.source_file_edits.get_mut(&file_id) {
+                    Some(existing) => {
+                        existing.union(edit).expect("edits were checked to be disjoint")
+                    }
+                    None => res.insert_source_edit(file_id, edit),
+                }
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::{
+        assists::AssistResolveStrategy,
+        base_db::{fixture::WithFixture, FileRange, SourceDatabaseExt},
+        RootDatabase,
+    };
+    use stdx::trim_indent;
+    use syntax::TextRange;
+    use test_utils::assert_eq_text;
+
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { edition_migration: true, ..DiagnosticsConfig::default() }
+    }
+
+    fn check_migration(ra_fixture_before: &str, ra_fixtures_after: &[&str]) {
+        let (db, position) = RootDatabase::with_position(ra_fixture_before);
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        let assist =
+            super::migrate_to_edition_2021(&db, &config(), &AssistResolveStrategy::All, frange)
+                .expect("no migration offered");
+        let source_change = assist.source_change.unwrap();
+        let mut file_ids: Vec<_> = source_change.source_file_edits.keys().copied().collect();
+        file_ids.sort();
+        assert_eq!(file_ids.len(), ra_fixtures_after.len());
+        for (file_id, after) in file_ids.into_iter().zip(ra_fixtures_after) {
+            let mut actual = db.file_text(file_id).to_string();
+            source_change.get_source_edit(file_id).unwrap().apply(&mut actual);
+            assert_eq_text!(&trim_indent(after), &actual);
+        }
+    }
+
+    #[test]
+    fn migrates_whole_crate() {
+        check_migration(
+            r#"
+//- minicore: panic
+//- /main.rs
+mod foo;
+macro_rules! m { ($($tt:tt)*) => {} }
+fn main() {
+    m!(z$0#x);
+    let s = "oops";
+    panic!(s);
+}
+//- /foo.rs
+fn foo() {
+    [1, 2].into_iter();
+}
+"#,
+            &[
+                r#"
+mod foo;
+macro_rules! m { ($($tt:tt)*) => {} }
+fn main() {
+    m!(z #x);
+    let s = "oops";
+    panic!("{}", s);
+}
+"#,
+                r#"
+fn foo() {
+    [1, 2].iter();
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn not_offered_without_migration_diagnostic() {
+        let (db, position) = RootDatabase::with_position(
+            r#"
+fn main() {
+    let x$0 = 92;
+    [1, 2].into_iter();
+}
+"#,
+        );
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        assert!(super::migrate_to_edition_2021(
+            &db,
+            &config(),
+            &AssistResolveStrategy::All,
+            frange
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn not_offered_when_disabled() {
+        let (db, position) = RootDatabase::with_position(
+            r#"
+fn main() {
+    [1, 2].into_iter$0();
+}
+"#,
+        );
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        assert!(super::migrate_to_edition_2021(
+            &db,
+            &DiagnosticsConfig::default(),
+            &AssistResolveStrategy::All,
+            frange
+        )
+        .is_none());
+        assert!(super::migrate_to_edition_2021(
+            &db,
+            &config(),
+            &AssistResolveStrategy::All,
+            frange
+        )
+        .is_some());
+    }
+}
@@ -0,0 +1,116 @@
+use hir::Semantics;
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use syntax::{ast, AstNode, SyntaxNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: array-into-iter
+//
+// This diagnostic is triggered when `.into_iter()` is called on an array. Before
+// Rust 2021 the call auto-references the array and yields references to the
+// elements, in Rust 2021 it resolves to `IntoIterator` for arrays and yields the
+// elements by value.
+pub(crate) fn array_into_iter(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let method_call = ast::MethodCallExpr::cast(node.clone())?;
+    let name_ref = method_call.name_ref()?;
+    if name_ref.text() != "into_iter" {
+        return None;
+    }
+    let receiver = method_call.receiver()?;
+    if !sema.type_of_expr(&receiver)?.is_array() {
+        return None;
+    }
+
+    let range = name_ref.syntax().text_range();
+    let edit = TextEdit::replace(range, "iter".to_string());
+    acc.push(
+        Diagnostic::new(
+            "array-into-iter",
+            "this method call resolves to `<&[T; N] as IntoIterator>::into_iter`, \
+             but will resolve to `<[T; N] as IntoIterator>::into_iter` in Rust 2021",
+            range,
+        )
+        .severity(Severity::WeakWarning)
+        .with_fixes(Some(vec![fix(
+            "replace_into_iter_with_iter",
+            "Replace `.into_iter()` with `.iter()`",
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )])),
+    );
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { edition_migration: true, ..DiagnosticsConfig::default() }
+    }
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        check_diagnostics_with_config(config(), ra_fixture);
+    }
+
+    #[track_caller]
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix_with_config(config(), 0, ra_fixture_before, ra_fixture_after);
+    }
+
+    #[test]
+    fn array_into_iter() {
+        check_diagnostics(
+            r#"
+//- minicore: iterator
+fn main() {
+    let a = [1, 2, 3];
+    a.into_iter();
+    //^^^^^^^^^ 💡 weak: this method call resolves to `<&[T; N] as IntoIterator>::into_iter`, but will resolve to `<[T; N] as IntoIterator>::into_iter` in Rust 2021
+    (&a).into_iter();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_in_edition_2021() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main edition:2021
+fn main() {
+    let a = [1, 2, 3];
+    a.into_iter();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_with_iter() {
+        check_fix(
+            r#"
+//- minicore: iterator
+fn main() {
+    [1, 2, 3].into_iter$0();
+}
+"#,
+            r#"
+fn main() {
+    [1, 2, 3].iter();
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use hir::{Semantics, Visibility};
+use ide_db::{
+    base_db::FileId,
+    defs::{Definition, NameClass},
+    helpers::is_lint_allowed_in_module,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, NameOwner},
+    match_ast, AstNode, SyntaxNode, TextRange,
+};
+
+use crate::{Diagnostic, Severity};
+
+const LINTS: &[&str] = &["dead_code", "unused"];
+
+// Diagnostic: dead-code
+//
+// This diagnostic is triggered if a private or crate-visible function, struct,
+// enum variant, field or constant is never referenced in its crate.
+//
+// Trait items, `main`, tests and items with `#[allow(dead_code)]` are never
+// reported. As this searches the whole crate for each item, it's only run if
+// `rust-analyzer.diagnostics.deadCode.enable` is set.
+pub(crate) fn dead_code(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+) {
+    let _p = profile::span("dead_code");
+    let source_file = sema.parse(file_id);
+    let mut dead_ranges: Vec<TextRange> = Vec::new();
+    for node in source_file.syntax().descendants() {
+        if dead_ranges.iter().any(|range| range.contains_range(node.text_range())) {
+            // Don't report the fields and nested items of dead items.
+            continue;
+        }
+        let (kind, name) = match_ast! {
+            match node {
+                ast::Fn(it) => ("function", it.name()),
+                ast::Struct(it) => ("struct", it.name()),
+                ast::Variant(it) => ("variant", it.name()),
+                ast::RecordField(it) => ("field", it.name()),
+                ast::Const(it) => ("constant", it.name()),
+                _ => continue,
+            }
+        };
+        let name = match name {
+            Some(it) if !it.text().starts_with('_') => it,
+            _ => continue,
+        };
+        if is_exempt(&node) {
+            continue;
+        }
+        if is_dead(sema, file_id, &node, &name) == Some(true) {
+            acc.push(
+                Diagnostic::new(
+                    "dead-code",
+                    format!(
+                        "{} `{}` is never {}",
+                        kind,
+                        name.text(),
+                        if kind == "field" { "read" } else { "used" }
+                    ),
+                    name.syntax().text_range(),
+                )
+                .severity(Severity::WeakWarning)
+                .with_unused(true),
+            );
+            dead_ranges.push(node.text_range());
+        }
+    }
+}
+
+fn is_dead(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    name: &ast::Name,
+) -> Option<bool> {
+    let def = match NameClass::classify(sema, name)? {
+        NameClass::Definition(it) => it,
+        _ => return None,
+    };
+    if def.visibility(sema.db)? == Visibility::Public {
+        return Some(false);
+    }
+    let module = def.module(sema.db)?;
+    if let Definition::ModuleDef(hir::ModuleDef::Function(_)) = def {
+        if name.text() == "main" && module.krate().root_module(sema.db) == module {
+            return Some(false);
+        }
+    }
+    if is_lint_allowed_in_module(sema.db, module, LINTS) {
+        return Some(false);
+    }
+
+    let usages = def.usages(sema).all();
+    let is_used = usages.iter().any(|(&ref_file_id, references)| {
+        references.iter().any(|reference| {
+            // Recursion and references from the item itself don't count.
+            if ref_file_id == file_id && node.text_range().contains_range(reference.range) {
+                return false;
+            }
+            // Initializing a field doesn't count as reading it.
+            if let (Definition::Field(_), ast::NameLike::NameRef(name_ref)) = (def, &reference.name)
+            {
+                if ast::RecordExprField::for_field_name(name_ref).is_some() {
+                    return false;
+                }
+            }
+            // Neither do `impl` blocks for the item.
+            let in_impl_header = reference.name.syntax().ancestors().any(|it| {
+                ast::Impl::cast(it).and_then(|impl_| impl_.self_ty()).map_or(false, |self_ty| {
+                    self_ty
+                        .syntax()
+                        .text_range()
+                        .contains_range(reference.name.syntax().text_range())
+                })
+            });
+            !in_impl_header
+        })
+    });
+    Some(!is_used)
+}
+
+/// Whether `node` is an item that is used without being referenced, or one
+/// for which the lint is allowed.
+fn is_exempt(node: &SyntaxNode) -> bool {
+    if let Some(parent) = node.parent() {
+        if let Some(assoc_items) = ast::AssocItemList::cast(parent.clone()) {
+            let is_trait_item = assoc_items.syntax().parent().map_or(false, |it| {
+                ast::Trait::can_cast(it.kind())
+                    || ast::Impl::cast(it).map_or(false, |impl_| impl_.trait_().is_some())
+            });
+            if is_trait_item {
+                return true;
+            }
+        }
+        if ast::ExternItemList::can_cast(parent.kind()) {
+            return true;
+        }
+    }
+
+    let attr_names = |node: &SyntaxNode| {
+        node.children().filter_map(ast::Attr::cast).filter_map(|attr| {
+            let segment = attr.path()?.segment()?;
+            Some((attr, segment.name_ref()?.text().to_string()))
+        })
+    };
+    let is_entry_point = attr_names(node).any(|(_, name)| {
+        matches!(
+            name.as_str(),
+            "test"
+                | "bench"
+                | "no_mangle"
+                | "export_name"
+                | "proc_macro"
+                | "proc_macro_derive"
+                | "proc_macro_attribute"
+        )
+    });
+    if is_entry_point {
+        return true;
+    }
+
+    // The attributes of modules are read from `hir` in `is_dead`, as they can
+    // be in other files.
+    node.ancestors().take_while(|it| !ast::Module::can_cast(it.kind())).any(|it| {
+        attr_names(&it).any(|(attr, name)| {
+            name == "allow"
+                && attr.token_tree().map_or(false, |tt| {
+                    tt.syntax()
+                        .children_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .any(|token| LINTS.contains(&token.text()))
+                })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::check_diagnostics_with_config, DiagnosticsConfig};
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        let config = DiagnosticsConfig { dead_code: true, ..DiagnosticsConfig::default() };
+        check_diagnostics_with_config(config, ra_fixture);
+    }
+
+    #[test]
+    fn dead_items() {
+        check_diagnostics(
+            r#"
+fn used() {}
+fn unused() {}
+ //^^^^^^ weak: function `unused` is never used
+fn _ignored() {}
+fn recursive() { recursive() }
+ //^^^^^^^^^ weak: function `recursive` is never used
+pub fn exported() {}
+pub(crate) fn crate_visible() {}
+            //^^^^^^^^^^^^^ weak: function `crate_visible` is never used
+struct Unused { field: u32 }
+     //^^^^^^ weak: struct `Unused` is never used
+impl Unused {}
+struct S { used: u32, unused: u32 }
+                    //^^^^^^ weak: field `unused` is never read
+impl S {
+    fn method(&self) {}
+     //^^^^^^ weak: function `method` is never used
+}
+enum E { A, B }
+          //^ weak: variant `B` is never used
+const C: u32 = 0;
+    //^ weak: constant `C` is never used
+
+fn main() {
+    used();
+    let s = S { used: 0, unused: 1 };
+    let _ = s.used;
+    let _ = E::A;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn exempt_items() {
+        check_diagnostics(
+            r#"
+trait Tr { fn required(&self); fn provided(&self) {} }
+struct S;
+impl Tr for S { fn required(&self) {} }
+#[allow(dead_code)]
+fn allowed() {}
+#[allow(unused)]
+mod m {
+    fn f() {}
+}
+mod n {
+    #![allow(dead_code)]
+    fn f() {}
+}
+#[test]
+fn test() {}
+#[no_mangle]
+fn exported() {}
+extern "C" { fn foreign(); }
+
+fn main() {
+    let _: &dyn Tr = &S;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn dead_code_across_modules() {
+        check_diagnostics(
+            r#"
+//- /main.rs
+mod helpers;
+fn main() {
+    helpers::used();
+}
+//- /helpers.rs
+pub(crate) fn used() {}
+pub(crate) fn unused() {}
+            //^^^^^^ weak: function `unused` is never used
+fn private() {}
+ //^^^^^^^ weak: function `private` is never used
+"#,
+        );
+    }
+
+    #[test]
+    fn allowed_in_parent_module_file() {
+        check_diagnostics(
+            r#"
+//- /main.rs
+#![allow(dead_code)]
+mod helpers;
+fn main() {}
+//- /helpers.rs
+fn private() {}
+"#,
+        );
+        check_diagnostics(
+            r#"
+//- /main.rs
+#[allow(unused)]
+mod helpers;
+fn main() {}
+//- /helpers.rs
+fn private() {}
+"#,
+        );
+    }
+}
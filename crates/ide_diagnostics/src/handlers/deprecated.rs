@@ -0,0 +1,209 @@
+use hir::Semantics;
+use ide_db::{
+    defs::{Definition, NameRefClass},
+    helpers::is_lint_allowed_in_module,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, AttrsOwner},
+    match_ast, AstNode, SyntaxNode,
+};
+
+use crate::{Diagnostic, Severity};
+
+// Diagnostic: deprecated
+//
+// This diagnostic is triggered when an item marked with `#[deprecated]` is
+// used, unless the use is itself inside a deprecated item, or `#[allow(deprecated)]`
+// applies to it through an enclosing statement, item, module or the crate root.
+pub(crate) fn deprecated(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let name_ref = ast::NameRef::cast(node.clone())?;
+    let def = match NameRefClass::classify(sema, &name_ref)? {
+        NameRefClass::Definition(def) => def,
+        NameRefClass::FieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+    };
+    let deprecation = def.deprecation(sema.db)?;
+    if is_deprecation_allowed(sema, &name_ref) {
+        return None;
+    }
+
+    let name = def.name(sema.db)?;
+    let mut message = format!("use of deprecated item `{}`", name);
+    if let Some(note) = &deprecation.note {
+        message.push_str(": ");
+        message.push_str(note);
+    }
+    acc.push(
+        Diagnostic::new("deprecated", message, name_ref.syntax().text_range())
+            .severity(Severity::WeakWarning),
+    );
+
+    Some(())
+}
+
+fn is_deprecation_allowed(sema: &Semantics<RootDatabase>, name_ref: &ast::NameRef) -> bool {
+    let allowed_by_ancestor = name_ref.syntax().ancestors().any(|node| {
+        let mut attrs = match_ast! {
+            match node {
+                ast::Item(it) => it.attrs(),
+                ast::ExprStmt(it) => it.attrs(),
+                ast::LetStmt(it) => it.attrs(),
+                _ => return false,
+            }
+        };
+        attrs.any(|attr| match attr.simple_name().as_deref() {
+            Some("deprecated" | "rustc_deprecated") => true,
+            Some("allow") => attr.token_tree().map_or(false, |tt| {
+                tt.syntax()
+                    .children_with_tokens()
+                    .filter_map(|it| it.into_token())
+                    .any(|token| token.text() == "deprecated")
+            }),
+            _ => false,
+        })
+    });
+    allowed_by_ancestor
+        || sema
+            .scope(name_ref.syntax())
+            .module()
+            .map_or(false, |module| is_lint_allowed_in_module(sema.db, module, &["deprecated"]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn deprecated_items() {
+        check_diagnostics(
+            r#"
+#[deprecated]
+struct Old;
+#[deprecated(since = "1.0.0", note = "use `bar` instead")]
+fn foo() {}
+fn bar() {}
+struct S { #[deprecated = "unused"] field: u32 }
+impl S {
+    #[deprecated]
+    fn method(&self) {}
+}
+
+fn main() {
+    let _ = Old;
+          //^^^ weak: use of deprecated item `Old`
+    foo();
+  //^^^ weak: use of deprecated item `foo`: use `bar` instead
+    bar();
+    let field = 0;
+    let s = S { field };
+              //^^^^^ weak: use of deprecated item `field`: unused
+    s.method();
+    //^^^^^^ weak: use of deprecated item `method`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn deprecated_trait_items() {
+        check_diagnostics(
+            r#"
+#[deprecated]
+trait Trait {
+    fn method(&self) {}
+}
+struct S;
+#[allow(deprecated)]
+impl Trait for S {}
+
+fn main() {
+    S.method();
+    //^^^^^^ weak: use of deprecated item `method`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn deprecated_across_crates() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:lib
+use lib::Old;
+       //^^^ weak: use of deprecated item `Old`: use `New`
+fn main() {
+    let _ = Old;
+          //^^^ weak: use of deprecated item `Old`: use `New`
+    let _ = lib::New;
+}
+//- /lib.rs crate:lib
+#[deprecated(note = "use `New`")]
+pub struct Old;
+pub struct New;
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_inside_deprecated_or_allowed_items() {
+        check_diagnostics(
+            r#"
+#[deprecated]
+fn foo() {}
+
+#[deprecated]
+fn bar() {
+    foo();
+}
+
+#[allow(unused, deprecated)]
+fn baz() {
+    foo();
+}
+
+#[allow(deprecated)]
+mod m {
+    fn quux() {
+        super::foo();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_with_statement_module_or_crate_allow() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:lib
+mod m;
+fn main() {
+    #[allow(deprecated)]
+    let _ = lib::Old;
+    #[allow(deprecated)]
+    lib::old();
+    lib::old();
+       //^^^ weak: use of deprecated item `old`: prefer "new"
+}
+//- /m.rs
+#![allow(deprecated)]
+fn f() {
+    lib::old();
+}
+//- /lib.rs crate:lib
+#![allow(deprecated)]
+#[deprecated]
+pub struct Old;
+#[deprecated(note = "prefer \"new\"")]
+pub fn old() {}
+fn f() {
+    old();
+}
+"#,
+        );
+    }
+}
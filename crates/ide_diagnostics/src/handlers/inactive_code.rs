@@ -1,11 +1,24 @@
-use cfg::DnfExpr;
+use std::collections::VecDeque;
+
+use cfg::{CfgAtom, DnfExpr};
+use ide_db::{
+    assists::{Assist, AssistCommand},
+    base_db::CrateId,
+};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
 use stdx::format_to;
+use syntax::TextRange;
 
-use crate::{Diagnostic, DiagnosticsContext, Severity};
+use crate::{unresolved_fix, Diagnostic, DiagnosticsContext, Severity};
 
 // Diagnostic: inactive-code
 //
 // This diagnostic is shown for code with inactive `#[cfg]` attributes.
+//
+// If the code is disabled by a Cargo feature, the diagnostic lists the features
+// of the package that would enable it, and offers to reload the workspace with
+// one of them enabled.
 pub(crate) fn inactive_code(
     ctx: &DiagnosticsContext<'_>,
     d: &hir::InactiveCode,
@@ -15,25 +28,123 @@ pub(crate) fn inactive_code(
         return None;
     }
 
-    let inactive = DnfExpr::new(d.cfg.clone()).why_inactive(&d.opts);
+    let dnf = DnfExpr::new(d.cfg.clone());
+    let inactive = dnf.why_inactive(&d.opts);
     let mut message = "code is inactive due to #[cfg] directives".to_string();
 
     if let Some(inactive) = inactive {
         format_to!(message, ": {}", inactive);
     }
 
-    let res = Diagnostic::new(
-        "inactive-code",
-        message,
-        ctx.sema.diagnostics_display_range(d.node.clone()).range,
-    )
-    .severity(Severity::WeakWarning)
-    .with_unused(true);
+    let range = ctx.sema.diagnostics_display_range(d.node.clone()).range;
+    let file_id = d.node.file_id.original_file(ctx.sema.db);
+    let krate = ctx.sema.to_module_def(file_id).map(|it| it.krate());
+    let enabling_features = match krate {
+        Some(krate) => {
+            let features = krate.features(ctx.sema.db);
+            let disabled = dnf.compute_enable_hints(&d.opts).filter_map(|diff| {
+                match (diff.enable(), diff.disable()) {
+                    ([CfgAtom::KeyValue { key, value }], []) if key == "feature" => {
+                        Some(value.to_string())
+                    }
+                    _ => None,
+                }
+            });
+            disabled.flat_map(|it| features_enabling(&features, &it)).unique().collect()
+        }
+        None => Vec::new(),
+    };
+
+    match enabling_features.as_slice() {
+        [] => {}
+        [feature] => format_to!(message, "; enable Cargo feature `{}`", feature),
+        features => format_to!(
+            message,
+            "; enable one of the Cargo features {}",
+            features.iter().map(|it| format!("`{}`", it)).join(", ")
+        ),
+    }
+
+    let res = Diagnostic::new("inactive-code", message, range)
+        .severity(Severity::WeakWarning)
+        .with_unused(true)
+        .with_fixes(
+            krate
+                .filter(|_| !enabling_features.is_empty())
+                .map(|krate| fixes(krate.into(), enabling_features, range)),
+        );
     Some(res)
 }
 
+fn fixes(krate: CrateId, features: Vec<String>, range: TextRange) -> Vec<Assist> {
+    features
+        .into_iter()
+        .map(|feature| {
+            let label = format!("Enable Cargo feature `{}`", feature);
+            let mut fix = unresolved_fix("enable_cargo_feature", &label, range);
+            fix.command = Some(AssistCommand::EnableCargoFeature { krate, feature });
+            fix
+        })
+        .collect()
+}
+
+/// Returns the features that (transitively) enable `feature`, nearest first.
+///
+/// A feature is enabled by a feature listing it, or an optional dependency of
+/// the same name, in its `[features]` entry.
+fn features_enabling(features: &FxHashMap<String, Vec<String>>, feature: &str) -> Vec<String> {
+    if !features.contains_key(feature) {
+        return Vec::new();
+    }
+
+    let mut res = Vec::new();
+    let mut seen = FxHashSet::default();
+    let mut queue = VecDeque::new();
+    queue.push_back(feature.to_string());
+    seen.insert(feature.to_string());
+    while let Some(feature) = queue.pop_front() {
+        let mut enabling = features
+            .iter()
+            .filter(|(_, enables)| enables.iter().any(|it| enables_feature(it, &feature)))
+            .map(|(name, _)| name.clone())
+            .filter(|name| !seen.contains(name))
+            .collect::<Vec<_>>();
+        // Undo the FxHashMap randomization for consistent output.
+        enabling.sort();
+        for name in enabling {
+            seen.insert(name.clone());
+            queue.push_back(name);
+        }
+        res.push(feature);
+    }
+    res
+}
+
+/// Checks whether the `[features]` entry `entry` enables `feature`: either
+/// directly, or by enabling a feature of the optional dependency `feature`.
+///
+/// `dep:name` only enables the optional dependency, and `name?/feature` only
+/// enables a feature of it if something else enables the dependency, so
+/// neither enables the feature `name`.
+fn enables_feature(entry: &str, feature: &str) -> bool {
+    if entry.starts_with("dep:") {
+        return false;
+    }
+    match entry.split_once('/') {
+        Some((dep, _)) if dep.ends_with('?') => false,
+        Some((dep, _)) => dep == feature,
+        None => entry == feature,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use ide_db::{
+        assists::{AssistCommand, AssistResolveStrategy},
+        base_db::{fixture::WithFixture, SourceDatabase},
+        RootDatabase,
+    };
+
     use crate::{tests::check_diagnostics_with_config, DiagnosticsConfig};
 
     pub(crate) fn check(ra_fixture: &str) {
@@ -113,4 +224,65 @@ fn f() {
 "#,
         );
     }
+
+    #[test]
+    fn names_enabling_cargo_features() {
+        check(
+            r#"
+//- /lib.rs crate:foo features:full=derive+std,derive,std=alloc,alloc,serde,json=serde/std
+    #[cfg(feature = "alloc")] pub fn f() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: code is inactive due to #[cfg] directives: feature = "alloc" is disabled; enable one of the Cargo features `alloc`, `std`, `full`
+
+    #[cfg(any(feature = "derive", test))] pub fn g() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: code is inactive due to #[cfg] directives: test and feature = "derive" are disabled; enable one of the Cargo features `derive`, `full`
+
+    #[cfg(feature = "serde")] pub fn h() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: code is inactive due to #[cfg] directives: feature = "serde" is disabled; enable one of the Cargo features `serde`, `json`
+
+    #[cfg(all(feature = "json", feature = "serde"))] pub fn i() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: code is inactive due to #[cfg] directives: feature = "json" and feature = "serde" are disabled
+
+    #[cfg(feature = "nightly")] pub fn j() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: code is inactive due to #[cfg] directives: feature = "nightly" is disabled
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_dependency_feature_syntax() {
+        check(
+            r#"
+//- /lib.rs crate:foo features:serde=dep:serde,json=serde/std,weak=serde?/derive,raw=dep:serde_json
+    #[cfg(feature = "serde")] pub fn f() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: code is inactive due to #[cfg] directives: feature = "serde" is disabled; enable one of the Cargo features `serde`, `json`
+
+    #[cfg(feature = "serde_json")] pub fn g() {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: code is inactive due to #[cfg] directives: feature = "serde_json" is disabled
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_enables_cargo_feature() {
+        let (db, file_id) = RootDatabase::with_single_file(
+            "//- /lib.rs crate:foo features:std\n#[cfg(feature = \"std\")] fn f() {}",
+        );
+        let diagnostic = crate::diagnostics(
+            &db,
+            &DiagnosticsConfig::default(),
+            &AssistResolveStrategy::All,
+            file_id,
+        )
+        .pop()
+        .unwrap();
+        let fix = &diagnostic.fixes.unwrap()[0];
+        assert_eq!(fix.label.to_string(), "Enable Cargo feature `std`");
+        assert_eq!(
+            fix.command,
+            Some(AssistCommand::EnableCargoFeature {
+                krate: db.crate_graph().iter().next().unwrap(),
+                feature: "std".to_string(),
+            })
+        );
+    }
 }
@@ -0,0 +1,353 @@
+use hir::{db::AstDatabase, Adt, CastError, HirDisplay};
+use ide_db::{assists::Assist, source_change::SourceChange};
+use syntax::{ast, AstNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: invalid-cast
+//
+// This diagnostic is triggered if an `as` cast is neither a coercion nor one of
+// the casts between numbers, raw pointers, function pointers and fieldless
+// enums that Rust allows.
+//
+// Example:
+//
+// ```rust
+// let x = 1.0f32 as bool;
+// ```
+pub(crate) fn invalid_cast(ctx: &DiagnosticsContext<'_>, d: &hir::InvalidCast) -> Diagnostic {
+    let db = ctx.sema.db;
+    let expr_ty = d.expr_ty.display(db);
+    let cast_ty = d.cast_ty.display(db);
+    let message = match d.error {
+        CastError::NonScalar => format!("non-primitive cast: `{}` as `{}`", expr_ty, cast_ty),
+        CastError::CastToBool => format!("cannot cast `{}` as `bool`", expr_ty),
+        CastError::CastToChar => format!("only `u8` can be cast as `char`, not `{}`", expr_ty),
+        CastError::NeedViaPtr => {
+            format!(
+                "casting `{}` as `{}` is invalid: cast through a raw pointer first",
+                expr_ty, cast_ty
+            )
+        }
+        CastError::NeedViaThinPtr => {
+            format!(
+                "casting `{}` as `{}` is invalid: cast through a thin pointer first",
+                expr_ty, cast_ty
+            )
+        }
+        CastError::NeedViaInt => {
+            format!(
+                "casting `{}` as `{}` is invalid: cast through an integer first",
+                expr_ty, cast_ty
+            )
+        }
+        CastError::SizedUnsizedCast => {
+            format!("cannot cast thin pointer `{}` to fat pointer `{}`", expr_ty, cast_ty)
+        }
+        CastError::IntToFatCast => {
+            format!("cannot cast `{}` to a fat pointer `{}`", expr_ty, cast_ty)
+        }
+        CastError::DifferingKinds => {
+            format!(
+                "casting `{}` as `{}` is invalid: the pointer metadata differs",
+                expr_ty, cast_ty
+            )
+        }
+        CastError::IllegalCast => format!("casting `{}` as `{}` is invalid", expr_ty, cast_ty),
+    };
+    Diagnostic::new(
+        "invalid-cast",
+        message,
+        ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range,
+    )
+    .with_fixes(fixes(ctx, d))
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::InvalidCast) -> Option<Vec<Assist>> {
+    let db = ctx.sema.db;
+    if d.expr.file_id.call_node(db).is_some() {
+        return None;
+    }
+    let root = db.parse_or_expand(d.expr.file_id)?;
+    let cast_expr = match d.expr.value.to_node(&root) {
+        ast::Expr::CastExpr(it) => it,
+        _ => return None,
+    };
+    let inner = cast_expr.expr()?;
+    let builtin_name = |ty: &hir::Type| ty.as_builtin().map(|it| it.name().to_string());
+
+    let (id, label, edit) = match d.error {
+        CastError::NeedViaPtr => {
+            let ptr = if d.expr_ty.is_mutable_reference() { "*mut _" } else { "*const _" };
+            let edit = TextEdit::insert(inner.syntax().text_range().end(), format!(" as {}", ptr));
+            ("cast_through_raw_pointer", "Cast through a raw pointer", edit)
+        }
+        CastError::NeedViaThinPtr => {
+            let edit = TextEdit::insert(inner.syntax().text_range().end(), " as *const ()".into());
+            ("cast_through_thin_pointer", "Cast through a thin pointer", edit)
+        }
+        CastError::NeedViaInt => {
+            let int = match builtin_name(&d.expr_ty).as_deref() {
+                Some("bool") => "u8",
+                Some("char") => "u32",
+                _ => match d.expr_ty.as_adt() {
+                    Some(Adt::Enum(_)) => "isize",
+                    _ => return None,
+                },
+            };
+            let edit = TextEdit::insert(inner.syntax().text_range().end(), format!(" as {}", int));
+            ("cast_through_integer", "Cast through an integer", edit)
+        }
+        CastError::IllegalCast if d.expr_ty.remove_ref().is_some() && d.cast_ty.is_raw_ptr() => {
+            // `&T as *const U` works as `&T as *const T as *const U`.
+            let ptr = match cast_expr.ty()? {
+                ast::Type::PtrType(it) if it.mut_token().is_some() => {
+                    if !d.expr_ty.is_mutable_reference() {
+                        return None;
+                    }
+                    "*mut _"
+                }
+                _ => "*const _",
+            };
+            let edit = TextEdit::insert(inner.syntax().text_range().end(), format!(" as {}", ptr));
+            ("cast_through_raw_pointer", "Cast through a raw pointer", edit)
+        }
+        CastError::CastToBool => {
+            let zero = match builtin_name(&d.expr_ty).as_deref() {
+                Some("f32" | "f64") => "0.0",
+                Some("char") => "'\\0'",
+                Some(_) => "0",
+                None => return None,
+            };
+            let mut replacement = format!("{} != {}", inner.syntax(), zero);
+            if cast_expr.syntax().parent().map_or(false, |it| {
+                ast::Expr::can_cast(it.kind()) && !ast::ParenExpr::can_cast(it.kind())
+            }) {
+                replacement = format!("({})", replacement);
+            }
+            let edit = TextEdit::replace(cast_expr.syntax().text_range(), replacement);
+            ("compare_with_zero", "Compare with zero instead", edit)
+        }
+        CastError::CastToChar => {
+            // Only builtin scalars can be cast to `u8` first.
+            builtin_name(&d.expr_ty)?;
+            let edit = TextEdit::insert(inner.syntax().text_range().end(), " as u8".to_string());
+            ("cast_through_u8", "Cast through `u8`", edit)
+        }
+        _ => return None,
+    };
+    let source_change = SourceChange::from_text_edit(d.expr.file_id.original_file(db), edit);
+    Some(vec![fix(id, label, source_change, cast_expr.syntax().text_range())])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn valid_casts() {
+        check_diagnostics(
+            r#"
+//- minicore: sized
+enum E { A, B }
+struct S;
+trait Tr {}
+impl Tr for S {}
+fn f() {}
+
+fn main(r: &S, p: *const S, s: &[u8], fp: fn()) {
+    let _ = 1u32 as u8;
+    let _ = 1.5f32 as i64;
+    let _ = 1 as f64;
+    let _ = 65 as char;
+    let _ = b'a' as char;
+    let _ = 'a' as u32;
+    let _ = true as i32;
+    let _ = E::A as u8;
+    let _ = r as *const S;
+    let _ = &[0u8; 4] as *const u8;
+    let _ = s as *const [u8];
+    let _ = r as *const dyn Tr;
+    let _ = p as usize;
+    let _ = p as *const u8;
+    let _ = 0usize as *const S;
+    let _ = s as *const [u8] as *const [i8];
+    let _ = fp as usize;
+    let _ = f as *const ();
+    let _ = f as fn();
+    let _ = S as S;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn invalid_casts() {
+        check_diagnostics(
+            r#"
+//- minicore: sized
+enum E { A(u32) }
+struct S;
+trait Tr {}
+
+fn main(r: &S, p: *const S, s: *const [u8], d: *const dyn Tr) {
+    let _ = S as u32;
+          //^^^^^^^^ error: non-primitive cast: `S` as `u32`
+    let _ = E::A(0) as u32;
+          //^^^^^^^^^^^^^^ error: non-primitive cast: `E` as `u32`
+    let _ = 0u32 as E;
+          //^^^^^^^^^ error: non-primitive cast: `u32` as `E`
+    let _ = 1.0f32 as bool;
+          //^^^^^^^^^^^^^^ 💡 error: cannot cast `f32` as `bool`
+    let _ = 1i32 as char;
+          //^^^^^^^^^^^^ 💡 error: only `u8` can be cast as `char`, not `i32`
+    let _ = r as usize;
+          //^^^^^^^^^^ 💡 error: casting `&S` as `usize` is invalid: cast through a raw pointer first
+    let _ = r as *const u8;
+          //^^^^^^^^^^^^^^ 💡 error: casting `&S` as `*const u8` is invalid
+    let _ = r as *mut S;
+          //^^^^^^^^^^^ error: casting `&S` as `*mut S` is invalid
+    let _ = s as usize;
+          //^^^^^^^^^^ 💡 error: casting `*const [u8]` as `usize` is invalid: cast through a thin pointer first
+    let _ = 'a' as f32;
+          //^^^^^^^^^^ 💡 error: casting `char` as `f32` is invalid: cast through an integer first
+    let _ = p as *const [u8];
+          //^^^^^^^^^^^^^^^^ error: cannot cast thin pointer `*const S` to fat pointer `*const [u8]`
+    let _ = 0usize as *const str;
+          //^^^^^^^^^^^^^^^^^^^^ error: cannot cast `usize` to a fat pointer `*const str`
+    let _ = s as *const dyn Tr;
+          //^^^^^^^^^^^^^^^^^^ error: casting `*const [u8]` as `*const dyn Tr` is invalid: the pointer metadata differs
+    let _ = d as *const [u8];
+          //^^^^^^^^^^^^^^^^ error: casting `*const dyn Tr` as `*const [u8]` is invalid: the pointer metadata differs
+    let _ = p as f64;
+          //^^^^^^^^ error: casting `*const S` as `f64` is invalid
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn cast_through_raw_pointer() {
+        check_fix(
+            r#"
+fn f(x: &mut u32) -> usize {
+    x as usize$0
+}
+"#,
+            r#"
+fn f(x: &mut u32) -> usize {
+    x as *mut _ as usize
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn f(x: &u32) -> *const u8 {
+    x as *const u8$0
+}
+"#,
+            r#"
+fn f(x: &u32) -> *const u8 {
+    x as *const _ as *const u8
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn cast_through_thin_pointer_or_integer() {
+        check_fix(
+            r#"
+fn f(x: *const [u8]) -> usize {
+    x as usize$0
+}
+"#,
+            r#"
+fn f(x: *const [u8]) -> usize {
+    x as *const () as usize
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn f(x: bool) -> f32 {
+    x as f32$0
+}
+"#,
+            r#"
+fn f(x: bool) -> f32 {
+    x as u8 as f32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn compare_with_zero() {
+        check_fix(
+            r#"
+fn f(x: i32) -> bool {
+    !(x as bool$0)
+}
+"#,
+            r#"
+fn f(x: i32) -> bool {
+    !(x != 0)
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn f(x: f64, y: bool) -> bool {
+    y == x as bool$0
+}
+"#,
+            r#"
+fn f(x: f64, y: bool) -> bool {
+    y == (x != 0.0)
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn f(c: char) {
+    let _ = c as bool$0;
+}
+"#,
+            r#"
+fn f(c: char) {
+    let _ = c != '\0';
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn cast_through_u8() {
+        check_fix(
+            r#"
+fn f(x: u32) -> char {
+    x as char$0
+}
+"#,
+            r#"
+fn f(x: u32) -> char {
+    x as u8 as char
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn f(x: i64) -> char {
+    x as char$0
+}
+"#,
+            r#"
+fn f(x: i64) -> char {
+    x as u8 as char
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use hir::{PathResolution, Semantics};
+use ide_db::{
+    helpers::injection::{self, Language},
+    RootDatabase,
+};
+use syntax::{ast, AstToken, SyntaxToken};
+
+use crate::Diagnostic;
+
+// Diagnostic: invalid-regex
+//
+// This diagnostic is triggered if a string literal passed to `Regex::new` of
+// the `regex` crate or marked with a `// language=regex` comment is not a
+// valid regular expression.
+pub(crate) fn invalid_regex(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<()> {
+    let string = ast::String::cast(token.clone())?;
+    if injection::injected_language(&string)? != Language::Regex {
+        return None;
+    }
+    if let Some(path) = injection::injecting_call_path(&string) {
+        // Other crates, like `fancy_regex`, have a `Regex::new` with another
+        // syntax.
+        if !is_from_regex_crate(sema, &path) {
+            return None;
+        }
+    }
+    let chars = injection::string_chars(&string)?;
+    let (range, message) = injection::regex_error(&chars)?;
+    acc.push(Diagnostic::new(
+        "invalid-regex",
+        format!("Invalid regex: {}", message),
+        range + token.text_range().start(),
+    ));
+    Some(())
+}
+
+fn is_from_regex_crate(sema: &Semantics<RootDatabase>, path: &ast::Path) -> bool {
+    let db = sema.db;
+    let module = match sema.resolve_path(path) {
+        Some(PathResolution::Def(def)) => def.module(db),
+        Some(PathResolution::AssocItem(item)) => Some(item.module(db)),
+        _ => None,
+    };
+    module
+        .and_then(|it| it.krate().display_name(db))
+        .map_or(false, |name| name.canonical_name() == "regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn invalid_regex() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:regex
+use regex::Regex;
+fn main() {
+    Regex::new("(a|b");
+              //^ error: Invalid regex: unclosed group
+    regex::Regex::new(r"\d{3,2}");
+                        //^ error: Invalid regex: invalid repetition range
+    Regex::new(r"[a-z]+\.rs$");
+    Regex::new(r"(?x) [a-z]+ # a word (");
+}
+//- /regex.rs crate:regex
+pub struct Regex;
+impl Regex {
+    pub fn new(re: &str) -> Regex { Regex }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn other_regex_crates() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:fancy_regex
+use fancy_regex::Regex;
+struct RegexBuilder;
+impl RegexBuilder {
+    fn new(re: &str) -> RegexBuilder { RegexBuilder }
+}
+fn main() {
+    Regex::new(r"(\w+) \1");
+    RegexBuilder::new("a(?=b)");
+    Unresolved::Regex::new("(a|b");
+}
+//- /fancy_regex.rs crate:fancy_regex
+pub struct Regex;
+impl Regex {
+    pub fn new(re: &str) -> Regex { Regex }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn invalid_regex_with_language_comment() {
+        check_diagnostics(
+            r#"
+fn main() {
+    // language=regex
+    let re = "a(?=b)";
+             //^^ error: Invalid regex: look-around is not supported
+    let not_a_regex = "a(?=b)";
+}
+"#,
+        );
+    }
+}
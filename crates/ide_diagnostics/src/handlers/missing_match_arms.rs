@@ -18,7 +18,10 @@ pub(crate) fn missing_match_arms(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::check_diagnostics;
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config},
+        DiagnosticsConfig,
+    };
 
     fn check_diagnostics_no_bails(ra_fixture: &str) {
         cov_mark::check_count!(validate_match_bailed_out, 0);
@@ -339,7 +342,10 @@ fn main() {
     #[test]
     fn expr_diverges() {
         cov_mark::check_count!(validate_match_bailed_out, 2);
-        check_diagnostics(
+        let mut config = DiagnosticsConfig::default();
+        config.disabled.insert("unreachable-code".to_string());
+        check_diagnostics_with_config(
+            config,
             r#"
 enum Either { A, B }
 
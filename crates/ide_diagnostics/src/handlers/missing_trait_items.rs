@@ -0,0 +1,187 @@
+use hir::{db::AstDatabase, HasSource, InFile};
+use ide_db::{assists::Assist, source_change::SourceChange, traits::add_trait_assoc_items_to_impl};
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxNodePtr, TextRange};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: missing-trait-items
+//
+// This diagnostic is triggered if a trait impl doesn't define all the items
+// of the trait that don't have a default.
+//
+// Example:
+//
+// ```rust
+// trait Shape {
+//     fn area(&self) -> f32;
+// }
+// struct Circle;
+//
+// impl Shape for Circle {}
+// ```
+pub(crate) fn missing_trait_items(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::MissingTraitItems,
+) -> Diagnostic {
+    let db = ctx.sema.db;
+    let missing =
+        d.missing.iter().filter_map(|it| it.name(db)).map(|it| format!("`{}`", it)).join(", ");
+    Diagnostic::new(
+        "missing-trait-items",
+        format!("not all trait items implemented, missing: {}", missing),
+        display_range(ctx, d),
+    )
+    .with_fixes(fixes(ctx, d))
+}
+
+/// Highlights the trait in the impl header rather than the whole impl.
+fn display_range(ctx: &DiagnosticsContext<'_>, d: &hir::MissingTraitItems) -> TextRange {
+    let ptr = ctx
+        .sema
+        .db
+        .parse_or_expand(d.impl_.file_id)
+        .and_then(|root| d.impl_.value.to_node(&root).trait_())
+        .map_or_else(|| d.impl_.value.clone().into(), |it| SyntaxNodePtr::new(it.syntax()));
+    ctx.sema.diagnostics_display_range(InFile::new(d.impl_.file_id, ptr)).range
+}
+
+/// Adds the missing items like the `add_impl_missing_members` assist does.
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::MissingTraitItems) -> Option<Vec<Assist>> {
+    let db = ctx.sema.db;
+    if d.impl_.file_id.call_node(db).is_some() {
+        return None;
+    }
+    let file_id = d.impl_.file_id.original_file(db);
+    let impl_ = d.impl_.value.to_node(ctx.sema.parse(file_id).syntax());
+
+    let items: Vec<_> = d
+        .missing
+        .iter()
+        .filter_map(|it| match it {
+            hir::AssocItem::Function(it) => Some(ast::AssocItem::Fn(it.source(db)?.value)),
+            hir::AssocItem::Const(it) => Some(ast::AssocItem::Const(it.source(db)?.value)),
+            hir::AssocItem::TypeAlias(it) => Some(ast::AssocItem::TypeAlias(it.source(db)?.value)),
+        })
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+
+    let target_scope = ctx.sema.scope(impl_.syntax());
+    let range = impl_.syntax().text_range();
+    let (new_impl, _) =
+        add_trait_assoc_items_to_impl(&ctx.sema, items, d.trait_, impl_, target_scope);
+    let edit = TextEdit::replace(range, new_impl.to_string());
+    Some(vec![fix(
+        "add_impl_missing_members",
+        "Implement missing members",
+        SourceChange::from_text_edit(file_id, edit),
+        display_range(ctx, d),
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn missing_items() {
+        check_diagnostics(
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: u32;
+    fn area(&self) -> f32;
+    fn name(&self) -> &str { "shape" }
+}
+struct Circle;
+impl Shape for Circle {
+   //^^^^^ 💡 error: not all trait items implemented, missing: `Unit`, `area`
+    const SIDES: u32 = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_complete_impls() {
+        check_diagnostics(
+            r#"
+trait Shape {
+    type Unit = f32;
+    const SIDES: u32 = 0;
+    fn area(&self) -> f32;
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> f32 { 0.0 }
+}
+impl Shape for () {
+    fn area(&self) -> f32 { 0.0 }
+    fn name(&self) {}
+     //^^^^ error: method `name` is not a member of trait `Shape`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_unexpanded_macro_calls() {
+        check_diagnostics(
+            r#"
+trait Shape {
+    fn area(&self) -> f32;
+}
+struct Circle;
+impl Shape for Circle {
+    area_impl!();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_derives() {
+        check_diagnostics(
+            r#"
+//- minicore: derive, clone, copy
+#[derive(Clone, Copy)]
+struct S;
+"#,
+        );
+    }
+
+    #[test]
+    fn add_missing_items() {
+        check_fix(
+            r#"
+trait Shape {
+    const SIDES: u32;
+    fn area(&self) -> f32;
+    fn name(&self) -> &str { "shape" }
+}
+struct Circle;
+impl Shape$0 for Circle {
+    const SIDES: u32 = 0;
+}
+"#,
+            r#"
+trait Shape {
+    const SIDES: u32;
+    fn area(&self) -> f32;
+    fn name(&self) -> &str { "shape" }
+}
+struct Circle;
+impl Shape for Circle {
+    const SIDES: u32 = 0;
+
+    fn area(&self) -> f32 {
+        todo!()
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use hir::Semantics;
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use syntax::{ast, AstNode, AstToken, SyntaxElement, SyntaxKind, SyntaxNode, TextRange, T};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: non-fmt-panics
+//
+// This diagnostic is triggered when `panic!` is called with a single argument
+// which is not a format string, or with a single string literal containing
+// braces. Rust 2021 always interprets the first argument of `panic!` as a format
+// string.
+pub(crate) fn non_fmt_panics(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    let tt = macro_call.token_tree()?;
+    let mac = sema.resolve_macro_call(&macro_call)?;
+    if mac.name(sema.db)?.to_string() != "panic" {
+        return None;
+    }
+    let krate = mac.module(sema.db)?.krate().display_name(sema.db)?;
+    if !matches!(krate.to_string().as_str(), "core" | "std") {
+        return None;
+    }
+
+    // Strip the delimiters and a trailing comma, then make sure there is a
+    // single argument.
+    let mut args: Vec<SyntaxElement> =
+        tt.syntax().children_with_tokens().skip(1).filter(|it| !it.kind().is_trivia()).collect();
+    args.pop();
+    if args.last().map(|it| it.kind()) == Some(T![,]) {
+        args.pop();
+    }
+    if args.is_empty() || args.iter().any(|it| it.kind() == T![,]) {
+        return None;
+    }
+
+    let message = match &*args {
+        [SyntaxElement::Token(token)] if token.kind() == SyntaxKind::STRING => {
+            let string = ast::String::cast(token.clone())?;
+            let value = string.value()?;
+            if !value.contains(&['{', '}'][..]) {
+                return None;
+            }
+            "panic message contains braces"
+        }
+        _ => "panic message is not a string literal",
+    };
+
+    let range = TextRange::new(args.first()?.text_range().start(), args.last()?.text_range().end());
+    let edit = TextEdit::insert(range.start(), "\"{}\", ".to_string());
+    acc.push(
+        Diagnostic::new("non-fmt-panics", message, range)
+            .severity(Severity::WeakWarning)
+            .with_fixes(Some(vec![fix(
+                "add_panic_format_string",
+                "Add a \"{}\" format string",
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )])),
+    );
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { edition_migration: true, ..DiagnosticsConfig::default() }
+    }
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        check_diagnostics_with_config(config(), ra_fixture);
+    }
+
+    #[track_caller]
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix_with_config(config(), 0, ra_fixture_before, ra_fixture_after);
+    }
+
+    #[test]
+    fn non_fmt_panics() {
+        check_diagnostics(
+            r#"
+//- minicore: panic
+fn main() {
+    let s = "oops";
+    panic!(s);
+         //^ 💡 weak: panic message is not a string literal
+    panic!(123,);
+         //^^^ 💡 weak: panic message is not a string literal
+    panic!("{}");
+         //^^^^ 💡 weak: panic message contains braces
+    panic!("oops");
+    panic!("{}", s);
+    panic!();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_user_defined_panic() {
+        check_diagnostics(
+            r#"
+macro_rules! panic { ($($tt:tt)*) => {} }
+fn main() {
+    let s = "oops";
+    panic!(s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_in_edition_2021() {
+        check_diagnostics(
+            r#"
+//- minicore: panic
+//- /main.rs crate:main edition:2021
+fn main() {
+    let s = "oops";
+    panic!("{}", s);
+    panic!(s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_format_string() {
+        check_fix(
+            r#"
+//- minicore: panic
+fn main() {
+    let s = "oops";
+    panic!(s$0);
+}
+"#,
+            r#"
+fn main() {
+    let s = "oops";
+    panic!("{}", s);
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Diagnostic, DiagnosticsContext};
+
+// Diagnostic: possibly-uninitialized
+//
+// This diagnostic is triggered if a variable is used or borrowed before it's
+// certainly initialized.
+//
+// Example:
+//
+// ```rust
+// let a: i32;
+// if cond { a = 1; }
+// let b = a;
+// ```
+pub(crate) fn possibly_uninitialized(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::PossiblyUninitialized,
+) -> Diagnostic {
+    let kind = if d.is_borrow { "borrow" } else { "use" };
+    let name = d.local.name(ctx.sema.db).map_or_else(|| "_".to_string(), |it| it.to_string());
+    Diagnostic::new(
+        "possibly-uninitialized",
+        format!("{} of possibly-uninitialized variable: `{}`", kind, name),
+        ctx.sema.diagnostics_display_range(d.node.clone()).range,
+    )
+    .experimental()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn uninitialized() {
+        check_diagnostics(
+            r#"
+fn f(cond: bool) {
+    let a: i32;
+    let b = a;
+          //^ error: use of possibly-uninitialized variable: `a`
+    let c: i32;
+    if cond {
+        c = 1;
+    }
+    let r = &c;
+          //^^ error: borrow of possibly-uninitialized variable: `c`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_when_initialized_on_all_paths() {
+        check_diagnostics(
+            r#"
+fn f(cond: bool) -> i32 {
+    let a: i32;
+    if cond {
+        a = 1;
+    } else {
+        a = 2;
+    }
+    let b: i32;
+    loop {
+        if cond {
+            b = 1;
+            break;
+        }
+    }
+    let c: i32;
+    if cond {
+        c = 1;
+    } else {
+        return 0;
+    }
+    a + b + c
+}
+"#,
+        );
+    }
+}
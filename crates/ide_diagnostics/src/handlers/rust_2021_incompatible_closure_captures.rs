@@ -0,0 +1,326 @@
+use hir::{PathResolution, Semantics, Type};
+use ide_db::{base_db::FileId, helpers::FamousDefs, source_change::SourceChange, RootDatabase};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode, SyntaxNode, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: rust-2021-incompatible-closure-captures
+//
+// This diagnostic is triggered for closures which use a local only through its
+// fields and take ownership of them. Rust 2021 captures just the used fields,
+// so the rest of the local is dropped at a different point.
+pub(crate) fn rust_2021_incompatible_closure_captures(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let closure = ast::ClosureExpr::cast(node.clone())?;
+    let param_list = closure.param_list()?;
+    let body = closure.body()?;
+    let krate = sema.scope(closure.syntax()).module()?.krate();
+    let drop_trait = FamousDefs(sema, Some(krate)).core_ops_Drop()?;
+    let db = sema.db;
+
+    let closure_range = closure.syntax().text_range();
+    let uses = body
+        .syntax()
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter_map(|path_expr| {
+            let local = match sema.resolve_path(&path_expr.path()?)? {
+                PathResolution::Local(it) => it,
+                _ => return None,
+            };
+            let decl_range = local
+                .source(db)
+                .value
+                .either(|it| it.syntax().text_range(), |it| it.syntax().text_range());
+            if closure_range.contains_range(decl_range) {
+                return None;
+            }
+            Some((local, path_expr))
+        })
+        .into_group_map();
+
+    let is_move = closure.move_token().is_some();
+    let mut affected: Vec<String> = uses
+        .into_iter()
+        .filter(|(local, uses)| {
+            let ty = local.ty(db);
+            if ty.remove_ref().is_some() || ty.is_copy(db) || impls_drop(&ty, db, drop_trait) {
+                return false;
+            }
+            let mut used_fields = Vec::new();
+            let mut by_value = is_move;
+            for path_expr in uses {
+                let field_expr = match path_expr.syntax().parent().and_then(ast::FieldExpr::cast) {
+                    Some(it) => it,
+                    None => return false,
+                };
+                match field_expr.name_ref() {
+                    Some(name_ref) => used_fields.push(name_ref.text().to_string()),
+                    None => return false,
+                }
+                by_value |= is_moved_out(sema, field_expr);
+            }
+            by_value
+                && ty
+                    .fields(db)
+                    .into_iter()
+                    .map(|(field, ty)| (field.name(db).to_string(), ty))
+                    .chain(
+                        ty.tuple_fields(db)
+                            .into_iter()
+                            .enumerate()
+                            .map(|(idx, ty)| (idx.to_string(), ty)),
+                    )
+                    .any(|(name, ty)| {
+                        !used_fields.contains(&name) && needs_drop(&ty, db, drop_trait, 3)
+                    })
+        })
+        .filter_map(|(local, _)| Some(local.name(db)?.to_string()))
+        .collect();
+    if affected.is_empty() {
+        return None;
+    }
+    affected.sort();
+
+    let let_stmt = match &*affected {
+        [name] => format!("let _ = &{};", name),
+        names => format!("let _ = ({});", names.iter().map(|it| format!("&{}", it)).join(", ")),
+    };
+    let edit = match &body {
+        ast::Expr::BlockExpr(block) => {
+            let l_curly = block.l_curly_token()?;
+            let multiline = l_curly
+                .next_token()
+                .map_or(false, |it| it.kind().is_trivia() && it.text().contains('\n'));
+            let first = block
+                .statements()
+                .map(|it| it.syntax().clone())
+                .next()
+                .or_else(|| block.tail_expr().map(|it| it.syntax().clone()));
+            let insert = match first {
+                Some(first) if multiline => {
+                    format!("\n{}{}", IndentLevel::from_node(&first), let_stmt)
+                }
+                _ => format!(" {}", let_stmt),
+            };
+            TextEdit::insert(l_curly.text_range().end(), insert)
+        }
+        _ => TextEdit::replace(body.syntax().text_range(), format!("{{ {} {} }}", let_stmt, body)),
+    };
+
+    let range = TextRange::new(closure_range.start(), param_list.syntax().text_range().end());
+    acc.push(
+        Diagnostic::new(
+            "rust-2021-incompatible-closure-captures",
+            "changes to closure capture in Rust 2021 will affect drop order",
+            range,
+        )
+        .severity(Severity::WeakWarning)
+        .with_fixes(Some(vec![fix(
+            "capture_whole_variables",
+            &format!("Add a dummy `{}` to capture whole variables", let_stmt),
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )])),
+    );
+
+    Some(())
+}
+
+/// Checks whether the place denoted by the field projection is moved out of.
+fn is_moved_out(sema: &Semantics<RootDatabase>, field_expr: ast::FieldExpr) -> bool {
+    let mut place = ast::Expr::FieldExpr(field_expr);
+    while let Some(parent) = place.syntax().parent().and_then(ast::FieldExpr::cast) {
+        place = ast::Expr::FieldExpr(parent);
+    }
+    let parent = match place.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if ast::RefExpr::can_cast(parent.kind()) || ast::MethodCallExpr::can_cast(parent.kind()) {
+        return false;
+    }
+    if let Some(bin_expr) = ast::BinExpr::cast(parent) {
+        if bin_expr.lhs().as_ref() == Some(&place)
+            && bin_expr.op_kind().map_or(false, |op| op.is_assignment())
+        {
+            return false;
+        }
+    }
+    sema.type_of_expr(&place).map_or(false, |ty| !ty.is_copy(sema.db))
+}
+
+fn impls_drop(ty: &Type, db: &RootDatabase, drop_trait: hir::Trait) -> bool {
+    ty.impls_trait(db, drop_trait, &[])
+}
+
+fn needs_drop(ty: &Type, db: &RootDatabase, drop_trait: hir::Trait, depth: usize) -> bool {
+    if impls_drop(ty, db, drop_trait) {
+        return true;
+    }
+    if depth == 0 {
+        return false;
+    }
+    ty.fields(db)
+        .into_iter()
+        .map(|(_, ty)| ty)
+        .chain(ty.tuple_fields(db))
+        .any(|ty| needs_drop(&ty, db, drop_trait, depth - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { edition_migration: true, ..DiagnosticsConfig::default() }
+    }
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        check_diagnostics_with_config(config(), ra_fixture);
+    }
+
+    #[track_caller]
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix_with_config(config(), 0, ra_fixture_before, ra_fixture_after);
+    }
+
+    #[test]
+    fn move_closure_captures_single_field() {
+        check_diagnostics(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let c = move || { let _a = p.a; };
+          //^^^^^^^ 💡 weak: changes to closure capture in Rust 2021 will affect drop order
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_when_drop_order_is_unaffected() {
+        check_diagnostics(
+            r#"
+//- minicore: drop, copy
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+struct Ints { a: i32, b: i32 }
+struct WithDrop { a: Guard, b: Guard }
+impl Drop for WithDrop { fn drop(&mut self) {} }
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let c = || { let _a = &p.a; };
+    let c = move || { let _p = p; };
+    let i = Ints { a: 0, b: 1 };
+    let c = move || { let _a = i.a; };
+    let w = WithDrop { a: Guard, b: Guard };
+    let c = move || { let _a = &w.a; };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_in_edition_2021() {
+        check_diagnostics(
+            r#"
+//- minicore: drop
+//- /main.rs crate:main edition:2021
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let c = move || { let _a = p.a; };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn capture_whole_variable_in_block() {
+        check_fix(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let c = move$0 || {
+        let _a = p.a;
+    };
+}
+"#,
+            r#"
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let c = move || {
+        let _ = &p;
+        let _a = p.a;
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn capture_whole_variables_in_expression() {
+        check_fix(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+
+fn consume(_: Guard, _: Guard) {}
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let q = (Guard, Guard);
+    let c = $0|| consume(p.a, q.1);
+}
+"#,
+            r#"
+struct Guard;
+impl Drop for Guard { fn drop(&mut self) {} }
+struct Pair { a: Guard, b: Guard }
+
+fn consume(_: Guard, _: Guard) {}
+
+fn main() {
+    let p = Pair { a: Guard, b: Guard };
+    let q = (Guard, Guard);
+    let c = || { let _ = (&p, &q); consume(p.a, q.1) };
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use ide_db::{base_db::FileId, source_change::SourceChange};
+use syntax::{SyntaxKind, SyntaxToken, T};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: rust-2021-prefixes-incompatible-syntax
+//
+// This diagnostic is triggered for identifiers immediately followed by a `#`, a
+// string, a character or a lifetime. Rust 2021 reserves such identifiers as
+// prefixes, so they will no longer be lexed as separate tokens.
+pub(crate) fn rust_2021_prefixes_incompatible_syntax(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    token: &SyntaxToken,
+) -> Option<()> {
+    if !(token.kind() == SyntaxKind::IDENT || token.kind().is_keyword()) {
+        return None;
+    }
+    if token.text().starts_with("r#") {
+        return None;
+    }
+    let next = token.next_token()?;
+    if !matches!(
+        next.kind(),
+        T![#] | SyntaxKind::STRING | SyntaxKind::CHAR | SyntaxKind::LIFETIME_IDENT
+    ) {
+        return None;
+    }
+
+    let range = token.text_range();
+    let edit = TextEdit::insert(range.end(), " ".to_string());
+    acc.push(
+        Diagnostic::new(
+            "rust-2021-prefixes-incompatible-syntax",
+            format!("prefix `{}` is unknown in Rust 2021", token.text()),
+            range,
+        )
+        .severity(Severity::WeakWarning)
+        .with_fixes(Some(vec![fix(
+            "insert_whitespace_after_prefix",
+            "Insert whitespace to avoid parsing as a prefix",
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )])),
+    );
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { edition_migration: true, ..DiagnosticsConfig::default() }
+    }
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        check_diagnostics_with_config(config(), ra_fixture);
+    }
+
+    #[track_caller]
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix_with_config(config(), 0, ra_fixture_before, ra_fixture_after);
+    }
+
+    #[test]
+    fn reserved_prefixes_in_macro_input() {
+        check_diagnostics(
+            r#"
+macro_rules! m { ($($tt:tt)*) => {} }
+fn main() {
+    m!(z#x);
+     //^ 💡 weak: prefix `z` is unknown in Rust 2021
+    m!(f"hello");
+     //^ 💡 weak: prefix `f` is unknown in Rust 2021
+    m!(match'a');
+     //^^^^^ 💡 weak: prefix `match` is unknown in Rust 2021
+    m!(z #x r#match"" r"raw" b'b');
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn insert_whitespace() {
+        check_fix(
+            r#"
+macro_rules! m { ($($tt:tt)*) => {} }
+fn main() {
+    m!(z$0#x);
+}
+"#,
+            r#"
+macro_rules! m { ($($tt:tt)*) => {} }
+fn main() {
+    m!(z #x);
+}
+"#,
+        );
+    }
+}
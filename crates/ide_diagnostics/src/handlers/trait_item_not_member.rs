@@ -0,0 +1,80 @@
+use hir::{db::AstDatabase, InFile};
+use syntax::{
+    ast::{self, NameOwner},
+    AstNode, SyntaxNodePtr,
+};
+
+use crate::{Diagnostic, DiagnosticsContext};
+
+// Diagnostic: trait-item-not-member
+//
+// This diagnostic is triggered if a trait impl defines an item that the trait
+// doesn't declare.
+//
+// Example:
+//
+// ```rust
+// trait Shape {}
+// struct Circle;
+//
+// impl Shape for Circle {
+//     fn area(&self) -> f32 { 0.0 }
+// }
+// ```
+pub(crate) fn trait_item_not_member(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::TraitItemNotMember,
+) -> Diagnostic {
+    let item = ctx.sema.db.parse_or_expand(d.item.file_id).map(|root| d.item.value.to_node(&root));
+    let (kind, name) = match &item {
+        Some(ast::AssocItem::Fn(it)) => ("method", it.name()),
+        Some(ast::AssocItem::Const(it)) => ("const", it.name()),
+        Some(ast::AssocItem::TypeAlias(it)) => ("type", it.name()),
+        Some(ast::AssocItem::MacroCall(_)) | None => ("item", None),
+    };
+    let ptr =
+        name.map_or_else(|| d.item.value.clone().into(), |it| SyntaxNodePtr::new(it.syntax()));
+    Diagnostic::new(
+        "trait-item-not-member",
+        format!("{} `{}` is not a member of trait `{}`", kind, d.name, d.trait_.name(ctx.sema.db)),
+        ctx.sema.diagnostics_display_range(InFile::new(d.item.file_id, ptr)).range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn items_not_in_trait() {
+        check_diagnostics(
+            r#"
+trait Shape {
+    fn area(&self) -> f32;
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> f32 { 0.0 }
+    fn radius(&self) -> f32 { 0.0 }
+     //^^^^^^ error: method `radius` is not a member of trait `Shape`
+    const PI: f32 = 3.14;
+        //^^ error: const `PI` is not a member of trait `Shape`
+    type Unit = f32;
+       //^^^^ error: type `Unit` is not a member of trait `Shape`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_inherent_impls() {
+        check_diagnostics(
+            r#"
+struct Circle;
+impl Circle {
+    fn radius(&self) -> f32 { 0.0 }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use hir::db::AstDatabase;
+use syntax::{ast, AstNode, SyntaxNodePtr};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: unreachable-code
+//
+// This diagnostic is triggered if a statement or expression can't be reached,
+// because the code before it always returns, breaks or panics.
+//
+// Example:
+//
+// ```rust
+// fn foo() -> i32 {
+//     return 0;
+//     1
+// }
+// ```
+pub(crate) fn unreachable_code(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnreachableCode,
+) -> Diagnostic {
+    // Highlight the whole statement, including `let` and the semicolon.
+    let stmt = ctx
+        .sema
+        .db
+        .parse_or_expand(d.expr.file_id)
+        .and_then(|root| d.expr.value.to_node(&root).syntax().parent())
+        .and_then(ast::Stmt::cast);
+    let (message, ptr) = match stmt {
+        Some(stmt) => ("unreachable statement", SyntaxNodePtr::new(stmt.syntax())),
+        None => ("unreachable expression", d.expr.value.clone().into()),
+    };
+    Diagnostic::new(
+        "unreachable-code",
+        message,
+        ctx.sema.diagnostics_display_range(d.expr.with_value(ptr)).range,
+    )
+    .severity(Severity::WeakWarning)
+    .experimental()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn unreachable_after_return() {
+        check_diagnostics(
+            r#"
+fn f() -> i32 {
+    return 0;
+    let a = 1;
+  //^^^^^^^^^^ weak: unreachable statement
+    a
+}
+fn g() -> i32 {
+    return 0;
+    1
+  //^ weak: unreachable expression
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unreachable_after_diverging_expressions() {
+        check_diagnostics(
+            r#"
+fn never() -> ! { loop {} }
+fn f(cond: bool) {
+    loop {
+        if cond {
+            break;
+            f(cond);
+          //^^^^^^^^ weak: unreachable statement
+        }
+        continue;
+    }
+}
+fn g() {
+    never();
+    g();
+  //^^^^ weak: unreachable statement
+}
+fn h(cond: bool) {
+    if cond { return } else { return }
+    h(cond);
+  //^^^^^^^^ weak: unreachable statement
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_reachable_code() {
+        check_diagnostics(
+            r#"
+fn f(cond: bool) -> i32 {
+    if cond {
+        return 1;
+    }
+    let mut i = 0;
+    while cond {
+        if i == 1 {
+            break;
+        }
+        i += 1;
+    }
+    'outer: loop {
+        loop {
+            break 'outer;
+        }
+    }
+    match cond {
+        true => return 2,
+        false => {}
+    }
+    i
+}
+fn g() -> i32 {
+    loop {
+        return 1;
+    }
+}
+"#,
+        );
+    }
+}
@@ -99,6 +99,7 @@ mod baz {}
                                             is_snippet: false,
                                         },
                                     ),
+                                    command: None,
                                 },
                             ],
                         ),
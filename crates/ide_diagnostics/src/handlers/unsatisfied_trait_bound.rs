@@ -0,0 +1,320 @@
+use hir::{GenericDef, HasSource, InFile, MacroKind, ScopeDef};
+use ide_db::{
+    assists::Assist, base_db::SourceDatabaseExt, source_change::SourceChange, RootDatabase,
+};
+use syntax::{
+    algo,
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner},
+    SyntaxKind::{COMMENT, IDENT, WHITESPACE},
+    TextSize,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: unsatisfied-trait-bound
+//
+// This diagnostic is triggered if a function is called with arguments whose
+// types don't implement the traits required by its where clauses.
+//
+// Example:
+//
+// ```rust
+// fn takes_clone<T: Clone>(_: T) {}
+// struct S;
+//
+// takes_clone(S);
+// ```
+pub(crate) fn unsatisfied_trait_bound(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnsatisfiedTraitBound,
+) -> Option<Diagnostic> {
+    let db = ctx.sema.db;
+    // The impl is likely generated by a derive or an attribute macro we
+    // couldn't expand.
+    if let Some(src) = d.ty.as_adt().and_then(|it| adt_source(db, it)) {
+        let trait_name = d.trait_.name(db).to_string();
+        let is_derived = derive_args(&src.value).map_or(false, |args| {
+            args.syntax()
+                .children_with_tokens()
+                .any(|it| it.kind() == IDENT && it.to_string() == trait_name)
+        });
+        if is_derived || has_attr_macro(&src.value) {
+            return None;
+        }
+    }
+
+    let required_by = match d.origin {
+        GenericDef::Function(it) => Some(it.name(db)),
+        GenericDef::Adt(it) => Some(it.name(db)),
+        GenericDef::Trait(it) => Some(it.name(db)),
+        GenericDef::TypeAlias(it) => Some(it.name(db)),
+        GenericDef::Variant(it) => Some(it.name(db)),
+        GenericDef::Const(it) => it.name(db),
+        GenericDef::Impl(_) => None,
+    };
+    let message = match required_by {
+        Some(name) => format!(
+            "the trait bound `{}` is not satisfied (required by a bound on `{}`)",
+            d.bound, name
+        ),
+        None => format!("the trait bound `{}` is not satisfied", d.bound),
+    };
+    Some(
+        Diagnostic::new(
+            "unsatisfied-trait-bound",
+            message,
+            ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range,
+        )
+        .with_fixes(fixes(ctx, d))
+        .experimental(),
+    )
+}
+
+/// Adds the trait to the derives of the type when a derive macro of the same
+/// name is in scope, and the type is defined in the workspace.
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::UnsatisfiedTraitBound) -> Option<Vec<Assist>> {
+    let db = ctx.sema.db;
+    let src = adt_source(db, d.ty.as_adt()?)?;
+    if src.file_id.call_node(db).is_some() {
+        return None;
+    }
+    let file_id = src.file_id.original_file(db);
+    if db.source_root(db.file_source_root(file_id)).is_library {
+        return None;
+    }
+    let file = ctx.sema.parse(file_id);
+    let adt: ast::Adt = algo::find_node_at_range(file.syntax(), src.value.syntax().text_range())?;
+
+    let trait_name = d.trait_.name(db);
+    let mut is_derivable = false;
+    ctx.sema.scope(adt.syntax()).process_all_names(&mut |name, def| {
+        if let ScopeDef::MacroDef(mac) = def {
+            if name == trait_name && mac.kind() == MacroKind::Derive {
+                is_derivable = true;
+            }
+        }
+    });
+    if !is_derivable {
+        return None;
+    }
+
+    let edit = match derive_args(&adt) {
+        Some(args) => {
+            let offset = args.syntax().text_range().end() - TextSize::of(')');
+            let has_derives = args.syntax().children_with_tokens().count() > 2;
+            let derive =
+                if has_derives { format!(", {}", trait_name) } else { trait_name.to_string() };
+            TextEdit::insert(offset, derive)
+        }
+        None => {
+            let offset = adt
+                .syntax()
+                .children_with_tokens()
+                .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+                .text_range()
+                .start();
+            let indent = IndentLevel::from_node(adt.syntax());
+            TextEdit::insert(offset, format!("#[derive({})]\n{}", trait_name, indent))
+        }
+    };
+    let source_change = SourceChange::from_text_edit(file_id, edit);
+    let label = format!("Add `#[derive({})]`", trait_name);
+    let target = ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range;
+    Some(vec![fix("add_derive", &label, source_change, target)])
+}
+
+fn adt_source(db: &RootDatabase, adt: hir::Adt) -> Option<InFile<ast::Adt>> {
+    let src = match adt {
+        hir::Adt::Struct(it) => it.source(db)?.map(ast::Adt::Struct),
+        hir::Adt::Enum(it) => it.source(db)?.map(ast::Adt::Enum),
+        hir::Adt::Union(it) => it.source(db)?.map(ast::Adt::Union),
+    };
+    Some(src)
+}
+
+fn derive_args(adt: &ast::Adt) -> Option<ast::TokenTree> {
+    adt.attrs()
+        .filter_map(|it| it.as_simple_call())
+        .find(|(name, _)| name == "derive")
+        .map(|(_, args)| args)
+}
+
+/// Attributes with qualified paths, like `#[salsa::database]`, are most likely
+/// attribute macros, except for the tool attributes.
+fn has_attr_macro(adt: &ast::Adt) -> bool {
+    adt.attrs().filter_map(|it| it.path()).any(|path| match path.first_segment() {
+        Some(first) if path.qualifier().is_some() => {
+            !matches!(first.to_string().as_str(), "rustfmt" | "clippy")
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_no_fix};
+
+    #[test]
+    fn unsatisfied_bound_on_function() {
+        check_diagnostics(
+            r#"
+trait Serialize {}
+struct S;
+fn to_string<T: Serialize>(_: T) {}
+fn main() {
+    to_string(S);
+  //^^^^^^^^^^^^ error: the trait bound `S: Serialize` is not satisfied (required by a bound on `to_string`)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unsatisfied_bound_on_method() {
+        check_diagnostics(
+            r#"
+trait Serialize {}
+struct S;
+struct Writer;
+impl Writer {
+    fn write<T>(&self, _: T) where T: Serialize {}
+}
+fn main() {
+    Writer.write(S);
+  //^^^^^^^^^^^^^^^ error: the trait bound `S: Serialize` is not satisfied (required by a bound on `write`)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn satisfied_bounds() {
+        check_diagnostics(
+            r#"
+trait Serialize {}
+struct S;
+impl Serialize for S {}
+impl<T: Serialize> Serialize for Vec<T> {}
+struct Vec<T>(T);
+fn to_string<T: Serialize>(_: T) {}
+fn generic<T: Serialize>(t: T) {
+    to_string(t);
+}
+fn main() {
+    to_string(S);
+    to_string(Vec(S));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_unknown_types() {
+        check_diagnostics(
+            r#"
+trait Serialize {}
+fn to_string<T: Serialize>(_: T) {}
+fn main() {
+    to_string(unresolved());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_unexpanded_derive() {
+        check_diagnostics(
+            r#"
+trait Serialize {}
+#[derive(Serialize)]
+struct S;
+fn to_string<T: Serialize>(_: T) {}
+fn main() {
+    to_string(S);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_derive() {
+        check_fix(
+            r#"
+//- minicore: derive, clone
+fn dup<T: Clone>(_: T) {}
+mod m {
+    /// Docs
+    pub struct S;
+}
+fn main() {
+    dup(m::S$0);
+}
+"#,
+            r#"
+fn dup<T: Clone>(_: T) {}
+mod m {
+    /// Docs
+    #[derive(Clone)]
+    pub struct S;
+}
+fn main() {
+    dup(m::S);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_derive_to_existing_derives() {
+        check_fix(
+            r#"
+//- minicore: derive, clone, copy
+fn dup<T: Clone>(_: T) {}
+#[derive(Copy)]
+struct S;
+fn main() {
+    dup(S$0);
+}
+"#,
+            r#"
+fn dup<T: Clone>(_: T) {}
+#[derive(Copy, Clone)]
+struct S;
+fn main() {
+    dup(S);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_fix_for_non_derivable_trait() {
+        check_no_fix(
+            r#"
+trait Serialize {}
+struct S;
+fn to_string<T: Serialize>(_: T) {}
+fn main() {
+    to_string(S$0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_attribute_macros() {
+        check_diagnostics(
+            r#"
+trait Database {}
+#[salsa::database(Storage)]
+#[rustfmt::skip]
+struct RootDatabase;
+fn snapshot<DB: Database>(_: DB) {}
+fn main() {
+    snapshot(RootDatabase);
+}
+"#,
+        );
+    }
+}
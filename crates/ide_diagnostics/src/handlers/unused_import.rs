@@ -0,0 +1,204 @@
+use hir::Semantics;
+use ide_db::{
+    base_db::FileId,
+    helpers::unused_imports::{remove_use_tree, unused_imports},
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{algo, ast, AstNode, SyntaxNodePtr};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: unused-import
+//
+// This diagnostic is triggered if an item imported by a private `use` item is
+// never referred to in the scope of the import.
+pub(crate) fn unused_import(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+) {
+    let unused = unused_imports(sema, file_id);
+    if unused.is_empty() {
+        return;
+    }
+    let source_file = sema.parse(file_id);
+    let remove_all = SourceChange::from_text_edit(file_id, remove_trees(&source_file, &unused));
+    for tree in &unused {
+        let range = tree.syntax().text_range();
+        let remove_one = remove_trees(&source_file, std::slice::from_ref(tree));
+        let mut fixes = vec![fix(
+            "remove_unused_import",
+            "Remove unused import",
+            SourceChange::from_text_edit(file_id, remove_one),
+            range,
+        )];
+        if unused.len() > 1 {
+            fixes.push(fix(
+                "remove_unused_imports",
+                "Remove all unused imports",
+                remove_all.clone(),
+                range,
+            ));
+        }
+        acc.push(
+            Diagnostic::new("unused-import", format!("unused import: `{}`", tree), range)
+                .severity(Severity::WeakWarning)
+                .with_fixes(Some(fixes)),
+        );
+    }
+}
+
+fn remove_trees(source_file: &ast::SourceFile, trees: &[ast::UseTree]) -> TextEdit {
+    let new_file = source_file.clone_for_update();
+    let trees: Vec<_> = trees
+        .iter()
+        .filter_map(|tree| {
+            ast::UseTree::cast(SyntaxNodePtr::new(tree.syntax()).to_node(new_file.syntax()))
+        })
+        .collect();
+    for tree in &trees {
+        remove_use_tree(tree);
+    }
+    let mut builder = TextEdit::builder();
+    algo::diff(source_file.syntax(), new_file.syntax()).into_text_edit(&mut builder);
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    #[track_caller]
+    fn check_diagnostics(ra_fixture: &str) {
+        check_diagnostics_with_config(DiagnosticsConfig::default(), ra_fixture);
+    }
+
+    #[track_caller]
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix_with_config(
+            DiagnosticsConfig::default(),
+            0,
+            ra_fixture_before,
+            ra_fixture_after,
+        );
+    }
+
+    #[track_caller]
+    fn check_fixes(ra_fixture_before: &str, ra_fixtures_after: Vec<&str>) {
+        for (i, ra_fixture_after) in ra_fixtures_after.iter().enumerate() {
+            check_nth_fix_with_config(
+                DiagnosticsConfig::default(),
+                i,
+                ra_fixture_before,
+                ra_fixture_after,
+            );
+        }
+    }
+
+    #[test]
+    fn unused_imports() {
+        check_diagnostics(
+            r#"
+mod m {
+    pub struct A;
+    pub struct B;
+    pub trait Tr { fn method(&self) {} }
+    impl Tr for A {}
+}
+use m::{A, B};
+         //^ 💡 weak: unused import: `B`
+use m::Tr;
+
+fn f() {
+    use m::B;
+      //^^^^ 💡 weak: unused import: `m::B`
+    A.method();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_unused_imports_used_through_macros_or_child_modules() {
+        check_diagnostics(
+            r#"
+//- /main.rs
+mod fmt {
+    pub trait Write { fn write_str(&mut self, s: &str); }
+    pub struct Buf;
+    impl Write for Buf { fn write_str(&mut self, _: &str) {} }
+    pub struct Config;
+}
+use fmt::{Buf, Config, Write};
+macro_rules! write { ($dst:expr, $s:expr) => { $dst.write_str($s) } }
+fn f(mut b: Buf) { write!(b, "x"); }
+mod child {
+    use super::Config;
+    fn g(_: Config) {}
+}
+mod tests;
+//- /tests.rs
+use super::*;
+fn h(_: Buf) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unused_import() {
+        check_fix(
+            r#"
+mod m { pub struct A; pub struct B; }
+use m::{A, B$0};
+fn f(_: A) {}
+"#,
+            r#"
+mod m { pub struct A; pub struct B; }
+use m::{A};
+fn f(_: A) {}
+"#,
+        );
+        check_fix(
+            r#"
+mod m { pub struct A; pub struct B; }
+use m::{A, B};
+use m::B as $0C;
+fn f(_: A, _: B) {}
+"#,
+            r#"
+mod m { pub struct A; pub struct B; }
+use m::{A, B};
+fn f(_: A, _: B) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_all_unused_imports() {
+        check_fixes(
+            r#"
+mod m { pub struct A; pub struct B; pub mod n { pub struct C; } }
+use m::{A, n::{self, C}};
+use m::B$0;
+fn f(_: n::C) {}
+"#,
+            vec![
+                r#"
+mod m { pub struct A; pub struct B; pub mod n { pub struct C; } }
+use m::{A, n::{self, C}};
+fn f(_: n::C) {}
+"#,
+                r#"
+mod m { pub struct A; pub struct B; pub mod n { pub struct C; } }
+use m::{n::{self}};
+fn f(_: n::C) {}
+"#,
+            ],
+        );
+    }
+}
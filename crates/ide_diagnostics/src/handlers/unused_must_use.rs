@@ -0,0 +1,418 @@
+use hir::{Adt, HasAttrs, HirDisplay, MustUse, PathResolution, Semantics, Type};
+use ide_db::{
+    assists::Assist, base_db::FileId, helpers::FamousDefs, source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: unused-must-use
+//
+// This diagnostic is triggered if an expression statement discards a value
+// whose type, or the function returning it, is marked `#[must_use]`, like a
+// `Result`, a future that is never `.await`ed or an iterator adapter that is
+// never consumed.
+pub(crate) fn unused_must_use(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let stmt = ast::ExprStmt::cast(node.clone())?;
+    stmt.semicolon_token()?;
+    let expr = stmt.expr()?;
+    if let ast::Expr::MacroCall(_) = expr {
+        return None;
+    }
+    let ty = sema.type_of_expr(&expr)?;
+
+    let called_fn = called_function(sema, &expr)
+        .and_then(|func| Some((func.name(sema.db), func.must_use(sema.db)?)));
+    let (mut message, must_use) = match called_fn {
+        Some((name, must_use)) => {
+            (format!("unused return value of `{}` that must be used", name), must_use)
+        }
+        None => {
+            let must_use = ty.must_use(sema.db)?;
+            (format!("unused `{}` that must be used", ty.display(sema.db)), must_use)
+        }
+    };
+    if is_must_use_allowed(&stmt) {
+        return None;
+    }
+    if let MustUse { note: Some(note) } = &must_use {
+        message.push_str(": ");
+        message.push_str(note);
+    }
+
+    acc.push(
+        Diagnostic::new("unused-must-use", message, expr.syntax().text_range())
+            .severity(Severity::WeakWarning)
+            .with_fixes(fixes(sema, file_id, &expr, &ty)),
+    );
+
+    Some(())
+}
+
+fn called_function(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> Option<hir::Function> {
+    match expr {
+        ast::Expr::MethodCallExpr(call) => sema.resolve_method_call(call),
+        ast::Expr::CallExpr(call) => {
+            let path = match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            match sema.resolve_path(&path)? {
+                PathResolution::Def(hir::ModuleDef::Function(func)) => Some(func),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_must_use_allowed(stmt: &ast::ExprStmt) -> bool {
+    stmt.syntax().ancestors().any(|node| {
+        node.children().filter_map(ast::Attr::cast).any(|attr| {
+            attr.simple_name().as_deref() == Some("allow")
+                && attr.token_tree().map_or(false, |tt| {
+                    tt.syntax()
+                        .children_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .any(|token| matches!(token.text(), "unused_must_use" | "unused"))
+                })
+        })
+    })
+}
+
+fn fixes(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    expr: &ast::Expr,
+    ty: &Type,
+) -> Option<Vec<Assist>> {
+    let db = sema.db;
+    let range = expr.syntax().text_range();
+    let body = enclosing_body(expr);
+    let mut res = Vec::new();
+
+    // `.await` and `?` bind tighter than most expressions.
+    let postfix = |suffix: &str| {
+        let mut edit = TextEdit::builder();
+        if needs_parens_for_postfix(expr) {
+            edit.insert(range.start(), "(".to_string());
+            edit.insert(range.end(), format!("){}", suffix));
+        } else {
+            edit.insert(range.end(), suffix.to_string());
+        }
+        SourceChange::from_text_edit(file_id, edit.finish())
+    };
+
+    let in_async = match &body {
+        Some(Body::Fn(it)) => it.async_token().is_some(),
+        Some(Body::Effect(it)) => it.async_token().is_some(),
+        _ => false,
+    };
+    if in_async && ty.impls_future(db) {
+        res.push(fix("add_await", "Add `.await`", postfix(".await"), range));
+    }
+
+    if let Some(Body::Fn(func)) = &body {
+        let krate = sema.scope(expr.syntax()).module()?.krate();
+        let famous_defs = FamousDefs(sema, Some(krate));
+        if let Some(result) = famous_defs.core_result_Result() {
+            let ret_ty =
+                func.ret_type().and_then(|it| it.ty()).and_then(|it| sema.resolve_type(&it));
+            let error_ty = |ty: &Type| match ty.as_adt() {
+                Some(Adt::Enum(it)) if it == result => ty.type_arguments().nth(1),
+                _ => None,
+            };
+            if let (Some(expr_err), Some(ret_err)) =
+                (error_ty(ty), ret_ty.as_ref().and_then(error_ty))
+            {
+                let converts = expr_err.could_unify_with(db, &ret_err)
+                    || famous_defs
+                        .core_convert_From()
+                        .map_or(false, |from| ret_err.impls_trait(db, from, &[expr_err]));
+                if converts {
+                    res.push(fix(
+                        "propagate_error",
+                        "Propagate the error with `?`",
+                        postfix("?"),
+                        range,
+                    ));
+                }
+            }
+        }
+    }
+
+    let edit = TextEdit::insert(range.start(), "let _ = ".to_string());
+    res.push(fix(
+        "add_let_underscore",
+        "Explicitly discard the value with `let _ =`",
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    ));
+
+    Some(res)
+}
+
+/// The function, closure or effect block whose `?` and `.await` apply to an
+/// expression.
+enum Body {
+    Fn(ast::Fn),
+    Closure,
+    Effect(ast::EffectExpr),
+}
+
+fn enclosing_body(expr: &ast::Expr) -> Option<Body> {
+    expr.syntax().ancestors().skip(1).find_map(|node| {
+        if let Some(it) = ast::Fn::cast(node.clone()) {
+            return Some(Body::Fn(it));
+        }
+        if ast::ClosureExpr::can_cast(node.kind()) {
+            return Some(Body::Closure);
+        }
+        match ast::EffectExpr::cast(node)? {
+            it if it.async_token().is_some() || it.try_token().is_some() => Some(Body::Effect(it)),
+            _ => None,
+        }
+    })
+}
+
+fn needs_parens_for_postfix(expr: &ast::Expr) -> bool {
+    !matches!(
+        expr,
+        ast::Expr::AwaitExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::Literal(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::PathExpr(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::TupleExpr(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn unused_must_use_values() {
+        check_diagnostics(
+            r#"
+//- minicore: result, future, iterators
+struct Error;
+#[must_use]
+struct Guard;
+#[must_use = "the lock is released when the guard is dropped"]
+fn lock() -> Guard { Guard }
+#[must_use]
+fn len() -> usize { 0 }
+fn fallible() -> Result<(), Error> { Ok(()) }
+async fn task() {}
+fn iter() -> core::iter::Take<core::iter::Repeat<u32>> { loop {} }
+fn unit() {}
+
+fn main() {
+    lock();
+  //^^^^^^ 💡 weak: unused return value of `lock` that must be used: the lock is released when the guard is dropped
+    len();
+  //^^^^^ 💡 weak: unused return value of `len` that must be used
+    Guard;
+  //^^^^^ 💡 weak: unused `Guard` that must be used
+    fallible();
+  //^^^^^^^^^^ 💡 weak: unused `Result<(), Error>` that must be used: this `Result` may be an `Err` variant, which should be handled
+    task();
+  //^^^^^^ 💡 weak: unused `impl Future<Output = ()>` that must be used: futures do nothing unless you `.await` or poll them
+    iter();
+  //^^^^^^ 💡 weak: unused `Take<Repeat<u32>>` that must be used: iterators are lazy and do nothing unless consumed
+    unit();
+    let _ = lock();
+    let _guard = fallible();
+    if true { len() } else { 0 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn allowed_unused_must_use() {
+        check_diagnostics(
+            r#"
+//- minicore: result
+fn fallible() -> Result<(), ()> { Ok(()) }
+
+#[allow(unused_must_use)]
+fn f() {
+    fallible();
+}
+fn g() {
+    #[allow(unused)]
+    fallible();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_let_underscore() {
+        check_fix(
+            r#"
+//- minicore: result
+fn fallible() -> Result<(), ()> { Ok(()) }
+fn main() {
+    fallible()$0;
+}
+"#,
+            r#"
+fn fallible() -> Result<(), ()> { Ok(()) }
+fn main() {
+    let _ = fallible();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn propagate_error() {
+        check_fix(
+            r#"
+//- minicore: result
+fn fallible() -> Result<(), ()> { Ok(()) }
+fn f() -> Result<u32, ()> {
+    fallible()$0;
+    Ok(0)
+}
+"#,
+            r#"
+fn fallible() -> Result<(), ()> { Ok(()) }
+fn f() -> Result<u32, ()> {
+    fallible()?;
+    Ok(0)
+}
+"#,
+        );
+        check_fix(
+            r#"
+//- minicore: result, from
+struct Error;
+struct OtherError;
+impl From<Error> for OtherError { fn from(_: Error) -> Self { OtherError } }
+fn fallible() -> Result<(), Error> { Ok(()) }
+fn f() -> Result<(), OtherError> {
+    fallible()$0;
+    Ok(())
+}
+"#,
+            r#"
+struct Error;
+struct OtherError;
+impl From<Error> for OtherError { fn from(_: Error) -> Self { OtherError } }
+fn fallible() -> Result<(), Error> { Ok(()) }
+fn f() -> Result<(), OtherError> {
+    fallible()?;
+    Ok(())
+}
+"#,
+        );
+        // The error types are unrelated, so only `let _ =` is offered.
+        check_fix(
+            r#"
+//- minicore: result
+struct Error;
+fn fallible() -> Result<(), Error> { Ok(()) }
+fn f() -> Result<(), ()> {
+    fallible()$0;
+    Ok(())
+}
+"#,
+            r#"
+struct Error;
+fn fallible() -> Result<(), Error> { Ok(()) }
+fn f() -> Result<(), ()> {
+    let _ = fallible();
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_await() {
+        check_fix(
+            r#"
+//- minicore: future
+async fn task() {}
+async fn f() {
+    task()$0;
+}
+"#,
+            r#"
+async fn task() {}
+async fn f() {
+    task().await;
+}
+"#,
+        );
+        check_fix(
+            r#"
+//- minicore: future
+async fn task() {}
+fn f() {
+    async {
+        task()$0;
+    };
+}
+"#,
+            r#"
+async fn task() {}
+fn f() {
+    async {
+        task().await;
+    };
+}
+"#,
+        );
+        // Not in an async context.
+        check_fix(
+            r#"
+//- minicore: future
+async fn task() {}
+fn f() {
+    task()$0;
+}
+"#,
+            r#"
+async fn task() {}
+fn f() {
+    let _ = task();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn parenthesize_before_await() {
+        check_fix(
+            r#"
+//- minicore: future
+async fn task() {}
+async fn f(b: bool) {
+    if b { task() } else { task() }$0;
+}
+"#,
+            r#"
+async fn task() {}
+async fn f(b: bool) {
+    (if b { task() } else { task() }).await;
+}
+"#,
+        );
+    }
+}
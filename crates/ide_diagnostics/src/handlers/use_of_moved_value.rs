@@ -0,0 +1,180 @@
+use crate::{Diagnostic, DiagnosticsContext};
+
+// Diagnostic: use-of-moved-value
+//
+// This diagnostic is triggered if a variable is used or borrowed after its
+// value may have been moved out of it.
+//
+// Example:
+//
+// ```rust
+// let a = String::new();
+// let b = a;
+// let c = a;
+// ```
+pub(crate) fn use_of_moved_value(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UseOfMovedValue,
+) -> Diagnostic {
+    let kind = if d.is_borrow { "borrow" } else { "use" };
+    let name = d.local.name(ctx.sema.db).map_or_else(|| "_".to_string(), |it| it.to_string());
+    Diagnostic::new(
+        "use-of-moved-value",
+        format!("{} of moved value: `{}`", kind, name),
+        ctx.sema.diagnostics_display_range(d.node.clone()).range,
+    )
+    .experimental()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn use_after_move() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct S;
+fn consume(_: S) {}
+fn f() {
+    let a = S;
+    consume(a);
+    consume(a);
+          //^ error: use of moved value: `a`
+    let b = S;
+    let c = b;
+    let r = &b;
+          //^^ error: borrow of moved value: `b`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_in_loop() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct S;
+fn consume(_: S) {}
+fn f(cond: bool) {
+    let a = S;
+    loop {
+        consume(a);
+              //^ error: use of moved value: `a`
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn partial_moves() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct S;
+struct Pair { a: S, b: S }
+fn consume<T>(_: T) {}
+fn f(p: Pair, q: (S, S)) {
+    consume(p.a);
+    consume(p.b);
+    consume(q.0);
+    consume(q);
+          //^ error: use of moved value: `q`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_after_reinitialization() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct S;
+fn consume(_: S) {}
+fn f(cond: bool) {
+    let mut a = S;
+    consume(a);
+    a = S;
+    consume(a);
+    let b = S;
+    if cond {
+        consume(b);
+        return;
+    }
+    consume(b);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_copy_types_and_references() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct C;
+impl Clone for C { fn clone(&self) -> C { C } }
+impl Copy for C {}
+struct S;
+fn consume<T>(_: T) {}
+fn f(s: &S) {
+    let c = C;
+    consume(c);
+    consume(c);
+    let n = 1;
+    consume(n);
+    consume(n);
+    consume(s);
+    consume(s);
+    let s = S;
+    let r = &s;
+    consume(r);
+    consume(s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_match_on_reference() {
+        check_diagnostics(
+            r#"
+//- minicore: copy, option
+struct S;
+fn consume(_: S) {}
+fn f(o: Option<S>) {
+    match &o {
+        Some(s) => {}
+        None => {}
+    }
+    if let Some(s) = o {
+        consume(s);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_in_match() {
+        check_diagnostics(
+            r#"
+//- minicore: copy, option
+struct S;
+fn consume<T>(_: T) {}
+fn f(o: Option<S>) {
+    match o {
+        Some(s) => consume(s),
+        None => {}
+    }
+    consume(o);
+          //^ error: use of moved value: `o`
+}
+"#,
+        );
+    }
+}
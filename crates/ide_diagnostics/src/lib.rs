@@ -24,44 +24,64 @@
 //! don't yet have a great pattern for how to do them properly.
 
 mod handlers {
+    pub(crate) mod array_into_iter;
     pub(crate) mod break_outside_of_loop;
+    pub(crate) mod dead_code;
+    pub(crate) mod deprecated;
     pub(crate) mod inactive_code;
     pub(crate) mod incorrect_case;
+    pub(crate) mod invalid_cast;
     pub(crate) mod macro_error;
     pub(crate) mod mismatched_arg_count;
     pub(crate) mod missing_fields;
     pub(crate) mod missing_match_arms;
     pub(crate) mod missing_ok_or_some_in_tail_expr;
+    pub(crate) mod missing_trait_items;
     pub(crate) mod missing_unsafe;
     pub(crate) mod no_such_field;
+    pub(crate) mod non_fmt_panics;
+    pub(crate) mod possibly_uninitialized;
     pub(crate) mod remove_this_semicolon;
     pub(crate) mod replace_filter_map_next_with_find_map;
+    pub(crate) mod rust_2021_incompatible_closure_captures;
+    pub(crate) mod rust_2021_prefixes_incompatible_syntax;
+    pub(crate) mod trait_item_not_member;
     pub(crate) mod unimplemented_builtin_macro;
+    pub(crate) mod unreachable_code;
     pub(crate) mod unresolved_extern_crate;
     pub(crate) mod unresolved_import;
     pub(crate) mod unresolved_macro_call;
     pub(crate) mod unresolved_module;
     pub(crate) mod unresolved_proc_macro;
+    pub(crate) mod unsatisfied_trait_bound;
+    pub(crate) mod unused_import;
+    pub(crate) mod unused_must_use;
+    pub(crate) mod use_of_moved_value;
 
     // The handlers bellow are unusual, the implement the diagnostics as well.
     pub(crate) mod field_shorthand;
+    pub(crate) mod invalid_regex;
     pub(crate) mod useless_braces;
     pub(crate) mod unlinked_file;
 }
 
+mod edition_migration;
+
 #[cfg(test)]
 mod tests;
 
 use hir::{diagnostics::AnyDiagnostic, Semantics};
 use ide_db::{
     assists::{Assist, AssistId, AssistKind, AssistResolveStrategy},
-    base_db::{FileId, SourceDatabase},
+    base_db::{Edition, FileId, SourceDatabase},
     label::Label,
     source_change::SourceChange,
     RootDatabase,
 };
 use rustc_hash::FxHashSet;
-use syntax::{ast::AstNode, TextRange};
+use syntax::{ast::AstNode, NodeOrToken, TextRange};
+
+pub use crate::edition_migration::migrate_to_edition_2021;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct DiagnosticCode(pub &'static str);
@@ -130,6 +150,13 @@ pub enum Severity {
 pub struct DiagnosticsConfig {
     pub disable_experimental: bool,
     pub disabled: FxHashSet<String>,
+    /// Whether to report the diagnostics helping to migrate crates using older
+    /// editions to Rust 2021. They are noisy for crates staying on their
+    /// edition, so they're off by default.
+    pub edition_migration: bool,
+    /// Whether to report dead code. This searches the crate for the references
+    /// of every private item, so it's off by default.
+    pub dead_code: bool,
 }
 
 struct DiagnosticsContext<'a> {
@@ -156,9 +183,16 @@ pub fn diagnostics(
         }),
     );
 
-    for node in parse.tree().syntax().descendants() {
-        handlers::useless_braces::useless_braces(&mut res, file_id, &node);
-        handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
+    for element in sema.parse(file_id).syntax().descendants_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                handlers::useless_braces::useless_braces(&mut res, file_id, &node);
+                handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
+            }
+            NodeOrToken::Token(token) => {
+                handlers::invalid_regex::invalid_regex(&mut res, &sema, &token);
+            }
+        }
     }
 
     let module = sema.to_module_def(file_id);
@@ -170,7 +204,18 @@ pub fn diagnostics(
 
     let mut diags = Vec::new();
     if let Some(m) = module {
-        m.diagnostics(db, &mut diags)
+        m.diagnostics(db, &mut diags);
+        for node in ctx.sema.parse(file_id).syntax().descendants() {
+            handlers::deprecated::deprecated(&mut res, &ctx.sema, &node);
+            handlers::unused_must_use::unused_must_use(&mut res, &ctx.sema, file_id, &node);
+        }
+        handlers::unused_import::unused_import(&mut res, &ctx.sema, file_id);
+        if config.dead_code {
+            handlers::dead_code::dead_code(&mut res, &ctx.sema, file_id);
+        }
+        if config.edition_migration && m.krate().edition(db) < Edition::Edition2021 {
+            edition_migration::edition_2021_diagnostics(&ctx.sema, &mut res, file_id);
+        }
     }
 
     for diag in diags {
@@ -178,26 +223,36 @@ pub fn diagnostics(
         let d = match diag {
             AnyDiagnostic::BreakOutsideOfLoop(d) => handlers::break_outside_of_loop::break_outside_of_loop(&ctx, &d),
             AnyDiagnostic::IncorrectCase(d) => handlers::incorrect_case::incorrect_case(&ctx, &d),
+            AnyDiagnostic::InvalidCast(d) => handlers::invalid_cast::invalid_cast(&ctx, &d),
             AnyDiagnostic::MacroError(d) => handlers::macro_error::macro_error(&ctx, &d),
             AnyDiagnostic::MismatchedArgCount(d) => handlers::mismatched_arg_count::mismatched_arg_count(&ctx, &d),
             AnyDiagnostic::MissingFields(d) => handlers::missing_fields::missing_fields(&ctx, &d),
             AnyDiagnostic::MissingMatchArms(d) => handlers::missing_match_arms::missing_match_arms(&ctx, &d),
             AnyDiagnostic::MissingOkOrSomeInTailExpr(d) => handlers::missing_ok_or_some_in_tail_expr::missing_ok_or_some_in_tail_expr(&ctx, &d),
+            AnyDiagnostic::MissingTraitItems(d) => handlers::missing_trait_items::missing_trait_items(&ctx, &d),
             AnyDiagnostic::MissingUnsafe(d) => handlers::missing_unsafe::missing_unsafe(&ctx, &d),
             AnyDiagnostic::NoSuchField(d) => handlers::no_such_field::no_such_field(&ctx, &d),
+            AnyDiagnostic::PossiblyUninitialized(d) => handlers::possibly_uninitialized::possibly_uninitialized(&ctx, &d),
             AnyDiagnostic::RemoveThisSemicolon(d) => handlers::remove_this_semicolon::remove_this_semicolon(&ctx, &d),
             AnyDiagnostic::ReplaceFilterMapNextWithFindMap(d) => handlers::replace_filter_map_next_with_find_map::replace_filter_map_next_with_find_map(&ctx, &d),
+            AnyDiagnostic::TraitItemNotMember(d) => handlers::trait_item_not_member::trait_item_not_member(&ctx, &d),
             AnyDiagnostic::UnimplementedBuiltinMacro(d) => handlers::unimplemented_builtin_macro::unimplemented_builtin_macro(&ctx, &d),
+            AnyDiagnostic::UnreachableCode(d) => handlers::unreachable_code::unreachable_code(&ctx, &d),
             AnyDiagnostic::UnresolvedExternCrate(d) => handlers::unresolved_extern_crate::unresolved_extern_crate(&ctx, &d),
             AnyDiagnostic::UnresolvedImport(d) => handlers::unresolved_import::unresolved_import(&ctx, &d),
             AnyDiagnostic::UnresolvedMacroCall(d) => handlers::unresolved_macro_call::unresolved_macro_call(&ctx, &d),
             AnyDiagnostic::UnresolvedModule(d) => handlers::unresolved_module::unresolved_module(&ctx, &d),
             AnyDiagnostic::UnresolvedProcMacro(d) => handlers::unresolved_proc_macro::unresolved_proc_macro(&ctx, &d),
+            AnyDiagnostic::UseOfMovedValue(d) => handlers::use_of_moved_value::use_of_moved_value(&ctx, &d),
 
             AnyDiagnostic::InactiveCode(d) => match handlers::inactive_code::inactive_code(&ctx, &d) {
                 Some(it) => it,
                 None => continue,
             }
+            AnyDiagnostic::UnsatisfiedTraitBound(d) => match handlers::unsatisfied_trait_bound::unsatisfied_trait_bound(&ctx, &d) {
+                Some(it) => it,
+                None => continue,
+            }
         };
         res.push(d)
     }
@@ -224,5 +279,6 @@ fn unresolved_fix(id: &'static str, label: &str, target: TextRange) -> Assist {
         group: None,
         target,
         source_change: None,
+        command: None,
     }
 }
@@ -33,17 +33,25 @@ pub(crate) fn check_fixes(ra_fixture_before: &str, ra_fixtures_after: Vec<&str>)
 
 #[track_caller]
 fn check_nth_fix(nth: usize, ra_fixture_before: &str, ra_fixture_after: &str) {
+    let mut config = DiagnosticsConfig::default();
+    config.disabled.insert("unused-import".to_string());
+    check_nth_fix_with_config(config, nth, ra_fixture_before, ra_fixture_after)
+}
+
+#[track_caller]
+pub(crate) fn check_nth_fix_with_config(
+    config: DiagnosticsConfig,
+    nth: usize,
+    ra_fixture_before: &str,
+    ra_fixture_after: &str,
+) {
     let after = trim_indent(ra_fixture_after);
 
     let (db, file_position) = RootDatabase::with_position(ra_fixture_before);
-    let diagnostic = super::diagnostics(
-        &db,
-        &DiagnosticsConfig::default(),
-        &AssistResolveStrategy::All,
-        file_position.file_id,
-    )
-    .pop()
-    .expect("no diagnostics");
+    let diagnostic =
+        super::diagnostics(&db, &config, &AssistResolveStrategy::All, file_position.file_id)
+            .pop()
+            .expect("no diagnostics");
     let fix = &diagnostic.fixes.expect("diagnostic misses fixes")[nth];
     let actual = {
         let source_change = fix.source_change.as_ref().unwrap();
@@ -68,14 +76,12 @@ fn check_nth_fix(nth: usize, ra_fixture_before: &str, ra_fixture_after: &str) {
 /// Checks that there's a diagnostic *without* fix at `$0`.
 pub(crate) fn check_no_fix(ra_fixture: &str) {
     let (db, file_position) = RootDatabase::with_position(ra_fixture);
-    let diagnostic = super::diagnostics(
-        &db,
-        &DiagnosticsConfig::default(),
-        &AssistResolveStrategy::All,
-        file_position.file_id,
-    )
-    .pop()
-    .unwrap();
+    let mut config = DiagnosticsConfig::default();
+    config.disabled.insert("unused-import".to_string());
+    let diagnostic =
+        super::diagnostics(&db, &config, &AssistResolveStrategy::All, file_position.file_id)
+            .pop()
+            .unwrap();
     assert!(diagnostic.fixes.is_none(), "got a fix when none was expected: {:?}", diagnostic);
 }
 
@@ -94,6 +100,7 @@ pub(crate) fn check_expect(ra_fixture: &str, expect: Expect) {
 pub(crate) fn check_diagnostics(ra_fixture: &str) {
     let mut config = DiagnosticsConfig::default();
     config.disabled.insert("inactive-code".to_string());
+    config.disabled.insert("unused-import".to_string());
     check_diagnostics_with_config(config, ra_fixture)
 }
 
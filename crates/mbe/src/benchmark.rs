@@ -3,7 +3,7 @@
 use rustc_hash::FxHashMap;
 use syntax::{
     ast::{self, NameOwner},
-    AstNode, SmolStr,
+    AstNode, Edition, SmolStr,
 };
 use test_utils::{bench, bench_fixture, skip_slow_tests};
 
@@ -21,7 +21,7 @@ fn benchmark_parse_macro_rules() {
     let rules = macro_rules_fixtures_tt();
     let hash: usize = {
         let _pt = bench("mbe parse macro rules");
-        rules.values().map(|it| MacroRules::parse(it).unwrap().rules.len()).sum()
+        rules.values().map(|it| MacroRules::parse(it, Edition::CURRENT).unwrap().rules.len()).sum()
     };
     assert_eq!(hash, 1144);
 }
@@ -51,7 +51,7 @@ fn benchmark_expand_macro_rules() {
 fn macro_rules_fixtures() -> FxHashMap<String, MacroRules> {
     macro_rules_fixtures_tt()
         .into_iter()
-        .map(|(id, tt)| (id, MacroRules::parse(&tt).unwrap()))
+        .map(|(id, tt)| (id, MacroRules::parse(&tt, Edition::CURRENT).unwrap()))
         .collect()
 }
 
@@ -6,17 +6,18 @@ mod matcher;
 mod transcriber;
 
 use rustc_hash::FxHashMap;
-use syntax::SmolStr;
+use syntax::{Edition, SmolStr};
 
 use crate::{ExpandError, ExpandResult};
 
 pub(crate) fn expand_rules(
     rules: &[crate::Rule],
     input: &tt::Subtree,
+    edition: Edition,
 ) -> ExpandResult<tt::Subtree> {
     let mut match_: Option<(matcher::Match, &crate::Rule)> = None;
     for rule in rules {
-        let new_match = matcher::match_(&rule.lhs, input);
+        let new_match = matcher::match_(&rule.lhs, input, edition);
 
         if new_match.err.is_none() {
             // If we find a rule that applies without errors, we're done.
@@ -160,7 +161,7 @@ mod tests {
             source_file.syntax().descendants().find_map(ast::MacroRules::cast).unwrap();
 
         let (definition_tt, _) = ast_to_token_tree(&macro_definition.token_tree().unwrap());
-        crate::MacroRules::parse(&definition_tt).unwrap()
+        crate::MacroRules::parse(&definition_tt, Edition::CURRENT).unwrap()
     }
 
     fn expand_first(rules: &crate::MacroRules, invocation: &str) -> ExpandResult<tt::Subtree> {
@@ -170,6 +171,6 @@ mod tests {
 
         let (invocation_tt, _) = ast_to_token_tree(&macro_invocation.token_tree().unwrap());
 
-        expand_rules(&rules.rules, &invocation_tt)
+        expand_rules(&rules.rules, &invocation_tt, rules.edition)
     }
 }
@@ -71,7 +71,7 @@ use crate::{
 use super::ExpandResult;
 use parser::FragmentKind::*;
 use smallvec::{smallvec, SmallVec};
-use syntax::SmolStr;
+use syntax::{Edition, SmolStr};
 
 impl Bindings {
     fn push_optional(&mut self, name: &SmolStr) {
@@ -120,8 +120,8 @@ impl Match {
 }
 
 /// Matching errors are added to the `Match`.
-pub(super) fn match_(pattern: &MetaTemplate, input: &tt::Subtree) -> Match {
-    let mut res = match_loop(pattern, input);
+pub(super) fn match_(pattern: &MetaTemplate, input: &tt::Subtree, edition: Edition) -> Match {
+    let mut res = match_loop(pattern, input, edition);
     res.bound_count = count(res.bindings.bindings());
     return res;
 
@@ -373,6 +373,7 @@ fn match_loop_inner<'t>(
     next_items: &mut Vec<MatchState<'t>>,
     eof_items: &mut SmallVec<[MatchState<'t>; 1]>,
     error_items: &mut SmallVec<[MatchState<'t>; 1]>,
+    edition: Edition,
 ) {
     macro_rules! try_push {
         ($items: expr, $it:expr) => {
@@ -479,7 +480,7 @@ fn match_loop_inner<'t>(
             OpDelimited::Op(Op::Var { kind, name, .. }) => {
                 if let Some(kind) = kind {
                     let mut fork = src.clone();
-                    let match_res = match_meta_var(kind.as_str(), &mut fork);
+                    let match_res = match_meta_var(kind.as_str(), &mut fork, edition);
                     match match_res.err {
                         None => {
                             // Some meta variables are optional (e.g. vis)
@@ -529,7 +530,7 @@ fn match_loop_inner<'t>(
     }
 }
 
-fn match_loop(pattern: &MetaTemplate, src: &tt::Subtree) -> Match {
+fn match_loop(pattern: &MetaTemplate, src: &tt::Subtree, edition: Edition) -> Match {
     let mut src = TtIter::new(src);
     let mut stack: SmallVec<[TtIter; 1]> = SmallVec::new();
     let mut res = Match::default();
@@ -568,6 +569,7 @@ fn match_loop(pattern: &MetaTemplate, src: &tt::Subtree) -> Match {
             &mut next_items,
             &mut eof_items,
             &mut error_items,
+            edition,
         );
         stdx::always!(cur_items.is_empty());
 
@@ -689,12 +691,17 @@ fn match_leaf(lhs: &tt::Leaf, src: &mut TtIter) -> Result<(), ExpandError> {
     Ok(())
 }
 
-fn match_meta_var(kind: &str, input: &mut TtIter) -> ExpandResult<Option<Fragment>> {
+fn match_meta_var(
+    kind: &str,
+    input: &mut TtIter,
+    edition: Edition,
+) -> ExpandResult<Option<Fragment>> {
     let fragment = match kind {
         "path" => Path,
         "expr" => Expr,
         "ty" => Type,
-        "pat" | "pat_param" => Pattern, // FIXME: edition2021
+        "pat" if edition >= Edition::Edition2021 => PatternTop,
+        "pat" | "pat_param" => Pattern,
         "stmt" => Statement,
         "block" => Block,
         "meta" => MetaItem,
@@ -735,7 +742,7 @@ fn match_meta_var(kind: &str, input: &mut TtIter) -> ExpandResult<Option<Fragmen
             return tt_result.map(|it| it.map(Fragment::Tokens)).into();
         }
     };
-    let result = input.expect_fragment(fragment);
+    let result = input.expect_fragment(fragment, edition);
     result.map(|tt| if kind == "expr" { tt.map(Fragment::Ast) } else { tt.map(Fragment::Tokens) })
 }
 
@@ -842,7 +849,8 @@ impl<'a> TtIter<'a> {
 
     fn eat_vis(&mut self) -> Option<tt::TokenTree> {
         let mut fork = self.clone();
-        match fork.expect_fragment(Visibility) {
+        // Visibilities are parsed the same way in all editions.
+        match fork.expect_fragment(Visibility, Edition::CURRENT) {
             ExpandResult { value: tt, err: None } => {
                 *self = fork;
                 tt
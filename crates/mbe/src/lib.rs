@@ -18,6 +18,7 @@ mod token_map;
 
 use std::fmt;
 
+use syntax::Edition;
 pub use tt::{Delimiter, DelimiterKind, Punct};
 
 use crate::{
@@ -81,6 +82,9 @@ pub struct MacroRules {
     rules: Vec<Rule>,
     /// Highest id of the token we have in TokenMap
     shift: Shift,
+    /// Edition of the crate the macro is defined in, used to interpret
+    /// fragment specifiers like `pat` and to parse the matched fragments.
+    edition: Edition,
 }
 
 /// For Macro 2.0
@@ -89,6 +93,7 @@ pub struct MacroDef {
     rules: Vec<Rule>,
     /// Highest id of the token we have in TokenMap
     shift: Shift,
+    edition: Edition,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -171,7 +176,7 @@ pub enum Origin {
 }
 
 impl MacroRules {
-    pub fn parse(tt: &tt::Subtree) -> Result<MacroRules, ParseError> {
+    pub fn parse(tt: &tt::Subtree, edition: Edition) -> Result<MacroRules, ParseError> {
         // Note: this parsing can be implemented using mbe machinery itself, by
         // matching against `$($lhs:tt => $rhs:tt);*` pattern, but implementing
         // manually seems easier.
@@ -192,14 +197,14 @@ impl MacroRules {
             validate(&rule.lhs)?;
         }
 
-        Ok(MacroRules { rules, shift: Shift::new(tt) })
+        Ok(MacroRules { rules, shift: Shift::new(tt), edition })
     }
 
     pub fn expand(&self, tt: &tt::Subtree) -> ExpandResult<tt::Subtree> {
         // apply shift
         let mut tt = tt.clone();
         self.shift.shift_all(&mut tt);
-        expander::expand_rules(&self.rules, &tt)
+        expander::expand_rules(&self.rules, &tt, self.edition)
     }
 
     pub fn map_id_down(&self, id: tt::TokenId) -> tt::TokenId {
@@ -215,7 +220,7 @@ impl MacroRules {
 }
 
 impl MacroDef {
-    pub fn parse(tt: &tt::Subtree) -> Result<MacroDef, ParseError> {
+    pub fn parse(tt: &tt::Subtree, edition: Edition) -> Result<MacroDef, ParseError> {
         let mut src = TtIter::new(tt);
         let mut rules = Vec::new();
 
@@ -245,14 +250,14 @@ impl MacroDef {
             validate(&rule.lhs)?;
         }
 
-        Ok(MacroDef { rules, shift: Shift::new(tt) })
+        Ok(MacroDef { rules, shift: Shift::new(tt), edition })
     }
 
     pub fn expand(&self, tt: &tt::Subtree) -> ExpandResult<tt::Subtree> {
         // apply shift
         let mut tt = tt.clone();
         self.shift.shift_all(&mut tt);
-        expander::expand_rules(&self.rules, &tt)
+        expander::expand_rules(&self.rules, &tt, self.edition)
     }
 
     pub fn map_id_down(&self, id: tt::TokenId) -> tt::TokenId {
@@ -1,6 +1,6 @@
 //! Conversions between [`SyntaxNode`] and [`tt::TokenTree`].
 
-use parser::{FragmentKind, ParseError, TreeSink};
+use parser::{Edition, FragmentKind, ParseError, TreeSink};
 use rustc_hash::FxHashMap;
 use syntax::{
     ast::{self, make::tokens::doc_comment},
@@ -44,6 +44,7 @@ pub fn syntax_node_to_token_tree(node: &SyntaxNode) -> (tt::Subtree, TokenMap) {
 pub fn token_tree_to_syntax_node(
     tt: &tt::Subtree,
     fragment_kind: FragmentKind,
+    edition: Edition,
 ) -> Result<(Parse<SyntaxNode>, TokenMap), ExpandError> {
     let buffer = match tt {
         tt::Subtree { delimiter: None, token_trees } => {
@@ -53,7 +54,7 @@ pub fn token_tree_to_syntax_node(
     };
     let mut token_source = SubtreeTokenSource::new(&buffer);
     let mut tree_sink = TtTreeSink::new(buffer.begin());
-    parser::parse_fragment(&mut token_source, &mut tree_sink, fragment_kind);
+    parser::parse_fragment(&mut token_source, &mut tree_sink, fragment_kind, edition);
     if tree_sink.roots.len() != 1 {
         return Err(ExpandError::ConversionError);
     }
@@ -94,7 +95,7 @@ pub fn parse_exprs_with_sep(tt: &tt::Subtree, sep: char) -> Vec<tt::Subtree> {
     let mut res = Vec::new();
 
     while iter.peek_n(0).is_some() {
-        let expanded = iter.expect_fragment(FragmentKind::Expr);
+        let expanded = iter.expect_fragment(FragmentKind::Expr, Edition::CURRENT);
         if expanded.err.is_some() {
             break;
         }
@@ -773,7 +774,9 @@ mod tests {
             "#,
         )
         .expand_tt("stmts!();");
-        assert!(token_tree_to_syntax_node(&expansion, FragmentKind::Expr).is_err());
+        assert!(
+            token_tree_to_syntax_node(&expansion, FragmentKind::Expr, Edition::CURRENT).is_err()
+        );
     }
 
     #[test]
@@ -822,7 +825,7 @@ mod tests {
         let source_file = ast::SourceFile::parse("struct Foo { a: x::Y }").ok().unwrap();
         let struct_def = source_file.syntax().descendants().find_map(ast::Struct::cast).unwrap();
         let tt = ast_to_token_tree(&struct_def).0;
-        token_tree_to_syntax_node(&tt, FragmentKind::Item).unwrap();
+        token_tree_to_syntax_node(&tt, FragmentKind::Item, Edition::CURRENT).unwrap();
     }
 
     #[test]
@@ -42,13 +42,16 @@ macro_rules! impl_fixture {
             #[allow(unused)]
             fn expand_items(&self, invocation: &str) -> SyntaxNode {
                 let expanded = self.expand_tt(invocation);
-                token_tree_to_syntax_node(&expanded, FragmentKind::Items).unwrap().0.syntax_node()
+                token_tree_to_syntax_node(&expanded, FragmentKind::Items, Edition::CURRENT)
+                    .unwrap()
+                    .0
+                    .syntax_node()
             }
 
             #[allow(unused)]
             fn expand_statements(&self, invocation: &str) -> SyntaxNode {
                 let expanded = self.expand_tt(invocation);
-                token_tree_to_syntax_node(&expanded, FragmentKind::Statements)
+                token_tree_to_syntax_node(&expanded, FragmentKind::Statements, Edition::CURRENT)
                     .unwrap()
                     .0
                     .syntax_node()
@@ -57,7 +60,10 @@ macro_rules! impl_fixture {
             #[allow(unused)]
             fn expand_expr(&self, invocation: &str) -> SyntaxNode {
                 let expanded = self.expand_tt(invocation);
-                token_tree_to_syntax_node(&expanded, FragmentKind::Expr).unwrap().0.syntax_node()
+                token_tree_to_syntax_node(&expanded, FragmentKind::Expr, Edition::CURRENT)
+                    .unwrap()
+                    .0
+                    .syntax_node()
             }
 
             #[allow(unused)]
@@ -105,12 +111,16 @@ macro_rules! impl_fixture {
                     wrapped
                 };
 
-                let expanded_tree =
-                    token_tree_to_syntax_node(&expanded, kind).unwrap().0.syntax_node();
+                let expanded_tree = token_tree_to_syntax_node(&expanded, kind, Edition::CURRENT)
+                    .unwrap()
+                    .0
+                    .syntax_node();
                 let expanded_tree = debug_dump_ignore_spaces(&expanded_tree).trim().to_string();
 
-                let expected_tree =
-                    token_tree_to_syntax_node(&expected, kind).unwrap().0.syntax_node();
+                let expected_tree = token_tree_to_syntax_node(&expected, kind, Edition::CURRENT)
+                    .unwrap()
+                    .0
+                    .syntax_node();
                 let expected_tree = debug_dump_ignore_spaces(&expected_tree).trim().to_string();
 
                 let expected_tree = expected_tree.replace("C_C__C", "$crate");
@@ -128,21 +138,25 @@ impl_fixture!(MacroFixture);
 impl_fixture!(MacroFixture2);
 
 pub(crate) fn parse_macro(ra_fixture: &str) -> MacroFixture {
+    parse_macro_with_edition(ra_fixture, Edition::CURRENT)
+}
+
+pub(crate) fn parse_macro_with_edition(ra_fixture: &str, edition: Edition) -> MacroFixture {
     let definition_tt = parse_macro_rules_to_tt(ra_fixture);
-    let rules = MacroRules::parse(&definition_tt).unwrap();
+    let rules = MacroRules::parse(&definition_tt, edition).unwrap();
     MacroFixture { rules }
 }
 
 pub(crate) fn parse_macro2(ra_fixture: &str) -> MacroFixture2 {
     let definition_tt = parse_macro_def_to_tt(ra_fixture);
-    let rules = MacroDef::parse(&definition_tt).unwrap();
+    let rules = MacroDef::parse(&definition_tt, Edition::CURRENT).unwrap();
     MacroFixture2 { rules }
 }
 
 pub(crate) fn parse_macro_error(ra_fixture: &str) -> ParseError {
     let definition_tt = parse_macro_rules_to_tt(ra_fixture);
 
-    match MacroRules::parse(&definition_tt) {
+    match MacroRules::parse(&definition_tt, Edition::CURRENT) {
         Ok(_) => panic!("Expect error"),
         Err(err) => err,
     }
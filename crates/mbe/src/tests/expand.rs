@@ -55,7 +55,8 @@ macro_rules! foobar {
     )
     .expand_tt("foobar!(baz);");
 
-    let (node, token_map) = token_tree_to_syntax_node(&expanded, FragmentKind::Items).unwrap();
+    let (node, token_map) =
+        token_tree_to_syntax_node(&expanded, FragmentKind::Items, Edition::CURRENT).unwrap();
     let content = node.syntax_node().to_string();
 
     let get_text =
@@ -823,6 +824,33 @@ fn test_pat_() {
     .assert_expand_items("foo! { (a, b) }", "fn foo () {let (a , b) ;}");
 }
 
+#[test]
+fn test_pat_top_level_or_2021() {
+    let fixture = r#"
+        macro_rules! foo {
+            ($ i:pat) => { fn foo() { match 0 { $ i => () } } }
+        }
+"#;
+    parse_macro_with_edition(fixture, Edition::Edition2021)
+        .assert_expand_items("foo! { 1 | 2 }", "fn foo () {match 0 {1 | 2 => ()}}");
+    assert!(parse_macro_with_edition(fixture, Edition::Edition2018)
+        .try_expand_tt("foo! { 1 | 2 }")
+        .is_err());
+}
+
+#[test]
+fn test_pat_param_2021() {
+    parse_macro_with_edition(
+        r#"
+        macro_rules! foo {
+            ($ i:pat_param | $ j:pat_param) => { fn foo() { match 0 { $ i | $ j => () } } }
+        }
+"#,
+        Edition::Edition2021,
+    )
+    .assert_expand_items("foo! { 1 | 2 }", "fn foo () {match 0 {1 | 2 => ()}}");
+}
+
 #[test]
 fn test_stmt() {
     parse_macro(
@@ -45,5 +45,5 @@ fn parse_macro_arm(arm_definition: &str) -> Result<crate::MacroRules, ParseError
         source_file.syntax().descendants().find_map(ast::MacroRules::cast).unwrap();
 
     let (definition_tt, _) = ast_to_token_tree(&macro_definition.token_tree().unwrap());
-    crate::MacroRules::parse(&definition_tt)
+    crate::MacroRules::parse(&definition_tt, Edition::CURRENT)
 }
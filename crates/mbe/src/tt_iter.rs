@@ -93,6 +93,7 @@ impl<'a> TtIter<'a> {
     pub(crate) fn expect_fragment(
         &mut self,
         fragment_kind: parser::FragmentKind,
+        edition: parser::Edition,
     ) -> ExpandResult<Option<tt::TokenTree>> {
         struct OffsetTokenSink<'a> {
             cursor: Cursor<'a>,
@@ -119,7 +120,7 @@ impl<'a> TtIter<'a> {
         let mut src = SubtreeTokenSource::new(&buffer);
         let mut sink = OffsetTokenSink { cursor: buffer.begin(), error: false };
 
-        parser::parse_fragment(&mut src, &mut sink, fragment_kind);
+        parser::parse_fragment(&mut src, &mut sink, fragment_kind, edition);
 
         let mut err = if !sink.cursor.is_root() || sink.error {
             Some(err!("expected {:?}", fragment_kind))
@@ -40,6 +40,7 @@ mod types;
 
 use crate::{
     parser::{CompletedMarker, Marker, Parser},
+    Edition,
     SyntaxKind::{self, *},
     TokenSet,
 };
@@ -56,7 +57,10 @@ pub(crate) mod fragments {
     use super::*;
 
     pub(crate) use super::{
-        expressions::block_expr, paths::type_path as path, patterns::pattern_single, types::type_,
+        expressions::block_expr,
+        paths::type_path as path,
+        patterns::{pattern_single, pattern_top},
+        types::type_,
     };
 
     pub(crate) fn expr(p: &mut Parser) {
@@ -45,6 +45,7 @@ fn type_with_bounds_cond(p: &mut Parser, allow_bounds: bool) {
         T![for] => for_type(p, allow_bounds),
         T![impl] => impl_trait_type(p),
         T![dyn] => dyn_trait_type(p),
+        IDENT if is_dyn_weak_keyword(p) => dyn_trait_type(p),
         // Some path types are not allowed to have bounds (no plus)
         T![<] => path_type_(p, allow_bounds),
         _ if paths::is_use_path_start(p) => path_or_macro_type_(p, allow_bounds),
@@ -261,13 +262,24 @@ fn impl_trait_type(p: &mut Parser) {
 // test dyn_trait_type
 // type A = dyn Iterator<Item=Foo<'a>> + 'a;
 fn dyn_trait_type(p: &mut Parser) {
-    assert!(p.at(T![dyn]));
+    assert!(p.at(T![dyn]) || p.at_contextual_kw("dyn"));
     let m = p.start();
-    p.bump(T![dyn]);
+    p.bump_remap(T![dyn]);
     type_params::bounds_without_colon(p);
     m.complete(p, DYN_TRAIT_TYPE);
 }
 
+/// Before 2018, `dyn` is a weak keyword: `dyn Trait` is a trait object type,
+/// but `dyn::Foo` and `dyn<T>` are paths.
+fn is_dyn_weak_keyword(p: &Parser) -> bool {
+    p.edition() < Edition::Edition2018
+        && p.at_contextual_kw("dyn")
+        && !p.nth_at(1, T![::])
+        && !p.nth_at(1, T![<])
+        && (paths::PATH_FIRST.contains(p.nth(1))
+            || matches!(p.nth(1), LIFETIME_IDENT | T![?] | T![for] | T!['(']))
+}
+
 // test path_type
 // type A = Foo;
 // type B = ::Foo;
@@ -16,13 +16,17 @@
 mod token_set;
 #[macro_use]
 mod syntax_kind;
+mod edition;
 mod event;
 mod parser;
 mod grammar;
 
 pub(crate) use token_set::TokenSet;
 
-pub use syntax_kind::SyntaxKind;
+pub use crate::{
+    edition::{Edition, ParseEditionError},
+    syntax_kind::SyntaxKind,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ParseError(pub Box<String>);
@@ -68,19 +72,23 @@ pub trait TreeSink {
     fn error(&mut self, error: ParseError);
 }
 
-fn parse_from_tokens<F>(token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink, f: F)
-where
+fn parse_from_tokens<F>(
+    token_source: &mut dyn TokenSource,
+    tree_sink: &mut dyn TreeSink,
+    edition: Edition,
+    f: F,
+) where
     F: FnOnce(&mut parser::Parser),
 {
-    let mut p = parser::Parser::new(token_source);
+    let mut p = parser::Parser::new(token_source, edition);
     f(&mut p);
     let events = p.finish();
     event::process(tree_sink, events);
 }
 
 /// Parse given tokens into the given sink as a rust file.
-pub fn parse(token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink) {
-    parse_from_tokens(token_source, tree_sink, grammar::root);
+pub fn parse(token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink, edition: Edition) {
+    parse_from_tokens(token_source, tree_sink, edition, grammar::root);
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -90,7 +98,10 @@ pub enum FragmentKind {
     Statement,
     StatementOptionalSemi,
     Type,
+    /// A single pattern, `$p:pat_param` (and `$p:pat` before 2021).
     Pattern,
+    /// A pattern which may be a top-level or-pattern, `$p:pat` in 2021.
+    PatternTop,
     Item,
     Block,
     Visibility,
@@ -108,12 +119,14 @@ pub fn parse_fragment(
     token_source: &mut dyn TokenSource,
     tree_sink: &mut dyn TreeSink,
     fragment_kind: FragmentKind,
+    edition: Edition,
 ) {
     let parser: fn(&'_ mut parser::Parser) = match fragment_kind {
         FragmentKind::Path => grammar::fragments::path,
         FragmentKind::Expr => grammar::fragments::expr,
         FragmentKind::Type => grammar::fragments::type_,
         FragmentKind::Pattern => grammar::fragments::pattern_single,
+        FragmentKind::PatternTop => grammar::fragments::pattern_top,
         FragmentKind::Item => grammar::fragments::item,
         FragmentKind::Block => grammar::fragments::block_expr,
         FragmentKind::Visibility => grammar::fragments::opt_visibility,
@@ -124,7 +137,7 @@ pub fn parse_fragment(
         FragmentKind::Statements => grammar::fragments::macro_stmts,
         FragmentKind::Attr => grammar::fragments::attr,
     };
-    parse_from_tokens(token_source, tree_sink, parser)
+    parse_from_tokens(token_source, tree_sink, edition, parser)
 }
 
 /// A parsing function for a specific braced-block.
@@ -144,9 +157,14 @@ impl Reparser {
     ///
     /// Tokens must start with `{`, end with `}` and form a valid brace
     /// sequence.
-    pub fn parse(self, token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink) {
+    pub fn parse(
+        self,
+        token_source: &mut dyn TokenSource,
+        tree_sink: &mut dyn TreeSink,
+        edition: Edition,
+    ) {
         let Reparser(r) = self;
-        let mut p = parser::Parser::new(token_source);
+        let mut p = parser::Parser::new(token_source, edition);
         r(&mut p);
         let events = p.finish();
         event::process(tree_sink, events);
@@ -6,8 +6,8 @@ use drop_bomb::DropBomb;
 
 use crate::{
     event::Event,
-    ParseError,
-    SyntaxKind::{self, EOF, ERROR, L_DOLLAR, R_DOLLAR, TOMBSTONE},
+    Edition, ParseError,
+    SyntaxKind::{self, EOF, ERROR, IDENT, L_DOLLAR, R_DOLLAR, TOMBSTONE},
     TokenSet, TokenSource, T,
 };
 
@@ -22,13 +22,18 @@ use crate::{
 /// finish expression". See `Event` docs for more.
 pub(crate) struct Parser<'t> {
     token_source: &'t mut dyn TokenSource,
+    edition: Edition,
     events: Vec<Event>,
     steps: Cell<u32>,
 }
 
 impl<'t> Parser<'t> {
-    pub(super) fn new(token_source: &'t mut dyn TokenSource) -> Parser<'t> {
-        Parser { token_source, events: Vec::new(), steps: Cell::new(0) }
+    pub(super) fn new(token_source: &'t mut dyn TokenSource, edition: Edition) -> Parser<'t> {
+        Parser { token_source, edition, events: Vec::new(), steps: Cell::new(0) }
+    }
+
+    pub(crate) fn edition(&self) -> Edition {
+        self.edition
     }
 
     pub(crate) fn finish(self) -> Vec<Event> {
@@ -51,7 +56,18 @@ impl<'t> Parser<'t> {
         assert!(steps <= 10_000_000, "the parser seems stuck");
         self.steps.set(steps + 1);
 
-        self.token_source.lookahead_nth(n).kind
+        self.raw_kind(n)
+    }
+
+    /// Kind of the nth token, with keywords which do not exist in the current
+    /// edition demoted to identifiers.
+    fn raw_kind(&self, n: usize) -> SyntaxKind {
+        let kind = self.token_source.lookahead_nth(n).kind;
+        if self.edition.is_ident_in_edition(kind) {
+            IDENT
+        } else {
+            kind
+        }
     }
 
     /// Checks if the current token is `kind`.
@@ -87,7 +103,7 @@ impl<'t> Parser<'t> {
             T![<<=] => self.at_composite3(n, T![<], T![<], T![=]),
             T![>>=] => self.at_composite3(n, T![>], T![>], T![=]),
 
-            _ => self.token_source.lookahead_nth(n).kind == kind,
+            _ => self.raw_kind(n) == kind,
         }
     }
 
@@ -17,7 +17,7 @@
 
 mod cargo_workspace;
 mod cfg_flag;
-mod project_json;
+pub mod project_json;
 mod sysroot;
 mod workspace;
 mod rustc_cfg;
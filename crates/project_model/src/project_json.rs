@@ -5,14 +5,15 @@
 //! system to generate `rust-project.json` which can be ingested by
 //! rust-analyzer.
 
-use std::path::PathBuf;
+use std::{path::PathBuf, process::Command};
 
+use anyhow::{bail, Context, Result};
 use base_db::{CrateDisplayName, CrateId, CrateName, Dependency, Edition};
 use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::FxHashMap;
 use serde::{de, Deserialize};
 
-use crate::cfg_flag::CfgFlag;
+use crate::{cfg_flag::CfgFlag, utf8_stdout, TargetKind};
 
 /// Roots and crates that compose this Rust project.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -37,6 +38,82 @@ pub struct Crate {
     pub(crate) is_workspace_member: bool,
     pub(crate) include: Vec<AbsPathBuf>,
     pub(crate) exclude: Vec<AbsPathBuf>,
+    pub(crate) build: Option<Build>,
+}
+
+impl Crate {
+    pub fn root_module(&self) -> &AbsPath {
+        &self.root_module
+    }
+    /// Returns how the build system builds and runs this crate, if the crate
+    /// isn't built by cargo.
+    pub fn build(&self) -> Option<&Build> {
+        self.build.as_ref()
+    }
+}
+
+/// Describes how a build system other than cargo checks, runs and tests a
+/// crate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Build {
+    /// The name of the crate in the build system, like `//foo:bar` for Bazel.
+    pub label: String,
+    /// The file defining the crate in the build system, like `foo/BUILD`.
+    pub build_file: AbsPathBuf,
+    pub target_kind: TargetKind,
+    pub runnables: Vec<Runnable>,
+}
+
+impl Build {
+    pub fn runnable(&self, kind: RunnableKind) -> Option<&Runnable> {
+        self.runnables.iter().find(|it| it.kind == kind)
+    }
+}
+
+/// A command template to check, run or test a crate.
+///
+/// The `{label}` and `{test_id}` placeholders in the arguments are replaced
+/// with the label of the crate and the path of the test to run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Runnable {
+    pub kind: RunnableKind,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: AbsPathBuf,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RunnableKind {
+    /// Checks the crate, reporting diagnostics in the JSON format of rustc or
+    /// cargo.
+    Check,
+    /// Runs the binary of the crate.
+    Run,
+    /// Runs a single test, or the tests of a module.
+    TestOne,
+}
+
+impl Runnable {
+    /// Returns the arguments of the command for the crates with the given
+    /// labels, with the placeholders replaced.
+    ///
+    /// An argument that is exactly `{label}` is replaced with one argument
+    /// per label.
+    pub fn args(&self, labels: &[String], test_id: Option<&str>) -> Vec<String> {
+        let mut res = Vec::new();
+        for arg in &self.args {
+            if arg == "{label}" {
+                res.extend(labels.iter().cloned());
+                continue;
+            }
+            let mut arg = arg.replace("{label}", &labels.join(" "));
+            if let Some(test_id) = test_id {
+                arg = arg.replace("{test_id}", test_id);
+            }
+            res.push(arg);
+        }
+        res
+    }
 }
 
 impl ProjectJson {
@@ -72,6 +149,21 @@ impl ProjectJson {
                         }
                         None => (vec![root_module.parent().unwrap().to_path_buf()], Vec::new()),
                     };
+                    let build = crate_data.build.map(|build| Build {
+                        label: build.label,
+                        build_file: base.join(build.build_file).normalize(),
+                        target_kind: build.target_kind.into(),
+                        runnables: build
+                            .runnables
+                            .into_iter()
+                            .map(|runnable| Runnable {
+                                kind: runnable.kind.into(),
+                                program: runnable.program,
+                                args: runnable.args,
+                                cwd: base.join(runnable.cwd).normalize(),
+                            })
+                            .collect(),
+                    });
 
                     Crate {
                         display_name: crate_data
@@ -96,11 +188,29 @@ impl ProjectJson {
                         is_workspace_member,
                         include,
                         exclude,
+                        build,
                     }
                 })
                 .collect::<Vec<_>>(),
         }
     }
+    /// Runs a command printing a project on stdout, with `file` as its last
+    /// argument.
+    ///
+    /// Relative paths in the project are interpreted relative to `base`,
+    /// which is also the working directory of the command.
+    pub fn discover(command: &[String], file: &AbsPath, base: &AbsPath) -> Result<ProjectJson> {
+        let (program, args) = match command.split_first() {
+            Some(it) => it,
+            None => bail!("empty project discovery command"),
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args).arg(file.as_os_str()).current_dir(base);
+        let stdout = utf8_stdout(cmd)?;
+        let data: ProjectJsonData = serde_json::from_str(&stdout)
+            .with_context(|| format!("Failed to deserialize the output of {:?}", command))?;
+        Ok(ProjectJson::new(base, data))
+    }
     /// Returns the number of crates in the project.
     pub fn n_crates(&self) -> usize {
         self.crates.len()
@@ -113,6 +223,10 @@ impl ProjectJson {
     pub fn path(&self) -> &AbsPath {
         &self.project_root
     }
+    /// Returns the crate with the given root module.
+    pub fn crate_by_root(&self, root: &AbsPath) -> Option<&Crate> {
+        self.crates.iter().find(|krate| krate.root_module.as_path() == root)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -135,6 +249,7 @@ struct CrateData {
     proc_macro_dylib_path: Option<PathBuf>,
     is_workspace_member: Option<bool>,
     source: Option<CrateSource>,
+    build: Option<BuildData>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -173,6 +288,60 @@ struct CrateSource {
     exclude_dirs: Vec<PathBuf>,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+struct BuildData {
+    label: String,
+    build_file: PathBuf,
+    target_kind: TargetKindData,
+    #[serde(default)]
+    runnables: Vec<RunnableData>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+enum TargetKindData {
+    Bin,
+    Lib,
+    Test,
+}
+
+impl From<TargetKindData> for TargetKind {
+    fn from(data: TargetKindData) -> Self {
+        match data {
+            TargetKindData::Bin => TargetKind::Bin,
+            TargetKindData::Lib => TargetKind::Lib,
+            TargetKindData::Test => TargetKind::Test,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct RunnableData {
+    kind: RunnableKindData,
+    program: String,
+    #[serde(default)]
+    args: Vec<String>,
+    cwd: PathBuf,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+enum RunnableKindData {
+    Check,
+    Run,
+    TestOne,
+}
+
+impl From<RunnableKindData> for RunnableKind {
+    fn from(data: RunnableKindData) -> Self {
+        match data {
+            RunnableKindData::Check => RunnableKind::Check,
+            RunnableKindData::Run => RunnableKind::Run,
+            RunnableKindData::TestOne => RunnableKind::TestOne,
+        }
+    }
+}
+
 fn deserialize_crate_name<'de, D>(de: D) -> Result<CrateName, D::Error>
 where
     D: de::Deserializer<'de>,
@@ -198,6 +198,20 @@ impl ProjectWorkspace {
         Ok(ProjectWorkspace::DetachedFiles { files: detached_files, sysroot, rustc_cfg })
     }
 
+    /// Checks whether `manifest` is the manifest this workspace was loaded
+    /// from, or the manifest of one of its members.
+    pub fn is_loaded_from(&self, manifest: &ProjectManifest) -> bool {
+        match (self, manifest) {
+            (ProjectWorkspace::Cargo { cargo, .. }, ProjectManifest::CargoToml(path)) => {
+                cargo.packages().any(|pkg| cargo[pkg].manifest == *path)
+            }
+            (ProjectWorkspace::Json { project, .. }, ProjectManifest::ProjectJson(path)) => {
+                path.parent() == Some(project.path())
+            }
+            _ => false,
+        }
+    }
+
     /// Returns the roots for the current `ProjectWorkspace`
     /// The return type contains the path and whether or not
     /// the root is a member of the current workspace
@@ -402,8 +416,10 @@ fn project_json_to_crate_graph(
                     file_id,
                     krate.edition,
                     krate.display_name.clone(),
+                    None,
                     cfg_options.clone(),
                     cfg_options,
+                    Default::default(),
                     env,
                     proc_macro.unwrap_or_default(),
                 ),
@@ -598,8 +614,10 @@ fn detached_files_to_crate_graph(
             file_id,
             Edition::CURRENT,
             display_name,
+            None,
             cfg_options.clone(),
             cfg_options.clone(),
+            Default::default(),
             Env::default(),
             Vec::new(),
         );
@@ -750,8 +768,10 @@ fn add_target_crate_root(
         file_id,
         edition,
         Some(display_name),
+        Some(pkg.version.clone()),
         cfg_options,
         potential_cfg_options,
+        pkg.features.clone(),
         env,
         proc_macro,
     );
@@ -780,8 +800,10 @@ fn sysroot_to_crate_graph(
                 file_id,
                 Edition::CURRENT,
                 Some(display_name),
+                None,
                 cfg_options.clone(),
                 cfg_options.clone(),
+                Default::default(),
                 env,
                 proc_macro,
             );
@@ -85,6 +85,17 @@ xflags::xflags! {
             optional --disable-proc-macros
         }
 
+        /// Print the monikers of the definitions in a project.
+        cmd monikers
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
         cmd ssr
             /// A structured search replace rule (`$a.foo($b) ==> bar($a, $b)`)
             repeated rule: SsrRule
@@ -123,6 +134,7 @@ pub enum RustAnalyzerCmd {
     Highlight(Highlight),
     AnalysisStats(AnalysisStats),
     Diagnostics(Diagnostics),
+    Monikers(Monikers),
     Ssr(Ssr),
     Search(Search),
     ProcMacro(ProcMacro),
@@ -171,6 +183,14 @@ pub struct Diagnostics {
     pub disable_proc_macros: bool,
 }
 
+#[derive(Debug)]
+pub struct Monikers {
+    pub path: PathBuf,
+
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
 #[derive(Debug)]
 pub struct Ssr {
     pub rule: Vec<SsrRule>,
@@ -8,7 +8,6 @@ mod rustc_wrapper;
 use std::{convert::TryFrom, env, fs, path::Path, process};
 
 use lsp_server::Connection;
-use project_model::ProjectManifest;
 use rust_analyzer::{
     cli::{self, AnalysisStatsCmd},
     config::Config,
@@ -107,6 +106,9 @@ fn try_main() -> Result<()> {
         flags::RustAnalyzerCmd::Diagnostics(cmd) => {
             cli::diagnostics(&cmd.path, !cmd.disable_build_scripts, !cmd.disable_proc_macros)?
         }
+        flags::RustAnalyzerCmd::Monikers(cmd) => {
+            cli::monikers(&cmd.path, !cmd.disable_build_scripts, !cmd.disable_proc_macros)?
+        }
         flags::RustAnalyzerCmd::Ssr(cmd) => cli::apply_ssr_rules(cmd.rule)?,
         flags::RustAnalyzerCmd::Search(cmd) => cli::search_for_patterns(cmd.pattern, cmd.debug)?,
     }
@@ -162,6 +164,7 @@ fn run_server() -> Result<()> {
 
     let (initialize_id, initialize_params) = connection.initialize_start()?;
     log::info!("InitializeParams: {}", initialize_params);
+    let raw_capabilities = initialize_params["capabilities"].clone();
     let initialize_params =
         from_json::<lsp_types::InitializeParams>("InitializeParams", initialize_params)?;
 
@@ -178,6 +181,7 @@ fn run_server() -> Result<()> {
     };
 
     let mut config = Config::new(root_path, initialize_params.capabilities);
+    config.update_pull_diagnostics_caps(&raw_capabilities);
     if let Some(json) = initialize_params.initialization_options {
         config.update(json);
     }
@@ -193,7 +197,13 @@ fn run_server() -> Result<()> {
         offset_encoding: if supports_utf8(&config.caps) { Some("utf-8".to_string()) } else { None },
     };
 
-    let initialize_result = serde_json::to_value(initialize_result).unwrap();
+    // `lsp_types::ServerCapabilities` predates the LSP 3.17 capabilities below.
+    let mut initialize_result = serde_json::to_value(initialize_result).unwrap();
+    initialize_result["capabilities"]["typeHierarchyProvider"] = serde_json::Value::Bool(true);
+    if let Some(diagnostic_provider) = rust_analyzer::diagnostic_provider(&config) {
+        initialize_result["capabilities"]["diagnosticProvider"] =
+            serde_json::to_value(diagnostic_provider).unwrap();
+    }
 
     connection.initialize_finish(initialize_id, initialize_result)?;
 
@@ -201,25 +211,20 @@ fn run_server() -> Result<()> {
         log::info!("Client '{}' {}", client_info.name, client_info.version.unwrap_or_default());
     }
 
+    config.workspace_roots = initialize_params
+        .workspace_folders
+        .map(|workspaces| {
+            workspaces
+                .into_iter()
+                .filter_map(|it| it.uri.to_file_path().ok())
+                .filter_map(|it| AbsPathBuf::try_from(it).ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|workspaces| !workspaces.is_empty())
+        .unwrap_or_else(|| vec![config.root_path.clone()]);
+
     if config.linked_projects().is_empty() && config.detached_files().is_empty() {
-        let workspace_roots = initialize_params
-            .workspace_folders
-            .map(|workspaces| {
-                workspaces
-                    .into_iter()
-                    .filter_map(|it| it.uri.to_file_path().ok())
-                    .filter_map(|it| AbsPathBuf::try_from(it).ok())
-                    .collect::<Vec<_>>()
-            })
-            .filter(|workspaces| !workspaces.is_empty())
-            .unwrap_or_else(|| vec![config.root_path.clone()]);
-
-        let discovered = ProjectManifest::discover_all(&workspace_roots);
-        log::info!("discovered projects: {:?}", discovered);
-        if discovered.is_empty() {
-            log::error!("failed to find any projects in {:?}", workspace_roots);
-        }
-        config.discovered_projects = Some(discovered);
+        config.rediscover_workspaces();
     }
 
     rust_analyzer::main_loop(config, connection)?;
@@ -2,19 +2,22 @@
 use lsp_types::{
     CallHierarchyServerCapability, ClientCapabilities, CodeActionKind, CodeActionOptions,
     CodeActionProviderCapability, CodeLensOptions, CompletionOptions, DeclarationCapability,
-    DocumentOnTypeFormattingOptions, FileOperationFilter, FileOperationPattern,
-    FileOperationPatternKind, FileOperationRegistrationOptions, FoldingRangeProviderCapability,
-    HoverProviderCapability, ImplementationProviderCapability, OneOf, RenameOptions, SaveOptions,
-    SelectionRangeProviderCapability, SemanticTokensFullOptions, SemanticTokensLegend,
-    SemanticTokensOptions, ServerCapabilities, SignatureHelpOptions, TextDocumentSyncCapability,
-    TextDocumentSyncKind, TextDocumentSyncOptions, TypeDefinitionProviderCapability,
-    WorkDoneProgressOptions, WorkspaceFileOperationsServerCapabilities,
+    DocumentOnTypeFormattingOptions, ExecuteCommandOptions, FileOperationFilter,
+    FileOperationPattern, FileOperationPatternKind, FileOperationRegistrationOptions,
+    FoldingRangeProviderCapability, HoverProviderCapability, ImplementationProviderCapability,
+    OneOf, RenameOptions, SaveOptions, SelectionRangeProviderCapability, SemanticTokensFullOptions,
+    SemanticTokensLegend, SemanticTokensOptions, ServerCapabilities, SignatureHelpOptions,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TypeDefinitionProviderCapability, WorkDoneProgressOptions,
+    WorkspaceFileOperationsServerCapabilities, WorkspaceFoldersServerCapabilities,
     WorkspaceServerCapabilities,
 };
 use serde_json::json;
 
 use crate::config::{Config, RustfmtConfig};
+use crate::lsp_ext;
 use crate::semantic_tokens;
+use crate::to_proto;
 
 pub fn server_capabilities(config: &Config) -> ServerCapabilities {
     ServerCapabilities {
@@ -66,9 +69,15 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
         linked_editing_range_provider: None,
         document_link_provider: None,
         color_provider: None,
-        execute_command_provider: None,
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: to_proto::command::SERVER_COMMANDS.iter().map(|it| it.to_string()).collect(),
+            work_done_progress_options: WorkDoneProgressOptions { work_done_progress: None },
+        }),
         workspace: Some(WorkspaceServerCapabilities {
-            workspace_folders: None,
+            workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                supported: Some(true),
+                change_notifications: Some(OneOf::Left(true)),
+            }),
             file_operations: Some(WorkspaceFileOperationsServerCapabilities {
                 did_create: None,
                 will_create: None,
@@ -111,7 +120,7 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
             }
             .into(),
         ),
-        moniker_provider: None,
+        moniker_provider: Some(OneOf::Left(true)),
         experimental: Some(json!({
             "joinLines": true,
             "ssr": true,
@@ -125,6 +134,19 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
     }
 }
 
+/// Pull diagnostics are advertised separately, as `lsp_types::ServerCapabilities`
+/// predates them.
+pub fn diagnostic_provider(config: &Config) -> Option<lsp_ext::DiagnosticOptions> {
+    if !config.pull_diagnostics() {
+        return None;
+    }
+    Some(lsp_ext::DiagnosticOptions {
+        identifier: Some("rust-analyzer".to_string()),
+        inter_file_dependencies: true,
+        workspace_diagnostics: true,
+    })
+}
+
 fn completions_resolve_provider(client_caps: &ClientCapabilities) -> Option<bool> {
     if completion_item_edit_resolve(client_caps) {
         Some(true)
@@ -1,11 +1,11 @@
-//! See `CargoTargetSpec`
+//! See `CargoTargetSpec` and `ProjectJsonTargetSpec`
 
 use cfg::{CfgAtom, CfgExpr};
 use ide::{FileId, RunnableKind, TestId};
-use project_model::{self, TargetKind};
+use project_model::{self, project_json, TargetKind};
 use vfs::AbsPathBuf;
 
-use crate::{global_state::GlobalStateSnapshot, Result};
+use crate::{global_state::GlobalStateSnapshot, lsp_ext, Result};
 
 /// Abstract representation of Cargo target.
 ///
@@ -164,6 +164,76 @@ impl CargoTargetSpec {
     }
 }
 
+/// A `rust-project.json` crate built by another build system than Cargo.
+///
+/// Its runnables use the command templates of the `build` section of the crate
+/// instead of Cargo.
+#[derive(Clone)]
+pub(crate) struct ProjectJsonTargetSpec {
+    pub(crate) label: String,
+    pub(crate) target_kind: TargetKind,
+    pub(crate) runnables: Vec<project_json::Runnable>,
+}
+
+impl ProjectJsonTargetSpec {
+    pub(crate) fn for_file(
+        global_state_snapshot: &GlobalStateSnapshot,
+        file_id: FileId,
+    ) -> Result<Option<ProjectJsonTargetSpec>> {
+        let crate_id = match global_state_snapshot.analysis.crate_for(file_id)?.first() {
+            Some(crate_id) => *crate_id,
+            None => return Ok(None),
+        };
+        let build = match global_state_snapshot
+            .project_json_crate_for_crate_root(crate_id)
+            .and_then(|krate| krate.build())
+        {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let res = ProjectJsonTargetSpec {
+            label: build.label.clone(),
+            target_kind: build.target_kind,
+            runnables: build.runnables.clone(),
+        };
+        Ok(Some(res))
+    }
+
+    /// Returns the command running `kind`, if the crate has a template for it.
+    pub(crate) fn runnable_args(&self, kind: &RunnableKind) -> Option<lsp_ext::ShellRunnable> {
+        let (template_kind, test_id) = match kind {
+            RunnableKind::Test { test_id, .. } | RunnableKind::Bench { test_id } => {
+                (project_json::RunnableKind::TestOne, Some(test_id.to_string()))
+            }
+            RunnableKind::TestMod { path } => {
+                (project_json::RunnableKind::TestOne, Some(path.clone()))
+            }
+            RunnableKind::Bin => (project_json::RunnableKind::Run, None),
+            // Doctests can only be run by cargo.
+            RunnableKind::DocTest { .. } => return None,
+        };
+        self.shell_runnable(template_kind, test_id.as_deref())
+    }
+
+    /// Returns the command checking the crate, if it has a template for it.
+    pub(crate) fn check_args(&self) -> Option<lsp_ext::ShellRunnable> {
+        self.shell_runnable(project_json::RunnableKind::Check, None)
+    }
+
+    fn shell_runnable(
+        &self,
+        kind: project_json::RunnableKind,
+        test_id: Option<&str>,
+    ) -> Option<lsp_ext::ShellRunnable> {
+        let template = self.runnables.iter().find(|it| it.kind == kind)?;
+        Some(lsp_ext::ShellRunnable {
+            program: template.program.clone(),
+            args: template.args(&[self.label.clone()], test_id),
+            cwd: template.cwd.clone().into(),
+        })
+    }
+}
+
 /// Fill minimal features needed
 fn required_features(cfg_expr: &CfgExpr, features: &mut Vec<String>) {
     match cfg_expr {
@@ -3,6 +3,7 @@
 pub(crate) mod load_cargo;
 mod analysis_stats;
 mod diagnostics;
+mod monikers;
 mod progress_report;
 mod ssr;
 
@@ -16,6 +17,7 @@ use vfs::Vfs;
 pub use self::{
     analysis_stats::AnalysisStatsCmd,
     diagnostics::diagnostics,
+    monikers::monikers,
     ssr::{apply_ssr_rules, search_for_patterns},
 };
 
@@ -45,6 +45,8 @@ pub fn diagnostics(
     let db = host.raw_database();
     let analysis = host.analysis();
 
+    // The dead code analysis is opt-in for the editor, but cheap enough here.
+    let config = DiagnosticsConfig { dead_code: true, ..DiagnosticsConfig::default() };
     let mut found_error = false;
     let mut visited_files = FxHashSet::default();
 
@@ -61,9 +63,8 @@ pub fn diagnostics(
             let crate_name =
                 module.krate().display_name(db).as_deref().unwrap_or("unknown").to_string();
             println!("processing crate: {}, module: {}", crate_name, _vfs.file_path(file_id));
-            for diagnostic in analysis
-                .diagnostics(&DiagnosticsConfig::default(), AssistResolveStrategy::None, file_id)
-                .unwrap()
+            for diagnostic in
+                analysis.diagnostics(&config, AssistResolveStrategy::None, file_id).unwrap()
             {
                 if matches!(diagnostic.severity, Severity::Error) {
                     found_error = true;
@@ -0,0 +1,69 @@
+//! Prints the monikers of the definitions of a project, for code intelligence
+//! tools linking references across repositories.
+
+use std::path::Path;
+
+use rustc_hash::FxHashSet;
+
+use hir::Crate;
+use ide::FilePosition;
+use ide_db::base_db::SourceDatabaseExt;
+use syntax::{ast, AstNode};
+
+use crate::{
+    cli::{
+        load_cargo::{load_workspace_at, LoadCargoConfig},
+        Result,
+    },
+    to_proto,
+};
+
+pub fn monikers(path: &Path, load_out_dirs_from_check: bool, with_proc_macro: bool) -> Result<()> {
+    let cargo_config = Default::default();
+    let load_cargo_config = LoadCargoConfig {
+        load_out_dirs_from_check,
+        with_proc_macro,
+        wrap_rustc: false,
+        prefill_caches: false,
+    };
+    let (host, vfs, _proc_macro) =
+        load_workspace_at(path, &cargo_config, &load_cargo_config, &|_| {})?;
+    let db = host.raw_database();
+    let analysis = host.analysis();
+
+    let mut visited_files = FxHashSet::default();
+    for krate in Crate::all(db) {
+        let source_root = db.file_source_root(krate.root_file(db));
+        if db.source_root(source_root).is_library {
+            continue;
+        }
+        for file_id in db.source_root(source_root).iter() {
+            if !visited_files.insert(file_id) {
+                continue;
+            }
+            let line_index = analysis.file_line_index(file_id)?;
+            let file = analysis.parse(file_id)?;
+            for name in file.syntax().descendants().filter_map(ast::Name::cast) {
+                let offset = name.syntax().text_range().start();
+                let monikers = match analysis.moniker(FilePosition { file_id, offset })? {
+                    Some(it) => it.info,
+                    None => continue,
+                };
+                let line_col = line_index.line_col(offset);
+                for moniker in monikers {
+                    let moniker = to_proto::moniker(moniker);
+                    println!(
+                        "{}:{}:{} {} {}",
+                        vfs.file_path(file_id),
+                        line_col.line + 1,
+                        line_col.col + 1,
+                        moniker.scheme,
+                        moniker.identifier
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
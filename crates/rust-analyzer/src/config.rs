@@ -101,6 +101,13 @@ config_data! {
         /// checking. The command should include `--message-format=json` or
         /// similar option.
         checkOnSave_overrideCommand: Option<Vec<String>> = "null",
+        /// Check only the packages owning the saved file (`-p <package>`)
+        /// instead of the whole workspace. Doesn't apply to
+        /// `#rust-analyzer.checkOnSave.overrideCommand#`.
+        checkOnSave_perPackage: bool                     = "false",
+        /// Whether per-package checks also check the workspace members which
+        /// depend on the saved file's packages.
+        checkOnSave_reverseDependencies: bool            = "false",
 
         /// Whether to add argument snippets when completing functions.
         /// Only applies when `#rust-analyzer.completion.addCallParenthesis#` is set.
@@ -115,6 +122,10 @@ config_data! {
         /// Toggles the additional completions that automatically show method calls and field accesses
         /// with `self` prefixed to them when inside a method.
         completion_autoself_enable: bool       = "true",
+        /// Toggles the additional completions that search for expressions of the
+        /// expected type, built from the locals, fields, method calls and
+        /// constructors in scope.
+        completion_termSearch_enable: bool     = "false",
 
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
@@ -123,6 +134,12 @@ config_data! {
         diagnostics_enableExperimental: bool    = "true",
         /// List of rust-analyzer diagnostics to disable.
         diagnostics_disabled: FxHashSet<String> = "[]",
+        /// Whether to show the diagnostics helping to migrate crates using
+        /// older editions to Rust 2021, and the fix migrating a whole crate.
+        diagnostics_editionMigration_enable: bool = "false",
+        /// Whether to show the `dead-code` diagnostic for private items that
+        /// are never used. This can be slow on large crates.
+        diagnostics_deadCode_enable: bool       = "false",
         /// Map of prefixes to be substituted when parsing diagnostic file paths.
         /// This should be the reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = "{}",
@@ -137,6 +154,13 @@ config_data! {
         /// and a blue icon in the `Problems Panel`.
         diagnostics_warningsAsInfo: Vec<String> = "[]",
 
+        /// Command printing a project in the `rust-project.json` format on
+        /// stdout, for projects whose build system generates it. It is
+        /// invoked with the path of an opened Rust file that doesn't belong to
+        /// any project as last argument, and again when the build files of the
+        /// project it printed change.
+        discoverProjectCommand: Option<Vec<String>> = "null",
+
         /// Expand attribute macros.
         experimental_procAttrMacros: bool = "false",
 
@@ -271,12 +295,29 @@ impl Default for ConfigData {
 #[derive(Debug, Clone)]
 pub struct Config {
     pub caps: lsp_types::ClientCapabilities,
+    /// Client capabilities for pull diagnostics, which `lsp_types` predates.
+    pull_diagnostics_caps: PullDiagnosticsCaps,
     data: ConfigData,
     detached_files: Vec<AbsPathBuf>,
+    /// The workspace folders opened in the client, in which projects are
+    /// discovered when no `linkedProjects` are configured.
+    pub workspace_roots: Vec<AbsPathBuf>,
     pub discovered_projects: Option<Vec<ProjectManifest>>,
+    /// The projects printed by `discoverProjectCommand`, with the file it was
+    /// invoked with.
+    pub discovered_project_jsons: Vec<(AbsPathBuf, ProjectJson)>,
+    /// Cargo features enabled from the editor (see
+    /// `to_proto::command::enable_cargo_feature`), on top of `cargo.features`.
+    pub enabled_cargo_features: Vec<String>,
     pub root_path: AbsPathBuf,
 }
 
+#[derive(Debug, Clone, Default)]
+struct PullDiagnosticsCaps {
+    pull: bool,
+    refresh: bool,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum LinkedProject {
     ProjectManifest(ProjectManifest),
@@ -376,6 +417,13 @@ pub enum RustfmtConfig {
     CustomCommand { command: String, args: Vec<String> },
 }
 
+/// Configuration for checking only the packages owning a saved file.
+#[derive(Debug, Clone, Copy)]
+pub struct FlycheckPackagesConfig {
+    /// Whether to also check the workspace members depending on those packages.
+    pub reverse_dependencies: bool,
+}
+
 /// Configuration for runnable items, such as `main` function or tests.
 #[derive(Debug, Clone)]
 pub struct RunnablesConfig {
@@ -398,9 +446,13 @@ impl Config {
     pub fn new(root_path: AbsPathBuf, caps: ClientCapabilities) -> Self {
         Config {
             caps,
+            pull_diagnostics_caps: PullDiagnosticsCaps::default(),
             data: ConfigData::default(),
             detached_files: Vec::new(),
+            workspace_roots: Vec::new(),
             discovered_projects: None,
+            discovered_project_jsons: Vec::new(),
+            enabled_cargo_features: Vec::new(),
             root_path,
         }
     }
@@ -433,7 +485,26 @@ macro_rules! try_or {
 }
 
 impl Config {
+    pub fn has_linked_projects(&self) -> bool {
+        !self.data.linkedProjects.is_empty()
+    }
+
+    pub fn rediscover_workspaces(&mut self) {
+        let discovered = ProjectManifest::discover_all(&self.workspace_roots);
+        log::info!("discovered projects: {:?}", discovered);
+        if discovered.is_empty() {
+            log::error!("failed to find any projects in {:?}", self.workspace_roots);
+        }
+        self.discovered_projects = Some(discovered);
+    }
+
     pub fn linked_projects(&self) -> Vec<LinkedProject> {
+        let mut res = self.configured_projects();
+        res.extend(self.discovered_project_jsons.iter().map(|(_, project)| project.clone().into()));
+        res
+    }
+
+    fn configured_projects(&self) -> Vec<LinkedProject> {
         if self.data.linkedProjects.is_empty() {
             self.discovered_projects
                 .as_ref()
@@ -465,6 +536,13 @@ impl Config {
         }
     }
 
+    pub fn discover_project_command(&self) -> Option<&[String]> {
+        match &self.data.discoverProjectCommand {
+            Some(command) if !command.is_empty() => Some(command),
+            Some(_) | None => None,
+        }
+    }
+
     pub fn detached_files(&self) -> &[AbsPathBuf] {
         &self.detached_files
     }
@@ -585,6 +663,8 @@ impl Config {
         DiagnosticsConfig {
             disable_experimental: !self.data.diagnostics_enableExperimental,
             disabled: self.data.diagnostics_disabled.clone(),
+            edition_migration: self.data.diagnostics_editionMigration_enable,
+            dead_code: self.data.diagnostics_deadCode_enable,
         }
     }
     pub fn diagnostics_map(&self) -> DiagnosticsMapConfig {
@@ -643,7 +723,13 @@ impl Config {
         CargoConfig {
             no_default_features: self.data.cargo_noDefaultFeatures,
             all_features: self.data.cargo_allFeatures,
-            features: self.data.cargo_features.clone(),
+            features: self
+                .data
+                .cargo_features
+                .iter()
+                .chain(&self.enabled_cargo_features)
+                .cloned()
+                .collect(),
             target: self.data.cargo_target.clone(),
             rustc_source,
             no_sysroot: self.data.cargo_noSysroot,
@@ -694,12 +780,23 @@ impl Config {
                     .data
                     .checkOnSave_features
                     .clone()
-                    .unwrap_or_else(|| self.data.cargo_features.clone()),
+                    .unwrap_or_else(|| self.data.cargo_features.clone())
+                    .into_iter()
+                    .chain(self.enabled_cargo_features.iter().cloned())
+                    .collect(),
                 extra_args: self.data.checkOnSave_extraArgs.clone(),
             },
         };
         Some(flycheck_config)
     }
+    pub fn flycheck_per_package(&self) -> Option<FlycheckPackagesConfig> {
+        if !self.data.checkOnSave_perPackage {
+            return None;
+        }
+        Some(FlycheckPackagesConfig {
+            reverse_dependencies: self.data.checkOnSave_reverseDependencies,
+        })
+    }
     pub fn runnables(&self) -> RunnablesConfig {
         RunnablesConfig {
             override_cargo: self.data.runnables_overrideCargo.clone(),
@@ -738,6 +835,7 @@ impl Config {
             enable_imports_on_the_fly: self.data.completion_autoimport_enable
                 && completion_item_edit_resolve(&self.caps),
             enable_self_on_the_fly: self.data.completion_autoself_enable,
+            enable_term_search: self.data.completion_termSearch_enable,
             add_call_parenthesis: self.data.completion_addCallParenthesis,
             add_call_argument_snippets: self.data.completion_addCallArgumentSnippets,
             insert_use: self.insert_use_config(),
@@ -833,6 +931,21 @@ impl Config {
         }
     }
 
+    /// Reads the client capabilities for LSP 3.17 pull diagnostics from the raw
+    /// `ClientCapabilities` JSON.
+    pub fn update_pull_diagnostics_caps(&mut self, caps: &serde_json::Value) {
+        self.pull_diagnostics_caps = PullDiagnosticsCaps {
+            pull: caps.pointer("/textDocument/diagnostic").is_some(),
+            refresh: caps.pointer("/workspace/diagnostics/refreshSupport")
+                == Some(&serde_json::Value::Bool(true)),
+        };
+    }
+    pub fn pull_diagnostics(&self) -> bool {
+        self.pull_diagnostics_caps.pull
+    }
+    pub fn diagnostics_refresh(&self) -> bool {
+        self.pull_diagnostics_caps.refresh
+    }
     pub fn semantic_tokens_refresh(&self) -> bool {
         try_or!(self.caps.workspace.as_ref()?.semantic_tokens.as_ref()?.refresh_support?, false)
     }
@@ -8,7 +8,20 @@ use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::lsp_ext;
 
+pub(crate) type CheckDiagnostics = Arc<FxHashMap<FileId, Vec<lsp_types::Diagnostic>>>;
 pub(crate) type CheckFixes = Arc<FxHashMap<FileId, Vec<Fix>>>;
+/// How often the contents or the check diagnostics of each file changed, see
+/// [`PulledDiagnostics`].
+pub(crate) type FileRevisions = Arc<FxHashMap<FileId, u64>>;
+
+/// The diagnostics of a closed file reported by `workspace/diagnostic`, which
+/// are reused until the revision of the file changes.
+#[derive(Debug, Clone)]
+pub(crate) struct PulledDiagnostics {
+    pub(crate) revision: u64,
+    pub(crate) result_id: String,
+    pub(crate) items: Vec<lsp_types::Diagnostic>,
+}
 
 #[derive(Debug, Default, Clone)]
 pub struct DiagnosticsMapConfig {
@@ -22,8 +35,10 @@ pub(crate) struct DiagnosticCollection {
     // FIXME: should be FxHashMap<FileId, Vec<ra_id::Diagnostic>>
     pub(crate) native: FxHashMap<FileId, Vec<lsp_types::Diagnostic>>,
     // FIXME: should be Vec<flycheck::Diagnostic>
-    pub(crate) check: FxHashMap<FileId, Vec<lsp_types::Diagnostic>>,
+    pub(crate) check: CheckDiagnostics,
     pub(crate) check_fixes: CheckFixes,
+    /// The files with check diagnostics emitted for each cargo package id.
+    check_package_files: FxHashMap<String, FxHashSet<FileId>>,
     changes: FxHashSet<FileId>,
 }
 
@@ -36,16 +51,40 @@ pub(crate) struct Fix {
 impl DiagnosticCollection {
     pub(crate) fn clear_check(&mut self) {
         Arc::make_mut(&mut self.check_fixes).clear();
-        self.changes.extend(self.check.drain().map(|(key, _value)| key))
+        self.check_package_files.clear();
+        self.changes.extend(Arc::make_mut(&mut self.check).drain().map(|(key, _value)| key))
+    }
+
+    /// Clears the check diagnostics of the files which the given packages
+    /// emitted diagnostics for.
+    ///
+    /// Diagnostics are tracked per file, so this also drops the diagnostics
+    /// other packages emitted for those files.
+    pub(crate) fn clear_check_for_packages(&mut self, package_ids: &[String]) {
+        for package_id in package_ids {
+            let files = match self.check_package_files.remove(package_id) {
+                Some(it) => it,
+                None => continue,
+            };
+            for file_id in files {
+                Arc::make_mut(&mut self.check).remove(&file_id);
+                Arc::make_mut(&mut self.check_fixes).remove(&file_id);
+                self.changes.insert(file_id);
+            }
+        }
     }
 
     pub(crate) fn add_check_diagnostic(
         &mut self,
         file_id: FileId,
+        package_id: Option<String>,
         diagnostic: lsp_types::Diagnostic,
         fixes: Vec<lsp_ext::CodeAction>,
     ) {
-        let diagnostics = self.check.entry(file_id).or_default();
+        if let Some(package_id) = package_id {
+            self.check_package_files.entry(package_id).or_default().insert(file_id);
+        }
+        let diagnostics = Arc::make_mut(&mut self.check).entry(file_id).or_default();
         for existing_diagnostic in diagnostics.iter() {
             if are_diagnostics_equal(existing_diagnostic, &diagnostic) {
                 return;
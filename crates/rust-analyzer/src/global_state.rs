@@ -12,14 +12,17 @@ use ide_db::base_db::{CrateId, VfsPath};
 use lsp_types::{SemanticTokens, Url};
 use parking_lot::{Mutex, RwLock};
 use project_model::{
-    BuildDataCollector, BuildDataResult, CargoWorkspace, ProcMacroClient, ProjectWorkspace, Target,
+    project_json, BuildDataCollector, BuildDataResult, CargoWorkspace, ProcMacroClient,
+    ProjectWorkspace, Target,
 };
-use rustc_hash::FxHashMap;
-use vfs::AnchoredPathBuf;
+use rustc_hash::{FxHashMap, FxHashSet};
+use vfs::{AbsPathBuf, AnchoredPathBuf};
 
 use crate::{
     config::Config,
-    diagnostics::{CheckFixes, DiagnosticCollection},
+    diagnostics::{
+        CheckDiagnostics, CheckFixes, DiagnosticCollection, FileRevisions, PulledDiagnostics,
+    },
     document::DocumentData,
     from_proto,
     line_index::{LineEndings, LineIndex},
@@ -59,6 +62,8 @@ pub(crate) struct GlobalState {
     pub(crate) diagnostics: DiagnosticCollection,
     pub(crate) mem_docs: FxHashMap<VfsPath, DocumentData>,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) file_revisions: FileRevisions,
+    pub(crate) pulled_diagnostics_cache: Arc<Mutex<FxHashMap<FileId, PulledDiagnostics>>>,
     pub(crate) shutdown_requested: bool,
     pub(crate) last_reported_status: Option<lsp_ext::ServerStatusParams>,
     pub(crate) source_root_config: SourceRootConfig,
@@ -80,11 +85,16 @@ pub(crate) struct GlobalState {
     ///
     /// If the fetch (partially) fails, we do not update the values.
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
-    pub(crate) fetch_workspaces_queue: OpQueue<(), Vec<anyhow::Result<ProjectWorkspace>>>,
+    /// The argument of a fetch is whether already loaded workspaces can be
+    /// reused, see `fetch_new_workspaces_request`.
+    pub(crate) fetch_workspaces_queue: OpQueue<bool, Vec<anyhow::Result<ProjectWorkspace>>>,
     pub(crate) workspace_build_data: Option<BuildDataResult>,
     pub(crate) fetch_build_data_queue:
         OpQueue<BuildDataCollector, Option<anyhow::Result<BuildDataResult>>>,
     pub(crate) prime_caches_queue: OpQueue<(), ()>,
+    /// The files `discoverProjectCommand` was invoked with, so that it isn't
+    /// invoked again when they are reopened.
+    pub(crate) discover_project_files: FxHashSet<AbsPathBuf>,
 
     latest_requests: Arc<RwLock<LatestRequests>>,
 }
@@ -93,10 +103,13 @@ pub(crate) struct GlobalState {
 pub(crate) struct GlobalStateSnapshot {
     pub(crate) config: Arc<Config>,
     pub(crate) analysis: Analysis,
+    pub(crate) check_diagnostics: CheckDiagnostics,
     pub(crate) check_fixes: CheckFixes,
     pub(crate) latest_requests: Arc<RwLock<LatestRequests>>,
     mem_docs: FxHashMap<VfsPath, DocumentData>,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) file_revisions: FileRevisions,
+    pub(crate) pulled_diagnostics_cache: Arc<Mutex<FxHashMap<FileId, PulledDiagnostics>>>,
     vfs: Arc<RwLock<(vfs::Vfs, FxHashMap<FileId, LineEndings>)>>,
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
 }
@@ -129,6 +142,8 @@ impl GlobalState {
             diagnostics: Default::default(),
             mem_docs: FxHashMap::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
+            file_revisions: Arc::new(Default::default()),
+            pulled_diagnostics_cache: Arc::new(Default::default()),
             shutdown_requested: false,
             last_reported_status: None,
             source_root_config: SourceRootConfig::default(),
@@ -148,6 +163,7 @@ impl GlobalState {
             fetch_workspaces_queue: OpQueue::default(),
             workspace_build_data: None,
             prime_caches_queue: OpQueue::default(),
+            discover_project_files: FxHashSet::default(),
 
             fetch_build_data_queue: OpQueue::default(),
             latest_requests: Default::default(),
@@ -170,7 +186,9 @@ impl GlobalState {
                 return false;
             }
 
+            let revisions = Arc::make_mut(&mut self.file_revisions);
             for file in changed_files {
+                *revisions.entry(file.file_id).or_default() += 1;
                 if file.is_created_or_deleted() {
                     if let Some(path) = vfs.file_path(file.file_id).as_path() {
                         fs_changes.push((path.to_path_buf(), file.change_kind));
@@ -205,6 +223,21 @@ impl GlobalState {
         true
     }
 
+    /// Marks the diagnostics pulled for the given files as stale.
+    pub(crate) fn bump_file_revisions(&mut self, file_ids: impl IntoIterator<Item = FileId>) {
+        let revisions = Arc::make_mut(&mut self.file_revisions);
+        for file_id in file_ids {
+            *revisions.entry(file_id).or_default() += 1;
+        }
+    }
+
+    /// Marks all pulled diagnostics as stale, as the diagnostics of a file also
+    /// depend on the other files.
+    pub(crate) fn bump_all_file_revisions(&mut self) {
+        let file_ids = self.pulled_diagnostics_cache.lock().keys().copied().collect::<Vec<_>>();
+        self.bump_file_revisions(file_ids);
+    }
+
     pub(crate) fn snapshot(&self) -> GlobalStateSnapshot {
         GlobalStateSnapshot {
             config: Arc::clone(&self.config),
@@ -212,9 +245,12 @@ impl GlobalState {
             analysis: self.analysis_host.analysis(),
             vfs: Arc::clone(&self.vfs),
             latest_requests: Arc::clone(&self.latest_requests),
+            check_diagnostics: Arc::clone(&self.diagnostics.check),
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
             mem_docs: self.mem_docs.clone(),
             semantic_tokens_cache: Arc::clone(&self.semantic_tokens_cache),
+            file_revisions: Arc::clone(&self.file_revisions),
+            pulled_diagnostics_cache: Arc::clone(&self.pulled_diagnostics_cache),
         }
     }
 
@@ -290,6 +326,28 @@ impl GlobalStateSnapshot {
         Ok(res)
     }
 
+    /// Returns the Rust files of the workspace which aren't open in the editor.
+    pub(crate) fn closed_workspace_files(&self) -> Cancellable<Vec<FileId>> {
+        let files = self
+            .vfs
+            .read()
+            .0
+            .iter()
+            .filter(|(_, path)| {
+                !self.mem_docs.contains_key(*path)
+                    && path.name_and_extension().map_or(false, |(_, ext)| ext == Some("rs"))
+            })
+            .map(|(file_id, _)| file_id)
+            .collect::<Vec<_>>();
+        let mut res = Vec::new();
+        for file_id in files {
+            if !self.analysis.is_library_file(file_id)? {
+                res.push(file_id);
+            }
+        }
+        Ok(res)
+    }
+
     pub(crate) fn url_file_version(&self, url: &Url) -> Option<i32> {
         let path = from_proto::vfs_path(url).ok()?;
         Some(self.mem_docs.get(&path)?.version)
@@ -318,6 +376,19 @@ impl GlobalStateSnapshot {
             ProjectWorkspace::DetachedFiles { .. } => None,
         })
     }
+
+    pub(crate) fn project_json_crate_for_crate_root(
+        &self,
+        crate_id: CrateId,
+    ) -> Option<&project_json::Crate> {
+        let file_id = self.analysis.crate_root(crate_id).ok()?;
+        let path = self.vfs.read().0.file_path(file_id);
+        let path = path.as_path()?;
+        self.workspaces.iter().find_map(|ws| match ws {
+            ProjectWorkspace::Json { project, .. } => project.crate_by_root(path),
+            ProjectWorkspace::Cargo { .. } | ProjectWorkspace::DetachedFiles { .. } => None,
+        })
+    }
 }
 
 pub(crate) fn file_id_to_url(vfs: &vfs::Vfs, id: FileId) -> Url {
@@ -3,6 +3,7 @@
 //! `ide` crate.
 
 use std::{
+    hash::{Hash, Hasher},
     io::{Read, Write as _},
     process::{self, Command, Stdio},
 };
@@ -25,13 +26,15 @@ use lsp_types::{
     SemanticTokensResult, SymbolInformation, SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
 use project_model::TargetKind;
+use rustc_hash::{FxHashMap, FxHasher};
 use serde_json::json;
 use stdx::{format_to, never};
 use syntax::{algo, ast, AstNode, TextRange, TextSize};
 
 use crate::{
-    cargo_target_spec::CargoTargetSpec,
-    config::RustfmtConfig,
+    cargo_target_spec::{CargoTargetSpec, ProjectJsonTargetSpec},
+    config::{Config, RustfmtConfig},
+    diagnostics::PulledDiagnostics,
     diff::diff,
     from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
@@ -97,6 +100,30 @@ pub(crate) fn handle_memory_usage(state: &mut GlobalState, _: ()) -> Result<Stri
     Ok(out)
 }
 
+pub(crate) fn handle_execute_command(
+    state: &mut GlobalState,
+    params: lsp_types::ExecuteCommandParams,
+) -> Result<Option<serde_json::Value>> {
+    let _p = profile::span("handle_execute_command");
+    match params.command.as_str() {
+        to_proto::command::ENABLE_CARGO_FEATURE => {
+            let feature: String = match params.arguments.into_iter().next() {
+                Some(it) => serde_json::from_value(it)?,
+                None => return Err("missing feature argument".into()),
+            };
+            let mut config = Config::clone(&*state.config);
+            if !config.enabled_cargo_features.contains(&feature) {
+                config.enabled_cargo_features.push(feature);
+            }
+            state.update_configuration(config);
+            state.fetch_workspaces_request();
+            state.fetch_workspaces_if_needed();
+            Ok(None)
+        }
+        command => Err(format!("unknown command: {}", command).into()),
+    }
+}
+
 pub(crate) fn handle_syntax_tree(
     snap: GlobalStateSnapshot,
     params: lsp_ext::SyntaxTreeParams,
@@ -629,6 +656,8 @@ pub(crate) fn handle_runnables(
     let line_index = snap.file_line_index(file_id)?;
     let offset = params.position.map(|it| from_proto::offset(&line_index, it));
     let cargo_spec = CargoTargetSpec::for_file(&snap, file_id)?;
+    let json_spec = ProjectJsonTargetSpec::for_file(&snap, file_id)?;
+    let target_kind = target_kind(cargo_spec.as_ref(), json_spec.as_ref());
 
     let expect_test = match offset {
         Some(offset) => {
@@ -647,13 +676,15 @@ pub(crate) fn handle_runnables(
                 continue;
             }
         }
-        if should_skip_target(&runnable, cargo_spec.as_ref()) {
+        if should_skip_target(&runnable, target_kind) {
             continue;
         }
         let mut runnable = to_proto::runnable(&snap, runnable)?;
         if expect_test {
-            runnable.label = format!("{} + expect", runnable.label);
-            runnable.args.expect_test = Some(true);
+            if let lsp_ext::RunnableArgs::Cargo(args) = &mut runnable.args {
+                runnable.label = format!("{} + expect", runnable.label);
+                args.expect_test = Some(true);
+            }
         }
         res.push(runnable);
     }
@@ -667,7 +698,7 @@ pub(crate) fn handle_runnables(
                     label: format!("cargo {} -p {} --all-targets", cmd, spec.package),
                     location: None,
                     kind: lsp_ext::RunnableKind::Cargo,
-                    args: lsp_ext::CargoRunnable {
+                    args: lsp_ext::RunnableArgs::Cargo(lsp_ext::CargoRunnable {
                         workspace_root: Some(spec.workspace_root.clone().into()),
                         override_cargo: config.override_cargo.clone(),
                         cargo_args: vec![
@@ -679,12 +710,20 @@ pub(crate) fn handle_runnables(
                         cargo_extra_args: config.cargo_extra_args.clone(),
                         executable_args: Vec::new(),
                         expect_test: None,
-                    },
+                    }),
                 })
             }
         }
         None => {
-            if !snap.config.linked_projects().is_empty()
+            // Add the check command of the build system of `rust-project.json` crates.
+            if let Some(args) = json_spec.as_ref().and_then(|spec| spec.check_args()) {
+                res.push(lsp_ext::Runnable {
+                    label: format!("{} {}", args.program, args.args.join(" ")),
+                    location: None,
+                    kind: lsp_ext::RunnableKind::Shell,
+                    args: lsp_ext::RunnableArgs::Shell(args),
+                });
+            } else if !snap.config.linked_projects().is_empty()
                 || !snap
                     .config
                     .discovered_projects
@@ -696,14 +735,14 @@ pub(crate) fn handle_runnables(
                     label: "cargo check --workspace".to_string(),
                     location: None,
                     kind: lsp_ext::RunnableKind::Cargo,
-                    args: lsp_ext::CargoRunnable {
+                    args: lsp_ext::RunnableArgs::Cargo(lsp_ext::CargoRunnable {
                         workspace_root: None,
                         override_cargo: config.override_cargo,
                         cargo_args: vec!["check".to_string(), "--workspace".to_string()],
                         cargo_extra_args: config.cargo_extra_args,
                         executable_args: Vec::new(),
                         expect_test: None,
-                    },
+                    }),
                 });
             }
         }
@@ -1018,6 +1057,12 @@ pub(crate) fn handle_code_action(
         let resolve_data =
             if code_action_resolve_cap { Some((index, params.clone())) } else { None };
         let code_action = to_proto::code_action(&snap, assist, resolve_data)?;
+        // Skip commands we can't run here, like enabling a Cargo feature of a
+        // crate which isn't part of a Cargo workspace.
+        if code_action.command.is_none() && code_action.edit.is_none() && code_action.data.is_none()
+        {
+            continue;
+        }
         res.push(code_action)
     }
 
@@ -1132,15 +1177,13 @@ pub(crate) fn handle_code_lens(
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let cargo_target_spec = CargoTargetSpec::for_file(&snap, file_id)?;
+    let json_target_spec = ProjectJsonTargetSpec::for_file(&snap, file_id)?;
 
     let annotations = snap.analysis.annotations(
         &AnnotationConfig {
-            binary_target: cargo_target_spec
-                .map(|spec| {
-                    matches!(
-                        spec.target_kind,
-                        TargetKind::Bin | TargetKind::Example | TargetKind::Test
-                    )
+            binary_target: target_kind(cargo_target_spec.as_ref(), json_target_spec.as_ref())
+                .map(|kind| {
+                    matches!(kind, TargetKind::Bin | TargetKind::Example | TargetKind::Test)
                 })
                 .unwrap_or(false),
             annotate_runnables: lens_config.runnable(),
@@ -1254,6 +1297,101 @@ pub(crate) fn publish_diagnostics(
     Ok(diagnostics)
 }
 
+pub(crate) fn handle_document_diagnostic(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DocumentDiagnosticParams,
+) -> Result<lsp_ext::DocumentDiagnosticReport> {
+    let _p = profile::span("handle_document_diagnostic");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let items = file_diagnostics(&snap, file_id)?;
+    let result_id = diagnostics_result_id(&items);
+    let res = if params.previous_result_id.as_ref() == Some(&result_id) {
+        lsp_ext::DocumentDiagnosticReport::Unchanged(lsp_ext::UnchangedDocumentDiagnosticReport {
+            result_id,
+        })
+    } else {
+        lsp_ext::DocumentDiagnosticReport::Full(lsp_ext::FullDocumentDiagnosticReport {
+            result_id: Some(result_id),
+            items,
+        })
+    };
+    Ok(res)
+}
+
+pub(crate) fn handle_workspace_diagnostic(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::WorkspaceDiagnosticParams,
+) -> Result<lsp_ext::WorkspaceDiagnosticReport> {
+    let _p = profile::span("handle_workspace_diagnostic");
+    let previous_result_ids: FxHashMap<Url, String> =
+        params.previous_result_ids.into_iter().map(|it| (it.uri, it.value)).collect();
+
+    // Open documents are reported through `textDocument/diagnostic`.
+    let mut items = Vec::new();
+    for file_id in snap.closed_workspace_files()? {
+        let uri = snap.file_id_to_url(file_id);
+        let revision = snap.file_revisions.get(&file_id).copied().unwrap_or_default();
+        let cached = snap
+            .pulled_diagnostics_cache
+            .lock()
+            .get(&file_id)
+            .filter(|it| it.revision == revision)
+            .cloned();
+        let PulledDiagnostics { result_id, items: diagnostics, .. } = match cached {
+            Some(it) => it,
+            None => {
+                let items = file_diagnostics(&snap, file_id)?;
+                let result_id = diagnostics_result_id(&items);
+                let pulled = PulledDiagnostics { revision, result_id, items };
+                snap.pulled_diagnostics_cache.lock().insert(file_id, pulled.clone());
+                pulled
+            }
+        };
+        let item = if previous_result_ids.get(&uri) == Some(&result_id) {
+            lsp_ext::WorkspaceDocumentDiagnosticReport::Unchanged(
+                lsp_ext::WorkspaceUnchangedDocumentDiagnosticReport {
+                    uri,
+                    version: None,
+                    report: lsp_ext::UnchangedDocumentDiagnosticReport { result_id },
+                },
+            )
+        } else {
+            lsp_ext::WorkspaceDocumentDiagnosticReport::Full(
+                lsp_ext::WorkspaceFullDocumentDiagnosticReport {
+                    uri,
+                    version: None,
+                    report: lsp_ext::FullDocumentDiagnosticReport {
+                        result_id: Some(result_id),
+                        items: diagnostics,
+                    },
+                },
+            )
+        };
+        items.push(item);
+    }
+    Ok(lsp_ext::WorkspaceDiagnosticReport { items })
+}
+
+/// Computes the native diagnostics of a file, together with the last `cargo
+/// check` diagnostics reported for it.
+fn file_diagnostics(snap: &GlobalStateSnapshot, file_id: FileId) -> Result<Vec<Diagnostic>> {
+    let mut res = if snap.config.publish_diagnostics() {
+        publish_diagnostics(snap, file_id)?
+    } else {
+        Vec::new()
+    };
+    res.extend(snap.check_diagnostics.get(&file_id).into_iter().flatten().cloned());
+    Ok(res)
+}
+
+/// The result id of a diagnostics report, which lets the client tell us which
+/// diagnostics it already has, so that unchanged reports aren't resent.
+fn diagnostics_result_id(diagnostics: &[Diagnostic]) -> String {
+    let mut hasher = FxHasher::default();
+    serde_json::to_string(diagnostics).unwrap().hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
 pub(crate) fn handle_inlay_hints(
     snap: GlobalStateSnapshot,
     params: InlayHintsParams,
@@ -1361,6 +1499,89 @@ pub(crate) fn handle_call_hierarchy_outgoing(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_type_hierarchy_prepare(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyPrepareParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_prepare");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+
+    let nav_info = match snap.analysis.type_hierarchy(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let RangeInfo { range: _, info: navs } = nav_info;
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_supertypes(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyItemParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_supertypes");
+    let item = params.item;
+
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange = from_proto::file_range(&snap, doc, item.selection_range)?;
+    let fpos = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+
+    let navs = match snap.analysis.supertypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_subtypes(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyItemParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_subtypes");
+    let item = params.item;
+
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange = from_proto::file_range(&snap, doc, item.selection_range)?;
+    let fpos = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+
+    let navs = match snap.analysis.subtypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_moniker(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::MonikerParams,
+) -> Result<Option<Vec<lsp_types::Moniker>>> {
+    let _p = profile::span("handle_moniker");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+
+    let monikers = match snap.analysis.moniker(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res = monikers.info.into_iter().map(to_proto::moniker).collect();
+
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_semantic_tokens_full(
     snap: GlobalStateSnapshot,
     params: SemanticTokensParams,
@@ -1547,8 +1768,10 @@ fn runnable_action_links(
     runnable: Runnable,
 ) -> Option<lsp_ext::CommandLinkGroup> {
     let cargo_spec = CargoTargetSpec::for_file(snap, runnable.nav.file_id).ok()?;
+    let json_spec = ProjectJsonTargetSpec::for_file(snap, runnable.nav.file_id).ok()?;
+    let target_kind = target_kind(cargo_spec.as_ref(), json_spec.as_ref());
     let hover_actions_config = snap.config.hover_actions();
-    if !hover_actions_config.runnable() || should_skip_target(&runnable, cargo_spec.as_ref()) {
+    if !hover_actions_config.runnable() || should_skip_target(&runnable, target_kind) {
         return None;
     }
 
@@ -1561,7 +1784,7 @@ fn runnable_action_links(
             group.commands.push(to_command_link(run_command, r.label.clone()));
         }
 
-        if hover_actions_config.debug {
+        if hover_actions_config.debug && matches!(r.kind, lsp_ext::RunnableKind::Cargo) {
             let dbg_command = to_proto::command::debug_single(&r);
             group.commands.push(to_command_link(dbg_command, r.label));
         }
@@ -1609,15 +1832,21 @@ fn prepare_hover_actions(
         .collect()
 }
 
-fn should_skip_target(runnable: &Runnable, cargo_spec: Option<&CargoTargetSpec>) -> bool {
+fn target_kind(
+    cargo_spec: Option<&CargoTargetSpec>,
+    json_spec: Option<&ProjectJsonTargetSpec>,
+) -> Option<TargetKind> {
+    cargo_spec.map(|spec| spec.target_kind).or_else(|| json_spec.map(|spec| spec.target_kind))
+}
+
+fn should_skip_target(runnable: &Runnable, target_kind: Option<TargetKind>) -> bool {
     match runnable.kind {
         RunnableKind::Bin => {
             // Do not suggest binary run on other target than binary
-            match &cargo_spec {
-                Some(spec) => !matches!(
-                    spec.target_kind,
-                    TargetKind::Bin | TargetKind::Example | TargetKind::Test
-                ),
+            match target_kind {
+                Some(kind) => {
+                    !matches!(kind, TargetKind::Bin | TargetKind::Example | TargetKind::Test)
+                }
                 None => true,
             }
         }
@@ -8,12 +8,14 @@ use std::{
 
 use always_assert::always;
 use crossbeam_channel::{select, Receiver};
+use flycheck::FlycheckConfig;
 use ide::{FileId, PrimeCachesProgress};
 use ide_db::base_db::VfsPath;
 use lsp_server::{Connection, Notification, Request, Response};
 use lsp_types::notification::Notification as _;
-use project_model::BuildDataCollector;
-use vfs::ChangeKind;
+use project_model::{BuildDataCollector, ProjectJson};
+use rustc_hash::FxHashMap;
+use vfs::{AbsPathBuf, ChangeKind};
 
 use crate::{
     config::Config,
@@ -66,6 +68,7 @@ pub(crate) enum Task {
     PrimeCaches(PrimeCachesProgress),
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
+    DiscoverProject(AbsPathBuf, anyhow::Result<ProjectJson>),
 }
 
 impl fmt::Debug for Event {
@@ -234,6 +237,7 @@ impl GlobalState {
                                     let old = Arc::clone(&self.workspaces);
                                     self.switch_workspaces();
                                     let workspaces_updated = !Arc::ptr_eq(&old, &self.workspaces);
+                                    self.discover_projects_of_open_files();
 
                                     if self.config.run_build_scripts() && workspaces_updated {
                                         let mut collector =
@@ -269,6 +273,9 @@ impl GlobalState {
                                 self.report_progress("Loading", state, msg, None);
                             }
                         }
+                        Task::DiscoverProject(file, project) => {
+                            self.discover_project_completed(file, project)
+                        }
                     }
 
                     // Coalesce multiple task events into one loop turn
@@ -350,7 +357,11 @@ impl GlobalState {
                 let _p = profile::span("GlobalState::handle_event/flycheck");
                 loop {
                     match task {
-                        flycheck::Message::AddDiagnostic { workspace_root, diagnostic } => {
+                        flycheck::Message::AddDiagnostic {
+                            workspace_root,
+                            package_id,
+                            diagnostic,
+                        } => {
                             let diagnostics =
                                 crate::diagnostics::to_proto::map_rust_diagnostic_to_lsp(
                                     &self.config.diagnostics_map(),
@@ -361,6 +372,7 @@ impl GlobalState {
                                 match url_to_file_id(&self.vfs.read().0, &diag.url) {
                                     Ok(file_id) => self.diagnostics.add_check_diagnostic(
                                         file_id,
+                                        package_id.clone(),
                                         diag.diagnostic,
                                         diag.fixes,
                                     ),
@@ -376,8 +388,15 @@ impl GlobalState {
 
                         flycheck::Message::Progress { id, progress } => {
                             let (state, message) = match progress {
-                                flycheck::Progress::DidStart => {
-                                    self.diagnostics.clear_check();
+                                flycheck::Progress::DidStart { packages } => {
+                                    match packages {
+                                        Some(packages) => {
+                                            let package_ids =
+                                                self.flycheck_package_ids(id, &packages);
+                                            self.diagnostics.clear_check_for_packages(&package_ids)
+                                        }
+                                        None => self.diagnostics.clear_check(),
+                                    }
                                     (Progress::Begin, None)
                                 }
                                 flycheck::Progress::DidCheckCrate(target) => {
@@ -435,16 +454,24 @@ impl GlobalState {
         }
 
         if let Some(diagnostic_changes) = self.diagnostics.take_changes() {
-            for file_id in diagnostic_changes {
-                let url = file_id_to_url(&self.vfs.read().0, file_id);
-                let diagnostics = self.diagnostics.diagnostics_for(file_id).cloned().collect();
-                let version = from_proto::vfs_path(&url)
-                    .map(|path| self.mem_docs.get(&path).map(|it| it.version))
-                    .unwrap_or_default();
-
-                self.send_notification::<lsp_types::notification::PublishDiagnostics>(
-                    lsp_types::PublishDiagnosticsParams { uri: url, diagnostics, version },
-                );
+            if self.config.pull_diagnostics() {
+                self.bump_file_revisions(diagnostic_changes);
+                // The client pulls diagnostics itself, so just tell it they're stale.
+                if self.config.diagnostics_refresh() {
+                    self.send_request::<lsp_ext::WorkspaceDiagnosticRefresh>((), |_, _| ());
+                }
+            } else {
+                for file_id in diagnostic_changes {
+                    let url = file_id_to_url(&self.vfs.read().0, file_id);
+                    let diagnostics = self.diagnostics.diagnostics_for(file_id).cloned().collect();
+                    let version = from_proto::vfs_path(&url)
+                        .map(|path| self.mem_docs.get(&path).map(|it| it.version))
+                        .unwrap_or_default();
+
+                    self.send_notification::<lsp_types::notification::PublishDiagnostics>(
+                        lsp_types::PublishDiagnosticsParams { uri: url, diagnostics, version },
+                    );
+                }
             }
         }
 
@@ -511,6 +538,9 @@ impl GlobalState {
                 handlers::handle_matching_brace(s.snapshot(), p)
             })?
             .on_sync::<lsp_ext::MemoryUsage>(|s, p| handlers::handle_memory_usage(s, p))?
+            .on_sync::<lsp_types::request::ExecuteCommand>(|s, p| {
+                handlers::handle_execute_command(s, p)
+            })?
             .on::<lsp_ext::AnalyzerStatus>(handlers::handle_analyzer_status)
             .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)
             .on::<lsp_ext::ViewHir>(handlers::handle_view_hir)
@@ -528,6 +558,8 @@ impl GlobalState {
             .on::<lsp_ext::OpenCargoToml>(handlers::handle_open_cargo_toml)
             .on::<lsp_ext::MoveItem>(handlers::handle_move_item)
             .on::<lsp_ext::WorkspaceSymbol>(handlers::handle_workspace_symbol)
+            .on::<lsp_ext::DocumentDiagnosticRequest>(handlers::handle_document_diagnostic)
+            .on::<lsp_ext::WorkspaceDiagnosticRequest>(handlers::handle_workspace_diagnostic)
             .on::<lsp_types::request::OnTypeFormatting>(handlers::handle_on_type_formatting)
             .on::<lsp_types::request::DocumentSymbolRequest>(handlers::handle_document_symbol)
             .on::<lsp_types::request::GotoDefinition>(handlers::handle_goto_definition)
@@ -546,6 +578,10 @@ impl GlobalState {
             .on::<lsp_types::request::Formatting>(handlers::handle_formatting)
             .on::<lsp_types::request::RangeFormatting>(handlers::handle_range_formatting)
             .on::<lsp_types::request::DocumentHighlightRequest>(handlers::handle_document_highlight)
+            .on::<lsp_ext::TypeHierarchyPrepare>(handlers::handle_type_hierarchy_prepare)
+            .on::<lsp_ext::TypeHierarchySupertypes>(handlers::handle_type_hierarchy_supertypes)
+            .on::<lsp_ext::TypeHierarchySubtypes>(handlers::handle_type_hierarchy_subtypes)
+            .on::<lsp_types::request::MonikerRequest>(handlers::handle_moniker)
             .on::<lsp_types::request::CallHierarchyPrepare>(handlers::handle_call_hierarchy_prepare)
             .on::<lsp_types::request::CallHierarchyIncomingCalls>(
                 handlers::handle_call_hierarchy_incoming,
@@ -608,6 +644,9 @@ impl GlobalState {
                         this.maybe_update_diagnostics();
                     }
                 }
+                if let Ok(path) = from_proto::abs_path(&params.text_document.uri) {
+                    this.maybe_discover_project(path);
+                }
                 Ok(())
             })?
             .on::<lsp_types::notification::DidChangeTextDocument>(|this, params| {
@@ -660,10 +699,41 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidSaveTextDocument>(|this, params| {
+                // Edits of the saved file may have changed the diagnostics of closed files.
+                this.bump_all_file_revisions();
+                // Check only the packages owning the file when configured to, and the
+                // whole workspace if the file doesn't belong to any cargo package.
+                let file_id = url_to_file_id(&this.vfs.read().0, &params.text_document.uri).ok();
+                let packages = this
+                    .config
+                    .flycheck_per_package()
+                    .zip(file_id)
+                    .map(|(config, file_id)| this.flycheck_packages(file_id, config))
+                    .filter(|it| !it.is_empty());
+                // `rust-project.json` crates are checked with their own check command,
+                // unless a custom command is configured.
+                let mut json_commands = match (this.config.flycheck(), file_id) {
+                    (Some(FlycheckConfig::CustomCommand { .. }), _) | (_, None) => {
+                        FxHashMap::default()
+                    }
+                    (_, Some(file_id)) => this.flycheck_project_json_commands(file_id),
+                };
                 for flycheck in &this.flycheck {
-                    flycheck.update();
+                    if let Some((command, args, cwd)) = json_commands.remove(&flycheck.id()) {
+                        flycheck.update_with_command(command, args, cwd);
+                        continue;
+                    }
+                    match &packages {
+                        Some(packages) => {
+                            if let Some(packages) = packages.get(&flycheck.id()) {
+                                flycheck.update_packages(packages.clone());
+                            }
+                        }
+                        None => flycheck.update(),
+                    }
                 }
                 if let Ok(abs_path) = from_proto::abs_path(&params.text_document.uri) {
+                    this.rediscover_projects(&[abs_path.clone()]);
                     this.maybe_refresh(&[(abs_path, ChangeKind::Modify)]);
                 }
                 Ok(())
@@ -704,12 +774,35 @@ impl GlobalState {
 
                 Ok(())
             })?
+            .on::<lsp_types::notification::DidChangeWorkspaceFolders>(|this, params| {
+                let mut config = Config::clone(&*this.config);
+                for workspace in params.event.removed {
+                    if let Ok(path) = from_proto::abs_path(&workspace.uri) {
+                        config.workspace_roots.retain(|it| *it != path);
+                    }
+                }
+                for workspace in params.event.added {
+                    if let Ok(path) = from_proto::abs_path(&workspace.uri) {
+                        if !config.workspace_roots.contains(&path) {
+                            config.workspace_roots.push(path);
+                        }
+                    }
+                }
+                if !config.has_linked_projects() && config.detached_files().is_empty() {
+                    config.rediscover_workspaces();
+                }
+                this.update_configuration(config);
+                Ok(())
+            })?
             .on::<lsp_types::notification::DidChangeWatchedFiles>(|this, params| {
+                let mut changed = Vec::new();
                 for change in params.changes {
                     if let Ok(path) = from_proto::abs_path(&change.uri) {
-                        this.loader.handle.invalidate(path);
+                        this.loader.handle.invalidate(path.clone());
+                        changed.push(path);
                     }
                 }
+                this.rediscover_projects(&changed);
                 Ok(())
             })?
             .finish();
@@ -738,6 +831,10 @@ impl GlobalState {
         });
     }
     fn maybe_update_diagnostics(&mut self) {
+        if self.config.pull_diagnostics() {
+            // The client requests native diagnostics when it needs them.
+            return;
+        }
         let subscriptions = self
             .mem_docs
             .keys()
@@ -30,7 +30,6 @@ impl<Args, Output> OpQueue<Args, Output> {
         self.last_op_result = result;
     }
 
-    #[allow(unused)]
     pub(crate) fn last_op_result(&self) -> &Output {
         &self.last_op_result
     }
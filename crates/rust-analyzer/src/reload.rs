@@ -3,13 +3,18 @@ use std::{mem, sync::Arc};
 
 use flycheck::{FlycheckConfig, FlycheckHandle};
 use hir::db::DefDatabase;
-use ide::Change;
+use ide::{Change, FileId};
 use ide_db::base_db::{CrateGraph, SourceRoot, VfsPath};
-use project_model::{BuildDataCollector, BuildDataResult, ProcMacroClient, ProjectWorkspace};
+use itertools::Itertools;
+use project_model::{
+    project_json::RunnableKind, BuildDataCollector, BuildDataResult, ProcMacroClient, ProjectJson,
+    ProjectWorkspace,
+};
+use rustc_hash::FxHashMap;
 use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
 
 use crate::{
-    config::{Config, FilesWatcher, LinkedProject},
+    config::{Config, FilesWatcher, FlycheckPackagesConfig, LinkedProject},
     global_state::GlobalState,
     lsp_ext,
     main_loop::Task,
@@ -44,7 +49,11 @@ impl GlobalState {
             self.analysis_host.update_lru_capacity(self.config.lru_capacity());
         }
         if self.config.linked_projects() != old_config.linked_projects() {
-            self.fetch_workspaces_request()
+            if self.config.cargo() == old_config.cargo() {
+                self.fetch_new_workspaces_request()
+            } else {
+                self.fetch_workspaces_request()
+            }
         } else if self.config.flycheck() != old_config.flycheck() {
             self.reload_flycheck();
         }
@@ -144,14 +153,33 @@ impl GlobalState {
     }
 
     pub(crate) fn fetch_workspaces_request(&mut self) {
-        self.fetch_workspaces_queue.request_op(())
+        self.fetch_workspaces_queue.request_op(false)
     }
-    pub(crate) fn fetch_workspaces_if_needed(&mut self) {
-        if self.fetch_workspaces_queue.should_start_op().is_none() {
-            return;
+    /// Requests loading the linked projects which aren't loaded yet, keeping
+    /// the already loaded workspaces of the others.
+    pub(crate) fn fetch_new_workspaces_request(&mut self) {
+        // A pending full reload loads the new projects as well.
+        if !self.fetch_workspaces_queue.op_requested() {
+            self.fetch_workspaces_queue.request_op(true)
         }
+    }
+    pub(crate) fn fetch_workspaces_if_needed(&mut self) {
+        let reuse_loaded = match self.fetch_workspaces_queue.should_start_op() {
+            Some(it) => it,
+            None => return,
+        };
         log::info!("will fetch workspaces");
 
+        let loaded = if reuse_loaded {
+            self.fetch_workspaces_queue
+                .last_op_result()
+                .iter()
+                .filter_map(|res| res.as_ref().ok().cloned())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         self.task_pool.handle.spawn_with_sender({
             let linked_projects = self.config.linked_projects();
             let detached_files = self.config.detached_files().to_vec();
@@ -173,11 +201,14 @@ impl GlobalState {
                     .iter()
                     .map(|project| match project {
                         LinkedProject::ProjectManifest(manifest) => {
-                            project_model::ProjectWorkspace::load(
-                                manifest.clone(),
-                                &cargo_config,
-                                &progress,
-                            )
+                            match loaded.iter().find(|ws| ws.is_loaded_from(manifest)) {
+                                Some(ws) => Ok(ws.clone()),
+                                None => project_model::ProjectWorkspace::load(
+                                    manifest.clone(),
+                                    &cargo_config,
+                                    &progress,
+                                ),
+                            }
                         }
                         LinkedProject::InlineJsonProject(it) => {
                             project_model::ProjectWorkspace::load_inline(
@@ -200,6 +231,111 @@ impl GlobalState {
             }
         });
     }
+    /// Invokes `discoverProjectCommand` with `file` if it is a Rust file that
+    /// doesn't belong to any loaded workspace.
+    ///
+    /// While workspaces are being fetched, this does nothing: the open files
+    /// are checked again once they're loaded, in `discover_projects_of_open_files`.
+    pub(crate) fn maybe_discover_project(&mut self, file: AbsPathBuf) {
+        if self.config.discover_project_command().is_none()
+            || file.extension().unwrap_or_default() != "rs"
+            || self.discover_project_files.contains(&file)
+            || self.fetch_workspaces_queue.op_in_progress()
+            || self.fetch_workspaces_queue.op_requested()
+        {
+            return;
+        }
+        let build_data = self.workspace_build_data.as_ref();
+        let is_loaded = self
+            .workspaces
+            .iter()
+            .flat_map(|ws| ws.to_roots(build_data))
+            .any(|root| root.include.iter().any(|dir| file.starts_with(dir)));
+        if !is_loaded {
+            self.discover_project(file);
+        }
+    }
+
+    /// Invokes `discoverProjectCommand` for the open files which don't belong
+    /// to any of the just loaded workspaces.
+    pub(crate) fn discover_projects_of_open_files(&mut self) {
+        let files = self
+            .mem_docs
+            .keys()
+            .filter_map(|path| path.as_path().map(|it| it.to_path_buf()))
+            .collect::<Vec<_>>();
+        for file in files {
+            self.maybe_discover_project(file);
+        }
+    }
+
+    /// Invokes `discoverProjectCommand` again for the discovered projects
+    /// whose build files changed, or which got a new build file.
+    pub(crate) fn rediscover_projects(&mut self, changed: &[AbsPathBuf]) {
+        let files = self
+            .config
+            .discovered_project_jsons
+            .iter()
+            .filter(|(_, project)| {
+                changed.iter().any(|path| {
+                    project.crates().filter_map(|(_, krate)| krate.build()).any(|build| {
+                        // New build files, like the one of a new target, are
+                        // named like the existing ones.
+                        *path == build.build_file
+                            || (path.file_name() == build.build_file.file_name()
+                                && path.starts_with(project.path()))
+                    })
+                })
+            })
+            .map(|(file, _)| file.clone())
+            .collect::<Vec<_>>();
+        for file in files {
+            self.discover_project(file);
+        }
+    }
+
+    fn discover_project(&mut self, file: AbsPathBuf) {
+        let command = match self.config.discover_project_command() {
+            Some(it) => it.to_vec(),
+            None => return,
+        };
+        log::info!("discovering the project of {}", file.display());
+        self.discover_project_files.insert(file.clone());
+        let base = self
+            .config
+            .workspace_roots
+            .iter()
+            .find(|root| file.starts_with(root))
+            .unwrap_or(&self.config.root_path)
+            .clone();
+        self.task_pool.handle.spawn(move || {
+            let project = ProjectJson::discover(&command, &file, &base);
+            Task::DiscoverProject(file, project)
+        });
+    }
+
+    pub(crate) fn discover_project_completed(
+        &mut self,
+        file: AbsPathBuf,
+        project: anyhow::Result<ProjectJson>,
+    ) {
+        let project = match project {
+            Ok(it) => it,
+            Err(err) => {
+                log::error!("failed to discover the project of {}: {:#}", file.display(), err);
+                return;
+            }
+        };
+        let mut config = Config::clone(&self.config);
+        // A project is replaced when it is discovered again, from the same file
+        // or from another one.
+        config
+            .discovered_project_jsons
+            .retain(|(it, discovered)| *it != file && discovered.path() != project.path());
+        config.discovered_project_jsons.push((file, project));
+        self.update_configuration(config);
+    }
+
     pub(crate) fn fetch_workspaces_completed(
         &mut self,
         workspaces: Vec<anyhow::Result<ProjectWorkspace>>,
@@ -282,6 +418,26 @@ impl GlobalState {
                                 ]
                             })
                         })
+                        // Build files of `rust-project.json` crates, see `rediscover_projects`.
+                        .chain(workspaces.iter().flat_map(|ws| {
+                            match ws {
+                                ProjectWorkspace::Json { project, .. } => project
+                                    .crates()
+                                    .filter_map(|(_, krate)| krate.build())
+                                    .flat_map(|build| {
+                                        let name = build.build_file.file_name()?.to_str()?;
+                                        Some([
+                                            build.build_file.display().to_string(),
+                                            format!("{}/**/{}", project.path().display(), name),
+                                        ])
+                                    })
+                                    .flatten()
+                                    .unique()
+                                    .collect(),
+                                ProjectWorkspace::Cargo { .. }
+                                | ProjectWorkspace::DetachedFiles { .. } => Vec::new(),
+                            }
+                        }))
                         .map(|glob_pattern| lsp_types::FileSystemWatcher {
                             glob_pattern,
                             kind: None,
@@ -371,6 +527,7 @@ impl GlobalState {
 
         self.analysis_host.apply_change(change);
         self.process_changes();
+        self.bump_all_file_revisions();
         self.reload_flycheck();
         log::info!("did switch workspaces");
     }
@@ -417,28 +574,155 @@ impl GlobalState {
             .iter()
             .enumerate()
             .filter_map(|(id, w)| match w {
-                ProjectWorkspace::Cargo { cargo, .. } => Some((id, cargo.workspace_root())),
+                ProjectWorkspace::Cargo { cargo, .. } => {
+                    Some((id, config.clone(), cargo.workspace_root().to_path_buf()))
+                }
                 ProjectWorkspace::Json { project, .. } => {
                     // Enable flychecks for json projects if a custom flycheck command was supplied
-                    // in the workspace configuration.
+                    // in the workspace configuration, or if the project has check commands.
                     match config {
-                        FlycheckConfig::CustomCommand { .. } => Some((id, project.path())),
-                        _ => None,
+                        FlycheckConfig::CustomCommand { .. } => {
+                            Some((id, config.clone(), project.path().to_path_buf()))
+                        }
+                        _ => project_json_check(project).map(|(config, cwd)| (id, config, cwd)),
                     }
                 }
                 ProjectWorkspace::DetachedFiles { .. } => None,
             })
-            .map(|(id, root)| {
+            .map(|(id, config, root)| {
                 let sender = sender.clone();
                 FlycheckHandle::spawn(
                     id,
                     Box::new(move |msg| sender.send(msg).unwrap()),
-                    config.clone(),
-                    root.to_path_buf().into(),
+                    config,
+                    root,
                 )
             })
             .collect();
     }
+
+    /// Returns the check commands of the `rust-project.json` crates owning
+    /// `file_id`, keyed by the index of their workspace.
+    pub(crate) fn flycheck_project_json_commands(
+        &self,
+        file_id: FileId,
+    ) -> FxHashMap<usize, (String, Vec<String>, AbsPathBuf)> {
+        let crate_roots = self.crate_root_paths(file_id);
+        let mut res = FxHashMap::default();
+        for (id, ws) in self.workspaces.iter().enumerate() {
+            let project = match ws {
+                ProjectWorkspace::Json { project, .. } => project,
+                ProjectWorkspace::Cargo { .. } | ProjectWorkspace::DetachedFiles { .. } => continue,
+            };
+            let builds = crate_roots
+                .iter()
+                .filter_map(|root| project.crate_by_root(root)?.build())
+                .collect::<Vec<_>>();
+            let check = match builds.iter().find_map(|build| build.runnable(RunnableKind::Check)) {
+                Some(it) => it,
+                None => continue,
+            };
+            // Crates checked by the same command are checked together.
+            let labels = builds
+                .iter()
+                .filter(|build| build.runnable(RunnableKind::Check) == Some(check))
+                .map(|build| build.label.clone())
+                .collect::<Vec<_>>();
+            res.insert(id, (check.program.clone(), check.args(&labels, None), check.cwd.clone()));
+        }
+        res
+    }
+
+    fn crate_root_paths(&self, file_id: FileId) -> Vec<AbsPathBuf> {
+        let analysis = self.analysis_host.analysis();
+        let vfs = &self.vfs.read().0;
+        analysis
+            .crate_for(file_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|crate_id| analysis.crate_root(crate_id).ok())
+            .filter_map(|root| vfs.file_path(root).as_path().map(AbsPath::to_path_buf))
+            .collect()
+    }
+
+    /// Returns the `-p` flags of the packages owning `file_id`, keyed by the
+    /// index of their workspace, which is also the id of its flycheck.
+    pub(crate) fn flycheck_packages(
+        &self,
+        file_id: FileId,
+        config: FlycheckPackagesConfig,
+    ) -> FxHashMap<usize, Vec<String>> {
+        let crate_roots = self.crate_root_paths(file_id);
+
+        let mut res = FxHashMap::default();
+        for (id, ws) in self.workspaces.iter().enumerate() {
+            let cargo = match ws {
+                ProjectWorkspace::Cargo { cargo, .. } => cargo,
+                ProjectWorkspace::Json { .. } | ProjectWorkspace::DetachedFiles { .. } => continue,
+            };
+            let mut packages = Vec::new();
+            for root in &crate_roots {
+                if let Some(target) = cargo.target_by_root(root) {
+                    let package = cargo[target].package;
+                    if !packages.contains(&package) {
+                        packages.push(package);
+                    }
+                }
+            }
+            if packages.is_empty() {
+                continue;
+            }
+            if config.reverse_dependencies {
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for member in cargo.packages().filter(|&it| cargo[it].is_member) {
+                        if !packages.contains(&member)
+                            && cargo[member]
+                                .dependencies
+                                .iter()
+                                .any(|dep| packages.contains(&dep.pkg))
+                        {
+                            packages.push(member);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            let flags = packages.into_iter().map(|it| cargo.package_flag(&cargo[it])).collect();
+            res.insert(id, flags);
+        }
+        res
+    }
+
+    /// Maps the `-p` flags a flycheck was restarted with to cargo package ids.
+    pub(crate) fn flycheck_package_ids(&self, flycheck_id: usize, flags: &[String]) -> Vec<String> {
+        match self.workspaces.get(flycheck_id) {
+            Some(ProjectWorkspace::Cargo { cargo, .. }) => cargo
+                .packages()
+                .filter(|&it| flags.contains(&cargo.package_flag(&cargo[it])))
+                .map(|it| cargo[it].id.clone())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Returns the command checking a whole `rust-project.json` project: the first
+/// check command of its crates, for all the crates sharing it.
+fn project_json_check(project: &ProjectJson) -> Option<(FlycheckConfig, AbsPathBuf)> {
+    let builds = project.crates().filter_map(|(_, krate)| krate.build()).collect::<Vec<_>>();
+    let check = builds.iter().find_map(|build| build.runnable(RunnableKind::Check))?;
+    let labels = builds
+        .iter()
+        .filter(|build| build.runnable(RunnableKind::Check) == Some(check))
+        .map(|build| build.label.clone())
+        .collect::<Vec<_>>();
+    let config = FlycheckConfig::CustomCommand {
+        command: check.program.clone(),
+        args: check.args(&labels, None),
+    };
+    Some((config, check.cwd.clone()))
 }
 
 #[derive(Default)]
@@ -9,15 +9,15 @@ use ide::{
     Annotation, AnnotationKind, Assist, AssistKind, CallInfo, Cancellable, CompletionItem,
     CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
     Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel, InlayHint,
-    InlayKind, Markup, NavigationTarget, ReferenceAccess, RenameError, Runnable, Severity,
-    SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
+    InlayKind, Markup, MonikerKind, MonikerResult, NavigationTarget, ReferenceAccess, RenameError,
+    Runnable, Severity, SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
 };
 use itertools::Itertools;
 use serde_json::to_value;
 use vfs::AbsPath;
 
 use crate::{
-    cargo_target_spec::CargoTargetSpec,
+    cargo_target_spec::{CargoTargetSpec, ProjectJsonTargetSpec},
     global_state::GlobalStateSnapshot,
     line_index::{LineEndings, LineIndex, OffsetEncoding},
     lsp_ext, semantic_tokens, Result,
@@ -538,6 +538,7 @@ fn semantic_token_type_and_modifiers(
         let modifier = match modifier {
             HlMod::Attribute => semantic_tokens::ATTRIBUTE_MODIFIER,
             HlMod::Definition => lsp_types::SemanticTokenModifier::DECLARATION,
+            HlMod::Deprecated => lsp_types::SemanticTokenModifier::DEPRECATED,
             HlMod::Documentation => lsp_types::SemanticTokenModifier::DOCUMENTATION,
             HlMod::Injected => semantic_tokens::INJECTED,
             HlMod::ControlFlow => semantic_tokens::CONTROL_FLOW,
@@ -920,6 +921,47 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn type_hierarchy_item(
+    snap: &GlobalStateSnapshot,
+    target: NavigationTarget,
+) -> Result<lsp_ext::TypeHierarchyItem> {
+    let name = target.name.to_string();
+    let detail = target.description.clone();
+    let kind = target.kind.map(symbol_kind).unwrap_or(lsp_types::SymbolKind::Class);
+    let (uri, range, selection_range) = location_info(snap, target)?;
+    Ok(lsp_ext::TypeHierarchyItem {
+        name,
+        kind,
+        tags: None,
+        detail,
+        uri,
+        range,
+        selection_range,
+        data: None,
+    })
+}
+
+/// Monikers are identified by the package, its version when known, and the
+/// path of the definition, e.g. `serde 1.0.130 serde::de::Deserialize`.
+pub(crate) fn moniker(moniker: MonikerResult) -> lsp_types::Moniker {
+    let package = moniker.package_information;
+    let (identifier, unique) = match package.version {
+        Some(version) => (
+            format!("{} {} {}", package.name, version, moniker.identifier),
+            lsp_types::UniquenessLevel::Scheme,
+        ),
+        None => (
+            format!("{} {}", package.name, moniker.identifier),
+            lsp_types::UniquenessLevel::Project,
+        ),
+    };
+    let kind = match moniker.kind {
+        MonikerKind::Import => lsp_types::MonikerKind::Import,
+        MonikerKind::Export => lsp_types::MonikerKind::Export,
+    };
+    lsp_types::Moniker { scheme: "rust-analyzer".to_string(), identifier, unique, kind: Some(kind) }
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -940,10 +982,15 @@ pub(crate) fn code_action(
         title: assist.label.to_string(),
         group: assist.group.filter(|_| snap.config.code_action_group()).map(|gr| gr.0),
         kind: Some(code_action_kind(assist.id.1)),
+        command: None,
         edit: None,
         is_preferred: None,
         data: None,
     };
+    if let Some(command) = assist.command {
+        res.command = command::assist_command(snap, &res.title, command);
+        return Ok(res);
+    }
     match (assist.source_change, resolve_data) {
         (Some(it), _) => res.edit = Some(snippet_workspace_edit(snap, it)?),
         (None, Some((index, code_action_params))) => {
@@ -963,6 +1010,19 @@ pub(crate) fn runnable(
     snap: &GlobalStateSnapshot,
     runnable: Runnable,
 ) -> Result<lsp_ext::Runnable> {
+    if let Some(spec) = ProjectJsonTargetSpec::for_file(snap, runnable.nav.file_id)? {
+        if let Some(args) = spec.runnable_args(&runnable.kind) {
+            let label = runnable.label(Some(spec.label));
+            let location = location_link(snap, None, runnable.nav)?;
+            return Ok(lsp_ext::Runnable {
+                label,
+                location: Some(location),
+                kind: lsp_ext::RunnableKind::Shell,
+                args: lsp_ext::RunnableArgs::Shell(args),
+            });
+        }
+    }
+
     let config = snap.config.runnables();
     let spec = CargoTargetSpec::for_file(snap, runnable.nav.file_id)?;
     let workspace_root = spec.as_ref().map(|it| it.workspace_root.clone());
@@ -976,14 +1036,14 @@ pub(crate) fn runnable(
         label,
         location: Some(location),
         kind: lsp_ext::RunnableKind::Cargo,
-        args: lsp_ext::CargoRunnable {
+        args: lsp_ext::RunnableArgs::Cargo(lsp_ext::CargoRunnable {
             workspace_root: workspace_root.map(|it| it.into()),
             override_cargo: config.override_cargo,
             cargo_args,
             cargo_extra_args: config.cargo_extra_args,
             executable_args,
             expect_test: None,
-        },
+        }),
     })
 }
 
@@ -1006,6 +1066,8 @@ pub(crate) fn code_lens(
                 | ide::RunnableKind::Bin => true,
             };
             let r = runnable(snap, run)?;
+            // Only cargo runnables can be built for debugging.
+            let can_debug = can_debug && matches!(r.kind, lsp_ext::RunnableKind::Cargo);
 
             let lens_config = snap.config.lens();
             if lens_config.run {
@@ -1102,7 +1164,7 @@ pub(crate) fn code_lens(
 }
 
 pub(crate) mod command {
-    use ide::{FileRange, NavigationTarget};
+    use ide::{AssistCommand, FileRange, NavigationTarget};
     use serde_json::to_value;
 
     use crate::{
@@ -1132,6 +1194,40 @@ pub(crate) mod command {
         }
     }
 
+    /// Commands the server executes itself, in `handlers::handle_execute_command`.
+    pub(crate) const SERVER_COMMANDS: &[&str] = &[ENABLE_CARGO_FEATURE];
+
+    pub(crate) const ENABLE_CARGO_FEATURE: &str = "rust-analyzer.enableCargoFeature";
+
+    pub(crate) fn assist_command(
+        snap: &GlobalStateSnapshot,
+        title: &str,
+        command: AssistCommand,
+    ) -> Option<lsp_types::Command> {
+        match command {
+            AssistCommand::EnableCargoFeature { krate, feature } => {
+                enable_cargo_feature(snap, title, krate, &feature)
+            }
+        }
+    }
+
+    /// Reloads the workspace with `feature` enabled in the package of `krate`,
+    /// using cargo's `package/feature` syntax.
+    pub(crate) fn enable_cargo_feature(
+        snap: &GlobalStateSnapshot,
+        title: &str,
+        krate: ide::CrateId,
+        feature: &str,
+    ) -> Option<lsp_types::Command> {
+        let (cargo_ws, target) = snap.cargo_target_for_crate_root(krate)?;
+        let package = &cargo_ws[cargo_ws[target].package].name;
+        Some(lsp_types::Command {
+            title: title.to_string(),
+            command: ENABLE_CARGO_FEATURE.into(),
+            arguments: Some(vec![to_value(format!("{}/{}", package, feature)).unwrap()]),
+        })
+    }
+
     pub(crate) fn run_single(runnable: &lsp_ext::Runnable, title: &str) -> lsp_types::Command {
         lsp_types::Command {
             title: title.to_string(),
@@ -1239,6 +1335,7 @@ mod tests {
                     enable_postfix_completions: true,
                     enable_imports_on_the_fly: true,
                     enable_self_on_the_fly: true,
+                    enable_term_search: false,
                     add_call_parenthesis: true,
                     add_call_argument_snippets: true,
                     snippet_cap: SnippetCap::new(true),
@@ -1275,13 +1372,13 @@ mod tests {
                 (
                     "&arg",
                     Some(
-                        "fffffff9",
+                        "fffffff2",
                     ),
                 ),
                 (
                     "arg",
                     Some(
-                        "fffffffd",
+                        "fffffff6",
                     ),
                 ),
             ]
@@ -17,17 +17,21 @@ use std::{collections::HashMap, path::PathBuf, time::Instant};
 
 use expect_test::expect;
 use lsp_types::{
-    notification::DidOpenTextDocument,
+    notification::{DidChangeWorkspaceFolders, DidOpenTextDocument, DidSaveTextDocument},
     request::{
         CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest,
         WillRenameFiles,
     },
-    CodeActionContext, CodeActionParams, CompletionParams, DidOpenTextDocumentParams,
-    DocumentFormattingParams, FileRename, FormattingOptions, GotoDefinitionParams, HoverParams,
-    PartialResultParams, Position, Range, RenameFilesParams, TextDocumentItem,
-    TextDocumentPositionParams, WorkDoneProgressParams,
+    CodeActionContext, CodeActionParams, CompletionParams, DidChangeWorkspaceFoldersParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams, FileRename,
+    FormattingOptions, GotoDefinitionParams, HoverParams, PartialResultParams, Position, Range,
+    RenameFilesParams, TextDocumentItem, TextDocumentPositionParams, Url, WorkDoneProgressParams,
+    WorkspaceFolder, WorkspaceFoldersChangeEvent,
+};
+use rust_analyzer::lsp_ext::{
+    DocumentDiagnosticParams, DocumentDiagnosticRequest, OnEnter, PreviousResultId, Runnables,
+    RunnablesParams, WorkspaceDiagnosticParams, WorkspaceDiagnosticRequest,
 };
-use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
 use test_utils::skip_slow_tests;
 
@@ -402,6 +406,187 @@ fn main() {}
     );
 }
 
+#[test]
+fn test_added_workspace_folder_is_loaded() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r#"
+//- /foo/Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /foo/src/lib.rs
+fn main() {}
+
+//- /bar/Cargo.toml
+[package]
+name = "bar"
+version = "0.0.0"
+
+//- /bar/src/lib.rs
+mod baz;
+"#,
+    )
+    .root("foo")
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    server.notification::<DidChangeWorkspaceFolders>(DidChangeWorkspaceFoldersParams {
+        event: WorkspaceFoldersChangeEvent {
+            added: vec![WorkspaceFolder {
+                uri: Url::from_file_path(server.path().join("bar")).unwrap(),
+                name: "bar".to_string(),
+            }],
+            removed: vec![],
+        },
+    });
+    let server = server.wait_until_workspace_is_reloaded();
+
+    server.request::<CodeActionRequest>(
+        CodeActionParams {
+            text_document: server.doc_id("bar/src/lib.rs"),
+            range: Range::new(Position::new(0, 4), Position::new(0, 7)),
+            context: CodeActionContext::default(),
+            partial_result_params: PartialResultParams::default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([{
+            "edit": {
+              "documentChanges": [
+                {
+                  "kind": "create",
+                  "uri": "file:///[..]/bar/src/baz.rs"
+                }
+              ]
+            },
+            "kind": "quickfix",
+            "title": "Create module"
+        }]),
+    );
+}
+
+#[test]
+fn test_runnables_json_project() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let tmp_dir = TestDir::new();
+    let path = tmp_dir.path();
+
+    let project = json!({
+        "roots": [path],
+        "crates": [ {
+            "root_module": path.join("src/main.rs"),
+            "deps": [],
+            "edition": "2018",
+            "build": {
+                "label": "//:app",
+                "build_file": path.join("BUILD"),
+                "target_kind": "bin",
+                "runnables": [
+                    { "kind": "check", "program": "bazel", "args": ["build", "{label}"], "cwd": path },
+                    { "kind": "run", "program": "bazel", "args": ["run", "{label}"], "cwd": path },
+                    {
+                        "kind": "testOne",
+                        "program": "bazel",
+                        "args": ["test", "{label}", "--test_arg={test_id}"],
+                        "cwd": path
+                    },
+                ],
+            },
+        } ]
+    });
+
+    let code = format!(
+        r#"
+//- /rust-project.json
+{PROJECT}
+
+//- /src/main.rs
+fn main() {{}}
+
+#[test]
+fn it_works() {{}}
+"#,
+        PROJECT = project.to_string(),
+    );
+
+    let server =
+        Project::with_fixture(&code).tmp_dir(tmp_dir).server().wait_until_workspace_is_loaded();
+
+    server.request::<Runnables>(
+        RunnablesParams { text_document: server.doc_id("src/main.rs"), position: None },
+        json!([
+          {
+            "args": { "program": "bazel", "args": ["run", "//:app"], "cwd": server.path() },
+            "kind": "shell",
+            "label": "run //:app",
+            "location": {
+              "targetRange": {
+                "end": { "character": 12, "line": 0 },
+                "start": { "character": 0, "line": 0 }
+              },
+              "targetSelectionRange": {
+                "end": { "character": 7, "line": 0 },
+                "start": { "character": 3, "line": 0 }
+              },
+              "targetUri": "file:///[..]/src/main.rs"
+            }
+          },
+          {
+            "args": {
+              "program": "bazel",
+              "args": ["test", "//:app", "--test_arg=it_works"],
+              "cwd": server.path()
+            },
+            "kind": "shell",
+            "label": "test it_works",
+            "location": {
+              "targetRange": {
+                "end": { "character": 16, "line": 3 },
+                "start": { "character": 0, "line": 2 }
+              },
+              "targetSelectionRange": {
+                "end": { "character": 11, "line": 3 },
+                "start": { "character": 3, "line": 3 }
+              },
+              "targetUri": "file:///[..]/src/main.rs"
+            }
+          },
+          {
+            "args": {
+              "program": "bazel",
+              "args": ["test", "//:app", "--test_arg="],
+              "cwd": server.path()
+            },
+            "kind": "shell",
+            "label": "test-mod ",
+            "location": {
+              "targetRange": {
+                "end": { "character": 0, "line": 4 },
+                "start": { "character": 0, "line": 0 }
+              },
+              "targetSelectionRange": {
+                "end": { "character": 0, "line": 4 },
+                "start": { "character": 0, "line": 0 }
+              },
+              "targetUri": "file:///[..]/src/main.rs"
+            }
+          },
+          {
+            "args": { "program": "bazel", "args": ["build", "//:app"], "cwd": server.path() },
+            "kind": "shell",
+            "label": "bazel build //:app"
+          }
+        ]),
+    );
+}
+
 #[test]
 fn test_missing_module_code_action_in_json_project() {
     if skip_slow_tests() {
@@ -472,6 +657,84 @@ fn main() {{}}
     );
 }
 
+#[test]
+fn test_discover_project_command() {
+    // The discovery command is a shell script.
+    if skip_slow_tests() || cfg!(windows) {
+        return;
+    }
+
+    let tmp_dir = TestDir::new();
+    let path = tmp_dir.path();
+
+    let project = json!({
+        "roots": [path.join("foo")],
+        "crates": [ {
+            "root_module": path.join("foo/src/lib.rs"),
+            "deps": [],
+            "edition": "2018",
+        } ]
+    });
+
+    let code = format!(
+        r#"
+//- /foo/rust-project.json
+{PROJECT}
+
+//- /foo/src/lib.rs
+fn main() {{}}
+
+//- /gen/project.json
+{{ "roots": ["gen"], "crates": [ {{ "root_module": "gen/src/lib.rs", "deps": [], "edition": "2018" }} ] }}
+
+//- /gen/src/lib.rs
+mod bar;
+"#,
+        PROJECT = project.to_string(),
+    );
+
+    let server = Project::with_fixture(&code)
+        .tmp_dir(tmp_dir)
+        .root("foo")
+        .with_config(json!({
+            "discoverProjectCommand": ["sh", "-c", "cat gen/project.json", "discover"],
+        }))
+        .server()
+        .wait_until_workspace_is_loaded();
+
+    server.notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: server.doc_id("gen/src/lib.rs").uri,
+            language_id: "rust".to_string(),
+            version: 0,
+            text: "mod bar;\n".to_string(),
+        },
+    });
+    let server = server.wait_until_workspace_is_reloaded();
+
+    server.request::<CodeActionRequest>(
+        CodeActionParams {
+            text_document: server.doc_id("gen/src/lib.rs"),
+            range: Range::new(Position::new(0, 4), Position::new(0, 7)),
+            context: CodeActionContext::default(),
+            partial_result_params: PartialResultParams::default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([{
+            "edit": {
+              "documentChanges": [
+                {
+                  "kind": "create",
+                  "uri": "file://[..]/gen/src/bar.rs"
+                }
+              ]
+            },
+            "kind": "quickfix",
+            "title": "Create module"
+        }]),
+    );
+}
+
 #[test]
 fn diagnostics_dont_block_typing() {
     if skip_slow_tests() {
@@ -934,3 +1197,137 @@ fn main() {}
         }),
     );
 }
+
+#[test]
+fn test_pull_diagnostics_reuse_result_ids() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+mod bar;
+"#,
+    )
+    .pull_diagnostics()
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let params = || DocumentDiagnosticParams {
+        text_document: server.doc_id("src/lib.rs"),
+        identifier: None,
+        previous_result_id: None,
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let res = server.send_request::<DocumentDiagnosticRequest>(params());
+    assert_eq!(res["kind"], "full");
+    assert_eq!(res["items"][0]["code"], "unresolved-module");
+    let result_id = res["resultId"].as_str().unwrap().to_string();
+
+    server.request::<DocumentDiagnosticRequest>(
+        DocumentDiagnosticParams { previous_result_id: Some(result_id.clone()), ..params() },
+        json!({ "kind": "unchanged", "resultId": result_id }),
+    );
+
+    server.request::<WorkspaceDiagnosticRequest>(
+        WorkspaceDiagnosticParams {
+            identifier: None,
+            previous_result_ids: vec![PreviousResultId {
+                uri: server.doc_id("src/lib.rs").uri,
+                value: result_id.clone(),
+            }],
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        },
+        json!({
+            "items": [{
+                "kind": "unchanged",
+                "uri": "file:///[..]/src/lib.rs",
+                "version": null,
+                "resultId": result_id,
+            }]
+        }),
+    );
+}
+
+#[test]
+fn test_workspace_diagnostics_are_cached_until_save() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+mod foo;
+mod bar;
+
+//- /src/foo.rs
+
+//- /src/bar.rs
+use crate::foo::f;
+pub(crate) fn g() {
+    f();
+}
+"#,
+    )
+    .with_config(serde_json::json!({ "checkOnSave": { "enable": false } }))
+    .pull_diagnostics()
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let bar_diagnostics = |previous_result_ids| {
+        let res = server.send_request::<WorkspaceDiagnosticRequest>(WorkspaceDiagnosticParams {
+            identifier: None,
+            previous_result_ids,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        });
+        res["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|it| it["uri"].as_str().unwrap().ends_with("src/bar.rs"))
+            .unwrap()
+            .clone()
+    };
+    let res = bar_diagnostics(Vec::new());
+    assert_eq!(res["kind"], "full");
+    assert_eq!(res["items"][0]["code"], "unresolved-import");
+    let result_id = res["resultId"].as_str().unwrap().to_string();
+    let previous_result_ids = || {
+        vec![PreviousResultId { uri: server.doc_id("src/bar.rs").uri, value: result_id.clone() }]
+    };
+
+    server.notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: server.doc_id("src/foo.rs").uri,
+            language_id: "rust".to_string(),
+            version: 0,
+            text: "pub(crate) fn f() {}\n".to_string(),
+        },
+    });
+    // The closed `bar.rs` didn't change, so its report is reused until the save.
+    let res = bar_diagnostics(previous_result_ids());
+    assert_eq!(res["kind"], "unchanged");
+
+    server.notification::<DidSaveTextDocument>(DidSaveTextDocumentParams {
+        text_document: server.doc_id("src/foo.rs"),
+        text: None,
+    });
+    let res = bar_diagnostics(previous_result_ids());
+    assert_eq!(res["kind"], "full");
+    assert_eq!(res["items"], json!([]));
+}
@@ -22,6 +22,7 @@ pub(crate) struct Project<'a> {
     fixture: &'a str,
     tmp_dir: Option<TestDir>,
     roots: Vec<PathBuf>,
+    pull_diagnostics: bool,
     config: serde_json::Value,
 }
 
@@ -31,6 +32,7 @@ impl<'a> Project<'a> {
             fixture,
             tmp_dir: None,
             roots: vec![],
+            pull_diagnostics: false,
             config: serde_json::json!({
                 "cargo": {
                     // Loading standard library is costly, let's ignore it by default
@@ -52,6 +54,11 @@ impl<'a> Project<'a> {
         self
     }
 
+    pub(crate) fn pull_diagnostics(mut self) -> Project<'a> {
+        self.pull_diagnostics = true;
+        self
+    }
+
     pub(crate) fn with_config(mut self, config: serde_json::Value) -> Project<'a> {
         fn merge(dst: &mut serde_json::Value, src: serde_json::Value) {
             match (dst, src) {
@@ -90,8 +97,8 @@ impl<'a> Project<'a> {
             roots.push(tmp_dir_path.clone());
         }
         let discovered_projects = roots
-            .into_iter()
-            .map(|it| ProjectManifest::discover_single(&it).unwrap())
+            .iter()
+            .map(|it| ProjectManifest::discover_single(it).unwrap())
             .collect::<Vec<_>>();
 
         let mut config = Config::new(
@@ -124,6 +131,10 @@ impl<'a> Project<'a> {
                 ..Default::default()
             },
         );
+        if self.pull_diagnostics {
+            config.update_pull_diagnostics_caps(&json!({ "textDocument": { "diagnostic": {} } }));
+        }
+        config.workspace_roots = roots;
         config.discovered_projects = Some(discovered_projects);
         config.update(self.config);
 
@@ -229,7 +240,15 @@ impl Server {
         panic!("no response for {:?}", r);
     }
     pub(crate) fn wait_until_workspace_is_loaded(self) -> Server {
-        self.wait_for_message_cond(1, &|msg: &Message| match msg {
+        self.wait_until_quiescent(1)
+    }
+    /// Waits for the workspace to be loaded again, after the workspace folders
+    /// changed.
+    pub(crate) fn wait_until_workspace_is_reloaded(self) -> Server {
+        self.wait_until_quiescent(2)
+    }
+    fn wait_until_quiescent(self, n: usize) -> Server {
+        self.wait_for_message_cond(n, &|msg: &Message| match msg {
             Message::Notification(n) if n.method == "experimental/serverStatus" => {
                 let status = n
                     .clone()
@@ -58,7 +58,7 @@ pub use crate::{
     },
     token_text::TokenText,
 };
-pub use parser::{SyntaxKind, T};
+pub use parser::{Edition, SyntaxKind, T};
 pub use rowan::{
     Direction, GreenNode, NodeOrToken, SyntaxText, TextRange, TextSize, TokenAtOffset, WalkEvent,
 };
@@ -133,25 +133,28 @@ impl Parse<SourceFile> {
         buf
     }
 
-    pub fn reparse(&self, indel: &Indel) -> Parse<SourceFile> {
-        self.incremental_reparse(indel).unwrap_or_else(|| self.full_reparse(indel))
+    /// Applies `indel` to the text of the file and reparses it, reusing as much
+    /// of the old tree as possible. `edition` must be the one the file was
+    /// originally parsed with.
+    pub fn reparse(&self, indel: &Indel, edition: Edition) -> Parse<SourceFile> {
+        self.incremental_reparse(indel, edition)
+            .unwrap_or_else(|| self.full_reparse(indel, edition))
     }
 
-    fn incremental_reparse(&self, indel: &Indel) -> Option<Parse<SourceFile>> {
+    fn incremental_reparse(&self, indel: &Indel, edition: Edition) -> Option<Parse<SourceFile>> {
         // FIXME: validation errors are not handled here
-        parsing::incremental_reparse(self.tree().syntax(), indel, self.errors.to_vec()).map(
-            |(green_node, errors, _reparsed_range)| Parse {
+        parsing::incremental_reparse(self.tree().syntax(), indel, self.errors.to_vec(), edition)
+            .map(|(green_node, errors, _reparsed_range)| Parse {
                 green: green_node,
                 errors: Arc::new(errors),
                 _ty: PhantomData,
-            },
-        )
+            })
     }
 
-    fn full_reparse(&self, indel: &Indel) -> Parse<SourceFile> {
+    fn full_reparse(&self, indel: &Indel, edition: Edition) -> Parse<SourceFile> {
         let mut text = self.tree().syntax().text().to_string();
         indel.apply(&mut text);
-        SourceFile::parse(&text)
+        SourceFile::parse_with_edition(&text, edition)
     }
 }
 
@@ -160,7 +163,12 @@ pub use crate::ast::SourceFile;
 
 impl SourceFile {
     pub fn parse(text: &str) -> Parse<SourceFile> {
-        let (green, mut errors) = parsing::parse_text(text);
+        SourceFile::parse_with_edition(text, Edition::CURRENT)
+    }
+
+    /// Parses `text` with the keywords and reserved syntax of `edition`.
+    pub fn parse_with_edition(text: &str, edition: Edition) -> Parse<SourceFile> {
+        let (green, mut errors) = parsing::parse_text(text, edition);
         let root = SyntaxNode::new_root(green.clone());
 
         if cfg!(debug_assertions) {
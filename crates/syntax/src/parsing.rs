@@ -6,7 +6,7 @@ mod text_token_source;
 mod text_tree_sink;
 mod reparsing;
 
-use parser::SyntaxKind;
+use parser::{Edition, SyntaxKind};
 use text_token_source::TextTokenSource;
 use text_tree_sink::TextTreeSink;
 
@@ -14,13 +14,16 @@ use crate::{syntax_node::GreenNode, AstNode, SyntaxError, SyntaxNode};
 
 pub(crate) use crate::parsing::{lexer::*, reparsing::incremental_reparse};
 
-pub(crate) fn parse_text(text: &str) -> (GreenNode, Vec<SyntaxError>) {
-    let (tokens, lexer_errors) = tokenize(text);
+pub(crate) fn parse_text(text: &str, edition: Edition) -> (GreenNode, Vec<SyntaxError>) {
+    let (tokens, mut lexer_errors) = tokenize(text);
+    if edition >= Edition::Edition2021 {
+        lexer_errors.extend(reserved_prefix_errors(text, &tokens));
+    }
 
     let mut token_source = TextTokenSource::new(text, &tokens);
     let mut tree_sink = TextTreeSink::new(text, &tokens);
 
-    parser::parse(&mut token_source, &mut tree_sink);
+    parser::parse(&mut token_source, &mut tree_sink, edition);
 
     let (tree, mut parser_errors) = tree_sink.finish();
     parser_errors.extend(lexer_errors);
@@ -45,7 +48,7 @@ pub(crate) fn parse_text_fragment<T: AstNode>(
     // tokens. We arbitrarily give it a SourceFile.
     use parser::TreeSink;
     tree_sink.start_node(SyntaxKind::SOURCE_FILE);
-    parser::parse_fragment(&mut token_source, &mut tree_sink, fragment_kind);
+    parser::parse_fragment(&mut token_source, &mut tree_sink, fragment_kind, Edition::CURRENT);
     tree_sink.finish_node();
 
     let (tree, parser_errors) = tree_sink.finish();
@@ -61,6 +61,30 @@ pub fn tokenize(text: &str) -> (Vec<Token>, Vec<SyntaxError>) {
     (tokens, errors)
 }
 
+/// Reports identifiers which are immediately followed by `#`, `"` or `'`.
+///
+/// Since Rust 2021 such prefixes are reserved (see RFC 3101), `rustc_lexer`
+/// still splits them into two separate tokens.
+pub(crate) fn reserved_prefix_errors(text: &str, tokens: &[Token]) -> Vec<SyntaxError> {
+    let mut errors = Vec::new();
+    let mut offset = TextSize::from(0);
+    for (token, next) in tokens.iter().zip(tokens.iter().skip(1)) {
+        let range = TextRange::at(offset, token.len);
+        offset += token.len;
+
+        if token.kind != IDENT && !token.kind.is_keyword() {
+            continue;
+        }
+        let prefix = &text[range];
+        if prefix.starts_with("r#") || !matches!(next.kind, T![#] | STRING | CHAR | LIFETIME_IDENT)
+        {
+            continue;
+        }
+        errors.push(SyntaxError::new(format!("prefix `{}` is unknown", prefix), range));
+    }
+    errors
+}
+
 /// Returns `SyntaxKind` and `Option<SyntaxError>` if `text` parses as a single token.
 ///
 /// Returns `None` if the string contains zero *or two or more* tokens.
@@ -13,7 +13,10 @@ use expect_test::expect_file;
 use rayon::prelude::*;
 use test_utils::{bench, bench_fixture, project_root, skip_slow_tests};
 
-use crate::{ast, fuzz, tokenize, AstNode, SourceFile, SyntaxError, TextRange, TextSize, Token};
+use crate::{
+    ast, fuzz, tokenize, AstNode, Edition, SourceFile, SyntaxError, SyntaxKind, TextRange,
+    TextSize, Token,
+};
 
 #[test]
 fn lexer_tests() {
@@ -46,6 +49,39 @@ fn main() {
     assert!(parse.ok().is_ok());
 }
 
+#[test]
+fn edition_2018_keywords_are_identifiers_in_2015() {
+    let code = "fn async() { let dyn = try!(await); }";
+
+    assert!(SourceFile::parse_with_edition(code, Edition::Edition2015).ok().is_ok());
+    assert!(SourceFile::parse_with_edition(code, Edition::Edition2018).ok().is_err());
+}
+
+#[test]
+fn dyn_is_a_weak_keyword_in_2015() {
+    let code = "type A = Box<dyn Trait + 'static>; type B = dyn::Foo; type C = dyn<T>;";
+
+    let parse = SourceFile::parse_with_edition(code, Edition::Edition2015);
+    assert!(parse.errors().is_empty());
+    let dyn_types = parse
+        .tree()
+        .syntax()
+        .descendants()
+        .filter(|it| it.kind() == SyntaxKind::DYN_TRAIT_TYPE)
+        .count();
+    assert_eq!(dyn_types, 1);
+}
+
+#[test]
+fn reserved_prefixes_in_2021() {
+    let code = r#"m!(foo"bar" k#keyword r#raw#x br"ok");"#;
+
+    assert!(SourceFile::parse_with_edition(code, Edition::Edition2018).errors().is_empty());
+    let parse = SourceFile::parse_with_edition(code, Edition::Edition2021);
+    let errors = parse.errors().iter().map(|it| it.to_string()).collect::<Vec<_>>();
+    assert_eq!(errors, ["prefix `foo` is unknown", "prefix `k` is unknown"]);
+}
+
 #[test]
 fn benchmark_parser() {
     if skip_slow_tests() {
@@ -48,10 +48,11 @@
 //!
 //! Metadata allows specifying all settings and variables
 //! that are available in a real rust project:
-//! - crate names via `crate:cratename`
+//! - crate names via `crate:cratename`, optionally with a version (`crate:cratename@0.1.0`)
 //! - dependencies via `deps:dep1,dep2`
 //! - configuration settings via `cfg:dbg=false,opt_level=2`
 //! - environment variables via `env:PATH=/bin,RUST_LOG=debug`
+//! - Cargo features via `features:full=derive+std,derive,std`
 //!
 //! Example using all available metadata:
 //! ```
@@ -72,6 +73,7 @@ pub struct Fixture {
     pub deps: Vec<String>,
     pub cfg_atoms: Vec<String>,
     pub cfg_key_values: Vec<(String, String)>,
+    pub features: FxHashMap<String, Vec<String>>,
     pub edition: Option<String>,
     pub env: FxHashMap<String, String>,
     pub introduce_new_source_root: bool,
@@ -161,6 +163,7 @@ impl Fixture {
         let mut edition = None;
         let mut cfg_atoms = Vec::new();
         let mut cfg_key_values = Vec::new();
+        let mut features = FxHashMap::default();
         let mut env = FxHashMap::default();
         let mut introduce_new_source_root = false;
         for component in components[1..].iter() {
@@ -179,6 +182,15 @@ impl Fixture {
                         }
                     }
                 }
+                "features" => {
+                    for entry in value.split(',') {
+                        let (feature, enables) = match entry.split_once('=') {
+                            Some((k, v)) => (k, v.split('+').map(|it| it.to_string()).collect()),
+                            None => (entry, Vec::new()),
+                        };
+                        features.insert(feature.to_string(), enables);
+                    }
+                }
                 "env" => {
                     for key in value.split(',') {
                         if let Some((k, v)) = key.split_once('=') {
@@ -198,6 +210,7 @@ impl Fixture {
             deps,
             cfg_atoms,
             cfg_key_values,
+            features,
             edition,
             env,
             introduce_new_source_root,
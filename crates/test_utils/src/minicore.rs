@@ -15,6 +15,7 @@
 //!     range:
 //!     deref: sized
 //!     deref_mut: deref
+//!     drop:
 //!     index: sized
 //!     fn:
 //!     pin:
@@ -30,6 +31,8 @@
 //!     eq: sized
 //!     ord: eq, option
 //!     derive:
+//!     mem:
+//!     panic:
 
 pub mod marker {
     // region:sized
@@ -124,6 +127,16 @@ pub mod convert {
 }
 // endregion:from
 
+// region:mem
+pub mod mem {
+    pub struct Discriminant<T>(*const T);
+
+    pub fn discriminant<T>(v: &T) -> Discriminant<T> {
+        loop {}
+    }
+}
+// endregion:mem
+
 pub mod ops {
     // region:coerce_unsized
     mod unsize {
@@ -168,6 +181,16 @@ pub mod ops {
     };
     // endregion:deref
 
+    // region:drop
+    mod drop {
+        #[lang = "drop"]
+        pub trait Drop {
+            fn drop(&mut self);
+        }
+    }
+    pub use self::drop::Drop;
+    // endregion:drop
+
     // region:index
     mod index {
         #[lang = "index"]
@@ -344,6 +367,7 @@ pub mod option {
 
 // region:result
 pub mod result {
+    #[must_use = "this `Result` may be an `Err` variant, which should be handled"]
     pub enum Result<T, E> {
         #[lang = "Ok"]
         Ok(T),
@@ -370,6 +394,7 @@ pub mod future {
         task::{Context, Poll},
     };
 
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
     #[lang = "future_trait"]
     pub trait Future {
         type Output;
@@ -395,6 +420,7 @@ pub mod task {
 pub mod iter {
     // region:iterators
     mod adapters {
+        #[must_use = "iterators are lazy and do nothing unless consumed"]
         pub struct Take<I> {
             iter: I,
             n: usize,
@@ -410,6 +436,7 @@ pub mod iter {
             }
         }
 
+        #[must_use = "iterators are lazy and do nothing unless consumed"]
         pub struct FilterMap<I, F> {
             iter: I,
             f: F,
@@ -434,6 +461,7 @@ pub mod iter {
                 loop {}
             }
 
+            #[must_use = "iterators are lazy and do nothing unless consumed"]
             pub struct Repeat<A> {
                 element: A,
             }
@@ -522,6 +550,15 @@ mod macros {
 }
 // endregion:derive
 
+// region:panic
+#[macro_export]
+macro_rules! panic {
+    ($($arg:tt)*) => {
+        loop {}
+    };
+}
+// endregion:panic
+
 pub mod prelude {
     pub mod v1 {
         pub use crate::{
@@ -534,8 +571,10 @@ pub mod prelude {
             macros::builtin::derive,            // :derive
             marker::Copy,                       // :copy
             marker::Sized,                      // :sized
+            ops::Drop,                          // :drop
             ops::{Fn, FnMut, FnOnce},           // :fn
             option::Option::{self, None, Some}, // :option
+            panic,                              // :panic
             result::Result::{self, Err, Ok},    // :result
         };
     }
@@ -344,6 +344,7 @@ impl VirtualPath {
     /// Append the given *relative* path `path` to `self`.
     ///
     /// This will resolve any leading `"../"` in `path` before appending it.
+    /// Like [`std::path::Path::join`], an absolute `path` replaces `self`.
     ///
     /// Returns [`None`] if `path` has more leading `"../"` than the number of
     /// components in `self`.
@@ -352,6 +353,9 @@ impl VirtualPath {
     ///
     /// In practice, appending here means `self/path` as strings.
     fn join(&self, mut path: &str) -> Option<VirtualPath> {
+        if path.starts_with('/') {
+            return Some(VirtualPath(path.to_string()));
+        }
         let mut res = self.clone();
         while path.starts_with("../") {
             if !res.pop() {
@@ -85,9 +85,9 @@ export function createClient(serverPath: string, workspace: Workspace, extraEnv:
                     const result: (vscode.CodeAction | vscode.Command)[] = [];
                     const groups = new Map<string, { index: number; items: vscode.CodeAction[] }>();
                     for (const item of values) {
-                        // In our case we expect to get code edits only from diagnostics
+                        // In our case we expect to get code edits only from diagnostics,
+                        // and commands only from fixes which are executed by the server.
                         if (lc.CodeAction.is(item)) {
-                            assert(!item.command, "We don't expect to receive commands in CodeActions");
                             const action = client.protocol2CodeConverter.asCodeAction(item);
                             result.push(action);
                             continue;
@@ -712,10 +712,12 @@ export function copyRunCommandLine(ctx: Ctx) {
     return async () => {
         const item = await selectRunnable(ctx, prevRunnable);
         if (!item) return;
-        const args = createArgs(item.runnable);
-        const commandLine = ["cargo", ...args].join(" ");
+        const runnable = item.runnable;
+        const commandLine = runnable.kind === "cargo" ?
+            ["cargo", ...createArgs(runnable)].join(" ") :
+            [runnable.args.program, ...runnable.args.args].join(" ");
         await vscode.env.clipboard.writeText(commandLine);
-        await vscode.window.showInformationMessage("Cargo invocation copied to the clipboard.");
+        await vscode.window.showInformationMessage("Command line copied to the clipboard.");
     };
 }
 
@@ -70,9 +70,14 @@ export interface RunnablesParams {
     position: lc.Position | null;
 }
 
-export interface Runnable {
+export type Runnable = CargoRunnable | ShellRunnable;
+
+interface RunnableBase {
     label: string;
     location?: lc.LocationLink;
+}
+
+export interface CargoRunnable extends RunnableBase {
     kind: "cargo";
     args: {
         workspaceRoot?: string;
@@ -83,6 +88,15 @@ export interface Runnable {
         overrideCargo?: string;
     };
 }
+
+export interface ShellRunnable extends RunnableBase {
+    kind: "shell";
+    args: {
+        program: string;
+        args: string[];
+        cwd: string;
+    };
+}
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>("experimental/runnables");
 
 export interface TestInfo {